@@ -0,0 +1,110 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+// CLI benchmarks, against a real, ephemeral, ZFS pool, with many snapshots of many files
+//
+// like the integration tests, these require root and the ZFS userland tools, and so are only
+// built with the "integration-tests" feature: sudo -E cargo bench --features integration-tests
+//
+// httm is a binary crate, without a library target, so each benchmark times a whole invocation,
+// and then, run once more with PROFILE, reports where that time was spent, phase by phase
+
+#[allow(dead_code)]
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::fs::{create_dir_all, remove_file, write};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use common::{httm, run, should_skip, ZfsPool};
+
+const NUM_SNAPSHOTS: usize = 100;
+const NUM_FILES: usize = 100;
+const ITERATIONS: usize = 10;
+
+fn main() {
+    if should_skip(&["zpool", "zfs"]) {
+        return;
+    }
+
+    let pool = ZfsPool::new();
+    let dir = pool.mount().join("bench");
+    create_dir_all(&dir).unwrap();
+
+    let files: Vec<_> = (0..NUM_FILES)
+        .map(|file_idx| dir.join(format!("file_{file_idx}.txt")))
+        .collect();
+
+    let deleted_dir = dir.join("deleted");
+    create_dir_all(&deleted_dir).unwrap();
+
+    let deleted_files: Vec<_> = (0..NUM_FILES)
+        .map(|file_idx| deleted_dir.join(format!("file_{file_idx}.txt")))
+        .collect();
+
+    // every file changes before every snapshot, so each snapshot holds a unique version of each
+    (0..NUM_SNAPSHOTS).for_each(|snap_idx| {
+        files
+            .iter()
+            .chain(deleted_files.iter())
+            .for_each(|file| write(file, format!("version {snap_idx}\n")).unwrap());
+        pool.snapshot(&format!("bench_{snap_idx}"));
+    });
+
+    // so the deleted search has something to find
+    deleted_files
+        .iter()
+        .for_each(|file| remove_file(file).unwrap());
+
+    bench("versions of one file", httm().arg("-n").arg(&files[0]));
+    bench("count of one file", httm().arg("--count").arg(&files[0]));
+    bench("versions of every file", httm().arg("-n").args(&files));
+    bench(
+        "unique versions of every file",
+        httm().args(["-n", "--uniqueness=contents"]).args(&files),
+    );
+    bench(
+        "recursive deleted search",
+        httm().args(["-n", "--deleted", "--recursive"]).arg(&dir),
+    );
+}
+
+fn bench(name: &str, command: &mut Command) {
+    // one untimed run, so every timed run finds the same warm caches
+    run(command);
+
+    let mut timings: Vec<Duration> = (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            run(command);
+            start.elapsed()
+        })
+        .collect();
+
+    timings.sort();
+
+    println!(
+        "{name:<32} median: {:>12.3?}  min: {:>12.3?}  max: {:>12.3?}",
+        timings[ITERATIONS / 2],
+        timings[0],
+        timings[ITERATIONS - 1]
+    );
+
+    let profile = run(command.arg("--profile"));
+    eprint!("{}", String::from_utf8_lossy(&profile.stderr));
+}
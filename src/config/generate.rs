@@ -15,23 +15,42 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use std::ffi::OsStr;
 use std::ops::Index;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use clap::OsValues;
 use rayon::prelude::*;
 
 use clap::{crate_name, crate_version, Arg, ArgMatches};
 use indicatif::ProgressBar;
+use regex::bytes::Regex;
 use time::UtcOffset;
 
 use crate::config::install_hot_keys::install_hot_keys;
-use crate::data::filesystem_info::FilesystemInfo;
+use crate::config::manual::Manual;
+use crate::data::filesystem_info::{FilesystemInfo, ReparseOptions};
 use crate::data::paths::PathData;
-use crate::library::results::{HttmError, HttmResult};
-use crate::library::utility::{read_stdin, HttmIsDir};
+use crate::exec::preview::PreviewCompare;
+use crate::exec::snap_mounts::{SnapshotMounts, DEFAULT_SNAPSHOT_SUFFIX};
+use crate::library::alt_root::AltRoot;
+use crate::library::date_locale::DateLocale;
+use crate::library::escalate::EscalationTool;
+use crate::library::messages::Catalog;
+use crate::library::profile::{Phase, Profile};
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult};
+use crate::library::utility::{
+    formatted_date, parse_date_format, parse_human_duration, read_files_from, read_stdin, HttmIsDir,
+};
+use crate::parse::aliases::MapOfAliases;
+use crate::parse::mounts::{DatasetHint, MapOfBinds, DEFAULT_EXCLUDE_PROPERTY};
 use crate::ROOT_DIRECTORY;
 
+const DEFAULT_MOST_CHANGED_TOP: usize = 10;
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_PREVIEW_WINDOW: &str = "up:50%";
+
 #[derive(Debug, Clone)]
 pub enum ExecMode {
     Interactive(InteractiveMode),
@@ -43,12 +62,63 @@ pub enum ExecMode {
     SnapsForFiles(Option<ListSnapsFilters>),
     NumVersions(NumVersionsMode),
     RollForward(RollForwardConfig),
+    Grep(Regex),
+    Count,
+    ListSnapsAll(Option<String>),
+    MostChanged(MostChangedConfig),
+    RecoverDeleted(RecoverDeletedConfig),
+    Index(indicatif::ProgressBar),
+    Info,
+    AssertSnapshotted(Option<Duration>),
+    Drift,
+    Rpc,
+    CleanupClones,
+}
+
+#[derive(Debug, Clone)]
+pub struct MostChangedConfig {
+    pub rank_by: RankBy,
+    pub top: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum RankBy {
+    Versions,
+    Bytes,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputFile {
+    pub path: PathBuf,
+    pub should_append: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct RollForwardConfig {
     pub full_snap_name: String,
     pub progress_bar: indicatif::ProgressBar,
+    pub recursive: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecoverDeletedConfig {
+    pub opt_restore_to: Option<PathBuf>,
+    pub progress_bar: indicatif::ProgressBar,
+}
+
+#[derive(Debug, Clone)]
+pub struct HookConfig {
+    pub opt_pre_snap: Option<String>,
+    pub opt_post_snap: Option<String>,
+    pub opt_pre_destroy: Option<String>,
+    pub timeout: Duration,
+    pub on_failure: HookFailure,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookFailure {
+    Abort,
+    Continue,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +138,7 @@ pub enum MountDisplay {
 pub enum InteractiveMode {
     Browse,
     Select,
+    Pick,
     Restore(RestoreMode),
 }
 
@@ -82,6 +153,7 @@ pub enum RestoreMode {
     CopyOnly,
     CopyAndPreserve,
     Overwrite(RestoreSnapGuard),
+    Clone,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -92,6 +164,27 @@ pub enum PrintMode {
     RawZero,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawField {
+    Path,
+    Mtime,
+    Size,
+    Snap,
+    Dataset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Zstd,
+    Gzip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardMode {
+    Path,
+    Contents,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DeletedMode {
     DepthOfOne,
@@ -99,18 +192,68 @@ pub enum DeletedMode {
     Only,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnerFormat {
+    Names,
+    Numeric,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    // auto only paints output destined for a terminal
+    pub fn is_enabled(&self, is_terminal: bool) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => is_terminal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeFormat {
+    Bytes,
+    Binary,
+    Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateDisplay {
+    Absolute,
+    Relative,
+    RelativeAndAbsolute,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ListSnapsOfType {
     All,
     UniqueMetadata,
     UniqueContents,
 }
 
+impl ListSnapsOfType {
+    // as the value would be specified to UNIQUENESS
+    pub fn name(&self) -> &'static str {
+        match self {
+            ListSnapsOfType::All => "all",
+            ListSnapsOfType::UniqueMetadata => "metadata",
+            ListSnapsOfType::UniqueContents => "contents",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ListSnapsFilters {
     pub select_mode: bool,
     pub omit_num_snaps: usize,
     pub name_filters: Option<Vec<String>>,
+    pub opt_older_than: Option<Duration>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -122,6 +265,12 @@ pub enum LastSnapMode {
     NoDittoInclusive,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatasetThreads {
+    Fixed(usize),
+    Auto,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NumVersionsMode {
     AllNumerals,
@@ -133,10 +282,16 @@ pub enum NumVersionsMode {
 }
 
 fn parse_args() -> ArgMatches {
+    build_command().get_matches()
+}
+
+pub fn build_command() -> clap::Command<'static> {
     clap::Command::new(crate_name!())
         .about("httm prints the size, date and corresponding locations of available unique versions of files residing on snapshots.  \
         May also be used interactively to select and restore from such versions, and even to snapshot datasets which contain certain files.")
         .version(crate_version!())
+        // replaced by HELP, which also offers the full help
+        .disable_help_flag(true)
         .arg(
             Arg::new("INPUT_FILES")
                 .help("in any non-interactive mode, put requested paths here.  If you include no paths as arguments, \
@@ -148,22 +303,50 @@ fn parse_args() -> ArgMatches {
                 .value_parser(clap::builder::ValueParser::os_string())
                 .display_order(1)
         )
+        .arg(
+            Arg::new("FILES_FROM")
+                .long("files-from")
+                .takes_value(true)
+                .require_equals(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .help("read requested paths from the file specified, instead of from the command line or stdin.  \
+                Paths are delimited just as they would be on stdin, that is -- by a NEWLINE or NULL character, if available.  \
+                Useful where the number of paths would exceed the system's maximum argument length, and, unlike reading from stdin, \
+                leaves stdin available for any confirmation dialogs.  A value of \"-\" reads paths from stdin explicitly.  \
+                Any paths specified at INPUT_FILES are also included.")
+                .display_order(2)
+        )
+        .arg(
+            Arg::new("FOLLOW_STDIN")
+                .long("follow-stdin")
+                .help("read requested paths from stdin continuously, one per line, and display the results for each batch of paths as it arrives, \
+                instead of waiting for stdin to close, so httm may be fed by a program like 'fswatch' or 'inotifywait'.  \
+                Mounts and datasets are parsed only once, at startup.  Errors for any path are printed as warnings, and httm continues until stdin is closed.  \
+                Only available in the default, and NUM_VERSIONS, display modes.")
+                .conflicts_with_all(&["INPUT_FILES", "FILES_FROM"])
+                .display_order(3)
+        )
         .arg(
             Arg::new("BROWSE")
                 .short('b')
                 .short_alias('i')
                 .long("browse")
                 .visible_alias("interactive")
-                .help("interactive browse and search a specified directory to display unique file versions.")
-                .display_order(2)
+                .help(Catalog::get("help-browse"))
+                .display_order(4)
         )
         .arg(
             Arg::new("SELECT")
                 .short('s')
                 .long("select")
-                .help("interactive browse and search a specified directory to display unique file versions.  Continue to another dialog to select a snapshot version to dump to stdout.")
+                .takes_value(true)
+                .default_missing_value("path")
+                .possible_values(["path", "contents"])
+                .min_values(0)
+                .require_equals(true)
+                .help(Catalog::get("help-select"))
                 .conflicts_with("RESTORE")
-                .display_order(3)
+                .display_order(5)
         )
         .arg(
             Arg::new("RESTORE")
@@ -171,7 +354,7 @@ fn parse_args() -> ArgMatches {
                 .long("restore")
                 .takes_value(true)
                 .default_missing_value("copy")
-                .possible_values(["copy", "copy-and-preserve", "overwrite", "yolo", "guard"])
+                .possible_values(["copy", "copy-and-preserve", "overwrite", "yolo", "guard", "clone"])
                 .min_values(0)
                 .require_equals(true)
                 .help("interactive browse and search a specified directory to display unique file versions.  Continue to another dialog to select a snapshot version to restore.  \
@@ -180,9 +363,21 @@ fn parse_args() -> ArgMatches {
                 Overwrite mode will attempt to preserve attributes, like the permissions/mode, timestamps, xattrs and ownership of the selected snapshot file version (this is and will likely remain a UNIX only feature).  \
                 In order to preserve such attributes in \"copy\" mode, specify the \"copy-and-preserve\" value.  User may also specify \"guard\".  \
                 Guard mode has the same semantics as \"overwrite\" but will attempt to take a precautionary snapshot before any overwrite action occurs.  \
-                Note: Guard mode is a ZFS only option.")
+                After a version is selected, a final dialog permits the user to choose a different mode for that selection alone, or to restore to some other path, \
+                with the mode specified here as the default.  \
+                User may also specify \"clone\", which, instead of copying, clones the snapshot of the version selected, via \"zfs clone\", \
+                and links the file within the clone into the place of a \"copy\", for very large files, like VM images, which would be slow to copy.  \
+                Each clone is recorded so that it may be destroyed later, see CLEANUP_CLONES.  \
+                Note: Guard and clone modes are ZFS only options.")
                 .conflicts_with("SELECT")
-                .display_order(4)
+                .display_order(6)
+        )
+        .arg(
+            Arg::new("PICK")
+                .long("pick")
+                .help(Catalog::get("help-pick"))
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "LAST_SNAP"])
+                .display_order(7)
         )
         .arg(
             Arg::new("DELETED")
@@ -195,18 +390,31 @@ fn parse_args() -> ArgMatches {
                 .min_values(0)
                 .require_equals(true)
                 .help("show deleted files in interactive modes.  In non-interactive modes, do a search for all files deleted from a specified directory. \
+                When only files are specified, any file which no longer exists is searched for among the files deleted from its parent directory, \
+                which may find versions of a file deleted from a directory since renamed (see FOLLOW_RENAMES).  \
                 This argument optionally takes a value.  The default behavior/value is \"all\".  \
                 If \"only\" is specified, then, in the interactive modes, non-deleted files will be excluded from the search. \
                 If \"single\" is specified, then, deleted files behind deleted directories, (that is -- files with a depth greater than one) will be ignored.")
-                .display_order(5)
+                .display_order(8)
+        )
+        .arg(
+            Arg::new("FOLLOW_RENAMES")
+                .long("follow-renames")
+                .help("during deleted searches, also search for the deleted files of a directory under its previous names.  \
+                When a directory has been renamed, its snapshot versions may only exist under its old name, and a deleted search, by its current name, would miss them.  \
+                With this option, httm also searches, within each snapshot of the dataset, for the directory with the same inode as the live directory, at each level of its path.  \
+                Directories moved to a different parent directory are only followed when each parent can be found by the same means.  \
+                Inodes are not comparable across datasets, so replicated datasets and user defined aliases are searched only by name.")
+                .display_order(9)
         )
         .arg(
             Arg::new("RECURSIVE")
                 .short('R')
                 .long("recursive")
                 .conflicts_with_all(&["SNAPSHOT"])
-                .help("recurse into the selected directory to find more files. Only available in interactive and deleted file modes.")
-                .display_order(6)
+                .help("recurse into the selected directory to find more files. Only available in interactive and deleted file modes.  \
+                When specified with ROLL_FORWARD, also roll forward every descendant dataset which has a snapshot of the same name.")
+                .display_order(10)
         )
         .arg(
             Arg::new("ALT_REPLICATED")
@@ -214,9 +422,21 @@ fn parse_args() -> ArgMatches {
                 .long("alt-replicated")
                 .help("automatically discover locally replicated datasets and list their snapshots as well.  \
                 NOTE: Be certain such replicated datasets are mounted before use.  \
-                httm will silently ignore unmounted datasets in the interactive modes.")
+                httm will silently ignore unmounted datasets in the interactive modes.  \
+                In Select or Restore modes, when versions reside on more than one replica, \"REPLICA\" entries are shown, which, when selected, \
+                scope the versions displayed to only those which reside on that replica's dataset, such as a local pool rather than a backup pool.")
                 .conflicts_with_all(&["REMOTE_DIR", "LOCAL_DIR"])
-                .display_order(7)
+                .display_order(11)
+        )
+        .arg(
+            Arg::new("INFER_BACKUP_ALIASES")
+                .long("infer-backup-aliases")
+                .help("like ALT_REPLICATED, but also discover local ZFS datasets which were received, via zfs send and receive, from another local dataset, \
+                even when their names and mount points differ completely, such as when a backup pool is mounted elsewhere.  \
+                A dataset is inferred to be a backup of another when both share the GUID of at least one snapshot, as zfs send preserves snapshot GUIDs.  \
+                NOTE: Be certain such replicated datasets are mounted before use.")
+                .conflicts_with_all(&["REMOTE_DIR", "LOCAL_DIR"])
+                .display_order(12)
         )
         .arg(
             Arg::new("PREVIEW")
@@ -224,12 +444,55 @@ fn parse_args() -> ArgMatches {
                 .long("preview")
                 .help("user may specify a command to preview snapshots while in select view.  This argument optionally takes a value specifying the command to be executed.  \
                 The default value/command, if no command value specified, is a 'bowie' formatted 'diff'.  \
-                User defined commands must specify the snapshot file name \"{snap_file}\" and the live file name \"{live_file}\" within their shell command.")
+                When either version is a binary file, the default instead compares the versions' sizes, checksums, and file types, and displays a hexdump of any differing rows within their first bytes.  \
+                User defined commands must specify the snapshot file name \"{snap_file}\" and the live file name \"{live_file}\" within their shell command.  \
+                The value \"force\" also permits a preview in Browse mode, and in the browse view of any interactive mode, which compares each file to its newest snapshot version.  \
+                To keep browsing quick, that comparison is by metadata only, unless both versions are regular files of 1MiB or less, when their contents are compared too.  \
+                Otherwise, \"force\" behaves as the default value.")
                 .takes_value(true)
                 .min_values(0)
                 .require_equals(true)
                 .default_missing_value("default")
-                .display_order(8)
+                .display_order(13)
+        )
+        .arg(
+            Arg::new("PREVIEW_WINDOW")
+                .long("preview-window")
+                .takes_value(true)
+                .require_equals(true)
+                .help("the layout of the preview pane in the interactive views, as a colon delimited list of a position (\"up\", \"down\", \"left\", or \"right\"), \
+                a size (in lines or columns, or as a percentage of the terminal), and either \"wrap\" or \"nowrap\", and/or \"hidden\", to begin with the pane hidden.  \
+                For instance, \"--preview-window=right:60%:wrap\" may suit wide diffs better than the default, \"up:50%\".  \
+                Whatever the layout, alt+p toggles the preview pane.  You may also set via the environment variable HTTM_PREVIEW_WINDOW.")
+                .display_order(14)
+        )
+        .arg(
+            Arg::new("COMPRESS")
+                .long("compress")
+                .takes_value(true)
+                .default_missing_value("zstd")
+                .possible_values(["zstd", "gzip"])
+                .min_values(0)
+                .require_equals(true)
+                .help("when the contents of the snapshot version selected are dumped to stdout, as with \"--select=contents\", compress the contents as they are written, \
+                so that huge versions may be piped efficiently to remote hosts or to files.  The default value is \"zstd\".  \
+                Compression is streamed, so the version need never be held in memory in its entirety.")
+                .conflicts_with("CLIPBOARD")
+                .display_order(15)
+        )
+        .arg(
+            Arg::new("CLIPBOARD")
+                .long("clipboard")
+                .takes_value(true)
+                .default_missing_value("path")
+                .possible_values(["path", "contents"])
+                .min_values(0)
+                .require_equals(true)
+                .help("in select mode, copy the selected snapshot version to the system clipboard, instead of printing it to stdout.  \
+                The default value, \"path\", copies the snapshot path, whereas \"contents\" copies the contents of the snapshot file.  \
+                Whether or not this option is specified, ctrl+y within any select view copies the highlighted version, and returns to the view.  \
+                httm tries wl-copy, xclip, xsel, and pbcopy, in that order, and, if none are available, falls back to an OSC 52 terminal escape sequence.")
+                .display_order(16)
         )
         .arg(
             Arg::new("UNIQUENESS")
@@ -245,15 +508,221 @@ fn parse_args() -> ArgMatches {
                 or a user can simply update the modify time via 'touch'.  If only this flag is specified, the \"contents\" option compares the actual file contents of file versions, if their sizes match, \
                 and overrides the default \"metadata\" behavior.  The \"contents\" option can be expensive, as the file versions need to be read back and compared, and should probably only be used for smaller files.  \
                 Given how expensive this operation can be, for larger files or files with many versions, \"contents\" option is not shown in Interactive browse mode, \
-                but after a selection is made, can be utilized in Select or Restore modes.  The \"all\" or \"no-filter\" option dumps all snapshot versions, and no attempt is made to determine if the file versions are distinct.")
-                .display_order(9)
+                but after a selection is made, can be utilized in Select or Restore modes.  The \"all\" or \"no-filter\" option dumps all snapshot versions, and no attempt is made to determine if the file versions are distinct.  \
+                Within the Select or Restore views, ctrl+u cycles the uniqueness used for the current selection, from \"metadata\", to \"contents\", to \"all\", and looks up its versions again.")
+                .display_order(17)
+        )
+        .arg(
+            Arg::new("SHOW_DUPLICATES")
+                .long("show-duplicates")
+                .help("when more than one snapshot holds an identical version of a file, httm displays only the version found in the newest such snapshot.  \
+                This flag also lists, beneath each version displayed, the paths of the identical versions which were omitted.  \
+                Duplicates are only listed in the default formatted output, and not for the \"all\" uniqueness, where no versions are omitted.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RAW", "ZEROS", "JSON", "RAW_FIELDS"])
+                .display_order(18)
+        )
+        .arg(
+            Arg::new("MTIME_TOLERANCE")
+                .long("mtime-tolerance")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DURATION")
+                .help("some backup and sync tools truncate timestamps to the second when rewriting files, which causes otherwise identical file versions to appear unique.  \
+                When comparing versions on the basis of metadata (the default \"metadata\" uniqueness), treat versions whose sizes match, and whose modify times differ by less than \
+                the duration specified, as the same version, like so: --mtime-tolerance=2s.  Units available are: ms, s, m, h, d, w, and y.  A bare number is understood as seconds.")
+                .display_order(19)
+        )
+        .arg(
+            Arg::new("MAX_RESULTS")
+                .long("max-results")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("N")
+                .help("files with thousands of snapshots can produce unwieldy output and slow interactive dialogs.  \
+                Limit the versions found for each file to only the N newest, like so: --max-results=50.  \
+                Snapshots are searched from newest to oldest, and the search stops once N versions are found, so that older snapshots need never be read.  \
+                In Select or Restore modes, a \"LOAD MORE\" entry is shown when there may be more versions, which, when selected, doubles the limit and searches again.")
+                .conflicts_with_all(&["NUM_VERSIONS", "LAST_SNAP"])
+                .display_order(20)
+        )
+        .arg(
+            Arg::new("OF_SNAP")
+                .long("of-snap")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("SNAPNAME")
+                .help("display only the version of each input file which resides in the snapshot named, like so: --of-snap=autosnap_2023-06-01_00:00:01_daily, \
+                whether or not that version is unique, for when you already know the snapshot you care about, perhaps from LIST_SNAPS.  \
+                The snapshot name is the name of the snapshot directory, for ZFS, the name after the '@', and for btrfs-snapper, the snapshot number.  \
+                When used with RESTORE, only that version is offered for restoration.")
+                .conflicts_with_all(&["NUM_VERSIONS", "LAST_SNAP", "MAX_RESULTS", "UNIQUENESS"])
+                .display_order(21)
+        )
+        .arg(
+            Arg::new("FOLLOW_BINDS")
+                .long("follow-binds")
+                .help("paths beneath bind mounts, as are common in containers and systemd units with ProtectHome, have no snapshot directory of their own.  \
+                Follow each such bind mount back to its source dataset, by way of /proc/self/mountinfo, and look up versions of the same path beneath the source dataset's mount.  \
+                On FreeBSD, nullfs mounts, as are common in jails, are followed back to their source dirs in the same way.  \
+                Only available on Linux and FreeBSD, and only where the source dataset is also mounted.")
+                .display_order(22)
+        )
+        .arg(
+            Arg::new("PROFILE")
+                .long("profile")
+                .help("report, to stderr, the time spent in each phase of this invocation: mount parsing, lookup, uniqueness filtering, and display.  \
+                Useful for diagnosing slow setups, such as aliases to network filesystems, and for reporting actionable numbers.  \
+                Uniqueness filtering is performed for each file in parallel, so its time is summed across threads, and is also included in the lookup time.")
+                .display_order(23)
+        )
+        .arg(
+            Arg::new("REPORT")
+                .long("report")
+                .help("at the end of an interactive session, report, to stderr, a summary of the actions taken: the files viewed, the versions restored, the bytes copied, and the snapshots created.  \
+                Each summary is also appended, as a single line, to a local log, \"report.log\", for the user's own records and audits.  \
+                The log is saved to \"$HTTM_STATE_DIR\", \"$XDG_STATE_HOME/httm\", or \"$HOME/.local/state/httm\", in that order of preference.  \
+                Nothing is ever sent over the network.")
+                .display_order(24)
+        )
+        .arg(
+            Arg::new("PRE_SNAP_HOOK")
+                .long("pre-snap-hook")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("COMMAND")
+                .help("a shell command to execute before httm takes any snapshot, whether requested via SNAPSHOT, or taken as a precaution before a restore or roll forward, \
+                for instance, to quiesce a database.  The names of the snapshots to be taken are passed, space separated, in the HTTM_SNAPSHOTS environment variable, \
+                and the event, here \"pre-snap\", in the HTTM_HOOK_EVENT environment variable.  You may also set via the HTTM_PRE_SNAP_HOOK environment variable.")
+                .display_order(25)
+        )
+        .arg(
+            Arg::new("POST_SNAP_HOOK")
+                .long("post-snap-hook")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("COMMAND")
+                .help("a shell command to execute after httm takes any snapshot, for instance, to resume a database quiesced by PRE_SNAP_HOOK.  \
+                Once PRE_SNAP_HOOK has succeeded, this hook is always executed, even when the snapshot fails, and whether it succeeded, \"success\" or \"failure\", \
+                is passed in the HTTM_SNAPSHOT_STATUS environment variable.  You may also set via the HTTM_POST_SNAP_HOOK environment variable.")
+                .display_order(26)
+        )
+        .arg(
+            Arg::new("PRE_DESTROY_HOOK")
+                .long("pre-destroy-hook")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("COMMAND")
+                .help("a shell command to execute before httm destroys any snapshot via PRUNE.  \
+                You may also set via the HTTM_PRE_DESTROY_HOOK environment variable.")
+                .display_order(27)
+        )
+        .arg(
+            Arg::new("HOOK_TIMEOUT")
+                .long("hook-timeout")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("DURATION")
+                .help("the time allowed for any hook to complete, after which the hook is killed and considered to have failed.  \
+                Units available are: ms, s, m, h, d, w, and y.  The default is 30s.")
+                .display_order(28)
+        )
+        .arg(
+            Arg::new("HOOK_FAILURE")
+                .long("hook-failure")
+                .takes_value(true)
+                .require_equals(true)
+                .possible_values(["abort", "continue"])
+                .help("what httm should do when a hook exits non-zero, or times out.  The default, \"abort\", stops before the snapshot is taken or destroyed.  \
+                \"continue\" prints a warning and proceeds.")
+                .display_order(29)
+        )
+        .arg(
+            Arg::new("RECOVER_DELETED")
+                .long("recover-deleted")
+                .help("a guided recovery of deleted files, for the most common disaster.  Recursively search the directory specified (or the working directory) for deleted files, \
+                present each with the date of its most recent snapshot version, and let the user select, with shift+tab, those to be restored.  \
+                After the user consents, the most recent snapshot version of each file selected is restored to its original location, or to the directory specified at RESTORE_TO.  \
+                Files are never overwritten.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "PICK", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "MOST_CHANGED", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY"])
+                .display_order(30)
+        )
+        .arg(
+            Arg::new("INDEX")
+                .long("index")
+                .help("build an index of the deleted files beneath the directory specified (or the working directory), and save it to the user's cache directory, \
+                so that later deleted searches, of huge datasets, need not scan every snapshot again.  \
+                Deleted searches consult an index automatically, for each directory, only while it is fresh, that is -- while no snapshots of its dataset have been taken or destroyed, \
+                and the directory itself has not been modified.  Otherwise, deleted searches fall back to scanning snapshots, as before.  \
+                Indexing is well suited to running periodically, after snapshots are taken, for instance, as a cron job or a POST_SNAP_HOOK.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "PICK", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "MOST_CHANGED", "RECOVER_DELETED", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY"])
+                .display_order(31)
+        )
+        .arg(
+            Arg::new("RESUME")
+                .long("resume")
+                .help("continue an interrupted restore, recovery, or roll forward copy of a very large file, from where it left off, instead of from the start.  \
+                While copying any file larger than 256MiB, httm records a checksum of each 64MiB segment written, in a sidecar file beside the destination (with the suffix \".httm_resume\"), which is removed once the copy completes.  \
+                When resuming, each segment recorded is verified against the destination as it is now, and the copy continues after the last segment which matches.  \
+                If the source version has changed since the copy was interrupted, the copy begins again from the start.")
+                .display_order(32)
+        )
+        .arg(
+            Arg::new("RESTORE_TO")
+                .long("restore-to")
+                .takes_value(true)
+                .require_equals(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .requires("RECOVER_DELETED")
+                .help("in RECOVER_DELETED mode, restore files beneath the directory specified, at the same paths relative to the directory searched, instead of to their original locations.")
+                .display_order(33)
+        )
+        .arg(
+            Arg::new("EXCLUDE_PROPERTY")
+                .long("exclude-property")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("")
+                .value_name("PROPERTY=VALUE")
+                .help("ignore any ZFS dataset upon which the user property specified is set, locally or by inheritance, to the value specified, \
+                so that, for instance, scratch datasets do not surface snapshot noise in searches.  This argument optionally takes a value.  \
+                The default value is \"com.sun:auto-snapshot=false\", the property most snapshot tools already honor.  \
+                Administrators may also set via the HTTM_EXCLUDE_PROPERTY environment variable, like so: HTTM_EXCLUDE_PROPERTY=\"org.example:httm=off\".")
+                .display_order(34)
         )
         .arg(
             Arg::new("EXACT")
                 .short('e')
                 .long("exact")
                 .help("use exact pattern matching for searches in the interactive modes (in contrast to the default fuzzy searching).")
-                .display_order(10)
+                .display_order(35)
+        )
+        .arg(
+            Arg::new("REMEMBER_QUERY")
+                .long("remember-query")
+                .help("in the interactive modes, remember the last search query entered for each directory browsed, across sessions, \
+                and begin the next session in that directory with that query.  Previous queries are also available via the query history keys (ctrl+p and ctrl+n).  \
+                Queries are saved to \"$HTTM_CACHE_DIR\", \"$XDG_CACHE_HOME/httm\", or \"$HOME/.cache/httm\", in that order of preference.  \
+                Within a single session, queries are always remembered.  You may also set via the environment variable HTTM_REMEMBER_QUERY.")
+                .display_order(36)
+        )
+        .arg(
+            Arg::new("QUERY")
+                .long("query")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("QUERY")
+                .help("in the interactive modes, begin the browse view with the search query given, instead of an empty query, \
+                or of any query remembered via REMEMBER_QUERY, like so: httm -r --query=\"nginx.conf\" /etc")
+                .display_order(37)
+        )
+        .arg(
+            Arg::new("AUTO_SELECT")
+                .long("auto-select")
+                .help("in the interactive modes, skip the browse view entirely, if, once the search of the directory browsed is complete, \
+                exactly one file matches the query, and select that file, as if the user had.  Most useful with QUERY, \
+                for semi-automated flows, like in runbooks.")
+                .display_order(38)
         )
         .arg(
             Arg::new("SNAPSHOT")
@@ -262,13 +731,36 @@ fn parse_args() -> ArgMatches {
                 .takes_value(true)
                 .min_values(0)
                 .require_equals(true)
-                .default_missing_value("httmSnapFileMount")
+                .default_missing_value(DEFAULT_SNAPSHOT_SUFFIX)
                 .visible_aliases(&["snap-file", "snapshot", "snap-file-mount"])
                 .help("snapshot a file/s most immediate mount.  \
                 This argument optionally takes a value for a snapshot suffix.  The default suffix is 'httmSnapFileMount'.  \
-                Note: This is a ZFS only option which requires either superuser or 'zfs allow' privileges.")
+                The suffix may contain the placeholders \"{date}\", \"{user}\" and \"{hostname}\", which are expanded to \
+                the snapshot timestamp, the user name (of the user who invoked sudo, if applicable), and the short host name, \
+                like so: --snap=\"pre_deploy_{date}_{user}\".  \
+                Note: This is a ZFS only option which requires either superuser or 'zfs allow' privileges.  \
+                Files within a MAP_ALIASES alias may only be snapshot-ed if a REMOTE_SNAP_COMMAND is specified for that alias.")
                 .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
-                .display_order(11)
+                .display_order(39)
+        )
+        .arg(
+            Arg::new("SHOW_DATASET")
+                .long("show-dataset")
+                .help("append to each version, in the default display, the dataset upon which it resides, and, for snapshot versions, the name of its snapshot, \
+                like \"[rpool/home@snap_1]\", so that one may know whether a version lives on the local pool or on a backup replica before restoring.  \
+                Especially useful with ALT_REPLICATED or MAP_ALIASES.  In JSON output, each version includes a \"dataset\" field instead.  \
+                For the raw modes, see the \"dataset\" value of RAW_FIELDS.")
+                .conflicts_with_all(&["RAW", "ZEROS", "RAW_FIELDS", "NUM_VERSIONS"])
+                .display_order(40)
+        )
+        .arg(
+            Arg::new("ANNOTATE")
+                .long("annotate")
+                .help("annotate each ZFS snapshot version, in the default display, and in LIST_SNAPS mode, with the 'zpool history' entry \
+                of the command which created its snapshot, including the user and the host, to help answer what job created a snapshot.  \
+                Reading the pool history may require superuser or 'zfs allow' privileges.  Annotations are only available in the default formatted output, and not in the raw, zero, not so pretty, or JSON output modes.")
+                .conflicts_with_all(&["RAW", "ZEROS", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY"])
+                .display_order(41)
         )
         .arg(
             Arg::new("LIST_SNAPS")
@@ -283,9 +775,26 @@ fn parse_args() -> ArgMatches {
                 The user may also omit the last \"n\" snapshots from any list.  By appending a comma, this argument also filters those snapshots which contain the specified pattern/s.  \
                 A value of \"5,prep_Apt\" would return the snapshot names of only the last 5 (at most) of all snapshot versions which contain \"prep_Apt\".  \
                 The value \"native\" will restrict selection to only 'httm' native snapshot suffix values, like \"httmSnapFileMount\" and \"ounceSnapFileMount\".  \
+                An age expression may also be appended, which limits the snapshots returned to those created longer ago than the age given.  \
+                A value of \"0,older:90d\" would return the names of only those snapshots older than 90 days, and, with PRUNE, destroy only those snapshots.  \
                 Note: This is a ZFS only option.")
                 .conflicts_with_all(&["BROWSE", "RESTORE"])
-                .display_order(12)
+                .display_order(42)
+        )
+        .arg(
+            Arg::new("LIST_SNAPS_ALL")
+                .long("list-snaps-all")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .multiple_values(false)
+                .help("display all snapshots upon every local dataset httm can find, along with each snapshot's creation time and space used, no input files required.  \
+                This argument optionally takes a value.  By default, this argument will return all available snapshots.  \
+                A value, like \"prep_Apt\", will return only those snapshots whose names contain the specified pattern.  \
+                In RAW or ZEROS mode, only snapshot names are printed, such that the output may be used as input elsewhere.  \
+                Note: This is a ZFS only option.")
+                .conflicts_with_all(&["INPUT_FILES", "FILES_FROM", "BROWSE", "SELECT", "RESTORE", "LIST_SNAPS", "PRUNE", "ROLL_FORWARD", "JSON", "RAW_FIELDS"])
+                .display_order(43)
         )
         .arg(
             Arg::new("ROLL_FORWARD")
@@ -299,9 +808,11 @@ fn parse_args() -> ArgMatches {
                 httm will copy only files and their attributes that have changed since a specified snapshot, from that snapshot, to its live dataset.  \
                 httm will also take two precautionary snapshots, one before and one after the copy.  \
                 Should the roll forward fail for any reason, httm will roll back to the pre-execution state.  \
+                If RECURSIVE is also specified, like 'zfs rollback -r', but non-destructive, httm will roll forward the named snapshot on the dataset specified, \
+                and on every descendant dataset with a snapshot of the same name.  Should any dataset fail, all datasets are rolled back to their pre-execution states.  \
                 Caveats: This is a ZFS only option which requires super user privileges.")
                 .conflicts_with_all(&["BROWSE", "RESTORE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
-                .display_order(13)
+                .display_order(44)
         )
         .arg(
             Arg::new("PRUNE")
@@ -315,7 +826,7 @@ fn parse_args() -> ArgMatches {
                 Note: This is a ZFS only option.")
                 .conflicts_with_all(&["BROWSE", "RESTORE", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
                 .requires("LIST_SNAPS")
-                .display_order(13)
+                .display_order(45)
         )
         .arg(
             Arg::new("FILE_MOUNT")
@@ -334,7 +845,7 @@ fn parse_args() -> ArgMatches {
                 \"source\" or \"device\" or \"dataset\", return the underlying dataset/device of the mount, and, \
                 \"relative-path\" or \"relative\", return the path relative to the underlying dataset/device of the mount.")
                 .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE"])
-                .display_order(14)
+                .display_order(46)
         )
         .arg(
             Arg::new("LAST_SNAP")
@@ -351,9 +862,37 @@ fn parse_args() -> ArgMatches {
                 \"ditto\", return only last snaps which are the same as the live file version, \
                 \"no-ditto-exclusive\", return only a last snap which is not the same as the live version (argument \"--no-ditto\" is an alias for this option), \
                 \"no-ditto-inclusive\", return a last snap which is not the same as the live version, or should none exist, return the live file, and, \
-                \"none\" or \"without\", return the live file only for those files without a last snapshot.")
+                \"none\" or \"without\", return the live file only for those files without a last snapshot.  \
+                When the input is a directory, httm compares everything beneath the directory, rather than the directory entry alone, \
+                such that \"no-ditto\" returns the most recent snapshot in which anything beneath the directory differs from live.  \
+                Note: this requires a walk of the directory within each snapshot and may be slow for large trees.")
                 .conflicts_with_all(&["NUM_VERSIONS", "SNAPSHOT", "FILE_MOUNT", "ALT_REPLICATED", "REMOTE_DIR", "LOCAL_DIR"])
-                .display_order(15)
+                .display_order(47)
+        )
+        .arg(
+            Arg::new("GROUP_BY")
+                .long("group-by")
+                .takes_value(true)
+                .possible_values(["file", "snapshot"])
+                .require_equals(true)
+                .help("when used with LAST_SNAP, select how the last snapshot versions of many input files are printed.  \
+                Possible values are: \
+                \"file\", print each input file with its last snapshot version, this is the default behavior/value, and, \
+                \"snapshot\", print each snapshot, as \"dataset@snapshot\", with the input files whose last snapshot version resides upon that snapshot.  \
+                Useful for choosing the right snapshot for a dataset-level rollback or clone.")
+                .requires("LAST_SNAP")
+                .display_order(48)
+        )
+        .arg(
+            Arg::new("COMPARE_AGAINST")
+                .long("compare-against")
+                .takes_value(true)
+                .require_equals(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .help("compare snapshot versions against the file specified, instead of the live version of the input file, \
+                when determining which versions are the same (\"ditto\") for the purposes of OMIT_DITTO and LAST_SNAP.  \
+                Useful when the live file has been moved, or to compare a restored copy against the history of a file.")
+                .display_order(49)
         )
         .arg(
             Arg::new("RAW")
@@ -362,7 +901,7 @@ fn parse_args() -> ArgMatches {
                 .visible_alias("newline")
                 .help("display the snapshot locations only, without extraneous information, delimited by a NEWLINE character.")
                 .conflicts_with_all(&["ZEROS", "NOT_SO_PRETTY"])
-                .display_order(16)
+                .display_order(50)
         )
         .arg(
             Arg::new("ZEROS")
@@ -370,7 +909,23 @@ fn parse_args() -> ArgMatches {
                 .long("zero")
                 .help("display the snapshot locations only, without extraneous information, delimited by a NULL character.")
                 .conflicts_with_all(&["RAW", "NOT_SO_PRETTY"])
-                .display_order(17)
+                .display_order(51)
+        )
+        .arg(
+            Arg::new("RAW_FIELDS")
+                .long("raw-fields")
+                .takes_value(true)
+                .require_equals(true)
+                .use_value_delimiter(true)
+                .possible_values(["path", "mtime", "size", "snap", "dataset"])
+                .help("in addition to the snapshot locations, display the requested fields of each version, delimited by a TAB character.  \
+                This argument requires a value.  Multiple fields may be specified delimited by a comma, ',', and are printed in the order given.  Possible values are: \
+                \"path\", the location of the version, \"mtime\", the modify time of the version in seconds since the UNIX epoch, \
+                \"size\", the size of the version in bytes, \"snap\", the name of the snapshot upon which the version resides (empty for live versions), \
+                and \"dataset\", the dataset upon which the version resides, and its snapshot, if any, as with SHOW_DATASET.  \
+                Each record remains delimited by a NEWLINE character, or by a NULL character if ZEROS is also specified.")
+                .conflicts_with_all(&["NOT_SO_PRETTY", "JSON", "NUM_VERSIONS", "LAST_SNAP"])
+                .display_order(52)
         )
         .arg(
             Arg::new("NOT_SO_PRETTY")
@@ -378,35 +933,58 @@ fn parse_args() -> ArgMatches {
                 .visible_aliases(&["tabs", "plain-jane", "not-pretty"])
                 .help("display the ordinary output, but tab delimited, without any pretty border lines.")
                 .conflicts_with_all(&["RAW", "ZEROS"])
-                .display_order(18)
+                .display_order(53)
         )
         .arg(
             Arg::new("JSON")
                 .long("json")
-                .help("display the ordinary output, but as formatted JSON.")
+                .help("display the ordinary output, but as formatted JSON.  Every JSON response is an object with a \"schema_version\" field, \
+                and one other field, named for the kind of response, such as \"versions\", \"mounts\", \"snapshot_names\", \"num_versions\", or \"info\", which holds the output itself.  \
+                Within a schema version, fields may be added, but none will be removed, renamed, or change type.")
                 .conflicts_with_all(&["SELECT", "RESTORE"])
-                .display_order(19)
+                .display_order(54)
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .takes_value(true)
+                .require_equals(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .value_name("FILE")
+                .help("write httm's output to the file specified, rather than to stdout, like so: --output=\"/var/log/httm_versions.json\".  \
+                The output is written to a temporary file in the same directory, which only replaces the file specified once httm has completed successfully, \
+                so a reader never sees a partial output, and a failed run leaves any previous output as it was.  \
+                Unlike shell redirection, NULL delimited output (ZEROS) is written exactly as it is, and no shell is required, as from a cron job.")
+                .conflicts_with_all(&["RESTORE", "PRUNE", "RPC"])
+                .display_order(55)
+        )
+        .arg(
+            Arg::new("APPEND")
+                .long("append")
+                .requires("OUTPUT")
+                .help("append to the OUTPUT file specified, rather than replacing its contents.  The append remains atomic, as httm appends to a copy of the existing file.")
+                .display_order(56)
         )
         .arg(
             Arg::new("OMIT_DITTO")
                 .long("omit-ditto")
                 .help("omit display of the snapshot version which may be identical to the live version (`httm` ordinarily displays all snapshot versions and the live version).")
                 .conflicts_with_all(&["NUM_VERSIONS"])
-                .display_order(20)
+                .display_order(57)
         )
         .arg(
             Arg::new("NO_FILTER")
                 .long("no-filter")
                 .help("by default, in the interactive modes, httm will filter out files residing upon non-supported datasets (like ext4, tmpfs, procfs, sysfs, or devtmpfs, etc.), and within any \"common\" snapshot paths.  \
                 Here, one may select to disable such filtering.  httm, however, will always show the input path, and results from behind any input path when that is the path being searched.")
-                .display_order(21)
+                .display_order(58)
         )
         .arg(
             Arg::new("FILTER_HIDDEN")
                 .long("no-hidden")
                 .aliases(&["no-hide", "nohide", "filter-hidden"])
                 .help("never show information regarding hidden files and directories (those that start with a \'.\') in the recursive or interactive modes.")
-                .display_order(22)
+                .display_order(59)
         )
         .arg(
             Arg::new("ONE_FILESYSTEM")
@@ -414,21 +992,21 @@ fn parse_args() -> ArgMatches {
                 .aliases(&["same-filesystem", "single-filesystem", "one-fs", "onefs"])
                 .requires("RECURSIVE")
                 .help("limit recursive search to file and directories on the same filesystem/device as the target directory.")
-                .display_order(23)
+                .display_order(60)
         )
         .arg(
             Arg::new("NO_TRAVERSE")
                 .long("no-traverse")
                 .help("in recursive mode, don't traverse symlinks.  Although httm does its best to prevent searching pathologically recursive symlink-ed paths, \
                 here, you may disable symlink traversal completely.  NOTE: httm will never traverse symlinks when a requested recursive search is on the root/base directory (\"/\").")
-                .display_order(24)
+                .display_order(61)
         )
         .arg(
             Arg::new("NO_LIVE")
                 .long("no-live")
                 .visible_aliases(&["dead", "disco"])
                 .help("only display information concerning snapshot versions (display no information regarding live versions of files or directories).")
-                .display_order(25)
+                .display_order(62)
         )
         .arg(
             Arg::new("NO_SNAP")
@@ -438,7 +1016,7 @@ fn parse_args() -> ArgMatches {
                 Useful for finding the \"files that once were\" and displaying only those pseudo-live/zombie files.")
                 .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "SNAPSHOT", "LAST_SNAP", "NOT_SO_PRETTY"])
                 .requires("DELETED")
-                .display_order(26)
+                .display_order(63)
         )
         .arg(
             Arg::new("MAP_ALIASES")
@@ -453,7 +1031,77 @@ fn parse_args() -> ArgMatches {
                 .use_value_delimiter(true)
                 .takes_value(true)
                 .value_parser(clap::builder::ValueParser::os_string())
-                .display_order(27)
+                .display_order(64)
+        )
+        .arg(
+            Arg::new("REMOTE_SNAP_COMMAND")
+                .long("remote-snap-command")
+                .help("specify a command which takes a snapshot of the dataset behind an alias, for use with SNAPSHOT, \
+                when that alias's remote dir is a share of a dataset on another machine (eg. a NAS).  \
+                Such a value is delimited by a colon, ':', and is specified in the form <LOCAL_DIR>:<COMMAND>, \
+                where LOCAL_DIR is the local dir of an alias and COMMAND is executed by the shell, \
+                after the placeholder \"{snap_name}\" is replaced by the name of the snapshot to be taken \
+                (eg. --remote-snap-command \"/Users/<User Name>:ssh nas zfs snapshot tank/home@{snap_name}\").  \
+                This option may be given once for each alias.  \
+                You may also set a single command via the environment variable HTTM_REMOTE_SNAP_COMMAND.")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .display_order(65)
+        )
+        .arg(
+            Arg::new("DATASET_HINT")
+                .long("dataset-hint")
+                .value_name("DATASET:MOUNT")
+                .help("manually specify that a ZFS dataset is mounted at a path, for when the mount table is misleading, \
+                such as inside a container, whose mount namespace may only describe a bind mount, or an overlay.  \
+                The mount specified must be where the root of the dataset, and its \".zfs/snapshot\" directory, is visible from within the namespace.  \
+                This option requires a value.  Such a value is delimited by a colon, ':', and is specified in the form <DATASET>:<MOUNT> \
+                (eg. --dataset-hint pool/data:/data).  Multiple hints may be specified delimited by a comma, ','.  \
+                You may also set via the environment variable HTTM_DATASET_HINTS.")
+                .use_value_delimiter(true)
+                .takes_value(true)
+                .display_order(66)
+        )
+        .arg(
+            Arg::new("ONLY_DATASET")
+                .long("only-dataset")
+                .visible_alias("mnt-filter")
+                .value_name("DATASET|MOUNT")
+                .help("restrict every search to the datasets specified, by dataset name or by mount point \
+                (eg. --only-dataset pool/home,/srv).  Every other dataset is ignored, as if it could not be snapshotted, \
+                which, on systems with many pools, spares httm the noise and latency of discovering snapshots elsewhere.  \
+                This option requires a value.  Multiple datasets may be specified delimited by a comma, ','.  \
+                You may also set via the environment variable HTTM_ONLY_DATASETS.")
+                .use_value_delimiter(true)
+                .takes_value(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .display_order(67)
+        )
+        .arg(
+            Arg::new("REMOTE_LATENCY")
+                .long("remote-latency")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("on")
+                .possible_values(["on", "off"])
+                .help("when a remote dir of an alias resides on a network filesystem, like sshfs, every metadata call may require a round trip over a slow link.  \
+                In this \"high-latency mode\", httm batches the metadata calls made for each directory browsed, looks ahead to find the versions of more files before they are displayed, \
+                and disables \"contents\" uniqueness for the interactive session, to keep the interactive views usable.  \
+                httm will attempt to detect whether such a network filesystem is in use, but the user may also specify \"on\", the default value, or \"off\", to override detection.")
+                .display_order(68)
+        )
+        .arg(
+            Arg::new("ROOT")
+                .long("root")
+                .takes_value(true)
+                .require_equals(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .help("treat the directory specified as the root directory, \"/\", such as when inspecting a container or a system image mounted elsewhere.  \
+                Absolute input paths, and the paths given to MAP_ALIASES, REMOTE_DIR and LOCAL_DIR, are resolved relative to this directory, \
+                symlinks are resolved as if this directory were \"/\", and mounts outside of this directory are ignored.")
+                .display_order(69)
         )
         .arg(
             Arg::new("NUM_VERSIONS")
@@ -462,60 +1110,422 @@ fn parse_args() -> ArgMatches {
                 .possible_values(["all", "graph", "single", "single-no-snap", "single-with-snap", "multiple"])
                 .min_values(0)
                 .require_equals(true)
-                .help("detect and display the number of unique versions available (e.g. one, \"1\", \
-                version is available if either a snapshot version exists, and is identical to live version, or only a live version exists).  \
-                This argument optionally takes a value.  The default value, \"all\", will print the filename and number of versions, \
-                \"graph\" will print the filename and a line of characters representing the number of versions, \
-                \"single\" will print only filenames which only have one version, \
-                (and \"single-no-snap\" will print those without a snap taken, and \"single-with-snap\" will print those with a snap taken), \
-                and \"multiple\" will print only filenames which only have multiple versions.")
-                .conflicts_with_all(&["LAST_SNAP", "BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "NOT_SO_PRETTY", "NO_LIVE", "NO_SNAP", "OMIT_DITTO", "RAW", "ZEROS"])
-                .display_order(28)
+                .help("detect and display the number of unique versions available (e.g. one, \"1\", \
+                version is available if either a snapshot version exists, and is identical to live version, or only a live version exists).  \
+                This argument optionally takes a value.  The default value, \"all\", will print the filename and number of versions, \
+                \"graph\" will print the filename and a line of characters representing the number of versions, \
+                \"single\" will print only filenames which only have one version, \
+                (and \"single-no-snap\" will print those without a snap taken, and \"single-with-snap\" will print those with a snap taken), \
+                and \"multiple\" will print only filenames which only have multiple versions.  \
+                When JSON is also specified, httm prints an array of records, each containing the path, the number of versions, whether any snapshot version exists, \
+                and whether the last snapshot version is identical to the live version, for those paths which the value specified would print.  \
+                Note: the \"graph\" value, when printed as JSON, emits these underlying counts.")
+                .conflicts_with_all(&["LAST_SNAP", "BROWSE", "SELECT", "RESTORE", "RECURSIVE", "SNAPSHOT", "NOT_SO_PRETTY", "NO_LIVE", "NO_SNAP", "OMIT_DITTO", "RAW", "ZEROS"])
+                .display_order(70)
+        )
+        .arg(
+            Arg::new("CACHE")
+                .long("cache")
+                .requires("NUM_VERSIONS")
+                .help("used with NUM_VERSIONS, remember the versions found for each file, and reuse them, without searching the snapshots again, \
+                so long as neither the file, nor the set of snapshots of its dataset, nor the options which determine which versions are found, have changed.  \
+                Useful for repeat audits of large directories.  The cache is saved to \"$HTTM_CACHE_DIR\", \"$XDG_CACHE_HOME/httm\", or \"$HOME/.cache/httm\", in that order of preference.  \
+                You may also enable via the environment variable HTTM_CACHE.")
+                .display_order(71)
+        )
+        .arg(
+            Arg::new("NO_CACHE")
+                .long("no-cache")
+                .conflicts_with("CACHE")
+                .help("disable the NUM_VERSIONS cache, even if enabled via the environment variable HTTM_CACHE.")
+                .display_order(72)
+        )
+        .arg(
+            Arg::new("COUNT")
+                .long("count")
+                .help("display only the number of unique versions available for each input file, one per line, in the order the input files are sorted.  \
+                Much faster than NUM_VERSIONS, as httm does no more work than is necessary to count, and useful for simple \"is there any history?\" checks, as in a shell prompt.  \
+                Unless \"contents\" is specified at UNIQUENESS, versions are only compared by metadata, and file contents are never read.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY", "OMIT_DITTO", "NO_LIVE", "NO_SNAP"])
+                .display_order(73)
+        )
+        .arg(
+            Arg::new("INFO")
+                .long("info")
+                .help("display a summary of each input file: the dataset upon which it resides, that dataset's filesystem type and snapshot directory, \
+                the number of snapshots of that dataset, the number of unique versions of the file, the dates of its oldest and newest versions, \
+                and whether the live version is identical to the newest snapshot version.  Specify JSON for the same summary as JSON.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "PICK", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "RAW_FIELDS", "NOT_SO_PRETTY", "OMIT_DITTO", "NO_LIVE", "NO_SNAP"])
+                .display_order(74)
+        )
+        .arg(
+            Arg::new("ASSERT_SNAPSHOTTED")
+                .long("assert-snapshotted")
+                .help("exit with an error unless every input file is contained within at least one snapshot, and, if WITHIN is specified, \
+                within at least one snapshot taken no longer ago than WITHIN.  The path of each file which fails is printed, one per line.  \
+                Intended as a check before deployments or other risky changes, to ensure that the files to be changed have been captured by a snapshot.  \
+                When a snapshot was taken is determined from ZFS snapshot creation times, and from snapper's info files, \
+                but, for other snapshots, falls back to the modify time of the version upon the snapshot.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "PICK", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "INFO", "JSON", "RAW_FIELDS", "OMIT_DITTO", "NO_LIVE", "NO_SNAP"])
+                .display_order(75)
+        )
+        .arg(
+            Arg::new("WITHIN")
+                .long("within")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("ASSERT_SNAPSHOTTED")
+                .help("used with ASSERT_SNAPSHOTTED, the age, like \"12h\" or \"1d\", within which a snapshot containing each file must have been taken.")
+                .display_order(76)
+        )
+        .arg(
+            Arg::new("DRIFT")
+                .long("drift")
+                .help("recursively compare the directories specified (or the working directory) against their counterparts upon the newest snapshot of each dataset, \
+                and display each file which has been added, modified or deleted since that snapshot was taken, followed by a count of each.  \
+                A directory added or deleted is displayed once, and its contents are not searched.  \
+                Files are compared on the basis of size and modify time, unless UNIQUENESS is \"contents\", in which case the contents of files of the same size are also compared.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "MOST_CHANGED", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY", "OMIT_DITTO", "NO_LIVE", "NO_SNAP"])
+                .display_order(77)
+        )
+        .arg(
+            Arg::new("RPC")
+                .long("rpc")
+                .help("serve requests, for versions and for restores, as JSON-RPC 2.0 over stdin and stdout, one request or response per line, until stdin is closed, \
+                so that programs not written in Rust, like file manager plugins, may integrate with httm.  \
+                The method \"list_versions\" takes the parameter \"path\", and returns the live version and its versions, each with the name of its snapshot.  \
+                The method \"restore\" takes the parameters \"path\", \"snap\", either a snapshot name or the path of a version, and \"mode\", any value RESTORE accepts, \"copy\" by default.  \
+                An overwrite of a live version newer than the snapshot version is refused, unless the boolean parameter \"force\" is true, or FORCE is specified.  \
+                Unlike interactive restore, a copy is restored beside the live version, and no consent is requested.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "MOST_CHANGED", "DRIFT", "JSON", "FOLLOW_STDIN", "FILES_FROM"])
+                .display_order(78)
+        )
+        .arg(
+            Arg::new("CLEANUP_CLONES")
+                .long("cleanup-clones")
+                .help("destroy every clone created by RESTORE in \"clone\" mode, and remove the links into each, and then exit.  \
+                Clones are recorded in the user's state directory (\"$HTTM_STATE_DIR\", \"$XDG_STATE_HOME/httm\", or \"$HOME/.local/state/httm\").")
+                .exclusive(true)
+                .display_order(79)
+        )
+        .arg(
+            Arg::new("MOST_CHANGED")
+                .long("most-changed")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("versions")
+                .possible_values(["versions", "bytes"])
+                .help("recursively search the directories specified (or the working directory), and display those files which have changed the most, \
+                as a way of finding churn hotspots, and candidates for tighter snapshot retention.  \
+                This argument optionally takes a value.  The default value, \"versions\", ranks files by number of unique versions, and \
+                \"bytes\" ranks files by the sum of the sizes of each version after the first, an estimate of the bytes written across versions.  \
+                Uniqueness is determined as specified at UNIQUENESS.  The number of files displayed may be specified at TOP.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "GREP", "COUNT", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY", "OMIT_DITTO", "NO_LIVE", "NO_SNAP"])
+                .display_order(80)
+        )
+        .arg(
+            Arg::new("TOP")
+                .long("top")
+                .takes_value(true)
+                .require_equals(true)
+                .requires("MOST_CHANGED")
+                .help("the number of files to display in MOST_CHANGED mode.  The default is 10.")
+                .display_order(81)
+        )
+        .arg(
+            Arg::new("GREP")
+                .long("grep")
+                .takes_value(true)
+                .require_equals(true)
+                .help("search each unique version of the input file/s for a regular expression pattern, and display the earliest version which contains the pattern, \
+                and the last version before it which does not, like a bisect, in order to determine when a change was introduced.  \
+                Binary files, and versions larger than 64 MiB, are skipped with a warning.  \
+                In RAW or ZEROS mode, only the path of the earliest version which contains the pattern is printed.")
+                .conflicts_with_all(&["BROWSE", "SELECT", "RESTORE", "RECURSIVE", "DELETED", "SNAPSHOT", "LIST_SNAPS", "ROLL_FORWARD", "PRUNE", "FILE_MOUNT", "LAST_SNAP", "NUM_VERSIONS", "JSON", "RAW_FIELDS", "NOT_SO_PRETTY", "NO_LIVE", "NO_SNAP"])
+                .display_order(82)
+        )
+        .arg(
+            Arg::new("PREVIEW_COMPARE")
+                .long("preview-compare")
+                .hide(true)
+                .help("used internally by the default PREVIEW command.  Displays a text diff of a snapshot version and a live version, \
+                or, if either is a binary file, compares their sizes, checksums, file types, and the hexdumps of their heads.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .exclusive(true)
+                .display_order(83)
+        )
+        .arg(
+            Arg::new("REMOTE_DIR")
+                .long("remote-dir")
+                .hide(true)
+                .visible_aliases(&["remote", "snap-point"])
+                .help("DEPRECATED.  Use MAP_ALIASES. Manually specify that mount point for ZFS (directory which contains a \".zfs\" directory) or btrfs-snapper \
+                (directory which contains a \".snapshots\" directory), such as the local mount point for a remote share.  You may also set via the HTTM_REMOTE_DIR environment variable.")
+                .takes_value(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .display_order(84)
+        )
+        .arg(
+            Arg::new("LOCAL_DIR")
+                .long("local-dir")
+                .hide(true)
+                .visible_alias("local")
+                .help("DEPRECATED.  Use MAP_ALIASES.  Used with \"remote-dir\" to determine where the corresponding live root filesystem of the dataset is.  \
+                Put more simply, the \"local-dir\" is likely the directory you backup to your \"remote-dir\".  If not set, httm defaults to your current working directory.  \
+                You may also set via the environment variable HTTM_LOCAL_DIR.")
+                .requires("REMOTE_DIR")
+                .takes_value(true)
+                .value_parser(clap::builder::ValueParser::os_string())
+                .display_order(85)
+        )
+        .arg(
+            Arg::new("THREADS")
+                .long("threads")
+                .takes_value(true)
+                .require_equals(true)
+                .help("limit the number of threads httm will use for lookups and copies to the number specified.  \
+                By default, httm uses one thread per logical CPU.")
+                .display_order(86)
+        )
+        .arg(
+            Arg::new("DATASET_THREADS")
+                .long("dataset-threads")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("auto")
+                .help("limit the number of concurrent lookups and copies upon any single dataset.  \
+                Hammering a pool of spinning disks with many concurrent reads can be much slower than reading with only a few.  \
+                This argument optionally takes a value.  The default value, \"auto\", limits only those datasets which httm detects are backed by rotational devices \
+                (detection is a Linux only feature).  A number limits every dataset to that number of concurrent lookups and copies.")
+                .display_order(87)
+        )
+        .arg(
+            Arg::new("DATE_FORMAT")
+                .long("date-format")
+                .takes_value(true)
+                .require_equals(true)
+                .help("display dates according to the format specified, instead of the default format, such as \"Mon Jan 01 00:00:00 2024\".  \
+                The format may either be a strftime style format, like \"%Y-%m-%d %H:%M\", or a format description as understood by the Rust 'time' crate, \
+                like \"[year]-[month]-[day] [hour]:[minute]\".  You may also set via the environment variable HTTM_DATE_FORMAT.")
+                .display_order(88)
+        )
+        .arg(
+            Arg::new("TIMESTAMP_FORMAT")
+                .long("timestamp-format")
+                .takes_value(true)
+                .require_equals(true)
+                .help("format the timestamps used in snapshot names and in the file names of restored files according to the format specified, \
+                instead of the default format, such as \"2024-01-01-00:00:00\".  Format syntax is the same as DATE_FORMAT.  \
+                As timestamps must be valid within snapshot names, only alphanumeric characters, and the characters '_', '-', ':', and '.' are allowed in the output.  \
+                You may also set via the environment variable HTTM_TIMESTAMP_FORMAT.")
+                .display_order(89)
+        )
+        .arg(
+            Arg::new("DATE_LOCALE")
+                .long("date-locale")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("auto")
+                .help("display month and weekday names according to the locale specified.  This argument optionally takes a value.  \
+                The default value, \"auto\", determines the locale from the LC_ALL, LC_TIME, or LANG environment variables.  \
+                A language, like \"de\", or a locale, like \"de_DE.UTF-8\", may also be specified.  \
+                Only a handful of languages are currently supported (de, es, fr, it, nl, pt), and English names are used for all others.  \
+                Timestamps, as used within snapshot names and the names of restored files, are never localized.")
+                .display_order(90)
+        )
+        .arg(
+            Arg::new("STRICT")
+                .long("strict")
+                .help("before any operation which modifies a ZFS pool (PRUNE, ROLL_FORWARD, or an \"overwrite\" RESTORE), \
+                httm checks the health of the pool, and warns if the pool is degraded or resilvering.  \
+                In STRICT mode, httm will instead refuse to continue.  \
+                Also, paths which httm would otherwise silently skip, like an unreadable directory during a recursive search, \
+                or a path which resides upon no supported dataset, are each reported, and httm exits with an error once finished.  \
+                Whatever the mode, the exit code upon an error indicates its kind: \
+                1, other, 65, a parse error, 66, not found, 69, an unsupported filesystem, 77, permission denied, and 127, a command missing.  \
+                With JSON, an error is also printed as a JSON response, like: {\"schema_version\": 1, \"error\": {\"kind\": \"not_found\", \"message\": \"...\"}}.")
+                .display_order(91)
+        )
+        .arg(
+            Arg::new("QUIET")
+                .short('q')
+                .long("quiet")
+                .help("suppress progress bars, and notices, such as those printed for each file restored during ROLL_FORWARD, \
+                printing only final summaries and errors.  Useful when httm is executed via cron, or in CI.  QUIET implies NO_PROGRESS.")
+                .display_order(92)
+        )
+        .arg(
+            Arg::new("NO_PROGRESS")
+                .long("no-progress")
+                .help("suppress progress bars and spinners, such as those displayed during ROLL_FORWARD and recursive deleted searches.")
+                .display_order(93)
+        )
+        .arg(
+            Arg::new("FORCE")
+                .long("force")
+                .help("when a restore would overwrite a live version which is newer than the snapshot version selected, overwrite without asking for confirmation.  \
+                Without this option, an interactive RESTORE warns how much newer the live version is, and asks again before overwriting, \
+                and a restore requested via RPC is refused, unless the request itself specifies \"force\".  \
+                FORCE also permits the restore of a version within a redacted ZFS snapshot (one received via \"zfs send --redact\"), \
+                which may be missing some or all of its contents, and which is otherwise refused.")
+                .display_order(94)
+        )
+        .arg(
+            Arg::new("VERIFY_RESTORES")
+                .long("verify-restores")
+                .help("after any restore (whether an interactive RESTORE or a ROLL_FORWARD), verify that each restored file matches its snapshot version \
+                by comparing blake3 checksums of their contents, rather than only comparing their size and modify time metadata.  \
+                Any mismatch is reported, and httm exits with a non-zero status.  When a precautionary snapshot was taken, httm will roll back to that snapshot.  \
+                Note: Verification requires reading back every restored file, and may be expensive for a large ROLL_FORWARD.")
+                .display_order(95)
+        )
+        .arg(
+            Arg::new("CHMOD")
+                .long("chmod")
+                .visible_alias("mode")
+                .takes_value(true)
+                .require_equals(true)
+                .value_name("OCTAL_MODE")
+                .help("set the mode of each regular file restored (whether by an interactive RESTORE, a ROLL_FORWARD, or a RECOVER_DELETED) to the octal mode specified, \
+                like so: --chmod=644.  Otherwise, a copy which preserves attributes has the mode of its snapshot version, \
+                and a plain copy has the mode of its snapshot version less the user's umask, as with \"cp\".  \
+                Directories are never affected by this option.")
+                .display_order(96)
+        )
+        .arg(
+            Arg::new("NO_SECURITY_ATTRS")
+                .long("no-security-attrs")
+                .help("when a restore preserves attributes, do not restore the extended attributes of the \"security\" namespace, \
+                like file capabilities (\"security.capability\", as granted by \"setcap\") and SELinux contexts (\"security.selinux\").  \
+                By default, these are restored verbatim, after the ownership of the file is set, as a change of owner would otherwise clear any capabilities.  \
+                Restoring capabilities requires super user privileges, and a failure to restore a security attribute is reported as a warning.  \
+                This option may be useful when restoring to a system with a different SELinux policy, such that files are labeled according to their new location.")
+                .display_order(97)
+        )
+        .arg(
+            Arg::new("ESCALATE")
+                .long("escalate")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("sudo")
+                .possible_values(["sudo", "doas", "pkexec"])
+                .help("instead of requiring httm be executed as the superuser, execute only the privileged 'zfs' operations, \
+                like snapshot, destroy, rollback, and diff, through the escalation tool specified, while httm itself remains unprivileged.  \
+                This argument optionally takes a value.  The default value is \"sudo\".  Other possible values are \"doas\" and \"pkexec\".  \
+                Note: ROLL_FORWARD still requires httm be executed as the superuser, as its file operations, which may modify any file upon the dataset, are not executed through the escalation tool.")
+                .display_order(98)
         )
         .arg(
-            Arg::new("REMOTE_DIR")
-                .long("remote-dir")
-                .hide(true)
-                .visible_aliases(&["remote", "snap-point"])
-                .help("DEPRECATED.  Use MAP_ALIASES. Manually specify that mount point for ZFS (directory which contains a \".zfs\" directory) or btrfs-snapper \
-                (directory which contains a \".snapshots\" directory), such as the local mount point for a remote share.  You may also set via the HTTM_REMOTE_DIR environment variable.")
+            Arg::new("SIZE_FORMAT")
+                .long("size-format")
                 .takes_value(true)
-                .value_parser(clap::builder::ValueParser::os_string())
-                .display_order(29)
+                .require_equals(true)
+                .possible_values(["bytes", "binary", "decimal"])
+                .help("specify how file sizes are displayed.  The default, \"binary\", displays sizes with binary prefixes, like \"1.5 MiB\".  \
+                \"decimal\" displays sizes with SI prefixes, like \"1.6 MB\", and \"bytes\" displays exact byte counts, which are useful for scripting and comparison.  \
+                The format specified applies to the versions display, the map displays, and to JSON output, where byte counts are output as numbers.")
+                .display_order(99)
         )
         .arg(
-            Arg::new("LOCAL_DIR")
-                .long("local-dir")
-                .hide(true)
-                .visible_alias("local")
-                .help("DEPRECATED.  Use MAP_ALIASES.  Used with \"remote-dir\" to determine where the corresponding live root filesystem of the dataset is.  \
-                Put more simply, the \"local-dir\" is likely the directory you backup to your \"remote-dir\".  If not set, httm defaults to your current working directory.  \
-                You may also set via the environment variable HTTM_LOCAL_DIR.")
-                .requires("REMOTE_DIR")
+            Arg::new("OWNER")
+                .long("owner")
+                .help("display the owner and group of each version, as a column between the size and the path, to help spot versions saved with the wrong ownership.  \
+                Names are displayed where the user or group is known to this system, else numeric ids.  Owner and group are also included in JSON output.")
+                .conflicts_with_all(&["RAW", "ZEROS", "RAW_FIELDS"])
+                .display_order(100)
+        )
+        .arg(
+            Arg::new("NUMERIC_IDS")
+                .long("numeric-ids")
+                .requires("OWNER")
+                .help("with OWNER, always display the numeric user and group ids, instead of names.")
+                .display_order(101)
+        )
+        .arg(
+            Arg::new("COLOR")
+                .long("color")
+                .visible_alias("colour")
                 .takes_value(true)
-                .value_parser(clap::builder::ValueParser::os_string())
-                .display_order(30)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("always")
+                .possible_values(&["auto", "always", "never"])
+                .help("choose when to color paths, per LS_COLORS, and status messages.  The default value is \"auto\", which colors output only when it is destined for a terminal, \
+                so output into pipes and logs contains no escape sequences.  If the argument is specified without a value, colors are always enabled.  \
+                When no value is specified, httm also honors the NO_COLOR, and CLICOLOR_FORCE, environment variables.")
+                .display_order(102)
+        )
+        .arg(
+            Arg::new("ESCAPE")
+                .long("escape")
+                .help("display paths which contain backslashes, control characters, or bytes which are not valid UTF-8 with C-style escapes, like \"ls -b\", \
+                so that every path has a single unambiguous, printable representation.  \
+                Without ESCAPE, such bytes are displayed lossily.  RAW and ZEROS output always contain the exact bytes of each path, unless ESCAPE is also specified.")
+                .display_order(103)
         )
         .arg(
             Arg::new("UTC")
                 .long("utc")
                 .help("use UTC for date display and timestamps")
-                .display_order(31)
+                .display_order(104)
+        )
+        .arg(
+            Arg::new("RELATIVE_DATES")
+                .long("relative-dates")
+                .help("display the date of each version, in the default display, relative to now, like \"3 hours ago\" or \"2 years ago\", \
+                which is much easier to scan when hunting for the version from before lunch.  \
+                The absolute date is no longer displayed, unless AGE is also specified.")
+                .conflicts_with_all(&["RAW", "ZEROS", "RAW_FIELDS"])
+                .display_order(105)
+        )
+        .arg(
+            Arg::new("AGE")
+                .long("age")
+                .help("display the age of each version, like \"3 hours ago\", as a column before its absolute date, in the default display.  \
+                In JSON output, each version includes an \"age\" field.")
+                .conflicts_with_all(&["RAW", "ZEROS", "RAW_FIELDS"])
+                .display_order(106)
         )
         .arg(
             Arg::new("DEBUG")
                 .long("debug")
                 .help("print configuration and debugging info")
-                .display_order(32)
+                .display_order(107)
         )
         .arg(
             Arg::new("ZSH_HOT_KEYS")
                 .long("install-zsh-hot-keys")
-                .help("install zsh hot keys to the users home directory, and then exit")
+                .help("install zsh hot keys to the user's data directory (\"$HTTM_DATA_DIR\", \"$XDG_DATA_HOME/httm\", or \"$HOME/.local/share/httm\"), source them from the user's .zshrc, and then exit")
                 .exclusive(true)
-                .display_order(33)
+                .display_order(108)
+        )
+        .arg(
+            Arg::new("HELP")
+                .short('h')
+                .long("help")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .default_missing_value("short")
+                .possible_values(["short", "full"])
+                .help("print help information, and then exit.  If \"full\" is specified, print the full help, each option described at length, through the user's PAGER, if stdout is a terminal.")
+                .exclusive(true)
+                .display_order(109)
+        )
+        .arg(
+            Arg::new("GENERATE_MAN")
+                .long("generate-man")
+                .help("print a man page, in roff format, generated from these same option definitions, and then exit.  For instance: \"httm --generate-man > httm.1\".")
+                .exclusive(true)
+                .display_order(110)
         )
-        .get_matches()
 }
 
 #[derive(Debug, Clone)]
@@ -523,23 +1533,66 @@ pub struct Config {
     pub paths: Vec<PathData>,
     pub opt_recursive: bool,
     pub opt_exact: bool,
+    pub opt_remember_query: bool,
+    pub opt_query: Option<String>,
+    pub opt_auto_select: bool,
+    pub opt_high_latency: bool,
     pub opt_no_filter: bool,
     pub opt_debug: bool,
     pub opt_no_traverse: bool,
     pub opt_omit_ditto: bool,
+    pub opt_cache: bool,
     pub opt_no_hidden: bool,
     pub opt_json: bool,
     pub opt_one_filesystem: bool,
+    pub opt_follow_renames: bool,
+    pub opt_resume: bool,
+    pub opt_strict: bool,
+    pub opt_verify_restores: bool,
+    pub opt_chmod: Option<u32>,
+    pub opt_no_security_attrs: bool,
+    pub opt_output: Option<OutputFile>,
+    pub opt_force: bool,
+    pub opt_annotate: bool,
+    pub opt_show_dataset: bool,
+    pub opt_show_duplicates: bool,
+    pub opt_report: bool,
+    pub opt_quiet: bool,
+    pub opt_escalate: Option<EscalationTool>,
+    pub opt_threads: Option<usize>,
+    pub opt_dataset_threads: Option<DatasetThreads>,
     pub uniqueness: ListSnapsOfType,
+    pub size_format: SizeFormat,
+    pub date_display: DateDisplay,
+    pub color_mode: ColorMode,
+    pub opt_owner: Option<OwnerFormat>,
+    pub opt_escape: bool,
+    pub opt_mtime_tolerance: Option<Duration>,
+    pub opt_max_results: Option<usize>,
+    pub opt_of_snap: Option<String>,
+    pub opt_follow_stdin: bool,
+    pub opt_hooks: Option<HookConfig>,
     pub opt_bulk_exclusion: Option<BulkExclusion>,
     pub opt_last_snap: Option<LastSnapMode>,
+    pub opt_group_by_snapshot: bool,
+    pub opt_compare_against: Option<PathData>,
     pub opt_preview: Option<String>,
+    pub preview_window: String,
+    pub opt_clipboard: Option<ClipboardMode>,
+    pub opt_select_contents: bool,
+    pub opt_compress: Option<Compression>,
     pub opt_deleted_mode: Option<DeletedMode>,
+    pub opt_raw_fields: Option<Vec<RawField>>,
     pub opt_requested_dir: Option<PathData>,
+    pub additional_requested_dirs: Vec<PathData>,
     pub requested_utc_offset: UtcOffset,
+    pub opt_date_format: Option<String>,
+    pub opt_timestamp_format: Option<String>,
+    pub opt_date_locale: Option<DateLocale>,
     pub exec_mode: ExecMode,
     pub print_mode: PrintMode,
     pub dataset_collection: FilesystemInfo,
+    pub reparse_options: ReparseOptions,
     pub pwd: PathData,
 }
 
@@ -558,6 +1611,29 @@ impl Config {
             install_hot_keys()?
         }
 
+        if let Some(help) = matches.value_of("HELP") {
+            Manual::help(help == "full")?;
+            std::process::exit(0)
+        }
+
+        if matches.is_present("GENERATE_MAN") {
+            Manual::man_page()?;
+            std::process::exit(0)
+        }
+
+        if let Some(mut values) = matches.values_of_os("PREVIEW_COMPARE") {
+            // exec here, as the preview has no need for the rest of the config, and mount parsing would slow every preview
+            let snap_file = values
+                .next()
+                .map(Path::new)
+                .unwrap_or_else(|| Path::new(""));
+            let opt_live_file = values.next().map(Path::new);
+
+            PreviewCompare::exec(snap_file, opt_live_file)?;
+
+            std::process::exit(0)
+        }
+
         let requested_utc_offset = if matches.is_present("UTC") {
             UtcOffset::UTC
         } else {
@@ -567,6 +1643,93 @@ impl Config {
             UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC)
         };
 
+        let opt_date_locale = match matches.value_of("DATE_LOCALE") {
+            Some("" | "auto") => DateLocale::from_env(),
+            Some(locale) => DateLocale::new(locale),
+            None => None,
+        };
+
+        let opt_date_format = match matches
+            .value_of("DATE_FORMAT")
+            .map(str::to_owned)
+            .or_else(|| std::env::var("HTTM_DATE_FORMAT").ok())
+        {
+            Some(value) => Some(parse_date_format(&value)?),
+            None => None,
+        };
+
+        let opt_timestamp_format = match matches
+            .value_of("TIMESTAMP_FORMAT")
+            .map(str::to_owned)
+            .or_else(|| std::env::var("HTTM_TIMESTAMP_FORMAT").ok())
+        {
+            Some(value) => {
+                let timestamp_format = parse_date_format(&value)?;
+
+                // check the output of the format, because there is no other way to know what names may be produced,
+                // timestamps are never localized, and English names are only ever ASCII letters
+                let sample = formatted_date(
+                    requested_utc_offset,
+                    &SystemTime::now(),
+                    &timestamp_format,
+                    None,
+                )?;
+
+                if sample.is_empty()
+                    || !sample
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '.'))
+                {
+                    let msg = format!(
+                        "TIMESTAMP_FORMAT produces a timestamp which is not valid within a snapshot name: \"{sample}\""
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+
+                Some(timestamp_format)
+            }
+            None => None,
+        };
+
+        // the global thread pool must be sized before any parallel iter is executed,
+        // but after the UTC offset is determined, while we are still single threaded
+        let opt_threads = match matches.value_of("THREADS") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(number) if number > 0 => {
+                    rayon::ThreadPoolBuilder::new()
+                        .num_threads(number)
+                        .build_global()
+                        .map_err(|err| {
+                            HttmError::with_context(
+                                "Could not initialize the rayon thread pool: ",
+                                &err,
+                            )
+                        })?;
+
+                    Some(number)
+                }
+                _ => {
+                    return Err(
+                        HttmError::new("THREADS requires a number greater than zero.").into(),
+                    )
+                }
+            },
+            None => None,
+        };
+
+        let opt_dataset_threads =
+            match matches.value_of("DATASET_THREADS") {
+                Some("" | "auto") => Some(DatasetThreads::Auto),
+                Some(value) => match value.parse::<usize>() {
+                    Ok(number) if number > 0 => Some(DatasetThreads::Fixed(number)),
+                    _ => return Err(HttmError::new(
+                        "DATASET_THREADS requires either \"auto\" or a number greater than zero.",
+                    )
+                    .into()),
+                },
+                None => None,
+            };
+
         let opt_json = matches.is_present("JSON");
 
         let mut print_mode = if matches.is_present("ZEROS") {
@@ -579,6 +1742,23 @@ impl Config {
             PrintMode::FormattedDefault
         };
 
+        let opt_raw_fields: Option<Vec<RawField>> = matches.values_of("RAW_FIELDS").map(|values| {
+            values
+                .map(|value| match value {
+                    "mtime" => RawField::Mtime,
+                    "size" => RawField::Size,
+                    "snap" => RawField::Snap,
+                    "dataset" => RawField::Dataset,
+                    _ => RawField::Path,
+                })
+                .collect()
+        });
+
+        // raw fields are a raw mode, so force a raw mode if one is not set
+        if opt_raw_fields.is_some() && matches!(print_mode, PrintMode::FormattedDefault) {
+            print_mode = PrintMode::RawNewline
+        }
+
         let opt_bulk_exclusion = if matches.is_present("NO_LIVE") {
             Some(BulkExclusion::NoLive)
         } else if matches.is_present("NO_SNAP") {
@@ -598,9 +1778,82 @@ impl Config {
 
         // force a raw mode if one is not set for no_snap mode
         let opt_one_filesystem = matches.is_present("ONE_FILESYSTEM");
+
+        let opt_follow_renames = matches.is_present("FOLLOW_RENAMES");
+
+        let opt_resume = matches.is_present("RESUME");
+        let opt_strict = matches.is_present("STRICT");
+        let opt_verify_restores = matches.is_present("VERIFY_RESTORES");
+        let opt_no_security_attrs = matches.is_present("NO_SECURITY_ATTRS");
+        let opt_chmod = match matches.value_of("CHMOD") {
+            Some(value) => match u32::from_str_radix(value.trim_start_matches("0o"), 8) {
+                Ok(mode) if mode <= 0o7777 => Some(mode),
+                _ => {
+                    let msg = format!("CHMOD must be an octal mode, like \"644\", not: {value:?}");
+                    return Err(HttmError::with_kind(HttmErrorKind::ParseError, &msg).into());
+                }
+            },
+            None => None,
+        };
+        let opt_output = matches.value_of_os("OUTPUT").map(|path| OutputFile {
+            path: PathBuf::from(path),
+            should_append: matches.is_present("APPEND"),
+        });
+        let opt_force = matches.is_present("FORCE");
+        let opt_annotate = matches.is_present("ANNOTATE");
+        let opt_show_dataset = matches.is_present("SHOW_DATASET");
+        let opt_show_duplicates = matches.is_present("SHOW_DUPLICATES");
+        let opt_report = matches.is_present("REPORT");
+
+        // the absolute date is only dropped when relative dates are requested without an AGE column
+        let date_display = match (
+            matches.is_present("RELATIVE_DATES"),
+            matches.is_present("AGE"),
+        ) {
+            (_, true) => DateDisplay::RelativeAndAbsolute,
+            (true, false) => DateDisplay::Relative,
+            (false, false) => DateDisplay::Absolute,
+        };
+
+        let size_format = match matches.value_of("SIZE_FORMAT") {
+            Some("bytes") => SizeFormat::Bytes,
+            Some("decimal") => SizeFormat::Decimal,
+            _ => SizeFormat::Binary,
+        };
+        let opt_owner = if matches.is_present("NUMERIC_IDS") {
+            Some(OwnerFormat::Numeric)
+        } else if matches.is_present("OWNER") {
+            Some(OwnerFormat::Names)
+        } else {
+            None
+        };
+        let opt_escape = matches.is_present("ESCAPE");
+        let is_env_set = |key: &str| {
+            std::env::var_os(key)
+                .filter(|value| !value.is_empty() && value != "0")
+                .is_some()
+        };
+        let color_mode = match matches.value_of("COLOR") {
+            Some("always") => ColorMode::Always,
+            Some("never") => ColorMode::Never,
+            // see: https://no-color.org and https://bixense.com/clicolors/
+            _ if is_env_set("NO_COLOR") => ColorMode::Never,
+            _ if is_env_set("CLICOLOR_FORCE") => ColorMode::Always,
+            _ => ColorMode::Auto,
+        };
+        let opt_quiet = matches.is_present("QUIET");
+        let opt_no_progress = opt_quiet || matches.is_present("NO_PROGRESS");
+        let opt_escalate = match matches.value_of("ESCALATE") {
+            Some(value) => Some(EscalationTool::new(value)?),
+            None => None,
+        };
         let opt_recursive = matches.is_present("RECURSIVE");
 
         let opt_exact = matches.is_present("EXACT");
+        let opt_remember_query = matches.is_present("REMEMBER_QUERY")
+            || std::env::var_os("HTTM_REMEMBER_QUERY").is_some();
+        let opt_query = matches.value_of("QUERY").map(|query| query.to_owned());
+        let opt_auto_select = matches.is_present("AUTO_SELECT");
         let opt_no_filter = matches.is_present("NO_FILTER");
         let opt_debug = matches.is_present("DEBUG");
         let opt_no_hidden = matches.is_present("FILTER_HIDDEN");
@@ -614,6 +1867,8 @@ impl Config {
             _ => None,
         };
 
+        let opt_group_by_snapshot = matches!(matches.value_of("GROUP_BY"), Some("snapshot"));
+
         let opt_num_versions = match matches.value_of("NUM_VERSIONS") {
             Some("" | "all") => Some(NumVersionsMode::AllNumerals),
             Some("graph") => Some(NumVersionsMode::AllGraph),
@@ -637,7 +1892,37 @@ impl Config {
             None => None,
         };
 
-        let mut opt_deleted_mode = match matches.value_of("DELETED") {
+        let preview_window = match matches
+            .value_of("PREVIEW_WINDOW")
+            .map(str::to_owned)
+            .or_else(|| std::env::var("HTTM_PREVIEW_WINDOW").ok())
+        {
+            Some(value) => Self::preview_window(&value)?,
+            None => DEFAULT_PREVIEW_WINDOW.to_owned(),
+        };
+
+        let opt_select_contents = matches!(matches.value_of("SELECT"), Some("contents"));
+
+        let opt_compress = match matches.value_of("COMPRESS") {
+            Some("" | "zstd") => Some(Compression::Zstd),
+            Some("gzip") => Some(Compression::Gzip),
+            _ => None,
+        };
+
+        if opt_compress.is_some() && !opt_select_contents {
+            return Err(HttmError::new(
+                "COMPRESS is only available when the contents of a snapshot version are selected, as with \"--select=contents\"",
+            )
+            .into());
+        }
+
+        let opt_clipboard = match matches.value_of("CLIPBOARD") {
+            Some("" | "path") => Some(ClipboardMode::Path),
+            Some("contents") => Some(ClipboardMode::Contents),
+            _ => None,
+        };
+
+        let opt_deleted_mode = match matches.value_of("DELETED") {
             Some("" | "all") => Some(DeletedMode::All),
             Some("single") => Some(DeletedMode::DepthOfOne),
             Some("only") => Some(DeletedMode::Only),
@@ -655,8 +1940,11 @@ impl Config {
                 Some("copy-and-preserve") => {
                     Some(InteractiveMode::Restore(RestoreMode::CopyAndPreserve))
                 }
+                Some("clone") => Some(InteractiveMode::Restore(RestoreMode::Clone)),
                 Some(_) | None => Some(InteractiveMode::Restore(RestoreMode::CopyOnly)),
             }
+        } else if matches.is_present("PICK") {
+            Some(InteractiveMode::Pick)
         } else if matches.is_present("SELECT") {
             Some(InteractiveMode::Select)
         } else if matches.is_present("BROWSE") {
@@ -671,6 +1959,79 @@ impl Config {
             Some("metadata" | _) | None => ListSnapsOfType::UniqueMetadata,
         };
 
+        let opt_mtime_tolerance = match matches.value_of("MTIME_TOLERANCE") {
+            Some(value) => {
+                let tolerance = parse_human_duration(value)?;
+
+                if matches!(uniqueness, ListSnapsOfType::UniqueMetadata) {
+                    Some(tolerance).filter(|tolerance| !tolerance.is_zero())
+                } else {
+                    eprintln!("WARNING: MTIME_TOLERANCE only applies when uniqueness is determined by metadata.  Ignoring.");
+                    None
+                }
+            }
+            None => None,
+        };
+
+        let opt_hooks = {
+            let hook_command = |arg: &str, env_var: &str| {
+                matches
+                    .value_of(arg)
+                    .map(str::to_owned)
+                    .or_else(|| std::env::var(env_var).ok())
+                    .filter(|command| !command.trim().is_empty())
+            };
+
+            let opt_pre_snap = hook_command("PRE_SNAP_HOOK", "HTTM_PRE_SNAP_HOOK");
+            let opt_post_snap = hook_command("POST_SNAP_HOOK", "HTTM_POST_SNAP_HOOK");
+            let opt_pre_destroy = hook_command("PRE_DESTROY_HOOK", "HTTM_PRE_DESTROY_HOOK");
+
+            let timeout = match matches.value_of("HOOK_TIMEOUT") {
+                Some(value) => parse_human_duration(value)?,
+                None => DEFAULT_HOOK_TIMEOUT,
+            };
+
+            let on_failure = match matches.value_of("HOOK_FAILURE") {
+                Some("continue") => HookFailure::Continue,
+                _ => HookFailure::Abort,
+            };
+
+            if opt_pre_snap.is_some() || opt_post_snap.is_some() || opt_pre_destroy.is_some() {
+                Some(HookConfig {
+                    opt_pre_snap,
+                    opt_post_snap,
+                    opt_pre_destroy,
+                    timeout,
+                    on_failure,
+                })
+            } else {
+                None
+            }
+        };
+
+        let opt_max_results = match matches.value_of("MAX_RESULTS") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(number) if number > 0 => Some(number),
+                _ => {
+                    return Err(
+                        HttmError::new("MAX_RESULTS requires a number greater than zero.").into(),
+                    )
+                }
+            },
+            None => None,
+        };
+
+        let opt_of_snap = match matches.value_of("OF_SNAP") {
+            Some(value) if value.is_empty() || value.contains('/') => {
+                return Err(HttmError::new(
+                    "OF_SNAP requires the name of a single snapshot, which may not contain a '/'.",
+                )
+                .into())
+            }
+            Some(value) => Some(value.to_owned()),
+            None => None,
+        };
+
         if opt_no_hidden && !opt_recursive && opt_interactive_mode.is_none() {
             return Err(HttmError::new(
                 "FILTER_HIDDEN is only available if either an interactive mode or recursive mode is specified.",
@@ -678,7 +2039,15 @@ impl Config {
             .into());
         }
 
+        if matches!(opt_preview.as_deref(), Some("force")) && opt_interactive_mode.is_none() {
+            return Err(HttmError::new(
+                "PREVIEW=force is only available in an interactive mode, such as Browse mode",
+            )
+            .into());
+        }
+
         if opt_preview.is_some()
+            && !matches!(opt_preview.as_deref(), Some("force"))
             && matches!(opt_interactive_mode, Some(InteractiveMode::Browse) | None)
         {
             return Err(
@@ -686,6 +2055,11 @@ impl Config {
             );
         }
 
+        if opt_clipboard.is_some() && !matches!(opt_interactive_mode, Some(InteractiveMode::Select))
+        {
+            return Err(HttmError::new("CLIPBOARD is only available in Select mode").into());
+        }
+
         // if in last snap and select mode we will want to return a raw value,
         // better to have this here.  It's more confusing if we work this logic later, I think.
         if opt_last_snap.is_some() && matches!(opt_interactive_mode, Some(InteractiveMode::Select))
@@ -703,6 +2077,10 @@ impl Config {
                     )
                     .into());
                 } else {
+                    // check any placeholders now, rather than after all the lookups are done,
+                    // the timestamp here is only a stand in for the real thing
+                    SnapshotMounts::expand_suffix(requested_snapshot_suffix, "0")?;
+
                     Some(requested_snapshot_suffix.to_owned())
                 }
             } else {
@@ -729,6 +2107,7 @@ impl Config {
                     select_mode,
                     omit_num_snaps: 0usize,
                     name_filters: None,
+                    opt_older_than: None,
                 })
             }
         } else {
@@ -736,15 +2115,76 @@ impl Config {
         };
 
         let mut exec_mode = if let Some(full_snap_name) = matches.value_of("ROLL_FORWARD") {
-            let progress_bar: ProgressBar = indicatif::ProgressBar::new_spinner();
+            let progress_bar: ProgressBar = Self::progress_bar(opt_no_progress);
             let roll_config: RollForwardConfig = RollForwardConfig {
                 full_snap_name: full_snap_name.to_string(),
                 progress_bar,
+                recursive: opt_recursive,
             };
 
             ExecMode::RollForward(roll_config)
         } else if let Some(num_versions_mode) = opt_num_versions {
             ExecMode::NumVersions(num_versions_mode)
+        } else if matches.is_present("LIST_SNAPS_ALL") {
+            ExecMode::ListSnapsAll(
+                matches
+                    .value_of("LIST_SNAPS_ALL")
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_owned),
+            )
+        } else if matches.is_present("COUNT") {
+            ExecMode::Count
+        } else if matches.is_present("INFO") {
+            ExecMode::Info
+        } else if matches.is_present("ASSERT_SNAPSHOTTED") {
+            let opt_within = match matches.value_of("WITHIN") {
+                Some(value) => Some(parse_human_duration(value)?),
+                None => None,
+            };
+
+            ExecMode::AssertSnapshotted(opt_within)
+        } else if matches.is_present("DRIFT") {
+            ExecMode::Drift
+        } else if matches.is_present("RPC") {
+            ExecMode::Rpc
+        } else if matches.is_present("CLEANUP_CLONES") {
+            ExecMode::CleanupClones
+        } else if let Some(rank_by) = matches.value_of("MOST_CHANGED") {
+            let rank_by = match rank_by {
+                "bytes" => RankBy::Bytes,
+                _ => RankBy::Versions,
+            };
+
+            let top = match matches.value_of("TOP") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(top) if top > 0 => top,
+                    _ => {
+                        return Err(
+                            HttmError::new("TOP must be a number of files greater than 0.").into(),
+                        )
+                    }
+                },
+                None => DEFAULT_MOST_CHANGED_TOP,
+            };
+
+            ExecMode::MostChanged(MostChangedConfig { rank_by, top })
+        } else if matches.is_present("RECOVER_DELETED") {
+            let progress_bar: ProgressBar = Self::progress_bar(opt_no_progress);
+
+            ExecMode::RecoverDeleted(RecoverDeletedConfig {
+                opt_restore_to: matches.value_of_os("RESTORE_TO").map(PathBuf::from),
+                progress_bar,
+            })
+        } else if matches.is_present("INDEX") {
+            let progress_bar: ProgressBar = Self::progress_bar(opt_no_progress);
+
+            ExecMode::Index(progress_bar)
+        } else if let Some(pattern) = matches.value_of("GREP") {
+            let regex = Regex::new(pattern).map_err(|err| {
+                HttmError::with_context("GREP pattern is not a valid regular expression: ", &err)
+            })?;
+
+            ExecMode::Grep(regex)
         } else if let Some(mount_display) = opt_mount_display {
             ExecMode::MountsForFiles(mount_display)
         } else if matches.is_present("PRUNE") {
@@ -756,33 +2196,82 @@ impl Config {
         } else if let Some(interactive_mode) = opt_interactive_mode {
             ExecMode::Interactive(interactive_mode)
         } else if opt_deleted_mode.is_some() {
-            let progress_bar: ProgressBar = indicatif::ProgressBar::new_spinner();
+            let progress_bar: ProgressBar = Self::progress_bar(opt_no_progress);
             ExecMode::NonInteractiveRecursive(progress_bar)
         } else {
             ExecMode::Display
         };
 
+        if opt_report && !matches!(exec_mode, ExecMode::Interactive(_)) {
+            return Err(
+                HttmError::new("REPORT is only available in the interactive modes.").into(),
+            );
+        }
+
+        if (opt_query.is_some() || opt_auto_select)
+            && !matches!(exec_mode, ExecMode::Interactive(_))
+        {
+            return Err(HttmError::new(
+                "QUERY and AUTO_SELECT are only available in the interactive modes.",
+            )
+            .into());
+        }
+
         if opt_recursive {
             if matches!(exec_mode, ExecMode::Display) {
                 return Err(HttmError::new("RECURSIVE not available in Display Mode.").into());
             }
-        } else if opt_no_filter {
+        } else if opt_no_filter && !matches!(exec_mode, ExecMode::MostChanged(_) | ExecMode::Drift)
+        {
             return Err(HttmError::new(
                 "NO_FILTER only available when recursive search is enabled.",
             )
             .into());
         }
 
+        // must be set before any input paths are converted to PathData
+        if let Some(requested_root) = matches.value_of_os("ROOT") {
+            AltRoot::set(Path::new(requested_root))?;
+        }
+
+        if matches.is_present("PROFILE") {
+            Profile::enable();
+        }
+
+        if matches.is_present("FOLLOW_BINDS") {
+            MapOfBinds::set()?;
+        }
+
         // current working directory will be helpful in a number of places
         let pwd = Self::pwd()?;
 
+        let opt_follow_stdin = matches.is_present("FOLLOW_STDIN");
+
+        if opt_follow_stdin && !matches!(exec_mode, ExecMode::Display | ExecMode::NumVersions(_)) {
+            return Err(HttmError::new(
+                "FOLLOW_STDIN is only available in the default, and NUM_VERSIONS, display modes.",
+            )
+            .into());
+        }
+
         // paths are immediately converted to our PathData struct
-        let paths: Vec<PathData> =
-            Self::paths(matches.values_of_os("INPUT_FILES"), &exec_mode, &pwd)?;
+        let paths: Vec<PathData> = Self::paths(
+            matches.values_of_os("INPUT_FILES"),
+            matches.value_of_os("FILES_FROM"),
+            opt_follow_stdin,
+            &exec_mode,
+            &pwd,
+        )?;
 
         // for exec_modes in which we can only take a single directory, process how we handle those here
         let opt_requested_dir: Option<PathData> =
-            Self::opt_requested_dir(&mut exec_mode, &mut opt_deleted_mode, &paths, &pwd)?;
+            Self::opt_requested_dir(&mut exec_mode, &paths, &pwd)?;
+
+        // browse may merge several dirs into one view, the first of which is the requested dir
+        let additional_requested_dirs: Vec<PathData> = match exec_mode {
+            ExecMode::Interactive(_) if paths.len() > 1 => paths[1..].to_vec(),
+            _ => Vec::new(),
+        };
 
         if opt_one_filesystem && opt_requested_dir.is_none() {
             return Err(HttmError::new(
@@ -795,7 +2284,8 @@ impl Config {
         // so we disable our bespoke "when to traverse symlinks" algo here, or if requested.
         let opt_no_traverse = matches.is_present("NO_TRAVERSE") || {
             if let Some(user_requested_dir) = opt_requested_dir.as_ref() {
-                user_requested_dir.path_buf.as_path() == Path::new(ROOT_DIRECTORY)
+                user_requested_dir.path_buf.as_path()
+                    == AltRoot::get().unwrap_or_else(|| Path::new(ROOT_DIRECTORY))
             } else {
                 false
             }
@@ -810,6 +2300,33 @@ impl Config {
 
         let opt_omit_ditto = matches.is_present("OMIT_DITTO");
 
+        let opt_cache = matches!(exec_mode, ExecMode::NumVersions(_))
+            && !matches.is_present("NO_CACHE")
+            && (matches.is_present("CACHE") || std::env::var_os("HTTM_CACHE").is_some());
+
+        let opt_compare_against = match matches.value_of_os("COMPARE_AGAINST") {
+            Some(value) => {
+                let pathdata = PathData::from(Path::new(value));
+
+                if pathdata.metadata.is_none() {
+                    return Err(HttmError::new(
+                        "COMPARE_AGAINST file does not exist or httm is unable to read its metadata.  Quitting.",
+                    )
+                    .into());
+                }
+
+                Some(pathdata)
+            }
+            None => None,
+        };
+
+        if opt_compare_against.is_some() && !opt_omit_ditto && opt_last_snap.is_none() && !opt_quiet
+        {
+            eprintln!(
+                "NOTICE: COMPARE_AGAINST has no effect unless OMIT_DITTO or LAST_SNAP is also specified."
+            );
+        }
+
         // opt_omit_identical doesn't make sense in Display Recursive mode as no live files will exists?
         if opt_omit_ditto && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_)) {
             return Err(HttmError::new(
@@ -824,38 +2341,150 @@ impl Config {
             );
         }
 
+        let opt_exclude_property = match matches.value_of("EXCLUDE_PROPERTY") {
+            Some("") => Some(DEFAULT_EXCLUDE_PROPERTY.to_owned()),
+            Some(value) => Some(value.to_owned()),
+            None => std::env::var("HTTM_EXCLUDE_PROPERTY")
+                .ok()
+                .filter(|value| !value.is_empty()),
+        };
+
+        let raw_dataset_hints: Vec<String> = match matches.values_of("DATASET_HINT") {
+            Some(values) => values.map(str::to_owned).collect(),
+            None => std::env::var("HTTM_DATASET_HINTS")
+                .map(|value| value.split_terminator(',').map(str::to_owned).collect())
+                .unwrap_or_default(),
+        };
+
+        let dataset_hints: Vec<DatasetHint> = raw_dataset_hints
+            .iter()
+            .map(|raw_hint| DatasetHint::new(raw_hint))
+            .collect::<HttmResult<Vec<DatasetHint>>>()?;
+
+        let only_datasets: Vec<PathBuf> = match matches.values_of_os("ONLY_DATASET") {
+            Some(values) => values.map(PathBuf::from).collect(),
+            None => std::env::var_os("HTTM_ONLY_DATASETS")
+                .map(|value| {
+                    value
+                        .to_string_lossy()
+                        .split_terminator(',')
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
         // obtain a map of datasets, a map of snapshot directories, and possibly a map of
         // alternate filesystems and map of aliases if the user requests
-        let dataset_collection = FilesystemInfo::new(
-            matches.is_present("ALT_REPLICATED"),
-            matches.value_of_os("REMOTE_DIR"),
-            matches.value_of_os("LOCAL_DIR"),
-            matches.values_of_os("MAP_ALIASES"),
-            &pwd,
-        )?;
+        let dataset_collection = Profile::time(Phase::MountParsing, || {
+            FilesystemInfo::new(
+                matches.is_present("ALT_REPLICATED"),
+                matches.is_present("INFER_BACKUP_ALIASES"),
+                matches.value_of_os("REMOTE_DIR"),
+                matches.value_of_os("LOCAL_DIR"),
+                matches.values_of_os("MAP_ALIASES"),
+                matches.values_of_os("REMOTE_SNAP_COMMAND"),
+                opt_exclude_property.as_deref(),
+                &dataset_hints,
+                &only_datasets,
+                &pwd,
+            )
+        })?;
+
+        // in the interactive modes, the mount table may be parsed again, should it change, see MountWatch
+        let reparse_options = ReparseOptions {
+            opt_exclude_property,
+            dataset_hints,
+            only_datasets,
+            opt_infer_backup_aliases: matches.is_present("INFER_BACKUP_ALIASES"),
+        };
+
+        let opt_high_latency = match matches.value_of("REMOTE_LATENCY") {
+            Some("off") => false,
+            Some(_) => true,
+            None => dataset_collection
+                .opt_map_of_aliases
+                .as_ref()
+                .map(MapOfAliases::is_high_latency)
+                .unwrap_or(false),
+        };
+
+        // reading back every version over a slow link would make the interactive views unusable
+        if opt_high_latency
+            && matches!(exec_mode, ExecMode::Interactive(_))
+            && matches!(uniqueness, ListSnapsOfType::UniqueContents)
+        {
+            if !opt_quiet {
+                eprintln!("NOTICE: High-latency mode is enabled.  Uniqueness will be determined by metadata, not contents, for this session.");
+            }
+            uniqueness = ListSnapsOfType::UniqueMetadata;
+        }
 
         let config = Config {
             paths,
             opt_bulk_exclusion,
             opt_recursive,
             opt_exact,
+            opt_remember_query,
+            opt_query,
+            opt_auto_select,
+            opt_high_latency,
             opt_no_filter,
             opt_debug,
             opt_no_traverse,
             opt_omit_ditto,
+            opt_cache,
             opt_no_hidden,
             opt_last_snap,
+            opt_group_by_snapshot,
+            opt_compare_against,
             opt_preview,
+            preview_window,
+            opt_clipboard,
+            opt_select_contents,
+            opt_compress,
             opt_json,
             opt_one_filesystem,
+            opt_follow_renames,
+            opt_resume,
+            opt_strict,
+            opt_verify_restores,
+            opt_chmod,
+            opt_no_security_attrs,
+            opt_output,
+            opt_force,
+            opt_annotate,
+            opt_show_dataset,
+            opt_show_duplicates,
+            opt_report,
+            opt_quiet,
+            opt_escalate,
+            opt_threads,
+            opt_dataset_threads,
             uniqueness,
+            size_format,
+            date_display,
+            color_mode,
+            opt_owner,
+            opt_escape,
+            opt_mtime_tolerance,
+            opt_max_results,
+            opt_of_snap,
+            opt_follow_stdin,
+            opt_hooks,
             requested_utc_offset,
+            opt_date_format,
+            opt_timestamp_format,
+            opt_date_locale,
             exec_mode,
             print_mode,
             opt_deleted_mode,
+            opt_raw_fields,
             dataset_collection,
+            reparse_options,
             pwd,
             opt_requested_dir,
+            additional_requested_dirs,
         };
 
         Ok(config)
@@ -874,6 +2503,8 @@ impl Config {
 
     pub fn paths(
         opt_os_values: Option<OsValues>,
+        opt_files_from: Option<&OsStr>,
+        opt_follow_stdin: bool,
         exec_mode: &ExecMode,
         pwd: &PathData,
     ) -> HttmResult<Vec<PathData>> {
@@ -883,8 +2514,15 @@ impl Config {
                 // canonicalize() on a deleted relative path will not exist,
                 // so we have to join with the pwd to make a path that
                 // will exist on a snapshot
+                .map(AltRoot::into_root)
                 .map(PathData::from)
+                .map(PathData::into_live_version)
+                .map(PathData::into_bind_source)
                 .collect()
+        } else if opt_files_from.is_some() || opt_follow_stdin {
+            // don't wait on stdin, or default to the pwd, if the paths are read from a file below,
+            // or if stdin will be read incrementally later
+            Vec::new()
         } else {
             match exec_mode {
                 // setting pwd as the path, here, keeps us from waiting on stdin when in certain modes
@@ -892,18 +2530,32 @@ impl Config {
                 // input, and waiting on one input from stdin is pretty silly
                 ExecMode::Interactive(_)
                 | ExecMode::NonInteractiveRecursive(_)
-                | ExecMode::RollForward(_) => {
+                | ExecMode::RollForward(_)
+                | ExecMode::MostChanged(_)
+                | ExecMode::Drift
+                | ExecMode::RecoverDeleted(_)
+                | ExecMode::Index(_) => {
                     vec![pwd.clone()]
                 }
+                // no input files are required at all, or, in RPC mode, paths are read from stdin per request
+                ExecMode::ListSnapsAll(_) | ExecMode::Rpc | ExecMode::CleanupClones => Vec::new(),
                 ExecMode::Display
                 | ExecMode::SnapFileMount(_)
                 | ExecMode::Prune(_)
                 | ExecMode::MountsForFiles(_)
                 | ExecMode::SnapsForFiles(_)
-                | ExecMode::NumVersions(_) => read_stdin()?,
+                | ExecMode::NumVersions(_)
+                | ExecMode::Grep(_)
+                | ExecMode::Count
+                | ExecMode::Info
+                | ExecMode::AssertSnapshotted(_) => read_stdin()?,
             }
         };
 
+        if let Some(files_from) = opt_files_from {
+            paths.extend(read_files_from(Path::new(files_from))?);
+        }
+
         // deduplicate pathdata and sort if in display mode --
         // so input of ./.z* and ./.zshrc will only print ./.zshrc once
         paths = if paths.len() > 1 {
@@ -919,14 +2571,30 @@ impl Config {
         Ok(paths)
     }
 
+    fn progress_bar(opt_no_progress: bool) -> ProgressBar {
+        if opt_no_progress {
+            return ProgressBar::hidden();
+        }
+
+        ProgressBar::new_spinner()
+    }
+
     pub fn opt_requested_dir(
         exec_mode: &mut ExecMode,
-        deleted_mode: &mut Option<DeletedMode>,
         paths: &[PathData],
         pwd: &PathData,
     ) -> HttmResult<Option<PathData>> {
         let res = match exec_mode {
             ExecMode::Interactive(_) | ExecMode::NonInteractiveRecursive(_) => {
+                if matches!(exec_mode, ExecMode::Interactive(InteractiveMode::Pick))
+                    && !matches!(paths, [single] if !single.httm_is_dir())
+                {
+                    return Err(HttmError::new(
+                        "Pick mode requires exactly one path, which must not be a directory.",
+                    )
+                    .into());
+                }
+
                 match paths.len() {
                     0 => Some(pwd.clone()),
                     // use our bespoke is_dir fn for determining whether a dir here see pub httm_is_dir
@@ -944,22 +2612,41 @@ impl Config {
                                                 )
                                                 .into());
                                     }
-                                    InteractiveMode::Restore(_) | InteractiveMode::Select => {
+                                    InteractiveMode::Restore(_)
+                                    | InteractiveMode::Select
+                                    | InteractiveMode::Pick => {
                                         // non-dir file will just cause us to skip the lookup phase
                                         None
                                     }
                                 }
                             }
                             // silently disable NonInteractiveRecursive when path given is not a directory
-                            // switch to a standard Display mode
+                            // switch to a standard Display mode, but retain the deleted mode, so the file
+                            // given, if it no longer exists, is searched for among its parent's deleted files
                             ExecMode::NonInteractiveRecursive(_) => {
                                 *exec_mode = ExecMode::Display;
-                                *deleted_mode = None;
                                 None
                             }
                             _ => unreachable!(),
                         }
                     }
+                    // files given explicitly, which may no longer exist, are displayed, with any deleted
+                    // versions found in their parent dirs, see VersionsMap::deleted_from_parents
+                    n if n > 1
+                        && matches!(exec_mode, ExecMode::NonInteractiveRecursive(_))
+                        && paths.iter().all(|path| !path.httm_is_dir()) =>
+                    {
+                        *exec_mode = ExecMode::Display;
+                        None
+                    }
+                    // several dirs may be browsed at once, merged into one view
+                    n if n > 1
+                        && matches!(exec_mode, ExecMode::Interactive(_))
+                        && !matches!(exec_mode, ExecMode::Interactive(InteractiveMode::Pick))
+                        && paths.iter().all(PathData::httm_is_dir) =>
+                    {
+                        Some(paths[0].clone())
+                    }
                     n if n > 1 => return Err(HttmError::new(
                         "May only specify one path in the display recursive or interactive modes.",
                     )
@@ -976,7 +2663,18 @@ impl Config {
             | ExecMode::Prune(_)
             | ExecMode::MountsForFiles(_)
             | ExecMode::SnapsForFiles(_)
-            | ExecMode::NumVersions(_) => {
+            | ExecMode::NumVersions(_)
+            | ExecMode::Grep(_)
+            | ExecMode::Count
+            | ExecMode::ListSnapsAll(_)
+            | ExecMode::MostChanged(_)
+            | ExecMode::RecoverDeleted(_)
+            | ExecMode::Index(_)
+            | ExecMode::Info
+            | ExecMode::AssertSnapshotted(_)
+            | ExecMode::Drift
+            | ExecMode::Rpc
+            | ExecMode::CleanupClones => {
                 // in non-interactive mode / display mode, requested dir is just a file
                 // like every other file and pwd must be the requested working dir.
                 None
@@ -985,6 +2683,29 @@ impl Config {
         Ok(res)
     }
 
+    // skim would silently ignore what it cannot parse, so we check the layout is one skim understands
+    fn preview_window(value: &str) -> HttmResult<String> {
+        let is_valid = value.split(':').all(|option| {
+            matches!(
+                option,
+                "up" | "down" | "left" | "right" | "wrap" | "nowrap" | "hidden"
+            ) || match option.strip_suffix('%') {
+                Some(percent) => matches!(percent.parse::<u8>(), Ok(1..=100)),
+                None => matches!(option.parse::<u16>(), Ok(size) if size > 0),
+            }
+        });
+
+        if !is_valid {
+            let msg = format!(
+                "PREVIEW_WINDOW could not be parsed from the value given: {value:?}.  \
+                Specify a position, a size, and any options, delimited by colons, like \"right:60%:wrap\"."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(value.to_owned())
+    }
+
     pub fn snap_filters(values: &str, select_mode: bool) -> HttmResult<ListSnapsFilters> {
         let mut raw = values.trim_end().split(',');
 
@@ -998,7 +2719,16 @@ impl Config {
             0usize
         };
 
-        let rest: Vec<&str> = raw.collect();
+        // an age expression, like "older:90d", is not a name filter, and may be combined with name filters
+        let mut opt_older_than = None;
+        let mut rest: Vec<&str> = Vec::new();
+
+        for item in raw {
+            match item.strip_prefix("older:") {
+                Some(age) => opt_older_than = Some(parse_human_duration(age)?),
+                None => rest.push(item),
+            }
+        }
 
         let name_filters = if !rest.is_empty() {
             if rest.len() == 1usize && rest.index(0) == &"none" {
@@ -1019,6 +2749,7 @@ impl Config {
             select_mode,
             omit_num_snaps,
             name_filters,
+            opt_older_than,
         })
     }
 
@@ -1029,24 +2760,71 @@ impl Config {
             paths: paths_selected.to_vec(),
             opt_recursive: false,
             opt_exact: false,
+            opt_remember_query: false,
+            opt_query: None,
+            opt_auto_select: false,
+            opt_high_latency: self.opt_high_latency,
             opt_no_filter: false,
             opt_debug: false,
             opt_no_traverse: false,
             opt_no_hidden: false,
             opt_json: false,
             opt_one_filesystem: false,
+            opt_follow_renames: self.opt_follow_renames,
+            opt_resume: self.opt_resume,
+            opt_strict: self.opt_strict,
+            opt_verify_restores: self.opt_verify_restores,
+            opt_chmod: self.opt_chmod,
+            opt_no_security_attrs: self.opt_no_security_attrs,
+            opt_output: self.opt_output.clone(),
+            opt_force: self.opt_force,
+            opt_annotate: self.opt_annotate,
+            opt_show_dataset: self.opt_show_dataset,
+            opt_show_duplicates: self.opt_show_duplicates,
+            opt_report: false,
+            opt_quiet: self.opt_quiet,
+            opt_escalate: self.opt_escalate,
+            opt_threads: self.opt_threads,
+            opt_dataset_threads: self.opt_dataset_threads.clone(),
             opt_bulk_exclusion: None,
             opt_last_snap: None,
+            opt_group_by_snapshot: false,
+            opt_compare_against: self.opt_compare_against.clone(),
             opt_preview: None,
+            preview_window: self.preview_window.clone(),
+            opt_clipboard: None,
+            opt_select_contents: false,
+            opt_compress: None,
             opt_deleted_mode: None,
+            opt_raw_fields: None,
             uniqueness: ListSnapsOfType::UniqueMetadata,
+            size_format: self.size_format,
+            date_display: self.date_display,
+            // the display config is only ever displayed within an interactive view, which is necessarily a terminal
+            color_mode: match self.color_mode {
+                ColorMode::Never => ColorMode::Never,
+                ColorMode::Auto | ColorMode::Always => ColorMode::Always,
+            },
+            opt_owner: self.opt_owner,
+            opt_escape: self.opt_escape,
+            opt_mtime_tolerance: self.opt_mtime_tolerance,
+            opt_max_results: self.opt_max_results,
+            opt_of_snap: self.opt_of_snap.clone(),
+            opt_follow_stdin: false,
+            opt_hooks: None,
             opt_omit_ditto: self.opt_omit_ditto,
+            opt_cache: self.opt_cache,
             requested_utc_offset: self.requested_utc_offset,
+            opt_date_format: self.opt_date_format.clone(),
+            opt_timestamp_format: self.opt_timestamp_format.clone(),
+            opt_date_locale: self.opt_date_locale.clone(),
             exec_mode: ExecMode::Display,
             print_mode: PrintMode::FormattedDefault,
             dataset_collection: self.dataset_collection.clone(),
+            reparse_options: self.reparse_options.clone(),
             pwd: self.pwd.clone(),
             opt_requested_dir: self.opt_requested_dir.clone(),
+            additional_requested_dirs: self.additional_requested_dirs.clone(),
         }
     }
 }
@@ -23,14 +23,15 @@ use std::{
 
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::make_tmp_path;
+use crate::library::xdg::BaseDir;
 
-const HTTM_SCRIPT_PATH: &str = ".httm-key-bindings.zsh";
+const HTTM_SCRIPT_PATH: &str = "httm-key-bindings.zsh";
 const ZSHRC_PATH: &str = ".zshrc";
 
 pub fn install_hot_keys() -> HttmResult<()> {
     // get our home directory
-    let home_dir = if let Ok(home) = std::env::var("HOME") {
-        if let Ok(path) = PathBuf::from(&home).canonicalize() {
+    let home_dir = if let Some(home) = BaseDir::home() {
+        if let Ok(path) = home.canonicalize() {
             path
         } else {
             return Err(HttmError::new(
@@ -42,10 +43,31 @@ pub fn install_hot_keys() -> HttmResult<()> {
         return Err(HttmError::new("$HOME does not appear to be set in your environment").into());
     };
 
+    // the script itself is installed to our data directory, rather than loose in the home directory
+    let data_dir = match BaseDir::Data.httm_dir() {
+        Some(data_dir) => data_dir,
+        None => {
+            return Err(HttmError::new(
+                "httm could not determine a data directory for the zsh hot keys script",
+            )
+            .into())
+        }
+    };
+
+    // zsh itself looks for .zshrc in $ZDOTDIR, when set
+    let zshrc_dir = match std::env::var_os("ZDOTDIR") {
+        Some(zdotdir) if !zdotdir.is_empty() => PathBuf::from(zdotdir),
+        _ => home_dir,
+    };
+
+    // create paths to use
+    let zsh_script_path: PathBuf = data_dir.join(HTTM_SCRIPT_PATH);
+    let zsh_script_tmp_path = make_tmp_path(zsh_script_path.as_path());
+
     // check whether httm-key-bindings.zsh is already sourced
     // and, if not, open ~/.zshrc append only for sourcing the httm-key-bindings.zsh
     let mut buffer = String::new();
-    let zshrc_path: PathBuf = home_dir.join(ZSHRC_PATH);
+    let zshrc_path: PathBuf = zshrc_dir.join(ZSHRC_PATH);
     let mut zshrc_file = OpenOptions::new()
         .read(true)
         .write(true)
@@ -67,9 +89,15 @@ pub fn install_hot_keys() -> HttmResult<()> {
         .filter(|line| !line.starts_with('#'))
         .any(|line| line.contains("source") && line.contains("httm-key-bindings.zsh"))
     {
-        // append "source ~/.httm-key-bindings.zsh" to zshrc
+        std::fs::create_dir_all(&data_dir)?;
+
+        // append "source <data dir>/httm-key-bindings.zsh" to zshrc
         zshrc_file.write_all(
-            "\n# httm: zsh hot keys script\nsource ~/.httm-key-bindings.zsh\n".as_bytes(),
+            format!(
+                "\n# httm: zsh hot keys script\nsource \"{}\"\n",
+                zsh_script_path.display()
+            )
+            .as_bytes(),
         )?;
     } else {
         return Err(HttmError::new(
@@ -81,13 +109,7 @@ pub fn install_hot_keys() -> HttmResult<()> {
     // create key binding file -- done at compile time
     let zsh_hot_key_script = include_str!("../../scripts/httm-key-bindings.zsh");
 
-    // create paths to use
-    let zsh_script_path: PathBuf = [&home_dir, &PathBuf::from(HTTM_SCRIPT_PATH)]
-        .iter()
-        .collect();
-    let zsh_script_tmp_path = make_tmp_path(zsh_script_path.as_path());
-
-    // create tmp file in user's home dir or will fail if file already exists
+    // create tmp file in user's data dir or will fail if file already exists
     match OpenOptions::new()
         .write(true)
         .create_new(true)
@@ -110,12 +132,12 @@ pub fn install_hot_keys() -> HttmResult<()> {
                     std::process::exit(0)
                 }
                 Err(err) => {
-                    Err(HttmError::with_context("httm: could not move .httm-key-bindings.zsh.tmp to .httm-key-bindings.zsh for the following reason: ", &err).into())
+                    Err(HttmError::with_context("httm: could not move httm-key-bindings.zsh.tmp to httm-key-bindings.zsh for the following reason: ", &err).into())
                 }
             }
         }
         Err(err) => Err(HttmError::with_context(
-            "Opening ~/.httm-key-bindings.zsh.tmp file failed for the following reason: ",
+            "Opening httm-key-bindings.zsh.tmp file failed for the following reason: ",
             &err,
         )
         .into()),
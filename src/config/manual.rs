@@ -0,0 +1,251 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command as ExecProcess, Stdio};
+
+use clap::{crate_name, crate_version, Arg, Command};
+use terminal_size::{terminal_size, Width};
+
+use crate::config::generate::build_command;
+use crate::library::results::HttmResult;
+use crate::library::utility::print_output_buf;
+
+const DEFAULT_PAGER: &str = "less";
+const MAX_WIDTH: usize = 100;
+const HELP_INDENT: usize = 8;
+
+// the single screen help is too dense for the number of flags httm has, so we also offer
+// the full help, each option described at length, and a man page generated from the same definitions
+pub struct Manual;
+
+impl Manual {
+    pub fn help(is_full: bool) -> HttmResult<()> {
+        let mut command = build_command();
+
+        if !is_full {
+            command.print_help()?;
+            return Ok(());
+        }
+
+        let full_help = Self::full_help(&command);
+
+        Self::page(&full_help)
+    }
+
+    pub fn man_page() -> HttmResult<()> {
+        let command = build_command();
+
+        print_output_buf(Self::roff(&command))
+    }
+
+    fn visible_args<'a>(command: &'a Command<'static>) -> impl Iterator<Item = &'a Arg<'static>> {
+        command.get_arguments().filter(|arg| !arg.is_hide_set())
+    }
+
+    fn full_help(command: &Command<'static>) -> String {
+        let width = match terminal_size() {
+            Some((Width(width), _height)) => (width as usize).min(MAX_WIDTH),
+            None => MAX_WIDTH,
+        };
+
+        let mut buffer = format!(
+            "{} {}\n\n{}\n\nUSAGE:\n    {} [OPTIONS] [INPUT_FILES]...\n\nOPTIONS:\n",
+            crate_name!(),
+            crate_version!(),
+            Self::wrap(command.get_about().unwrap_or_default(), width, 0),
+            crate_name!()
+        );
+
+        Self::visible_args(command).for_each(|arg| {
+            buffer += &format!("    {}\n", Self::synopsis(arg));
+
+            if let Some(help) = arg.get_long_help().or_else(|| arg.get_help()) {
+                buffer += &Self::wrap(help, width, HELP_INDENT);
+                buffer.push('\n');
+            }
+
+            if let Some(possible_values) = Self::possible_values(arg) {
+                buffer += &format!(
+                    "{:indent$}[possible values: {possible_values}]\n",
+                    "",
+                    indent = HELP_INDENT
+                );
+            }
+
+            buffer.push('\n');
+        });
+
+        buffer
+    }
+
+    // e.g. "-r, --restore[=<copy|copy-and-preserve|...>]"
+    fn synopsis(arg: &Arg<'static>) -> String {
+        let flags: Vec<String> = arg
+            .get_short()
+            .map(|short| format!("-{short}"))
+            .into_iter()
+            .chain(arg.get_long().map(|long| format!("--{long}")))
+            .collect();
+
+        let flags = if flags.is_empty() {
+            // a positional arg
+            format!("<{}>", arg.get_id())
+        } else {
+            flags.join(", ")
+        };
+
+        if !arg.is_takes_value_set() || arg.is_positional() {
+            return flags;
+        }
+
+        let value_name = Self::possible_values(arg)
+            .map(|possible_values| possible_values.replace(", ", "|"))
+            .unwrap_or_else(|| arg.get_id().to_owned());
+
+        if arg.is_require_equals_set() {
+            format!("{flags}[=<{value_name}>]")
+        } else {
+            format!("{flags} <{value_name}>")
+        }
+    }
+
+    fn possible_values(arg: &Arg<'static>) -> Option<String> {
+        let possible_values: Vec<&str> = arg
+            .get_possible_values()?
+            .iter()
+            .filter(|possible_value| !possible_value.is_hide_set())
+            .map(|possible_value| possible_value.get_name())
+            .collect();
+
+        Some(possible_values.join(", "))
+    }
+
+    fn wrap(text: &str, width: usize, indent: usize) -> String {
+        let available = width.saturating_sub(indent).max(20);
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut line = String::new();
+
+        text.split_whitespace().for_each(|word| {
+            if !line.is_empty() && line.len() + 1 + word.len() > available {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.push_str(word);
+        });
+
+        if !line.is_empty() {
+            lines.push(line);
+        }
+
+        lines
+            .iter()
+            .map(|line| format!("{:indent$}{line}", ""))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // through the user's PAGER, when a person is reading
+    fn page(text: &str) -> HttmResult<()> {
+        if !std::io::stdout().is_terminal() {
+            return print_output_buf(text.to_owned());
+        }
+
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PAGER.to_owned());
+
+        let spawned = ExecProcess::new("sh")
+            .arg("-c")
+            .arg(&pager)
+            .stdin(Stdio::piped())
+            .spawn();
+
+        match spawned {
+            Ok(mut child) => {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // the user may quit the pager before reading it all, which is no error
+                    let _ = stdin.write_all(text.as_bytes());
+                }
+
+                child.wait()?;
+
+                Ok(())
+            }
+            Err(_) => print_output_buf(text.to_owned()),
+        }
+    }
+
+    fn roff(command: &Command<'static>) -> String {
+        let name = crate_name!();
+
+        let mut buffer = format!(
+            ".TH {} 1 \"\" \"{name} {}\" \"User Commands\"\n",
+            name.to_uppercase(),
+            crate_version!()
+        );
+
+        buffer += &format!(
+            ".SH NAME\n{name} \\- {}\n",
+            Self::roff_escape(command.get_about().unwrap_or_default())
+        );
+        buffer +=
+            &format!(".SH SYNOPSIS\n\\fB{name}\\fR [\\fIOPTIONS\\fR] [\\fIINPUT_FILES\\fR]...\n");
+        buffer += ".SH OPTIONS\n";
+
+        Self::visible_args(command).for_each(|arg| {
+            buffer += &format!(".TP\n\\fB{}\\fR\n", Self::roff_escape(&Self::synopsis(arg)));
+
+            if let Some(help) = arg.get_long_help().or_else(|| arg.get_help()) {
+                buffer += &Self::roff_escape(help);
+                buffer.push('\n');
+            }
+
+            if let Some(possible_values) = Self::possible_values(arg) {
+                buffer += &format!(
+                    ".RS\n[possible values: {}]\n.RE\n",
+                    Self::roff_escape(&possible_values)
+                );
+            }
+        });
+
+        buffer
+    }
+
+    fn roff_escape(text: &str) -> String {
+        let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+
+        // a line beginning with a period or an apostrophe would be read as a request
+        escaped
+            .lines()
+            .map(|line| {
+                if line.starts_with('.') || line.starts_with('\'') {
+                    format!("\\&{line}")
+                } else {
+                    line.to_owned()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
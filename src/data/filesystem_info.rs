@@ -15,16 +15,24 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::{ffi::OsStr, path::PathBuf};
+use std::sync::{Arc, RwLock};
+use std::{ffi::OsStr, path::Path, path::PathBuf};
 
 use clap::OsValues;
+use once_cell::sync::Lazy;
 
 use crate::data::paths::PathData;
-use crate::library::results::HttmResult;
+use crate::exec::recursive::SharedRecursive;
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::fs_capabilities::FsCapabilities;
+use crate::library::redaction::Redaction;
+use crate::library::results::{HttmError, HttmResult};
+use crate::lookup::versions::RelativePathAndSnapMounts;
 use crate::parse::aliases::MapOfAliases;
 use crate::parse::alts::MapOfAlts;
-use crate::parse::mounts::{BaseFilesystemInfo, FilterDirs, MapOfDatasets};
+use crate::parse::mounts::{BaseFilesystemInfo, DatasetHint, FilterDirs, MapOfDatasets};
 use crate::parse::snaps::MapOfSnaps;
+use crate::GLOBAL_CONFIG;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FilesystemInfo {
@@ -45,19 +53,29 @@ pub struct FilesystemInfo {
 impl FilesystemInfo {
     pub fn new(
         opt_alt_replicated: bool,
+        opt_infer_backup_aliases: bool,
         opt_remote_dir: Option<&OsStr>,
         opt_local_dir: Option<&OsStr>,
         opt_map_aliases: Option<OsValues>,
+        opt_remote_snap_commands: Option<OsValues>,
+        opt_exclude_property: Option<&str>,
+        dataset_hints: &[DatasetHint],
+        only_datasets: &[PathBuf],
         pwd: &PathData,
     ) -> HttmResult<FilesystemInfo> {
-        let base_fs_info = BaseFilesystemInfo::new()?;
+        let base_fs_info =
+            BaseFilesystemInfo::new(opt_exclude_property, dataset_hints, only_datasets)?;
 
         // for a collection of btrfs mounts, indicates a common snapshot directory to ignore
         let opt_common_snap_dir = base_fs_info.common_snap_dir();
 
         // only create a map of alts if necessary
-        let opt_map_of_alts = if opt_alt_replicated {
-            Some(MapOfAlts::new(&base_fs_info.map_of_datasets))
+        // inferring backups is a broader means of finding alt replicated datasets
+        let opt_map_of_alts = if opt_alt_replicated || opt_infer_backup_aliases {
+            Some(MapOfAlts::new(
+                &base_fs_info.map_of_datasets,
+                opt_infer_backup_aliases,
+            ))
         } else {
             None
         };
@@ -89,6 +107,19 @@ impl FilesystemInfo {
             std::env::var_os("HTTM_SNAP_POINT")
         };
 
+        // commands may themselves contain commas, so the env var holds only a single command
+        let remote_snap_values: Option<Vec<String>> =
+            if let Some(env_remote_snap) = std::env::var_os("HTTM_REMOTE_SNAP_COMMAND") {
+                Some(vec![env_remote_snap.to_string_lossy().to_string()])
+            } else {
+                opt_remote_snap_commands.map(|cmd_remote_snap| {
+                    cmd_remote_snap
+                        .into_iter()
+                        .map(|os_str| os_str.to_string_lossy().to_string())
+                        .collect()
+                })
+            };
+
         let opt_map_of_aliases = if raw_snap_dir.is_some() || alias_values.is_some() {
             let env_local_dir = std::env::var_os("HTTM_LOCAL_DIR");
 
@@ -103,7 +134,13 @@ impl FilesystemInfo {
                 &raw_local_dir,
                 pwd.path_buf.as_path(),
                 &alias_values,
+                &remote_snap_values,
             )?)
+        } else if remote_snap_values.is_some() {
+            return Err(HttmError::new(
+                "REMOTE_SNAP_COMMAND requires an alias to snapshot, specified via MAP_ALIASES or REMOTE_DIR.",
+            )
+            .into());
         } else {
             None
         };
@@ -118,3 +155,119 @@ impl FilesystemInfo {
         })
     }
 }
+
+// what is needed to parse the mount table again, aliases are specified by the user, and never change
+#[derive(Debug, Clone)]
+pub struct ReparseOptions {
+    pub opt_exclude_property: Option<String>,
+    pub dataset_hints: Vec<DatasetHint>,
+    pub only_datasets: Vec<PathBuf>,
+    pub opt_infer_backup_aliases: bool,
+}
+
+// a dataset may be mounted, or unmounted, during a long interactive session, like when a backup drive is
+// plugged in, so, in the interactive modes, the FilesystemInfo parsed at startup may be replaced by one
+// parsed again, see MountWatch.  until then, and in every other mode, the startup FilesystemInfo is used
+static LIVE_FILESYSTEM_INFO: Lazy<RwLock<Option<Arc<FilesystemInfo>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+pub struct LiveFilesystemInfo;
+
+impl LiveFilesystemInfo {
+    // a lookup takes one snapshot of the mount table, and uses it throughout, so it never mixes two tables
+    pub fn current() -> Option<Arc<FilesystemInfo>> {
+        LIVE_FILESYSTEM_INFO
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    // for whatever is looked up once per dataset, and cached, rather than once per lookup
+    pub fn with_current<T>(f: impl FnOnce(&FilesystemInfo) -> T) -> T {
+        match Self::current() {
+            Some(fs_info) => f(&fs_info),
+            None => f(&GLOBAL_CONFIG.dataset_collection),
+        }
+    }
+
+    pub fn reparse(reparse_options: &ReparseOptions) -> HttmResult<()> {
+        let opt_current = Self::current();
+        let current: &FilesystemInfo = opt_current
+            .as_deref()
+            .unwrap_or(&GLOBAL_CONFIG.dataset_collection);
+
+        let base_fs_info = BaseFilesystemInfo::new(
+            reparse_options.opt_exclude_property.as_deref(),
+            &reparse_options.dataset_hints,
+            &reparse_options.only_datasets,
+        )?;
+
+        let opt_common_snap_dir = base_fs_info.common_snap_dir();
+
+        // alts are simply datasets which share a name, so these may come and go with a mount too
+        let opt_map_of_alts = current.opt_map_of_alts.as_ref().map(|_map_of_alts| {
+            MapOfAlts::new(
+                &base_fs_info.map_of_datasets,
+                reparse_options.opt_infer_backup_aliases,
+            )
+        });
+
+        let reparsed = FilesystemInfo {
+            map_of_datasets: base_fs_info.map_of_datasets,
+            map_of_snaps: base_fs_info.map_of_snaps,
+            filter_dirs: base_fs_info.filter_dirs,
+            opt_map_of_alts,
+            opt_common_snap_dir,
+            opt_map_of_aliases: current.opt_map_of_aliases.clone(),
+        };
+
+        // nothing which concerns us has changed, like a mount of a filesystem we have no interest in
+        if reparsed == *current {
+            return Ok(());
+        }
+
+        Self::replace(reparsed);
+
+        // whatever was derived from the mount table replaced must be derived again
+        FsCapabilities::reload();
+        RelativePathAndSnapMounts::clear_cache();
+        SharedRecursive::clear_cache();
+        Redaction::clear_cache();
+        DatasetPermit::clear_cache();
+
+        Ok(())
+    }
+
+    // a snapshot taken mid-session is only found once its dataset's snapshot directory is read again
+    pub fn refresh_snaps(dataset_mount: &Path) -> HttmResult<()> {
+        let opt_current = Self::current();
+        let current: &FilesystemInfo = opt_current
+            .as_deref()
+            .unwrap_or(&GLOBAL_CONFIG.dataset_collection);
+
+        let dataset_info = match current.map_of_datasets.get(dataset_mount) {
+            Some(dataset_info) => dataset_info,
+            None => return Ok(()),
+        };
+
+        let refreshed = FilesystemInfo {
+            map_of_snaps: current
+                .map_of_snaps
+                .with_snaps_of(dataset_mount, dataset_info)?,
+            ..current.clone()
+        };
+
+        Self::replace(refreshed);
+
+        RelativePathAndSnapMounts::clear_cache_of(dataset_mount);
+
+        Ok(())
+    }
+
+    // a lookup already underway keeps its own snapshot, which is dropped once the lookup is done
+    fn replace(fs_info: FilesystemInfo) {
+        *LIVE_FILESYSTEM_INFO
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(fs_info));
+    }
+}
@@ -0,0 +1,106 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use serde::Serialize;
+
+use crate::config::generate::PrintMode;
+use crate::library::utility::delimiter;
+
+// bumped upon any change which could break a consumer of httm's JSON, that is, whenever a field
+// is removed, renamed, or changes type.  fields may be added within a schema version, so
+// consumers should simply ignore any fields they do not recognize
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+// every response, whatever the mode, is an object of the same shape, the schema version, and
+// a single field, named for the mode, which holds its body: {"schema_version": 1, "<field>": <body>}
+#[derive(Serialize)]
+pub struct JsonResponse<'a, T: Serialize> {
+    schema_version: u32,
+    #[serde(flatten)]
+    body: JsonBody<'a, T>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JsonBody<'a, T: Serialize> {
+    // {"<live path>": [<version>, ...]}, where each version is either an object, with its metadata,
+    // or, in LAST_SNAP mode, only its path
+    Versions(&'a T),
+    // {"<live path>": ["<mount>", ...]}
+    Mounts(&'a T),
+    // {"<live path>": ["<snapshot name>", ...]}
+    SnapshotNames(&'a T),
+    // [{"path": ..., "num_versions": ..., ...}, ...]
+    NumVersions(&'a T),
+    // [{"path": ..., "dataset": ..., ...}, ...]
+    Info(&'a T),
+    // {"kind": "not_found", "message": "..."}
+    Error(&'a T),
+}
+
+impl<'a, T: Serialize> JsonResponse<'a, T> {
+    pub fn versions(versions: &'a T) -> Self {
+        Self::new(JsonBody::Versions(versions))
+    }
+
+    pub fn mounts(mounts: &'a T) -> Self {
+        Self::new(JsonBody::Mounts(mounts))
+    }
+
+    pub fn snapshot_names(snapshot_names: &'a T) -> Self {
+        Self::new(JsonBody::SnapshotNames(snapshot_names))
+    }
+
+    pub fn num_versions(num_versions: &'a T) -> Self {
+        Self::new(JsonBody::NumVersions(num_versions))
+    }
+
+    pub fn info(info: &'a T) -> Self {
+        Self::new(JsonBody::Info(info))
+    }
+
+    pub fn error(error: &'a T) -> Self {
+        Self::new(JsonBody::Error(error))
+    }
+
+    fn new(body: JsonBody<'a, T>) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            body,
+        }
+    }
+
+    pub fn to_json(&self, print_mode: &PrintMode) -> String {
+        let res = match print_mode {
+            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
+                serde_json::to_string(self)
+            }
+            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
+        };
+
+        match res {
+            Ok(s) => {
+                let delimiter = delimiter();
+                format!("{s}{delimiter}")
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            }
+        }
+    }
+}
@@ -20,30 +20,45 @@ use std::{
     ffi::OsStr,
     fs::{symlink_metadata, DirEntry, File, FileType, Metadata},
     io::{BufRead, BufReader, ErrorKind},
-    path::{Path, PathBuf},
+    os::unix::ffi::OsStrExt,
+    os::unix::fs::MetadataExt,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
     time::SystemTime,
 };
 
-use once_cell::sync::OnceCell;
+use hashbrown::HashMap;
+use nix::unistd::{Gid, Group, Uid, User};
+use once_cell::sync::{Lazy, OnceCell};
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
 use simd_adler32::Adler32;
 
-use crate::parse::mounts::MapOfDatasets;
-use crate::parse::mounts::MaxLen;
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::library::alt_root::AltRoot;
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::redaction::Redaction;
+use crate::parse::mounts::{MapOfBinds, MapOfDatasets};
 use crate::{config::generate::ListSnapsOfType, parse::aliases::MapOfAliases};
 use crate::{
-    config::generate::PrintMode,
+    config::generate::{DateDisplay, OwnerFormat, PrintMode, SizeFormat},
     library::{
-        results::{HttmError, HttmResult},
+        results::{HttmError, HttmErrorKind, HttmResult},
         utility::DateFormat,
     },
 };
 use crate::{
-    library::utility::{date_string, display_human_size},
+    library::utility::{date_string, display_human_size, display_relative_date},
     GLOBAL_CONFIG,
 };
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, ZFS_SNAPSHOT_DIRECTORY};
+
+// key: uid or gid, val: the name of that user or group, or None if unknown to this system
+static USER_NAMES: Lazy<Mutex<HashMap<u32, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static GROUP_NAMES: Lazy<Mutex<HashMap<u32, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 // only the most basic data from a DirEntry
 // for use to display in browse window and internally
@@ -91,9 +106,10 @@ impl Ord for PathData {
 
 impl<T: AsRef<Path>> From<T> for PathData {
     fn from(path: T) -> Self {
+        let path = Self::from_file_url(path.as_ref());
         // this metadata() function will not traverse symlinks
-        let opt_metadata = symlink_metadata(path.as_ref()).ok();
-        PathData::new(path.as_ref(), opt_metadata)
+        let opt_metadata = symlink_metadata(&path).ok();
+        PathData::new(&path, opt_metadata)
     }
 }
 
@@ -119,7 +135,8 @@ impl PathData {
         //
         // in general we handle those cases elsewhere, like the ingest
         // of input files in Config::from for deleted relative paths, etc.
-        let absolute_path: PathBuf = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let absolute_path: PathBuf =
+            AltRoot::canonicalize(path).unwrap_or_else(|_| Self::lexical_normalize(path));
 
         let path_metadata = Self::opt_metadata(opt_metadata);
 
@@ -129,6 +146,84 @@ impl PathData {
         }
     }
 
+    // paths copied from a file manager may arrive as "file:///home/user/My%20File", but only
+    // decode when the path, as given, does not exist, so a real dir named "file:" still works
+    fn from_file_url(path: &Path) -> PathBuf {
+        let bytes = path.as_os_str().as_bytes();
+
+        let encoded = match bytes.strip_prefix(b"file://") {
+            Some(rest) if !path.exists() => rest,
+            _ => return path.to_path_buf(),
+        };
+
+        // "file://localhost/etc" is the same as "file:///etc", but a URL naming any other host
+        // is not ours to read, so it is left as given, rather than read as a relative path
+        let encoded = match encoded.strip_prefix(b"localhost") {
+            Some(rest) if rest.starts_with(b"/") => rest,
+            _ if encoded.starts_with(b"/") => encoded,
+            _ => return path.to_path_buf(),
+        };
+
+        let mut decoded: Vec<u8> = Vec::with_capacity(encoded.len());
+        let mut iter = encoded.iter().copied();
+
+        while let Some(byte) = iter.next() {
+            if byte != b'%' {
+                decoded.push(byte);
+                continue;
+            }
+
+            let mut lookahead = iter.clone();
+
+            let opt_hex = lookahead
+                .next()
+                .zip(lookahead.next())
+                .and_then(|(high, low)| {
+                    let hex = [high, low];
+                    std::str::from_utf8(&hex)
+                        .ok()
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                });
+
+            match opt_hex {
+                Some(value) => {
+                    decoded.push(value);
+                    iter = lookahead;
+                }
+                // a lone '%' is left as it is
+                None => decoded.push(byte),
+            }
+        }
+
+        PathBuf::from(OsStr::from_bytes(&decoded))
+    }
+
+    // when a path cannot be canonicalized, because it DNE, as with a deleted file, we still
+    // want "./dir/../file" and "dir/" to resolve to the same names as their live counterparts,
+    // else relative path computation and dataset resolution go astray
+    fn lexical_normalize(path: &Path) -> PathBuf {
+        let mut normalized = PathBuf::new();
+
+        path.components().for_each(|component| match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // as with the kernel, ".." at the root is the root
+                Some(Component::RootDir) => {}
+                _ => normalized.push(component),
+            },
+            _ => normalized.push(component),
+        });
+
+        if normalized.as_os_str().is_empty() {
+            return PathBuf::from(".");
+        }
+
+        normalized
+    }
+
     // call symlink_metadata, as we need to resolve symlinks to get non-"phantom" metadata
     fn opt_metadata(opt_metadata: Option<Metadata>) -> Option<PathMetadata> {
         opt_metadata.and_then(|md| {
@@ -188,18 +283,11 @@ impl PathData {
         &'a self,
         map_of_datasets: &MapOfDatasets,
     ) -> HttmResult<&'a Path> {
-        // for /usr/bin, we prefer the most proximate: /usr/bin to /usr and /
-        // ancestors() iterates in this top-down order, when a value: dataset/fstype is available
-        // we map to return the key, instead of the value
-
-        let dataset_max_len = map_of_datasets.max_len();
-
-        self.path_buf
-            .ancestors()
-            .skip_while(|ancestor| ancestor.components().count() > dataset_max_len)
-            .find(|ancestor| map_of_datasets.contains_key(*ancestor))
+        map_of_datasets
+            .proximate_mount(&self.path_buf)
             .ok_or_else(|| {
-                HttmError::new(
+                HttmError::with_kind(
+                    HttmErrorKind::UnsupportedFilesystem,
                     "httm could not identify any qualifying dataset.  \
                     Maybe consider specifying manually at SNAP_POINT?",
                 )
@@ -207,6 +295,125 @@ impl PathData {
             })
     }
 
+    // name of the snapshot upon which this path resides, None if the path is not
+    // a ZFS or btrfs-snapper snapshot path, as is the case for live versions
+    pub fn snap_name(&self) -> Option<&OsStr> {
+        self.path_buf.ancestors().find_map(|ancestor| {
+            let parent = ancestor.parent()?;
+
+            if parent.ends_with(ZFS_SNAPSHOT_DIRECTORY) {
+                return ancestor.file_name();
+            }
+
+            if ancestor.ends_with(BTRFS_SNAPPER_SUFFIX)
+                && parent.parent()?.ends_with(BTRFS_SNAPPER_HIDDEN_DIRECTORY)
+            {
+                return parent.file_name();
+            }
+
+            None
+        })
+    }
+
+    // the dataset upon which this version resides, and, for a snapshot version, its snapshot,
+    // like "rpool/home@snap_1", so that a version on a backup replica may be told from a local one
+    pub fn dataset_label(&self, map_of_datasets: &MapOfDatasets) -> Option<String> {
+        let dataset_mount = map_of_datasets.proximate_mount(&self.path_buf)?;
+        let source = map_of_datasets.get(dataset_mount)?.source.to_string_lossy();
+
+        match self.snap_name() {
+            Some(snap_name) => Some(format!("{source}@{}", snap_name.to_string_lossy())),
+            None => Some(source.into_owned()),
+        }
+    }
+
+    // input paths which point into a snapshot, like "/srv/.zfs/snapshot/snap_1/file",
+    // are resolved to their live paths, like "/srv/file", so that all versions may be displayed
+    pub fn into_live_version(self) -> Self {
+        let opt_live_path = self.path_buf.ancestors().find_map(|ancestor| {
+            let parent = ancestor.parent()?;
+
+            let opt_mount = if parent.ends_with(ZFS_SNAPSHOT_DIRECTORY) {
+                // ZFS: <mount>/.zfs/snapshot/<snap name>/<relative path>
+                parent.parent()?.parent()
+            } else if ancestor.ends_with(BTRFS_SNAPPER_SUFFIX)
+                && parent.parent()?.ends_with(BTRFS_SNAPPER_HIDDEN_DIRECTORY)
+            {
+                // snapper: <mount>/.snapshots/<number>/snapshot/<relative path>
+                parent.parent()?.parent()
+            } else {
+                None
+            };
+
+            let relative_path = self.path_buf.strip_prefix(ancestor).ok()?;
+
+            opt_mount.map(|mount| mount.join(relative_path))
+        });
+
+        match opt_live_path {
+            Some(live_path) => PathData::from(live_path.as_path()),
+            None => self,
+        }
+    }
+
+    // ownership isn't needed to compare versions, so it is read only as needed for display
+    pub fn owner_and_group(&self, owner_format: &OwnerFormat) -> Option<(String, String)> {
+        let md = self.path_buf.symlink_metadata().ok()?;
+
+        let (uid, gid) = (md.uid(), md.gid());
+
+        match owner_format {
+            OwnerFormat::Numeric => Some((uid.to_string(), gid.to_string())),
+            OwnerFormat::Names => {
+                let owner = Self::cached_name(&USER_NAMES, uid, |uid| {
+                    User::from_uid(Uid::from_raw(uid))
+                        .ok()
+                        .flatten()
+                        .map(|user| user.name)
+                });
+                let group = Self::cached_name(&GROUP_NAMES, gid, |gid| {
+                    Group::from_gid(Gid::from_raw(gid))
+                        .ok()
+                        .flatten()
+                        .map(|group| group.name)
+                });
+
+                Some((owner, group))
+            }
+        }
+    }
+
+    // every version of a file usually shares an owner, so each id is looked up only once
+    fn cached_name(
+        cache: &Mutex<HashMap<u32, Option<String>>>,
+        id: u32,
+        lookup: impl Fn(u32) -> Option<String>,
+    ) -> String {
+        let mut cache = cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        cache
+            .entry(id)
+            .or_insert_with(|| lookup(id))
+            .clone()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    pub fn display_owner(&self, owner_format: &OwnerFormat) -> Option<String> {
+        self.owner_and_group(owner_format)
+            .map(|(owner, group)| format!("{owner}:{group}"))
+    }
+
+    // input paths beneath a bind mount are resolved to the same path beneath the source dataset's mount,
+    // as the bind mount has no snapshot directory of its own
+    pub fn into_bind_source(self) -> Self {
+        match MapOfBinds::get().and_then(|map_of_binds| map_of_binds.source_path(&self.path_buf)) {
+            Some(source_path) => PathData::from(source_path.as_path()),
+            None => self,
+        }
+    }
+
     pub fn alias_dataset<'a>(&self, map_of_alias: &'a MapOfAliases) -> Option<&'a Path> {
         // find_map_first should return the first seq result with a par_iter
         // but not with a par_bridge
@@ -223,10 +430,31 @@ impl Serialize for PathData {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PathData", 2)?;
+        let mut state = serializer.serialize_struct("PathData", 4)?;
 
         state.serialize_field("path", &self.path_buf)?;
         state.serialize_field("metadata", &self.metadata)?;
+
+        if GLOBAL_CONFIG.opt_show_dataset {
+            state.serialize_field(
+                "dataset",
+                &LiveFilesystemInfo::with_current(|fs_info| {
+                    self.dataset_label(&fs_info.map_of_datasets)
+                }),
+            )?;
+        }
+
+        if let Some(owner_format) = &GLOBAL_CONFIG.opt_owner {
+            let opt_owner_and_group = self.owner_and_group(owner_format);
+            let (opt_owner, opt_group) = match opt_owner_and_group {
+                Some((owner, group)) => (Some(owner), Some(group)),
+                None => (None, None),
+            };
+
+            state.serialize_field("owner", &opt_owner)?;
+            state.serialize_field("group", &opt_group)?;
+        }
+
         state.end()
     }
 }
@@ -236,7 +464,7 @@ impl Serialize for PathMetadata {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PathData", 2)?;
+        let mut state = serializer.serialize_struct("PathData", 3)?;
 
         if matches!(
             GLOBAL_CONFIG.print_mode,
@@ -245,15 +473,25 @@ impl Serialize for PathMetadata {
             state.serialize_field("size", &self.size)?;
             state.serialize_field("modify_time", &self.modify_time)?;
         } else {
-            let size = display_human_size(self.size);
             let date = date_string(
                 GLOBAL_CONFIG.requested_utc_offset,
                 &self.modify_time,
                 DateFormat::Display,
-            );
+            )
+            .map_err(serde::ser::Error::custom)?;
+
+            // exact byte counts are more useful as numbers than as strings
+            if matches!(GLOBAL_CONFIG.size_format, SizeFormat::Bytes) {
+                state.serialize_field("size", &self.size)?;
+            } else {
+                state.serialize_field("size", &display_human_size(self.size))?;
+            }
 
-            state.serialize_field("size", &size)?;
             state.serialize_field("modify_time", &date)?;
+
+            if !matches!(GLOBAL_CONFIG.date_display, DateDisplay::Absolute) {
+                state.serialize_field("age", &display_relative_date(&self.modify_time))?;
+            }
         }
 
         state.end()
@@ -304,9 +542,14 @@ impl Ord for CompareVersionsContainer {
         }
 
         // if files, differ re mtime, but have same size, we test by bytes whether the same
+        //
+        // FYI object identity, like the same inode number and birth time, cannot short-circuit this test:
+        // an object unmodified between snapshots has the same mtime in each, and is already equal above,
+        // so versions which reach here were modified, or touched, in place, and only their bytes can tell
         if self_md.size == other_md.size
             && self.opt_hash.is_some()
-            // if above is true/false then "&& other.opt_hash.is_some()" is the same
+            // a version within a redacted snapshot has no hash, as its contents may be missing
+            && other.opt_hash.is_some()
             && self.is_same_file(other)
         {
             return Ordering::Equal;
@@ -319,13 +562,21 @@ impl Ord for CompareVersionsContainer {
 impl CompareVersionsContainer {
     pub fn new(pathdata: PathData, snaps_of_type: &ListSnapsOfType) -> Self {
         let opt_hash = match snaps_of_type {
-            ListSnapsOfType::UniqueContents => Some(OnceCell::new()),
-            ListSnapsOfType::UniqueMetadata | ListSnapsOfType::All => None,
+            ListSnapsOfType::UniqueContents if !Redaction::is_redacted(&pathdata) => {
+                Some(OnceCell::new())
+            }
+            ListSnapsOfType::UniqueContents
+            | ListSnapsOfType::UniqueMetadata
+            | ListSnapsOfType::All => None,
         };
 
         CompareVersionsContainer { pathdata, opt_hash }
     }
 
+    pub fn pathdata(&self) -> &PathData {
+        &self.pathdata
+    }
+
     #[inline]
     #[allow(unused_assignments)]
     fn is_same_file(&self, other: &Self) -> bool {
@@ -377,6 +628,8 @@ impl HashFromFile {
     fn new(path: &Path) -> HttmResult<Self> {
         const IN_BUFFER_SIZE: usize = 131_072;
 
+        let _opt_permit = DatasetPermit::acquire(path);
+
         let file = File::open(path)?;
 
         let mut reader = BufReader::with_capacity(IN_BUFFER_SIZE, file);
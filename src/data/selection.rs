@@ -15,16 +15,21 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::{fs::FileType, path::PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::{fs::FileType, fs::Metadata, path::Path, path::PathBuf};
 
 use lscolors::Colorable;
+use once_cell::sync::OnceCell;
 use skim::prelude::*;
 
 use crate::data::paths::{BasicDirEntryInfo, PathData};
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::exec::preview::PreviewCompare;
 use crate::exec::recursive::PathProvenance;
 use crate::library::results::HttmResult;
-use crate::library::utility::paint_string;
+use crate::library::utility::{display_human_duration, paint_string};
 use crate::{VersionsMap, GLOBAL_CONFIG};
 
 // these represent the items ready for selection and preview
@@ -34,6 +39,12 @@ use crate::{VersionsMap, GLOBAL_CONFIG};
 pub struct SelectionCandidate {
     path: PathBuf,
     file_type: Option<FileType>,
+    // version summary suffix, like "[v:4, 2d]", is filled in by a background
+    // thread the first time this candidate is displayed
+    version_summary: Arc<OnceCell<String>>,
+    summary_requested: AtomicBool,
+    // metadata is only used to paint the path, so one lookup is enough, see impl Colorable
+    opt_metadata: OnceCell<Option<Metadata>>,
 }
 
 impl SelectionCandidate {
@@ -53,9 +64,71 @@ impl SelectionCandidate {
         SelectionCandidate {
             path: basic_info.path,
             file_type,
+            version_summary: Arc::new(OnceCell::new()),
+            summary_requested: AtomicBool::new(false),
+            opt_metadata: OnceCell::new(),
         }
     }
 
+    // in high-latency mode, metadata is looked up in a batch, before display, instead of on the display thread
+    pub fn prefetch_metadata(&self) {
+        let _ = self.cached_metadata();
+    }
+
+    // in high-latency mode, version summaries are looked up before display
+    pub fn prefetch_version_summary(&self) {
+        let _ = self.opt_version_summary();
+    }
+
+    fn cached_metadata(&self) -> Option<Metadata> {
+        self.opt_metadata
+            .get_or_init(|| self.path.symlink_metadata().ok())
+            .clone()
+    }
+
+    // lookups are much too expensive to do on the display thread, so the first
+    // display request spawns the lookup, and later redraws pick up the result
+    fn opt_version_summary(&self) -> Option<&str> {
+        if let Some(summary) = self.version_summary.get() {
+            return Some(summary.as_str());
+        }
+
+        if !self.summary_requested.swap(true, Ordering::Relaxed) {
+            let path = self.path.clone();
+            let summary_cell = self.version_summary.clone();
+
+            rayon::spawn(move || {
+                if let Some(summary) = Self::version_summary(&path) {
+                    let _ = summary_cell.set(summary);
+                }
+            });
+        }
+
+        None
+    }
+
+    fn version_summary(path: &Path) -> Option<String> {
+        let paths_selected = &[PathData::from(path)];
+        let display_config = GLOBAL_CONFIG.generate_display_config(paths_selected);
+
+        let versions_map = VersionsMap::new(&display_config, &display_config.paths).ok()?;
+        let snaps = versions_map.values().next()?;
+
+        // age is of the newest snapshot version, not of the snapshot itself
+        let summary = match snaps.last() {
+            Some(newest) => {
+                let age = SystemTime::now()
+                    .duration_since(newest.md_infallible().modify_time)
+                    .unwrap_or_default();
+
+                format!("[v:{}, {}]", snaps.len(), display_human_duration(age))
+            }
+            None => "[v:0]".to_owned(),
+        };
+
+        Some(summary)
+    }
+
     fn preview_view(&self) -> HttmResult<String> {
         let config = &GLOBAL_CONFIG;
         let paths_selected = &[PathData::from(self.path.as_path())];
@@ -65,20 +138,56 @@ impl SelectionCandidate {
 
         // finally run search on those paths
         let versions_map = VersionsMap::new(&display_config, &display_config.paths)?;
-        let output_buf = VersionsDisplayWrapper::from(&display_config, versions_map).to_string();
+
+        let opt_newest_version = if matches!(config.opt_preview.as_deref(), Some("force")) {
+            versions_map
+                .values()
+                .next()
+                .and_then(|snaps| snaps.last())
+                .map(|newest| newest.path_buf.clone())
+        } else {
+            None
+        };
+
+        let mut output_buf =
+            VersionsDisplayWrapper::from(&display_config, versions_map).to_output_string()?;
+
+        if let Some(newest_version) = opt_newest_version {
+            match PreviewCompare::forced(&newest_version, &self.path) {
+                Ok(comparison) => output_buf += &comparison,
+                Err(err) => output_buf += &format!("\nCould not compare versions: {err}\n"),
+            }
+        }
 
         Ok(output_buf)
     }
 
     fn generate_display_name(&self) -> Cow<str> {
-        self.path
-            .strip_prefix(
-                &GLOBAL_CONFIG
-                    .opt_requested_dir
-                    .as_ref()
-                    .expect("requested_dir should never be None in Interactive Browse mode")
-                    .path_buf,
+        let requested_dir = &GLOBAL_CONFIG
+            .opt_requested_dir
+            .as_ref()
+            .expect("requested_dir should never be None in Interactive Browse mode")
+            .path_buf;
+
+        if GLOBAL_CONFIG.additional_requested_dirs.is_empty() {
+            return self
+                .path
+                .strip_prefix(requested_dir)
+                .unwrap_or(&self.path)
+                .to_string_lossy();
+        }
+
+        // when several dirs are merged into one view, each entry is named beginning with the dir it is from
+        std::iter::once(requested_dir)
+            .chain(
+                GLOBAL_CONFIG
+                    .additional_requested_dirs
+                    .iter()
+                    .map(|additional_dir| &additional_dir.path_buf),
             )
+            .find(|dir| self.path.starts_with(dir))
+            .and_then(|dir| dir.parent())
+            .and_then(|parent| self.path.strip_prefix(parent).ok())
             .unwrap_or(&self.path)
             .to_string_lossy()
     }
@@ -95,7 +204,7 @@ impl Colorable for &SelectionCandidate {
         self.file_type
     }
     fn metadata(&self) -> Option<std::fs::Metadata> {
-        self.path.symlink_metadata().ok()
+        self.cached_metadata()
     }
 }
 
@@ -104,7 +213,18 @@ impl SkimItem for SelectionCandidate {
         self.path.to_string_lossy()
     }
     fn display(&self, _context: DisplayContext<'_>) -> AnsiString {
-        AnsiString::parse(&paint_string(self, &self.generate_display_name()))
+        let display_name = self.generate_display_name();
+        // the browse view is necessarily a terminal
+        let painted_name = paint_string(
+            self,
+            &display_name,
+            GLOBAL_CONFIG.color_mode.is_enabled(true),
+        );
+
+        match self.opt_version_summary() {
+            Some(summary) => AnsiString::parse(&format!("{painted_name} {summary}")),
+            None => AnsiString::parse(&painted_name),
+        }
     }
     fn output(&self) -> Cow<str> {
         self.text()
@@ -18,14 +18,16 @@
 use std::collections::BTreeMap;
 use std::ops::Deref;
 
-use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
 use crate::config::generate::MountDisplay;
 use crate::config::generate::{ExecMode, PrintMode};
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::json::JsonResponse;
 use crate::display_versions::format::NOT_SO_PRETTY_FIXED_WIDTH_PADDING;
 use crate::display_versions::format::QUOTATION_MARKS_LEN;
-use crate::library::utility::delimiter;
+use crate::library::utility::{delimiter, display_path};
+use crate::library::zpool_history::ZpoolHistory;
 use crate::SnapNameMap;
 use crate::VersionsMap;
 use crate::{MountsForFiles, GLOBAL_CONFIG};
@@ -54,10 +56,7 @@ impl Serialize for PrintAsMap {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("PrintAsMap", 1)?;
-
-        state.serialize_field("inner", &self)?;
-        state.end()
+        self.inner.serialize(serializer)
     }
 }
 
@@ -69,20 +68,20 @@ impl<'a> From<&MountsForFiles<'a>> for PrintAsMap {
                 let res = values
                     .iter()
                     .filter_map(|value| match mounts_for_files.mount_display() {
-                        MountDisplay::Target => Some(value.path_buf.to_string_lossy()),
-                        MountDisplay::Source => GLOBAL_CONFIG
-                            .dataset_collection
-                            .map_of_datasets
-                            .get(&value.path_buf)
-                            .map(|md| md.source.to_string_lossy()),
+                        MountDisplay::Target => Some(display_path(&value.path_buf).into_owned()),
+                        MountDisplay::Source => LiveFilesystemInfo::with_current(|fs_info| {
+                            fs_info
+                                .map_of_datasets
+                                .get(&value.path_buf)
+                                .map(|md| md.source.to_string_lossy().into_owned())
+                        }),
                         MountDisplay::RelativePath => key
                             .relative_path(value.path_buf.as_path())
                             .ok()
-                            .map(|path| path.to_string_lossy()),
+                            .map(|path| display_path(path).into_owned()),
                     })
-                    .map(|s| s.to_string())
                     .collect();
-                (key.path_buf.to_string_lossy().to_string(), res)
+                (display_path(&key.path_buf).to_string(), res)
             })
             .collect();
         Self { inner }
@@ -96,9 +95,9 @@ impl From<&VersionsMap> for PrintAsMap {
             .map(|(key, values)| {
                 let res = values
                     .iter()
-                    .map(|value| value.path_buf.to_string_lossy().to_string())
+                    .map(|value| display_path(&value.path_buf).to_string())
                     .collect();
-                (key.path_buf.to_string_lossy().to_string(), res)
+                (display_path(&key.path_buf).to_string(), res)
             })
             .collect();
         Self { inner }
@@ -109,7 +108,7 @@ impl From<&SnapNameMap> for PrintAsMap {
     fn from(map: &SnapNameMap) -> Self {
         let inner = map
             .iter()
-            .map(|(key, value)| (key.path_buf.to_string_lossy().to_string(), value.clone()))
+            .map(|(key, value)| (display_path(&key.path_buf).to_string(), value.clone()))
             .collect();
         Self { inner }
     }
@@ -118,20 +117,31 @@ impl From<&SnapNameMap> for PrintAsMap {
 impl std::string::ToString for PrintAsMap {
     fn to_string(&self) -> String {
         if GLOBAL_CONFIG.opt_json {
-            let json_string = self.to_json();
+            let print_mode = &GLOBAL_CONFIG.print_mode;
 
-            let res = match &GLOBAL_CONFIG.exec_mode {
+            return match &GLOBAL_CONFIG.exec_mode {
                 ExecMode::Display | ExecMode::Interactive(_) => {
-                    json_string.replace("\"inner\": ", "\"versions\": ")
+                    JsonResponse::versions(self).to_json(print_mode)
                 }
-                ExecMode::MountsForFiles(_) => json_string.replace("\"inner\": ", "\"mounts\": "),
+                ExecMode::MountsForFiles(_) => JsonResponse::mounts(self).to_json(print_mode),
                 ExecMode::SnapsForFiles(_) => {
-                    json_string.replace("\"inner\": ", "\"snapshot_names\": ")
+                    JsonResponse::snapshot_names(self).to_json(print_mode)
                 }
                 ExecMode::NonInteractiveRecursive(_)
                 | ExecMode::RollForward(_)
                 | ExecMode::NumVersions(_)
                 | ExecMode::Prune(_)
+                | ExecMode::Grep(_)
+                | ExecMode::Count
+                | ExecMode::ListSnapsAll(_)
+                | ExecMode::MostChanged(_)
+                | ExecMode::RecoverDeleted(_)
+                | ExecMode::Index(_)
+                | ExecMode::Info
+                | ExecMode::AssertSnapshotted(_)
+                | ExecMode::Drift
+                | ExecMode::Rpc
+                | ExecMode::CleanupClones
                 | ExecMode::SnapFileMount(_) => {
                     unreachable!(
                         "JSON print should not be available in the selected {:?} execution mode.",
@@ -139,8 +149,6 @@ impl std::string::ToString for PrintAsMap {
                     );
                 }
             };
-
-            return res;
         }
 
         match &GLOBAL_CONFIG.print_mode {
@@ -158,6 +166,28 @@ impl std::string::ToString for PrintAsMap {
 }
 
 impl PrintAsMap {
+    // inverts a map of last snaps, such that each key is a snapshot, like "rpool/home@snap_1",
+    // and its values are those input files whose last version resides upon that snapshot
+    pub fn by_snapshot(map: &VersionsMap) -> Self {
+        let mut inner: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        map.iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+            .for_each(|(key, value)| {
+                let snapshot = LiveFilesystemInfo::with_current(|fs_info| {
+                    value.dataset_label(&fs_info.map_of_datasets)
+                })
+                .unwrap_or_else(|| display_path(&value.path_buf).to_string());
+
+                inner
+                    .entry(snapshot)
+                    .or_default()
+                    .push(display_path(&key.path_buf).to_string());
+            });
+
+        Self { inner }
+    }
+
     pub fn map_padding(&self) -> usize {
         self.keys().max_by_key(|key| key.len()).map_or_else(
             || QUOTATION_MARKS_LEN,
@@ -165,24 +195,17 @@ impl PrintAsMap {
         )
     }
 
-    pub fn to_json(&self) -> String {
-        let res = match GLOBAL_CONFIG.print_mode {
-            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
-                serde_json::to_string(&self)
-            }
-            PrintMode::FormattedDefault => serde_json::to_string_pretty(&self),
-        };
-
-        match res {
-            Ok(s) => {
-                let delimiter = delimiter();
-                format!("{s}{delimiter}")
-            }
-            Err(error) => {
-                eprintln!("Error: {error}");
-                std::process::exit(1)
+    // in list snaps mode, values are full snapshot names, which may be annotated with their zpool history
+    fn annotation(value: &str) -> String {
+        if GLOBAL_CONFIG.opt_annotate
+            && matches!(GLOBAL_CONFIG.exec_mode, ExecMode::SnapsForFiles(_))
+        {
+            if let Some(annotation) = ZpoolHistory::annotate(value) {
+                return format!("  # {annotation}");
             }
         }
+
+        String::new()
     }
 
     pub fn format(&self) -> String {
@@ -213,13 +236,18 @@ impl PrintAsMap {
                             format!("{NOT_SO_PRETTY_FIXED_WIDTH_PADDING}{value}")
                         } else if idx == 0 {
                             format!(
-                                "{:<width$} : \"{}\"\n",
+                                "{:<width$} : \"{}\"{}\n",
                                 display_path,
                                 value,
+                                Self::annotation(value),
                                 width = padding
                             )
                         } else {
-                            format!("{:<padding$} : \"{value}\"\n", "")
+                            format!(
+                                "{:<padding$} : \"{value}\"{}\n",
+                                "",
+                                Self::annotation(value)
+                            )
                         }
                     })
                     .collect::<String>();
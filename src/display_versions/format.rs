@@ -16,14 +16,24 @@
 // that was distributed with this source code.
 
 use std::borrow::Cow;
+use std::io::IsTerminal;
 use std::ops::Deref;
+use std::os::unix::ffi::OsStrExt;
+use std::time::SystemTime;
 
 use terminal_size::{terminal_size, Height, Width};
 
-use crate::config::generate::{BulkExclusion, Config, PrintMode};
+use crate::config::generate::{BulkExclusion, Config, DateDisplay, PrintMode, RawField};
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::{PathData, PHANTOM_DATE, PHANTOM_SIZE};
+use crate::library::redaction::Redaction;
+use crate::library::results::HttmResult;
 use crate::library::utility::delimiter;
-use crate::library::utility::{date_string, display_human_size, paint_string, DateFormat};
+use crate::library::utility::{
+    date_string, display_human_size, display_path, display_relative_date, paint_string, DateFormat,
+};
+use crate::library::zpool_history::ZpoolHistory;
+use crate::lookup::versions::DuplicateVersions;
 use crate::VersionsDisplayWrapper;
 // 2 space wide padding - used between date and size, and size and path
 pub const PRETTY_FIXED_WIDTH_PADDING: &str = "  ";
@@ -33,14 +43,16 @@ pub const PRETTY_FIXED_WIDTH_PADDING_LEN_X2: usize = PRETTY_FIXED_WIDTH_PADDING.
 pub const NOT_SO_PRETTY_FIXED_WIDTH_PADDING: &str = "\t";
 // and we add 2 quotation marks to the path when we format
 pub const QUOTATION_MARKS_LEN: usize = 2;
+// tab delimiter used between raw fields
+pub const RAW_FIELDS_DELIMITER: &str = "\t";
 
 impl<'a> VersionsDisplayWrapper<'a> {
-    pub fn format(&self) -> String {
+    pub fn format(&self) -> HttmResult<String> {
         let keys: Vec<&PathData> = self.keys().collect();
         let values: Vec<&PathData> = self.values().flatten().collect();
 
         let global_display_set = DisplaySet::from((keys, values));
-        let padding_collection = PaddingCollection::new(self.config, &global_display_set);
+        let padding_collection = PaddingCollection::new(self.config, &global_display_set)?;
 
         // if a single instance immediately return the global we already prepared
         if matches!(
@@ -66,7 +78,7 @@ impl<'a> VersionsDisplayWrapper<'a> {
                     PrintMode::RawNewline | PrintMode::RawZero => {
                         let delimiter = delimiter();
 
-                        display_set
+                        let raw = display_set
                             .iter()
                             .enumerate()
                             .map(|(idx, snap_or_live_set)| {
@@ -78,16 +90,61 @@ impl<'a> VersionsDisplayWrapper<'a> {
                             .map(|(_idx, snap_or_live_set)| {
                                 snap_or_live_set
                                     .iter()
-                                    .map(|pathdata| {
-                                        format!("{}{delimiter}", pathdata.path_buf.display())
+                                    .map(|pathdata| match &self.config.opt_raw_fields {
+                                        Some(raw_fields) => {
+                                            format!(
+                                                "{}{delimiter}",
+                                                pathdata.raw_fields(raw_fields)
+                                            )
+                                        }
+                                        None => {
+                                            format!(
+                                                "{}{delimiter}",
+                                                display_path(&pathdata.path_buf)
+                                            )
+                                        }
                                     })
                                     .collect::<String>()
                             })
-                            .collect()
+                            .collect::<String>();
+
+                        Ok(raw)
                     }
                 }
             })
-            .collect::<String>()
+            .collect::<HttmResult<String>>()
+    }
+
+    // raw paths, as bytes, exactly as they are on disk, because a lossy conversion
+    // would just hand a path which does not exist to the next program in the pipeline
+    pub fn format_raw_bytes(&self) -> Vec<u8> {
+        let mut delimiter_buffer = [0u8; 4];
+        let delimiter = delimiter().encode_utf8(&mut delimiter_buffer).as_bytes();
+
+        self.iter()
+            .flat_map(|(key, values)| {
+                let keys: Vec<&PathData> = vec![key];
+                let values: Vec<&PathData> = values.iter().collect();
+
+                let display_set = DisplaySet::from((keys, values));
+
+                display_set
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, snap_or_live_set)| (DisplaySetType::from(idx), snap_or_live_set))
+                    .filter(|(display_set_type, _snap_or_live_set)| {
+                        display_set_type.filter_bulk_exclusions(self.config)
+                    })
+                    .flat_map(|(_idx, snap_or_live_set)| {
+                        snap_or_live_set.iter().flat_map(|pathdata| {
+                            let mut bytes = pathdata.path_buf.as_os_str().as_bytes().to_vec();
+                            bytes.extend_from_slice(delimiter);
+                            bytes
+                        })
+                    })
+                    .collect::<Vec<u8>>()
+            })
+            .collect()
     }
 }
 
@@ -149,7 +206,11 @@ impl DisplaySetType {
 }
 
 impl<'a> DisplaySet<'a> {
-    pub fn format(&self, config: &Config, padding_collection: &PaddingCollection) -> String {
+    pub fn format(
+        &self,
+        config: &Config,
+        padding_collection: &PaddingCollection,
+    ) -> HttmResult<String> {
         // get the display buffer for each set snaps and live
         self.iter()
             .enumerate()
@@ -157,15 +218,24 @@ impl<'a> DisplaySet<'a> {
             .filter(|(display_set_type, _snap_or_live_set)| {
                 display_set_type.filter_bulk_exclusions(config)
             })
-            .fold(
+            .try_fold(
                 String::new(),
                 |mut display_set_buffer, (display_set_type, snap_or_live_set)| {
                     let component_buffer: String = snap_or_live_set
                         .iter()
                         .map(|pathdata| {
-                            pathdata.format(config, &display_set_type, padding_collection)
+                            let mut line =
+                                pathdata.format(config, &display_set_type, padding_collection)?;
+
+                            if config.opt_show_duplicates
+                                && matches!(display_set_type, DisplaySetType::IsSnap)
+                            {
+                                line += &pathdata.format_duplicates(config, padding_collection)?;
+                            }
+
+                            Ok(line)
                         })
-                        .collect();
+                        .collect::<HttmResult<String>>()?;
 
                     // add each buffer to the set - print fancy border string above, below and between sets
                     if matches!(config.print_mode, PrintMode::FormattedNotPretty) {
@@ -180,19 +250,41 @@ impl<'a> DisplaySet<'a> {
                         display_set_buffer += &component_buffer;
                         display_set_buffer += &padding_collection.fancy_border_string;
                     }
-                    display_set_buffer
+                    Ok(display_set_buffer)
                 },
             )
     }
 }
 
 impl PathData {
+    // identical versions omitted in favor of this version are listed beneath it, marked as duplicates
+    fn format_duplicates(
+        &self,
+        config: &Config,
+        padding_collection: &PaddingCollection,
+    ) -> HttmResult<String> {
+        DuplicateVersions::of(&self.path_buf)
+            .iter()
+            .map(|duplicate| {
+                let line = duplicate.format(config, &DisplaySetType::IsSnap, padding_collection)?;
+
+                match &config.print_mode {
+                    PrintMode::FormattedNotPretty => Ok(line),
+                    _ => Ok(format!(
+                        "{}{PRETTY_FIXED_WIDTH_PADDING}# duplicate\n",
+                        line.trim_end()
+                    )),
+                }
+            })
+            .collect()
+    }
+
     pub fn format(
         &self,
         config: &Config,
         display_set_type: &DisplaySetType,
         padding_collection: &PaddingCollection,
-    ) -> String {
+    ) -> HttmResult<String> {
         // obtain metadata for timestamp and size
         let metadata = self.md_infallible();
 
@@ -208,7 +300,7 @@ impl PathData {
                 } else {
                     Cow::Borrowed(&padding_collection.phantom_size_pad_str)
                 };
-                let path = self.path_buf.to_string_lossy();
+                let path = display_path(&self.path_buf);
                 let padding = NOT_SO_PRETTY_FIXED_WIDTH_PADDING;
                 (size, path, padding)
             }
@@ -227,14 +319,18 @@ impl PathData {
                     ))
                 };
                 let path = {
-                    let path_buf = &self.path_buf;
+                    let display_name = display_path(&self.path_buf);
 
                     // paint the live strings with ls colors - idx == 1 is 2nd or live set
                     let painted_path_str = match display_set_type {
-                        DisplaySetType::IsLive => {
-                            paint_string(self, path_buf.to_str().unwrap_or_default())
-                        }
-                        DisplaySetType::IsSnap => path_buf.to_string_lossy(),
+                        DisplaySetType::IsLive => paint_string(
+                            self,
+                            &display_name,
+                            config
+                                .color_mode
+                                .is_enabled(std::io::stdout().is_terminal()),
+                        ),
+                        DisplaySetType::IsSnap => Cow::Borrowed(display_name.as_ref()),
                     };
 
                     Cow::Owned(format!(
@@ -250,77 +346,288 @@ impl PathData {
         };
 
         let display_date = if self.metadata.is_some() {
-            Cow::Owned(date_string(
-                config.requested_utc_offset,
+            Cow::Owned(display_version_date(
+                config,
                 &metadata.modify_time,
-                DateFormat::Display,
-            ))
+                padding_collection,
+                display_padding,
+            )?)
         } else {
             Cow::Borrowed(&padding_collection.phantom_date_pad_str)
         };
 
-        format!(
-            "{}{}{}{}{}\n",
-            display_date, display_padding, display_size, display_padding, display_path
+        // an owner column, if requested, is placed between the size and the path
+        let owner_column = match &config.opt_owner {
+            Some(owner_format) => {
+                let owner = self.display_owner(owner_format).unwrap_or_default();
+
+                match &config.print_mode {
+                    PrintMode::FormattedNotPretty => format!("{owner}{display_padding}"),
+                    _ => format!(
+                        "{:<width$}{display_padding}",
+                        owner,
+                        width = padding_collection.owner_padding_len
+                    ),
+                }
+            }
+            None => String::new(),
+        };
+
+        // a redacted version is always annotated, lest its restore fail confusingly
+        let opt_annotation = match display_set_type {
+            DisplaySetType::IsSnap if matches!(config.print_mode, PrintMode::FormattedDefault) => {
+                let opt_history = if config.opt_annotate {
+                    ZpoolHistory::annotate_path(self)
+                } else {
+                    None
+                };
+
+                match (Redaction::is_redacted(self), opt_history) {
+                    (true, Some(history)) => Some(format!("{}, {history}", Redaction::ANNOTATION)),
+                    (true, None) => Some(Redaction::ANNOTATION.to_owned()),
+                    (false, opt_history) => opt_history,
+                }
+            }
+            DisplaySetType::IsSnap | DisplaySetType::IsLive => None,
+        };
+
+        let dataset_column = if config.opt_show_dataset {
+            let label = LiveFilesystemInfo::with_current(|fs_info| {
+                self.dataset_label(&fs_info.map_of_datasets)
+            })
+            .unwrap_or_else(|| "-".to_owned());
+
+            format!("{display_padding}[{label}]")
+        } else {
+            String::new()
+        };
+
+        let line = match opt_annotation {
+            Some(annotation) => format!(
+                "{}{}{}{}{}{}{}{}# {}\n",
+                display_date,
+                display_padding,
+                display_size,
+                display_padding,
+                owner_column,
+                display_path,
+                dataset_column,
+                display_padding,
+                annotation
+            ),
+            None => format!(
+                "{}{}{}{}{}{}{}\n",
+                display_date,
+                display_padding,
+                display_size,
+                display_padding,
+                owner_column,
+                display_path,
+                dataset_column
+            ),
+        };
+
+        Ok(line)
+    }
+}
+
+// the date column, or columns, as DateDisplay requests, the age column is only padded when pretty
+fn display_version_date(
+    config: &Config,
+    modify_time: &SystemTime,
+    padding_collection: &PaddingCollection,
+    display_padding: &str,
+) -> HttmResult<String> {
+    let absolute = || {
+        date_string(
+            config.requested_utc_offset,
+            modify_time,
+            DateFormat::Display,
         )
+    };
+
+    let relative = || match &config.print_mode {
+        PrintMode::FormattedNotPretty => display_relative_date(modify_time),
+        _ => format!(
+            "{:<width$}",
+            display_relative_date(modify_time),
+            width = padding_collection.age_padding_len
+        ),
+    };
+
+    match config.date_display {
+        DateDisplay::Absolute => absolute(),
+        DateDisplay::Relative => Ok(relative()),
+        DateDisplay::RelativeAndAbsolute => {
+            Ok(format!("{}{display_padding}{}", relative(), absolute()?))
+        }
+    }
+}
+
+impl PathData {
+    pub fn raw_fields(&self, raw_fields: &[RawField]) -> String {
+        // phantom versions have no metadata, so print empty fields rather than dummy values
+        let fields: Vec<String> = raw_fields
+            .iter()
+            .map(|field| match field {
+                RawField::Path => display_path(&self.path_buf).into_owned(),
+                RawField::Mtime => self
+                    .metadata
+                    .map(|md| {
+                        let since_epoch = md
+                            .modify_time
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .unwrap_or_default();
+
+                        format!(
+                            "{}.{:09}",
+                            since_epoch.as_secs(),
+                            since_epoch.subsec_nanos()
+                        )
+                    })
+                    .unwrap_or_default(),
+                RawField::Size => self
+                    .metadata
+                    .map(|md| md.size.to_string())
+                    .unwrap_or_default(),
+                RawField::Snap => self
+                    .snap_name()
+                    .map(|snap_name| snap_name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                RawField::Dataset => LiveFilesystemInfo::with_current(|fs_info| {
+                    self.dataset_label(&fs_info.map_of_datasets)
+                })
+                .unwrap_or_default(),
+            })
+            .collect();
+
+        fields.join(RAW_FIELDS_DELIMITER)
     }
 }
 
 pub struct PaddingCollection {
     pub size_padding_len: usize,
+    pub owner_padding_len: usize,
+    pub age_padding_len: usize,
     pub fancy_border_string: String,
     pub phantom_date_pad_str: String,
     pub phantom_size_pad_str: String,
 }
 
 impl PaddingCollection {
-    pub fn new(config: &Config, display_set: &DisplaySet) -> PaddingCollection {
+    pub fn new(config: &Config, display_set: &DisplaySet) -> HttmResult<PaddingCollection> {
         // calculate padding and borders for display later
-        let (size_padding_len, fancy_border_len) = display_set.iter().flatten().fold(
-            (0usize, 0usize),
-            |(mut size_padding_len, mut fancy_border_len), pathdata| {
-                let metadata = pathdata.md_infallible();
-
-                let (display_date, display_size, display_path) = {
-                    let date = date_string(
-                        config.requested_utc_offset,
-                        &metadata.modify_time,
-                        DateFormat::Display,
-                    );
-                    let size = format!(
-                        "{:>width$}",
-                        display_human_size(metadata.size),
-                        width = size_padding_len
-                    );
-                    let path = pathdata.path_buf.to_string_lossy();
+        let (size_padding_len, owner_padding_len, age_padding_len, fancy_border_len) =
+            display_set.iter().flatten().try_fold(
+                (0usize, 0usize, 0usize, 0usize),
+                |(
+                    mut size_padding_len,
+                    mut owner_padding_len,
+                    mut age_padding_len,
+                    mut fancy_border_len,
+                ),
+                 pathdata| {
+                    let metadata = pathdata.md_infallible();
+
+                    let display_age_len = match config.date_display {
+                        DateDisplay::Absolute => 0usize,
+                        DateDisplay::Relative | DateDisplay::RelativeAndAbsolute => {
+                            display_relative_date(&metadata.modify_time).len()
+                        }
+                    };
 
-                    (date, size, path)
-                };
+                    let (display_date, display_size, display_path) = {
+                        let date = match config.date_display {
+                            DateDisplay::Relative => String::new(),
+                            DateDisplay::Absolute | DateDisplay::RelativeAndAbsolute => {
+                                date_string(
+                                    config.requested_utc_offset,
+                                    &metadata.modify_time,
+                                    DateFormat::Display,
+                                )?
+                            }
+                        };
+                        let size = format!(
+                            "{:>width$}",
+                            display_human_size(metadata.size),
+                            width = size_padding_len
+                        );
+                        let path = display_path(&pathdata.path_buf);
+
+                        (date, size, path)
+                    };
 
-                let display_size_len = display_human_size(metadata.size).len();
-                let formatted_line_len = display_date.len()
-                    + display_size.len()
-                    + display_path.len()
-                    + PRETTY_FIXED_WIDTH_PADDING_LEN_X2
-                    + QUOTATION_MARKS_LEN;
-
-                size_padding_len = display_size_len.max(size_padding_len);
-                fancy_border_len = formatted_line_len.max(fancy_border_len);
-                (size_padding_len, fancy_border_len)
-            },
-        );
+                    let display_owner_len = match &config.opt_owner {
+                        Some(owner_format) => pathdata
+                            .display_owner(owner_format)
+                            .map(|owner| owner.chars().count())
+                            .unwrap_or_default(),
+                        None => 0usize,
+                    };
+
+                    let display_size_len = display_human_size(metadata.size).len();
+                    let mut formatted_line_len = display_date.len()
+                        + display_size.len()
+                        + display_path.len()
+                        + PRETTY_FIXED_WIDTH_PADDING_LEN_X2
+                        + QUOTATION_MARKS_LEN;
+
+                    if config.opt_owner.is_some() {
+                        formatted_line_len += display_owner_len + PRETTY_FIXED_WIDTH_PADDING.len();
+                    }
+
+                    // the age column, and, if the date follows, the padding between them
+                    match config.date_display {
+                        DateDisplay::Absolute => (),
+                        DateDisplay::Relative => formatted_line_len += display_age_len,
+                        DateDisplay::RelativeAndAbsolute => {
+                            formatted_line_len += display_age_len + PRETTY_FIXED_WIDTH_PADDING.len()
+                        }
+                    }
+
+                    if config.opt_show_dataset {
+                        // the label and its brackets
+                        formatted_line_len += LiveFilesystemInfo::with_current(|fs_info| {
+                            pathdata.dataset_label(&fs_info.map_of_datasets)
+                        })
+                        .map(|label| label.chars().count())
+                        .unwrap_or(1)
+                            + PRETTY_FIXED_WIDTH_PADDING.len()
+                            + 2;
+                    }
+
+                    size_padding_len = display_size_len.max(size_padding_len);
+                    owner_padding_len = display_owner_len.max(owner_padding_len);
+                    age_padding_len = display_age_len.max(age_padding_len);
+                    fancy_border_len = formatted_line_len.max(fancy_border_len);
+                    HttmResult::Ok((
+                        size_padding_len,
+                        owner_padding_len,
+                        age_padding_len,
+                        fancy_border_len,
+                    ))
+                },
+            )?;
 
         let fancy_border_string: String = Self::fancy_border_string(fancy_border_len);
 
+        let phantom_date_len = date_string(
+            config.requested_utc_offset,
+            &PHANTOM_DATE,
+            DateFormat::Display,
+        )?
+        .chars()
+        .count();
+
         let phantom_date_pad_str = format!(
             "{:<width$}",
             "",
-            width = date_string(
-                config.requested_utc_offset,
-                &PHANTOM_DATE,
-                DateFormat::Display
-            )
-            .len()
+            width = match config.date_display {
+                DateDisplay::Absolute => phantom_date_len,
+                DateDisplay::Relative => age_padding_len,
+                DateDisplay::RelativeAndAbsolute =>
+                    age_padding_len + PRETTY_FIXED_WIDTH_PADDING.len() + phantom_date_len,
+            }
         );
         let phantom_size_pad_str = format!(
             "{:<width$}",
@@ -328,12 +635,14 @@ impl PaddingCollection {
             width = display_human_size(PHANTOM_SIZE).len()
         );
 
-        PaddingCollection {
+        Ok(PaddingCollection {
             size_padding_len,
+            owner_padding_len,
+            age_padding_len,
             fancy_border_string,
             phantom_date_pad_str,
             phantom_size_pad_str,
-        }
+        })
     }
 
     fn fancy_border_string(fancy_border_len: usize) -> String {
@@ -15,14 +15,46 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use std::path::Path;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
 use crate::config::generate::NumVersionsMode;
+use crate::data::json::JsonResponse;
 use crate::data::paths::PathData;
 use crate::display_map::format::PrintAsMap;
 use crate::lookup::versions::VersionsMap;
 use crate::VersionsDisplayWrapper;
 
+pub struct NumVersionsRecord<'a> {
+    path: &'a Path,
+    num_versions: usize,
+    has_snap: bool,
+    identical_to_live: bool,
+}
+
+impl<'a> Serialize for NumVersionsRecord<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("NumVersions", 4)?;
+
+        state.serialize_field("path", &self.path)?;
+        state.serialize_field("num_versions", &self.num_versions)?;
+        state.serialize_field("has_snap", &self.has_snap)?;
+        state.serialize_field("identical_to_live", &self.identical_to_live)?;
+        state.end()
+    }
+}
+
 impl<'a> VersionsDisplayWrapper<'a> {
     pub fn format_as_num_versions(&self, num_versions_mode: &NumVersionsMode) -> String {
+        if self.config.opt_json {
+            return self.num_versions_to_json(num_versions_mode);
+        }
+
         // let delimiter = get_delimiter(config);
         let delimiter = '\n';
 
@@ -118,41 +150,65 @@ impl<'a> VersionsDisplayWrapper<'a> {
                     ))
                 }
             }
-            NumVersionsMode::Multiple => {
-                if num_versions == 0
-                    || (num_versions == 1
-                        && VersionsMap::is_live_version_redundant(live_version, snaps))
-                {
-                    None
-                } else {
-                    Some(format!("{display_path}{delimiter}"))
-                }
-            }
-            NumVersionsMode::SingleAll => {
-                if num_versions == 0
-                    || (num_versions == 1
-                        && VersionsMap::is_live_version_redundant(live_version, snaps))
-                {
+            NumVersionsMode::Multiple
+            | NumVersionsMode::SingleAll
+            | NumVersionsMode::SingleNoSnap
+            | NumVersionsMode::SingleWithSnap => {
+                if Self::is_selected(num_versions_mode, live_version, snaps) {
                     Some(format!("{display_path}{delimiter}"))
                 } else {
                     None
                 }
             }
-            NumVersionsMode::SingleNoSnap => {
-                if num_versions == 0 {
-                    Some(format!("{display_path}{delimiter}"))
-                } else {
-                    None
+        }
+    }
+
+    fn is_selected(
+        num_versions_mode: &NumVersionsMode,
+        live_version: &PathData,
+        snaps: &[PathData],
+    ) -> bool {
+        let num_snaps = snaps.len();
+        let is_live_redundant = VersionsMap::is_live_version_redundant(live_version, snaps);
+        let is_single = num_snaps == 0 || (num_snaps == 1 && is_live_redundant);
+
+        match num_versions_mode {
+            NumVersionsMode::AllNumerals | NumVersionsMode::AllGraph => true,
+            NumVersionsMode::Multiple => !is_single,
+            NumVersionsMode::SingleAll => is_single,
+            NumVersionsMode::SingleNoSnap => num_snaps == 0,
+            NumVersionsMode::SingleWithSnap => num_snaps == 1 && is_live_redundant,
+        }
+    }
+
+    // the graph is only a picture of these same counts, so every mode emits the same records,
+    // and the mode only determines which paths are included
+    fn num_versions_to_json(&self, num_versions_mode: &NumVersionsMode) -> String {
+        let records: Vec<NumVersionsRecord> = self
+            .iter()
+            .filter(|(live_version, _snaps)| {
+                if live_version.metadata.is_none() {
+                    eprintln!("{} : Path does not exist.", live_version.path_buf.display());
+                    return false;
                 }
-            }
-            NumVersionsMode::SingleWithSnap => {
-                if num_versions == 1 && VersionsMap::is_live_version_redundant(live_version, snaps)
-                {
-                    Some(format!("{display_path}{delimiter}"))
-                } else {
-                    None
+
+                true
+            })
+            .filter(|(live_version, snaps)| {
+                Self::is_selected(num_versions_mode, live_version, snaps)
+            })
+            .map(|(live_version, snaps)| {
+                let identical_to_live = VersionsMap::is_live_version_redundant(live_version, snaps);
+
+                NumVersionsRecord {
+                    path: &live_version.path_buf,
+                    num_versions: snaps.len() + usize::from(!identical_to_live),
+                    has_snap: !snaps.is_empty(),
+                    identical_to_live,
                 }
-            }
-        }
+            })
+            .collect();
+
+        JsonResponse::num_versions(&records).to_json(&self.config.print_mode)
     }
 }
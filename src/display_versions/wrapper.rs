@@ -17,13 +17,13 @@
 
 use std::{collections::BTreeMap, ops::Deref};
 
-use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
 use crate::config::generate::{BulkExclusion, Config, ExecMode, PrintMode};
+use crate::data::json::JsonResponse;
 use crate::data::paths::PathData;
 use crate::display_map::format::PrintAsMap;
-use crate::library::utility::delimiter;
+use crate::library::results::HttmResult;
 use crate::lookup::versions::VersionsMap;
 
 pub struct VersionsDisplayWrapper<'a> {
@@ -31,59 +31,63 @@ pub struct VersionsDisplayWrapper<'a> {
     pub map: VersionsMap,
 }
 
-impl<'a> std::string::ToString for VersionsDisplayWrapper<'a> {
-    fn to_string(&self) -> String {
+impl<'a> Deref for VersionsDisplayWrapper<'a> {
+    type Target = BTreeMap<PathData, Vec<PathData>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<'a> VersionsDisplayWrapper<'a> {
+    pub fn from(config: &'a Config, map: VersionsMap) -> Self {
+        Self { config, map }
+    }
+
+    pub fn to_output_string(&self) -> HttmResult<String> {
         match &self.config.exec_mode {
             ExecMode::NumVersions(num_versions_mode) => {
-                self.format_as_num_versions(num_versions_mode)
+                Ok(self.format_as_num_versions(num_versions_mode))
             }
             _ => {
                 if self.config.opt_last_snap.is_some() {
-                    let printable_map = PrintAsMap::from(&self.map);
-                    return printable_map.to_string();
+                    let printable_map = if self.config.opt_group_by_snapshot {
+                        PrintAsMap::by_snapshot(&self.map)
+                    } else {
+                        PrintAsMap::from(&self.map)
+                    };
+                    return Ok(printable_map.to_string());
                 }
 
                 if self.config.opt_json {
-                    return self.to_json();
+                    return Ok(self.to_json());
                 }
 
                 self.format()
             }
         }
     }
-}
 
-impl<'a> Deref for VersionsDisplayWrapper<'a> {
-    type Target = BTreeMap<PathData, Vec<PathData>>;
+    // raw modes output the exact bytes of each path, all others are necessarily strings
+    pub fn to_bytes(&self) -> HttmResult<Vec<u8>> {
+        let is_raw_paths = matches!(
+            self.config.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) && !matches!(self.config.exec_mode, ExecMode::NumVersions(_))
+            && self.config.opt_last_snap.is_none()
+            && !self.config.opt_json
+            && self.config.opt_raw_fields.is_none()
+            && !self.config.opt_escape;
 
-    fn deref(&self) -> &Self::Target {
-        &self.map
-    }
-}
+        if is_raw_paths {
+            return Ok(self.format_raw_bytes());
+        }
 
-impl<'a> VersionsDisplayWrapper<'a> {
-    pub fn from(config: &'a Config, map: VersionsMap) -> Self {
-        Self { config, map }
+        self.to_output_string().map(String::into_bytes)
     }
 
     pub fn to_json(&self) -> String {
-        let res = match self.config.print_mode {
-            PrintMode::FormattedNotPretty | PrintMode::RawNewline | PrintMode::RawZero => {
-                serde_json::to_string(self)
-            }
-            PrintMode::FormattedDefault => serde_json::to_string_pretty(self),
-        };
-
-        match res {
-            Ok(s) => {
-                let delimiter = delimiter();
-                format!("{s}{delimiter}")
-            }
-            Err(error) => {
-                eprintln!("Error: {error}");
-                std::process::exit(1)
-            }
-        }
+        JsonResponse::versions(self).to_json(&self.config.print_mode)
     }
 }
 
@@ -92,9 +96,6 @@ impl<'a> Serialize for VersionsDisplayWrapper<'a> {
     where
         S: Serializer,
     {
-        // 3 is the number of fields in the struct.
-        let mut state = serializer.serialize_struct("VersionMap", 1)?;
-
         // add live file key to values if needed before serializing
         let new_map: BTreeMap<String, Vec<PathData>> = self
             .deref()
@@ -111,7 +112,6 @@ impl<'a> Serialize for VersionsDisplayWrapper<'a> {
             })
             .collect();
 
-        state.serialize_field("versions", &new_map)?;
-        state.end()
+        new_map.serialize(serializer)
     }
 }
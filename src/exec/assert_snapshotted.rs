@@ -0,0 +1,182 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use hashbrown::HashMap;
+
+use crate::data::filesystem_info::{FilesystemInfo, LiveFilesystemInfo};
+use crate::data::paths::PathData;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{delimiter, display_human_duration, print_output_buf};
+use crate::lookup::all_snaps::AllSnaps;
+use crate::lookup::versions::ProximateDatasetAndOptAlts;
+use crate::parse::aliases::FilesystemType;
+use crate::{BTRFS_SNAPPER_SUFFIX, GLOBAL_CONFIG};
+
+// snapper writes each snapshot's info file once, when the snapshot is taken
+pub const SNAPPER_INFO_FILE: &str = "info.xml";
+
+// a gate for scripts: succeeds only if every input file is contained within a snapshot,
+// and, if WITHIN is given, within a snapshot taken no longer ago than WITHIN
+pub struct AssertSnapshotted {
+    opt_cutoff: Option<SystemTime>,
+    // key: full ZFS snapshot name, like "rpool/data@snap_1", val: that snapshot's creation time
+    zfs_creation_times: HashMap<String, SystemTime>,
+}
+
+impl AssertSnapshotted {
+    pub fn exec(opt_within: &Option<Duration>) -> HttmResult<()> {
+        let assert_snapshotted = Self::new(opt_within);
+
+        let offending: Vec<&PathData> = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .filter(|pathdata| !assert_snapshotted.is_snapshotted(pathdata))
+            .collect();
+
+        if offending.is_empty() {
+            return Ok(());
+        }
+
+        // offending paths are output, so that they may be snapshotted or otherwise dealt with
+        let delimiter = delimiter();
+
+        let output_buf: String = offending
+            .iter()
+            .map(|pathdata| format!("{}{delimiter}", pathdata.path_buf.display()))
+            .collect();
+
+        print_output_buf(output_buf)?;
+
+        let msg = match opt_within {
+            Some(within) => format!(
+                "{} of the {} files specified are not contained in any snapshot taken within the last {}.",
+                offending.len(),
+                GLOBAL_CONFIG.paths.len(),
+                display_human_duration(*within)
+            ),
+            None => format!(
+                "{} of the {} files specified are not contained in any snapshot.",
+                offending.len(),
+                GLOBAL_CONFIG.paths.len()
+            ),
+        };
+
+        Err(HttmError::new(&msg).into())
+    }
+
+    fn new(opt_within: &Option<Duration>) -> Self {
+        let opt_cutoff = opt_within.map(|within| {
+            SystemTime::now()
+                .checked_sub(within)
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        });
+
+        // one listing of every snapshot is far cheaper than asking after each snapshot in turn,
+        // and, if there are no local ZFS datasets, there is nothing to list
+        let zfs_creation_times = match opt_cutoff {
+            Some(_) => AllSnaps::new(&None)
+                .map(AllSnaps::into_inner)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|snap_info| (snap_info.name, snap_info.creation))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        Self {
+            opt_cutoff,
+            zfs_creation_times,
+        }
+    }
+
+    fn is_snapshotted(&self, pathdata: &PathData) -> bool {
+        LiveFilesystemInfo::with_current(|fs_info| self.is_snapshotted_in(fs_info, pathdata))
+    }
+
+    fn is_snapshotted_in(&self, fs_info: &FilesystemInfo, pathdata: &PathData) -> bool {
+        let prox_opt_alts = match ProximateDatasetAndOptAlts::with_fs_info(fs_info, pathdata) {
+            Ok(prox_opt_alts) => prox_opt_alts,
+            Err(_) => return false,
+        };
+
+        prox_opt_alts
+            .into_search_bundles()
+            .any(|relative_path_snap_mounts| {
+                relative_path_snap_mounts
+                    .snap_mounts
+                    .iter()
+                    .any(|snap_mount| {
+                        let snap_path = snap_mount.join(relative_path_snap_mounts.relative_path);
+
+                        let snap_md = match snap_path.symlink_metadata() {
+                            Ok(snap_md) => snap_md,
+                            Err(_) => return false,
+                        };
+
+                        match self.opt_cutoff {
+                            Some(cutoff) => {
+                                let snap_time = self
+                                    .snapshot_time(
+                                        fs_info,
+                                        relative_path_snap_mounts.dataset_of_interest,
+                                        snap_mount,
+                                    )
+                                    .or_else(|| snap_md.modified().ok());
+
+                                matches!(snap_time, Some(snap_time) if snap_time >= cutoff)
+                            }
+                            None => true,
+                        }
+                    })
+            })
+    }
+
+    // when a snapshot was taken is only known for ZFS and snapper snapshots, for
+    // any other, the caller falls back to the modify time of the version itself
+    fn snapshot_time(
+        &self,
+        fs_info: &FilesystemInfo,
+        dataset_of_interest: &Path,
+        snap_mount: &Path,
+    ) -> Option<SystemTime> {
+        let dataset_md = fs_info.map_of_datasets.get(dataset_of_interest)?;
+
+        match dataset_md.fs_type {
+            FilesystemType::Zfs => {
+                let snap_name = snap_mount.file_name()?.to_string_lossy();
+                let full_name = format!("{}@{snap_name}", dataset_md.source.to_string_lossy());
+
+                self.zfs_creation_times.get(&full_name).copied()
+            }
+            FilesystemType::Btrfs
+                if snap_mount.file_name() == Some(BTRFS_SNAPPER_SUFFIX.as_ref()) =>
+            {
+                snap_mount
+                    .parent()?
+                    .join(SNAPPER_INFO_FILE)
+                    .symlink_metadata()
+                    .ok()?
+                    .modified()
+                    .ok()
+            }
+            FilesystemType::Btrfs | FilesystemType::Nilfs2 => None,
+        }
+    }
+}
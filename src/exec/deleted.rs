@@ -22,7 +22,7 @@ use skim::prelude::*;
 
 use crate::config::generate::DeletedMode;
 use crate::data::paths::{BasicDirEntryInfo, PathData};
-use crate::exec::recursive::{PathProvenance, SharedRecursive};
+use crate::exec::recursive::{EntrySender, PathProvenance, SharedRecursive};
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::{is_channel_closed, Never};
 use crate::lookup::deleted::{DeletedFiles, LastInTimeSet};
@@ -35,24 +35,24 @@ impl SpawnDeletedThread {
     pub fn exec(
         requested_dir: &Path,
         deleted_scope: &Scope,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
         hangup_rx: &Receiver<Never>,
     ) {
         // spawn_enumerate_deleted will send deleted files back to
         // the main thread for us
         let requested_dir_clone = requested_dir.to_path_buf();
-        let skim_tx_clone = skim_tx.clone();
+        let entry_tx_clone = entry_tx.clone();
         let hangup_rx_clone = hangup_rx.clone();
 
         deleted_scope.spawn(move |_| {
-            let _ = Self::enter_directory(&requested_dir_clone, &skim_tx_clone, &hangup_rx_clone);
+            let _ = Self::enter_directory(&requested_dir_clone, &entry_tx_clone, &hangup_rx_clone);
         })
     }
 
     // deleted file search for all modes
     fn enter_directory(
         requested_dir: &Path,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
         hangup_rx: &Receiver<Never>,
     ) -> HttmResult<()> {
         // check -- should deleted threads keep working?
@@ -81,7 +81,7 @@ impl SpawnDeletedThread {
             &vec_dirs,
             PathProvenance::IsPhantom,
             requested_dir,
-            skim_tx,
+            entry_tx,
         )?;
 
         // disable behind deleted dirs with DepthOfOne,
@@ -102,7 +102,7 @@ impl SpawnDeletedThread {
                     RecurseBehindDeletedDir::exec(
                         deleted_dir.as_path(),
                         requested_dir,
-                        skim_tx,
+                        entry_tx,
                         hangup_rx,
                     )
                 });
@@ -126,7 +126,7 @@ impl RecurseBehindDeletedDir {
     fn exec(
         deleted_dir: &Path,
         requested_dir: &Path,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
         hangup_rx: &Receiver<Never>,
     ) -> HttmResult<()> {
         // check -- should deleted threads keep working?
@@ -148,7 +148,7 @@ impl RecurseBehindDeletedDir {
                     Path::new(dir_name),
                     from_deleted_dir,
                     from_requested_dir,
-                    skim_tx,
+                    entry_tx,
                 ) {
                     Ok(res) if !res.vec_dirs.is_empty() => Vec::from([res]),
                     _ => return Ok(()),
@@ -172,7 +172,7 @@ impl RecurseBehindDeletedDir {
                         dir_name,
                         &item.deleted_dir_on_snap,
                         &item.pseudo_live_dir,
-                        skim_tx,
+                        entry_tx,
                     )
                 })
                 .try_for_each(|res| {
@@ -191,7 +191,7 @@ impl RecurseBehindDeletedDir {
         dir_name: &Path,
         from_deleted_dir: &Path,
         from_requested_dir: &Path,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
     ) -> HttmResult<RecurseBehindDeletedDir> {
         // deleted_dir_on_snap is the path from the deleted dir on the snapshot
         // pseudo_live_dir is the path from the fake, deleted directory that once was
@@ -206,7 +206,7 @@ impl RecurseBehindDeletedDir {
             &vec_dirs,
             PathProvenance::IsPhantom,
             &pseudo_live_dir,
-            skim_tx,
+            entry_tx,
         )?;
 
         Ok(RecurseBehindDeletedDir {
@@ -0,0 +1,299 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::cmp::Ordering;
+use std::ffi::OsString;
+use std::fs::{read_dir, Metadata};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use hashbrown::HashMap;
+use rayon::prelude::*;
+
+use crate::config::generate::{ListSnapsOfType, PrintMode};
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::{BasicDirEntryInfo, CompareVersionsContainer, PathData};
+use crate::exec::recursive::SharedRecursive;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{delimiter, display_path, print_output_bytes};
+use crate::lookup::versions::ProximateDatasetAndOptAlts;
+use crate::GLOBAL_CONFIG;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DriftKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl DriftKind {
+    fn label(&self) -> &'static str {
+        match self {
+            DriftKind::Added => "added",
+            DriftKind::Modified => "modified",
+            DriftKind::Deleted => "deleted",
+        }
+    }
+}
+
+struct Drifted {
+    kind: DriftKind,
+    // always the live path, even for a file which now only exists upon the snapshot
+    path: PathBuf,
+}
+
+pub struct Drift;
+
+impl Drift {
+    pub fn exec() -> HttmResult<()> {
+        let mut drifted: Vec<Drifted> = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .map(|pathdata| Self::drift_beneath(&pathdata.path_buf))
+            .collect::<HttmResult<Vec<Vec<Drifted>>>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        drifted.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        print_output_bytes(&Self::format(&drifted))
+    }
+
+    // walks the live dir and its counterpart upon the newest snapshot side by side
+    fn drift_beneath(requested_dir: &Path) -> HttmResult<Vec<Drifted>> {
+        if !requested_dir.is_dir() {
+            let msg = format!("DRIFT requires a directory: {:?}", requested_dir);
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let snap_dir = match Self::newest_snap_dir(requested_dir) {
+            Some(snap_dir) if snap_dir.is_dir() => snap_dir,
+            _ => {
+                let msg = format!(
+                    "httm could not find this directory upon the newest snapshot of its dataset: {:?}",
+                    requested_dir
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+        };
+
+        let mut drifted: Vec<Drifted> = Vec::new();
+        let mut queue: Vec<(PathBuf, PathBuf)> = vec![(requested_dir.to_path_buf(), snap_dir)];
+
+        while let Some((live_dir, snap_dir)) = queue.pop() {
+            // no errors will be propagated here, like any other recursive search
+            // far too likely to run into a dir we don't have permissions to view
+            let (live_dirs, live_files) = match SharedRecursive::entries_partitioned(&live_dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            let mut snap_entries: HashMap<OsString, Metadata> = match read_dir(&snap_dir) {
+                Ok(read_dir) => read_dir
+                    .flatten()
+                    .filter_map(|dir_entry| {
+                        let md = dir_entry.path().symlink_metadata().ok()?;
+                        Some((dir_entry.file_name(), md))
+                    })
+                    .collect(),
+                Err(_) => continue,
+            };
+
+            live_dirs.into_iter().for_each(|entry| {
+                match snap_entries.remove(entry.filename()) {
+                    Some(snap_md) if snap_md.is_dir() => {
+                        // a nested dataset has snapshots of its own
+                        let next_snap_dir = Self::nested_snap_dir(&entry.path)
+                            .unwrap_or_else(|| snap_dir.join(entry.filename()));
+
+                        queue.push((entry.path, next_snap_dir));
+                    }
+                    Some(_snap_md) => drifted.push(Drifted {
+                        kind: DriftKind::Modified,
+                        path: entry.path,
+                    }),
+                    None => drifted.push(Drifted {
+                        kind: DriftKind::Added,
+                        path: entry.path,
+                    }),
+                }
+            });
+
+            let live_and_snap: Vec<(BasicDirEntryInfo, Option<Metadata>)> = live_files
+                .into_iter()
+                .map(|entry| {
+                    let opt_snap_md = snap_entries.remove(entry.filename());
+                    (entry, opt_snap_md)
+                })
+                .collect();
+
+            // comparing contents may mean reading back both versions in full, so compare in parallel
+            drifted.par_extend(
+                live_and_snap
+                    .into_par_iter()
+                    .filter_map(|(entry, opt_snap_md)| {
+                        let kind = match opt_snap_md {
+                            Some(snap_md) if snap_md.is_dir() => DriftKind::Modified,
+                            Some(snap_md) => {
+                                let snap_path = snap_dir.join(entry.filename());
+
+                                if !Self::is_modified(&entry.path, &snap_path, snap_md) {
+                                    return None;
+                                }
+
+                                DriftKind::Modified
+                            }
+                            None => DriftKind::Added,
+                        };
+
+                        Some(Drifted {
+                            kind,
+                            path: entry.path,
+                        })
+                    }),
+            );
+
+            // whatever remains upon the snapshot has no live counterpart
+            drifted.extend(
+                snap_entries
+                    .into_keys()
+                    .filter(|file_name| {
+                        GLOBAL_CONFIG.opt_no_filter
+                            || !GLOBAL_CONFIG.opt_no_hidden
+                            || !file_name.as_bytes().starts_with(b".")
+                    })
+                    .map(|file_name| Drifted {
+                        kind: DriftKind::Deleted,
+                        path: live_dir.join(file_name),
+                    }),
+            );
+        }
+
+        Ok(drifted)
+    }
+
+    fn newest_snap_dir(live_dir: &Path) -> Option<PathBuf> {
+        let pathdata = PathData::from(live_dir);
+
+        LiveFilesystemInfo::with_current(|fs_info| {
+            let prox_opt_alts =
+                ProximateDatasetAndOptAlts::with_fs_info(fs_info, &pathdata).ok()?;
+
+            // only the proximate dataset, and not any alternate, is compared against
+            prox_opt_alts
+                .into_search_bundles()
+                .next()
+                .and_then(|relative_path_snap_mounts| {
+                    relative_path_snap_mounts
+                        .newest_snap_mount()
+                        .map(|snap_mount| snap_mount.join(relative_path_snap_mounts.relative_path))
+                })
+        })
+    }
+
+    fn nested_snap_dir(live_dir: &Path) -> Option<PathBuf> {
+        if !LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info.map_of_datasets.contains_key(live_dir)
+        }) {
+            return None;
+        }
+
+        Self::newest_snap_dir(live_dir)
+    }
+
+    fn is_modified(live_path: &Path, snap_path: &Path, snap_md: Metadata) -> bool {
+        let live_md = match live_path.symlink_metadata() {
+            Ok(md) => md,
+            Err(_) => return true,
+        };
+
+        // only a comparison of contents is more exacting than a comparison of metadata
+        let uniqueness = match GLOBAL_CONFIG.uniqueness {
+            ListSnapsOfType::UniqueContents => ListSnapsOfType::UniqueContents,
+            ListSnapsOfType::UniqueMetadata | ListSnapsOfType::All => {
+                ListSnapsOfType::UniqueMetadata
+            }
+        };
+
+        let live =
+            CompareVersionsContainer::new(PathData::new(live_path, Some(live_md)), &uniqueness);
+        let snap =
+            CompareVersionsContainer::new(PathData::new(snap_path, Some(snap_md)), &uniqueness);
+
+        live.cmp(&snap) != Ordering::Equal
+    }
+
+    fn format(drifted: &[Drifted]) -> Vec<u8> {
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            let delimiter = delimiter();
+
+            if GLOBAL_CONFIG.opt_escape {
+                return drifted
+                    .iter()
+                    .map(|drifted| format!("{}{delimiter}", display_path(&drifted.path)))
+                    .collect::<String>()
+                    .into_bytes();
+            }
+
+            // raw paths are output exactly as they are on disk
+            let mut delimiter_buffer = [0u8; 4];
+            let delimiter = delimiter.encode_utf8(&mut delimiter_buffer).as_bytes();
+
+            return drifted
+                .iter()
+                .flat_map(|drifted| {
+                    let mut bytes = drifted.path.as_os_str().as_bytes().to_vec();
+                    bytes.extend_from_slice(delimiter);
+                    bytes
+                })
+                .collect();
+        }
+
+        let padding = DriftKind::Modified.label().len();
+
+        let mut buffer: String = drifted
+            .iter()
+            .map(|drifted| {
+                format!(
+                    "{:>padding$} : {}\n",
+                    drifted.kind.label(),
+                    display_path(&drifted.path)
+                )
+            })
+            .collect();
+
+        let count = |kind: DriftKind| {
+            drifted
+                .iter()
+                .filter(|drifted| drifted.kind == kind)
+                .count()
+        };
+
+        buffer += &format!(
+            "Added: {}, Modified: {}, Deleted: {}\n",
+            count(DriftKind::Added),
+            count(DriftKind::Modified),
+            count(DriftKind::Deleted)
+        );
+
+        buffer.into_bytes()
+    }
+}
@@ -0,0 +1,101 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+
+use crate::data::paths::PathData;
+use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::library::results::HttmResult;
+use crate::library::utility::{into_input_pathdata, print_output_bytes};
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+pub struct FollowStdin;
+
+impl FollowStdin {
+    // the config, and thus our map of datasets and snapshots, is built once and stays warm,
+    // while paths are read and their results displayed, a batch at a time, until stdin is closed
+    pub fn exec() -> HttmResult<()> {
+        let stdin = std::io::stdin();
+        // our own BufReader, because only a BufReader will tell us what it has buffered without blocking
+        let mut stdin = BufReader::new(stdin.lock());
+
+        loop {
+            let batch = Self::next_batch(&mut stdin)?;
+
+            if batch.is_empty() {
+                return Ok(());
+            }
+
+            // a single bad path should not end a session which may be expected to run indefinitely
+            match VersionsMap::new(&GLOBAL_CONFIG, &batch) {
+                Ok(versions_map) => {
+                    let output_buf =
+                        VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_bytes()?;
+
+                    print_output_bytes(&output_buf)?;
+                }
+                Err(err) => eprintln!("WARNING: {err}"),
+            }
+        }
+    }
+
+    // blocks for the first line, then takes every other complete line already buffered,
+    // so a burst of events from a watcher is looked up together. an empty batch means EOF
+    fn next_batch<R: Read>(stdin: &mut BufReader<R>) -> HttmResult<Vec<PathData>> {
+        let mut batch: Vec<PathData> = Vec::new();
+
+        loop {
+            let mut line = Vec::new();
+
+            let bytes_read = match stdin.read_until(b'\n', &mut line) {
+                Ok(bytes_read) => bytes_read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            if bytes_read == 0 {
+                break;
+            }
+
+            match std::str::from_utf8(&line) {
+                Ok(line) => {
+                    let path = line.trim_end_matches(['\n', '\r']);
+
+                    if !path.is_empty() {
+                        batch.push(into_input_pathdata(path));
+                    }
+                }
+                Err(_) => eprintln!(
+                    "WARNING: Skipping a path which is not valid UTF-8: {:?}",
+                    String::from_utf8_lossy(&line)
+                ),
+            }
+
+            let is_more_buffered = stdin.buffer().contains(&b'\n');
+
+            if !batch.is_empty() && !is_more_buffered {
+                break;
+            }
+        }
+
+        batch.sort_unstable();
+        batch.dedup();
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,180 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::File;
+use std::io::Read;
+
+use rayon::prelude::*;
+use regex::bytes::Regex;
+
+use crate::config::generate::PrintMode;
+use crate::data::paths::PathData;
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::results::HttmResult;
+use crate::library::utility::{date_string, delimiter, print_output_buf, DateFormat};
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+// versions are read whole into memory, so we skip any version larger than this
+pub const GREP_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+// like grep, peek at the head of a file for a NULL byte to decide whether it is binary
+const BINARY_DETECTION_LEN: usize = 8_192;
+
+enum GrepMatch {
+    Found,
+    NotFound,
+    Skipped(String),
+}
+
+pub struct GrepVersions;
+
+impl GrepVersions {
+    pub fn exec(regex: &Regex) -> HttmResult<()> {
+        let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
+
+        let output_buf: String = versions_map
+            .iter()
+            .map(|(live_version, snaps)| Self::bisect(regex, live_version, snaps))
+            .collect::<HttmResult<String>>()?;
+
+        print_output_buf(output_buf)
+    }
+
+    fn bisect(regex: &Regex, live_version: &PathData, snaps: &[PathData]) -> HttmResult<String> {
+        // the live version, if it exists, is simply the newest version
+        let mut versions: Vec<&PathData> = snaps.iter().collect();
+
+        if live_version.metadata.is_some() {
+            versions.push(live_version);
+        }
+
+        versions.sort_by_key(|pathdata| pathdata.md_infallible().modify_time);
+
+        // content may appear, disappear, and reappear, so every version is searched,
+        // rather than assuming the pattern is monotonic and probing only log(n) versions
+        let results: Vec<GrepMatch> = versions
+            .par_iter()
+            .map(|pathdata| Self::search(regex, pathdata))
+            .collect();
+
+        versions
+            .iter()
+            .zip(results.iter())
+            .for_each(|(pathdata, grep_match)| {
+                if let GrepMatch::Skipped(reason) = grep_match {
+                    eprintln!(
+                        "WARNING: Skipping search of {:?}, as {reason}.",
+                        pathdata.path_buf
+                    );
+                }
+            });
+
+        let opt_earliest_found = results
+            .iter()
+            .position(|grep_match| matches!(grep_match, GrepMatch::Found));
+
+        // the last version missing the pattern is the newest not found before the earliest found,
+        // or, if the pattern was never found, simply the newest version not found
+        let opt_last_missing = results[..opt_earliest_found.unwrap_or(results.len())]
+            .iter()
+            .rposition(|grep_match| matches!(grep_match, GrepMatch::NotFound));
+
+        let opt_earliest_found = opt_earliest_found.map(|idx| versions[idx]);
+        let opt_last_missing = opt_last_missing.map(|idx| versions[idx]);
+
+        match GLOBAL_CONFIG.print_mode {
+            PrintMode::RawNewline | PrintMode::RawZero => match opt_earliest_found {
+                Some(earliest_found) => {
+                    let delimiter = delimiter();
+                    Ok(format!("{}{delimiter}", earliest_found.path_buf.display()))
+                }
+                None => Ok(String::new()),
+            },
+            PrintMode::FormattedDefault | PrintMode::FormattedNotPretty => {
+                let mut buffer = format!("{:?}\n", live_version.path_buf);
+
+                buffer += &Self::format_version(
+                    "earliest version containing pattern:",
+                    opt_earliest_found,
+                    "no version contains the pattern",
+                )?;
+                buffer += &Self::format_version(
+                    "last version missing pattern:      ",
+                    opt_last_missing,
+                    "no earlier version is missing the pattern",
+                )?;
+
+                Ok(buffer)
+            }
+        }
+    }
+
+    fn format_version(
+        label: &str,
+        opt_version: Option<&PathData>,
+        none_msg: &str,
+    ) -> HttmResult<String> {
+        match opt_version {
+            Some(version) => {
+                let date = date_string(
+                    GLOBAL_CONFIG.requested_utc_offset,
+                    &version.md_infallible().modify_time,
+                    DateFormat::Display,
+                )?;
+
+                Ok(format!("  {label}  {date}  {:?}\n", version.path_buf))
+            }
+            None => Ok(format!("  {label}  {none_msg}\n")),
+        }
+    }
+
+    fn search(regex: &Regex, pathdata: &PathData) -> GrepMatch {
+        if !pathdata.path_buf.is_file() {
+            return GrepMatch::Skipped("it is not a regular file".to_owned());
+        }
+
+        if pathdata.md_infallible().size > GREP_MAX_FILE_SIZE {
+            return GrepMatch::Skipped(format!(
+                "it is larger than the max search size of {GREP_MAX_FILE_SIZE} bytes"
+            ));
+        }
+
+        let _opt_permit = DatasetPermit::acquire(&pathdata.path_buf);
+
+        let mut contents = Vec::new();
+
+        if let Err(err) =
+            File::open(&pathdata.path_buf).and_then(|mut file| file.read_to_end(&mut contents))
+        {
+            return GrepMatch::Skipped(format!("it could not be read: {err}"));
+        }
+
+        if contents
+            .iter()
+            .take(BINARY_DETECTION_LEN)
+            .any(|byte| *byte == 0)
+        {
+            return GrepMatch::Skipped("it appears to be a binary file".to_owned());
+        }
+
+        if regex.is_match(&contents) {
+            GrepMatch::Found
+        } else {
+            GrepMatch::NotFound
+        }
+    }
+}
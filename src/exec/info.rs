@@ -0,0 +1,264 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::{Path, PathBuf};
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::data::filesystem_info::{FilesystemInfo, LiveFilesystemInfo};
+use crate::data::json::JsonResponse;
+use crate::data::paths::{PathData, PathMetadata};
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{date_string, display_path, print_output_buf, DateFormat};
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, VersionsMap};
+use crate::parse::aliases::FilesystemType;
+use crate::{BTRFS_SNAPPER_SUFFIX, GLOBAL_CONFIG};
+
+// a one-stop summary of a single path: where its snapshots reside, and what they contain
+pub struct PathInfo<'a> {
+    pathdata: &'a PathData,
+    opt_dataset: Option<PathBuf>,
+    dataset_mount: PathBuf,
+    opt_fs_type: Option<FilesystemType>,
+    opt_snapshot_dir: Option<PathBuf>,
+    num_snapshots: usize,
+    versions: Vec<PathData>,
+    opt_live_matches_newest: Option<bool>,
+}
+
+impl<'a> Serialize for PathInfo<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("PathInfo", 10)?;
+
+        state.serialize_field("path", &self.pathdata.path_buf)?;
+        state.serialize_field("dataset", &self.opt_dataset)?;
+        state.serialize_field("dataset_mount", &self.dataset_mount)?;
+        state.serialize_field(
+            "fs_type",
+            &self.opt_fs_type.as_ref().map(Self::fs_type_name),
+        )?;
+        state.serialize_field("snapshot_directory", &self.opt_snapshot_dir)?;
+        state.serialize_field("num_snapshots", &self.num_snapshots)?;
+        state.serialize_field("num_unique_versions", &self.versions.len())?;
+        state.serialize_field("oldest_version", &self.opt_oldest())?;
+        state.serialize_field("newest_version", &self.opt_newest())?;
+        state.serialize_field("live_matches_newest_snap", &self.opt_live_matches_newest)?;
+        state.end()
+    }
+}
+
+impl<'a> PathInfo<'a> {
+    pub fn exec() -> HttmResult<()> {
+        let all_info: Vec<PathInfo> = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .map(PathInfo::new)
+            .collect::<HttmResult<Vec<PathInfo>>>()?;
+
+        let output_buf = if GLOBAL_CONFIG.opt_json {
+            JsonResponse::info(&all_info).to_json(&GLOBAL_CONFIG.print_mode)
+        } else {
+            all_info
+                .iter()
+                .map(PathInfo::format)
+                .collect::<HttmResult<Vec<String>>>()?
+                .join("\n")
+        };
+
+        print_output_buf(output_buf)
+    }
+
+    fn new(pathdata: &'a PathData) -> HttmResult<Self> {
+        LiveFilesystemInfo::with_current(|fs_info| Self::with_fs_info(fs_info, pathdata))
+    }
+
+    fn with_fs_info(fs_info: &FilesystemInfo, pathdata: &'a PathData) -> HttmResult<Self> {
+        let prox_opt_alts =
+            ProximateDatasetAndOptAlts::with_fs_info(fs_info, pathdata).map_err(|_err| {
+                let msg = format!(
+                    "httm could not determine the dataset upon which this path resides: {:?}",
+                    pathdata.path_buf
+                );
+                HttmError::new(&msg)
+            })?;
+
+        let dataset_mount = prox_opt_alts.proximate_dataset_mount.to_path_buf();
+
+        let (opt_dataset, opt_fs_type) = Self::dataset_and_fs_type(fs_info, &dataset_mount);
+
+        let num_snapshots = prox_opt_alts
+            .datasets_of_interest
+            .iter()
+            .filter_map(|dataset| fs_info.map_of_snaps.get(dataset))
+            .map(Vec::len)
+            .sum();
+
+        let opt_snapshot_dir = prox_opt_alts
+            .datasets_of_interest
+            .iter()
+            .rev()
+            .filter_map(|dataset| fs_info.map_of_snaps.get(dataset))
+            .flatten()
+            .next()
+            .and_then(|snap_mount| Self::snapshot_dir(snap_mount));
+
+        let mut versions: Vec<PathData> = prox_opt_alts
+            .into_search_bundles()
+            .flat_map(|relative_path_snap_mounts| {
+                relative_path_snap_mounts.versions_processed(&GLOBAL_CONFIG.uniqueness)
+            })
+            .collect();
+
+        versions.sort_by_key(|version| version.md_infallible().modify_time);
+
+        let opt_live_matches_newest = pathdata
+            .metadata
+            .filter(|_live_md| !versions.is_empty())
+            .map(|_live_md| VersionsMap::is_live_version_redundant(pathdata, &versions));
+
+        Ok(Self {
+            pathdata,
+            opt_dataset,
+            dataset_mount,
+            opt_fs_type,
+            opt_snapshot_dir,
+            num_snapshots,
+            versions,
+            opt_live_matches_newest,
+        })
+    }
+
+    fn dataset_and_fs_type(
+        fs_info: &FilesystemInfo,
+        dataset_mount: &Path,
+    ) -> (Option<PathBuf>, Option<FilesystemType>) {
+        if let Some(dataset_md) = fs_info.map_of_datasets.get(dataset_mount) {
+            return (
+                Some(dataset_md.source.clone()),
+                Some(dataset_md.fs_type.clone()),
+            );
+        }
+
+        // a user defined alias has no source dataset, only a remote dir
+        match fs_info
+            .opt_map_of_aliases
+            .as_ref()
+            .and_then(|map_of_aliases| map_of_aliases.get(dataset_mount))
+        {
+            Some(alias_info) => (None, Some(alias_info.fs_type.clone())),
+            None => (None, None),
+        }
+    }
+
+    // snap mounts are each a directory within the snapshot dir, or, for snapper, a "snapshot" dir within one
+    fn snapshot_dir(snap_mount: &Path) -> Option<PathBuf> {
+        let snap_mount = if snap_mount.file_name() == Some(BTRFS_SNAPPER_SUFFIX.as_ref()) {
+            snap_mount.parent()?
+        } else {
+            snap_mount
+        };
+
+        snap_mount.parent().map(Path::to_path_buf)
+    }
+
+    fn fs_type_name(fs_type: &FilesystemType) -> &'static str {
+        match fs_type {
+            FilesystemType::Zfs => "zfs",
+            FilesystemType::Btrfs => "btrfs",
+            FilesystemType::Nilfs2 => "nilfs2",
+        }
+    }
+
+    fn opt_oldest(&self) -> Option<PathMetadata> {
+        self.versions.first().map(PathData::md_infallible)
+    }
+
+    fn opt_newest(&self) -> Option<PathMetadata> {
+        self.versions.last().map(PathData::md_infallible)
+    }
+
+    fn format(&self) -> HttmResult<String> {
+        let not_available = "-".to_owned();
+
+        let display_date = |opt_md: Option<PathMetadata>| match opt_md {
+            Some(md) => date_string(
+                GLOBAL_CONFIG.requested_utc_offset,
+                &md.modify_time,
+                DateFormat::Display,
+            ),
+            None => Ok(not_available.clone()),
+        };
+
+        let dataset = match &self.opt_dataset {
+            Some(dataset) => format!(
+                "{} (mounted at \"{}\")",
+                display_path(dataset),
+                display_path(&self.dataset_mount)
+            ),
+            None => format!("\"{}\"", display_path(&self.dataset_mount)),
+        };
+
+        let fs_type = self
+            .opt_fs_type
+            .as_ref()
+            .map(|fs_type| Self::fs_type_name(fs_type).to_owned())
+            .unwrap_or_else(|| not_available.clone());
+
+        let snapshot_dir = self
+            .opt_snapshot_dir
+            .as_ref()
+            .map(|snapshot_dir| format!("\"{}\"", display_path(snapshot_dir)))
+            .unwrap_or_else(|| not_available.clone());
+
+        let live_matches_newest = match self.opt_live_matches_newest {
+            Some(true) => "yes".to_owned(),
+            Some(false) => "no".to_owned(),
+            None => not_available.clone(),
+        };
+
+        let fields: [(&str, String); 9] = [
+            (
+                "path",
+                format!("\"{}\"", display_path(&self.pathdata.path_buf)),
+            ),
+            ("dataset", dataset),
+            ("filesystem", fs_type),
+            ("snapshot directory", snapshot_dir),
+            ("snapshots", self.num_snapshots.to_string()),
+            ("unique versions", self.versions.len().to_string()),
+            ("oldest version", display_date(self.opt_oldest())?),
+            ("newest version", display_date(self.opt_newest())?),
+            ("live matches newest", live_matches_newest),
+        ];
+
+        let padding = fields
+            .iter()
+            .map(|(label, _value)| label.len())
+            .max()
+            .unwrap_or_default()
+            + 1;
+
+        Ok(fields
+            .iter()
+            .map(|(label, value)| format!("{:<padding$} {value}\n", format!("{label}:")))
+            .collect())
+    }
+}
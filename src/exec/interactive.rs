@@ -15,32 +15,58 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use std::collections::BTreeSet;
+use std::fs::{read_dir, Metadata};
+use std::io::Write;
+use std::os::unix::fs::MetadataExt;
 use std::thread::JoinHandle;
+use std::time::SystemTime;
 use std::{io::Cursor, path::Path, path::PathBuf, thread};
 
-use crossbeam_channel::unbounded;
 use skim::prelude::*;
 
 use crate::config::generate::{
-    ExecMode, InteractiveMode, PrintMode, RestoreMode, RestoreSnapGuard,
+    ClipboardMode, Compression, Config, ExecMode, InteractiveMode, ListSnapsOfType, PrintMode,
+    RestoreMode, RestoreSnapGuard,
 };
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::{PathData, PathMetadata};
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
-use crate::exec::preview::PreviewSelection;
-use crate::exec::recursive::RecursiveSearch;
+use crate::exec::assert_snapshotted::SNAPPER_INFO_FILE;
+use crate::exec::preview::{PreviewCompare, PreviewSelection};
+use crate::exec::recursive::{EntrySender, RecursiveSearch};
+use crate::exec::snap_mounts::{SnapshotMounts, DEFAULT_SNAPSHOT_SUFFIX};
+use crate::library::checksum::Checksum;
+use crate::library::clipboard::Clipboard;
+use crate::library::clone_restore::CloneRestore;
+use crate::library::diff_copy::has_resume_state;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::messages::Catalog;
+use crate::library::output_sink::OutputSink;
+use crate::library::pool_health::PoolHealth;
+use crate::library::query_cache::QueryCache;
+use crate::library::redaction::Redaction;
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::session_report::{SessionReport, Stat};
 use crate::library::snap_guard::SnapGuard;
 use crate::library::utility::{
-    copy_recursive, date_string, delimiter, print_output_buf, user_has_effective_root,
-    user_has_zfs_allow_snap_priv, DateFormat, Never,
+    copy_recursive, date_string, delimiter, display_human_duration, generate_dst_parent,
+    live_newer_by, print_notice, print_output_buf, user_has_effective_root, DateFormat, Never,
 };
-use crate::lookup::versions::VersionsMap;
+use crate::library::zfs_allow::{ZfsAllow, SNAPSHOT_PRIVS};
+use crate::lookup::all_snaps::AllSnaps;
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, VersionsMap};
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
 use crate::GLOBAL_CONFIG;
+use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, ZFS_SNAPSHOT_DIRECTORY};
 
 #[derive(Debug)]
 pub struct InteractiveBrowse {
     pub selected_pathdata: Vec<PathData>,
     pub opt_background_handle: Option<JoinHandle<()>>,
+    // the user followed the breadcrumbs out of a snapshot dir, back to the live dir
+    pub is_back_to_live: bool,
 }
 
 impl InteractiveBrowse {
@@ -50,7 +76,7 @@ impl InteractiveBrowse {
         // do we return back to our main exec function to print,
         // or continue down the interactive rabbit hole?
         match interactive_mode {
-            InteractiveMode::Restore(_) | InteractiveMode::Select => {
+            InteractiveMode::Restore(_) | InteractiveMode::Select | InteractiveMode::Pick => {
                 InteractiveSelect::exec(browse_result, interactive_mode)?;
                 unreachable!()
             }
@@ -64,12 +90,14 @@ impl InteractiveBrowse {
             // collect string paths from what we get from lookup_view
             Some(requested_dir) => {
                 let view_mode = ViewMode::Browse;
-                let browse_result = view_mode.browse(requested_dir)?;
+                // any additional dirs requested are merged into the same view
+                let requested_dirs: Vec<PathData> = std::iter::once(requested_dir)
+                    .chain(GLOBAL_CONFIG.additional_requested_dirs.iter())
+                    .cloned()
+                    .collect();
+                let browse_result = view_mode.browse(&requested_dirs, None, true)?;
                 if browse_result.selected_pathdata.is_empty() {
-                    return Err(HttmError::new(
-                        "None of the selected strings could be converted to paths.",
-                    )
-                    .into());
+                    return Err(HttmError::localized("error-no-valid-selection").into());
                 }
 
                 browse_result
@@ -85,6 +113,7 @@ impl InteractiveBrowse {
                         Self {
                             selected_pathdata: vec![selected_file],
                             opt_background_handle: None,
+                            is_back_to_live: false,
                         }
                     }
                     // Config::from should never allow us to have an instance where we don't
@@ -103,11 +132,15 @@ impl InteractiveBrowse {
 struct InteractiveSelect;
 
 impl InteractiveSelect {
+    const LOAD_MORE: &'static str = "LOAD MORE";
+    const REPLICA: &'static str = "REPLICA: ";
+    const ALL_REPLICAS: &'static str = "ALL";
+
     fn exec(
-        browse_result: InteractiveBrowse,
+        mut browse_result: InteractiveBrowse,
         interactive_mode: &InteractiveMode,
     ) -> HttmResult<()> {
-        let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &browse_result.selected_pathdata)?;
+        let mut versions_map = VersionsMap::new(&GLOBAL_CONFIG, &browse_result.selected_pathdata)?;
 
         // snap and live set has no snaps
         if versions_map.is_empty() {
@@ -116,11 +149,7 @@ impl InteractiveSelect {
                 .iter()
                 .map(|path| path.path_buf.to_string_lossy().to_string())
                 .collect();
-            let msg = format!(
-                "{}{:?}",
-                "Cannot select or restore from the following paths as they have no snapshots:\n",
-                paths
-            );
+            let msg = format!("{}\n{:?}", Catalog::get("error-no-snapshots"), paths);
             return Err(HttmError::new(&msg).into());
         }
 
@@ -128,33 +157,283 @@ impl InteractiveSelect {
             Self::last_snap(&browse_result.selected_pathdata, &versions_map)?
         } else {
             // same stuff we do at fn exec, snooze...
-            let display_config =
+            let mut display_config =
                 GLOBAL_CONFIG.generate_display_config(&browse_result.selected_pathdata);
 
-            let display_map = VersionsDisplayWrapper::from(&display_config, versions_map);
-
-            let selection_buffer = display_map.to_string();
-
-            let opt_live_version: Option<String> = browse_result
+            let mut opt_live_version: Option<String> = browse_result
                 .selected_pathdata
                 .get(0)
                 .map(|pathdata| pathdata.path_buf.to_string_lossy().into_owned());
 
-            // loop until user selects a valid snapshot version
-            loop {
-                let view_mode = &ViewMode::Select(opt_live_version.clone());
-                // get the file name
-                let requested_file_name = view_mode.select(&selection_buffer, false)?;
-                // ... we want everything between the quotes
-                let broken_string: Vec<_> = requested_file_name[0].split_terminator('"').collect();
-                // ... and the file is the 2nd item or the indexed "1" object
-                if let Some(path_string) = broken_string.get(1) {
-                    // and cannot select a 'live' version or other invalid value.
-                    if display_map.map.iter().all(|(live_version, _snaps)| {
-                        Path::new(path_string) != live_version.path_buf.as_path()
-                    }) {
+            SessionReport::add(
+                Stat::FilesViewed,
+                browse_result.selected_pathdata.len() as u64,
+            );
+
+            let mut opt_max_results = GLOBAL_CONFIG.opt_max_results;
+            let mut opt_replica: Option<PathBuf> = None;
+            let mut uniqueness = GLOBAL_CONFIG.uniqueness.clone();
+            // the version, marked by the user, against which another version may be compared
+            let mut opt_base: Option<String> = None;
+            // the outcome of an action, shown in the status line of the next select view only
+            let mut opt_notice: Option<String> = None;
+
+            'load_more: loop {
+                // only alt replicated datasets may have versions residing on more than one replica
+                let replicas = Self::replicas(&browse_result.selected_pathdata);
+
+                let display_map =
+                    VersionsDisplayWrapper::from(&display_config, versions_map.clone());
+
+                // if any file has as many versions as the limit, there may be more to load
+                let opt_may_have_more = opt_max_results.filter(|max_results| {
+                    display_map
+                        .map
+                        .values()
+                        .any(|snaps| snaps.len() >= *max_results)
+                });
+
+                let mut selection_buffer = display_map.to_output_string()?;
+
+                if opt_may_have_more.is_some() {
+                    selection_buffer.push_str(Self::LOAD_MORE);
+                    selection_buffer.push('\n');
+                }
+
+                if replicas.len() > 1 {
+                    let current = " (current)";
+
+                    selection_buffer += &format!(
+                        "{}{}{}\n",
+                        Self::REPLICA,
+                        Self::ALL_REPLICAS,
+                        if opt_replica.is_none() { current } else { "" }
+                    );
+
+                    replicas.iter().for_each(|replica| {
+                        selection_buffer += &format!(
+                            "{}{}{}\n",
+                            Self::REPLICA,
+                            replica.display(),
+                            if opt_replica.as_ref() == Some(replica) {
+                                current
+                            } else {
+                                ""
+                            }
+                        );
+                    });
+                }
+
+                // loop until user selects a valid snapshot version
+                loop {
+                    let view_mode = &ViewMode::Select(opt_live_version.clone());
+                    let opt_shown_notice = opt_notice.take();
+                    // get the file name
+                    let (requested_file_name, action) = view_mode.select_or_act(
+                        &selection_buffer,
+                        &uniqueness,
+                        opt_base.as_deref(),
+                        opt_shown_notice.as_deref(),
+                    )?;
+
+                    match action {
+                        SelectAction::Select => {}
+                        SelectAction::Copy => {
+                            if let Some(path_string) =
+                                Self::snap_path_of(&requested_file_name[0], &display_map)
+                            {
+                                Self::copy_to_clipboard(path_string);
+                            }
+
+                            continue;
+                        }
+                        SelectAction::CycleUniqueness => {
+                            uniqueness = Self::next_uniqueness(&uniqueness);
+                            versions_map = Self::lookup(
+                                &browse_result.selected_pathdata,
+                                opt_max_results,
+                                &uniqueness,
+                                opt_replica.as_deref(),
+                            )?;
+                            continue 'load_more;
+                        }
+                        SelectAction::MarkBase => {
+                            if let Some(path_string) =
+                                Self::snap_path_of(&requested_file_name[0], &display_map)
+                            {
+                                opt_base = Some(path_string.to_owned());
+                            }
+
+                            continue;
+                        }
+                        SelectAction::CompareToBase => {
+                            let opt_compare =
+                                Self::snap_path_of(&requested_file_name[0], &display_map);
+
+                            match (opt_base.as_deref(), opt_compare) {
+                                (Some(base), Some(compare)) if base != compare => {
+                                    let diff_buffer =
+                                        PreviewCompare::between(Path::new(base), Path::new(compare))?;
+
+                                    ViewMode::Diff.page(&diff_buffer)?;
+                                }
+                                (None, _) => print_notice(
+                                    "Mark a version as the base, with ctrl+b, before comparing another version to it.",
+                                ),
+                                _ => {}
+                            }
+
+                            continue;
+                        }
+                        SelectAction::Snapshot => {
+                            if let Some(path_string) =
+                                Self::snap_path_of(&requested_file_name[0], &display_map)
+                            {
+                                let live_pathdata =
+                                    PathData::from(Path::new(path_string)).into_live_version();
+
+                                // a failure to snapshot should not end the session
+                                match Self::snapshot(&[live_pathdata]) {
+                                    Ok(_) => {
+                                        // the new snapshot, now read in, may hold a new version
+                                        versions_map = Self::lookup(
+                                            &browse_result.selected_pathdata,
+                                            opt_max_results,
+                                            &uniqueness,
+                                            opt_replica.as_deref(),
+                                        )?;
+                                        continue 'load_more;
+                                    }
+                                    Err(err) => eprintln!("WARNING: {err}"),
+                                }
+                            }
+
+                            continue;
+                        }
+                        SelectAction::RestoreBesideLive => {
+                            if let Some(path_string) =
+                                Self::snap_path_of(&requested_file_name[0], &display_map)
+                            {
+                                // a failure to restore should not end the session
+                                match Self::restore_beside_live(Path::new(path_string)) {
+                                    Ok(new_file_path_buf) => {
+                                        opt_notice =
+                                            Some(format!("RESTORED: {new_file_path_buf:?}"))
+                                    }
+                                    Err(err) => eprintln!("WARNING: {err}"),
+                                }
+                            }
+
+                            continue;
+                        }
+                        SelectAction::JumpToSnapshot => {
+                            let breadcrumbs =
+                                match Self::snap_path_of(&requested_file_name[0], &display_map)
+                                    .and_then(|path_string| {
+                                        Breadcrumbs::new(Path::new(path_string), &display_map)
+                                    }) {
+                                    Some(breadcrumbs) => breadcrumbs,
+                                    None => continue,
+                                };
+
+                            let snap_browse = ViewMode::Browse.browse(
+                                std::slice::from_ref(&breadcrumbs.snap_dir),
+                                Some(&breadcrumbs),
+                                false,
+                            )?;
+
+                            if let Some(handle) = snap_browse.opt_background_handle {
+                                let _ = handle.join();
+                            }
+
+                            if !snap_browse.is_back_to_live {
+                                // a sibling from the same point in time is the selection
+                                match snap_browse.selected_pathdata.get(0) {
+                                    Some(sibling) => {
+                                        break 'load_more sibling
+                                            .path_buf
+                                            .to_string_lossy()
+                                            .into_owned()
+                                    }
+                                    None => continue,
+                                }
+                            }
+
+                            // back in the live dir, the user browses for a new selection
+                            let live_browse = ViewMode::Browse.browse(
+                                std::slice::from_ref(&breadcrumbs.live_dir),
+                                None,
+                                false,
+                            )?;
+
+                            if live_browse.selected_pathdata.is_empty() {
+                                return Err(HttmError::localized("error-no-valid-selection").into());
+                            }
+
+                            if let Some(handle) = browse_result.opt_background_handle.take() {
+                                let _ = handle.join();
+                            }
+
+                            browse_result = live_browse;
+                            SessionReport::add(
+                                Stat::FilesViewed,
+                                browse_result.selected_pathdata.len() as u64,
+                            );
+                            display_config = GLOBAL_CONFIG
+                                .generate_display_config(&browse_result.selected_pathdata);
+                            opt_live_version = browse_result
+                                .selected_pathdata
+                                .get(0)
+                                .map(|pathdata| pathdata.path_buf.to_string_lossy().into_owned());
+                            opt_replica = None;
+                            versions_map = Self::lookup(
+                                &browse_result.selected_pathdata,
+                                opt_max_results,
+                                &uniqueness,
+                                opt_replica.as_deref(),
+                            )?;
+                            continue 'load_more;
+                        }
+                    }
+
+                    if let Some(max_results) = opt_may_have_more {
+                        if requested_file_name[0].trim() == Self::LOAD_MORE {
+                            let doubled = max_results.saturating_mul(2);
+                            opt_max_results = Some(doubled);
+                            versions_map = Self::lookup(
+                                &browse_result.selected_pathdata,
+                                opt_max_results,
+                                &uniqueness,
+                                opt_replica.as_deref(),
+                            )?;
+                            continue 'load_more;
+                        }
+                    }
+
+                    if let Some(requested_replica) =
+                        requested_file_name[0].trim().strip_prefix(Self::REPLICA)
+                    {
+                        let requested_replica = requested_replica.trim_end_matches(" (current)");
+
+                        opt_replica = replicas
+                            .iter()
+                            .find(|replica| replica.as_path() == Path::new(requested_replica))
+                            .cloned();
+
+                        versions_map = Self::lookup(
+                            &browse_result.selected_pathdata,
+                            opt_max_results,
+                            &uniqueness,
+                            opt_replica.as_deref(),
+                        )?;
+                        continue 'load_more;
+                    }
+
+                    if let Some(path_string) =
+                        Self::snap_path_of(&requested_file_name[0], &display_map)
+                    {
                         // return string from the loop
-                        break (*path_string).to_string();
+                        break 'load_more path_string.to_string();
                     }
                 }
             }
@@ -164,6 +443,14 @@ impl InteractiveSelect {
             let _ = handle.join();
         }
 
+        // a version hard linked within its snapshot may be acted upon by any of its names
+        let opt_hard_links = SnapHardLinks::new(Path::new(&path_string));
+
+        let path_string = match &opt_hard_links {
+            Some(hard_links) => hard_links.choose(&path_string)?,
+            None => path_string,
+        };
+
         // continue to interactive_restore or print and exit here?
         if matches!(interactive_mode, InteractiveMode::Restore(_)) {
             // one only allow one to select one path string during select
@@ -172,15 +459,259 @@ impl InteractiveSelect {
             Ok(InteractiveRestore::exec(
                 &path_string,
                 &browse_result.selected_pathdata,
+                opt_hard_links.as_ref(),
             )?)
         } else {
             Ok(Self::print_selection(&path_string)?)
         }
     }
 
+    // snapshot the datasets upon which the live files reside, as SNAPSHOT would, with a suffix the user types
+    fn snapshot(live_paths: &[PathData]) -> HttmResult<()> {
+        let files: String = live_paths
+            .iter()
+            .map(|pathdata| format!("\tfile: {:?}\n", pathdata.path_buf))
+            .collect();
+
+        let suffix_buffer = format!(
+            "Please type a suffix for a snapshot of the dataset upon which each file resides, and press enter:\n\n\
+            {files}\n\
+            The suffix may contain the placeholders \"{{date}}\", \"{{user}}\" and \"{{hostname}}\".  \
+            An empty suffix is the default suffix, \"{DEFAULT_SNAPSHOT_SUFFIX}\"."
+        );
+
+        let requested = ViewMode::Snapshot.query(&suffix_buffer, "suffix> ")?;
+
+        let suffix = match requested.trim() {
+            "" => DEFAULT_SNAPSHOT_SUFFIX,
+            suffix => suffix,
+        };
+
+        SnapshotMounts::exec_for(live_paths, suffix)?;
+
+        // the snap mounts of each dataset were read at startup, so the new snapshot must be read in
+        let dataset_mounts: BTreeSet<PathBuf> = LiveFilesystemInfo::with_current(|fs_info| {
+            live_paths
+                .iter()
+                .filter_map(|pathdata| {
+                    pathdata
+                        .proximate_dataset(&fs_info.map_of_datasets)
+                        .ok()
+                        .map(Path::to_path_buf)
+                })
+                .collect()
+        });
+
+        dataset_mounts
+            .iter()
+            .try_for_each(|dataset_mount| LiveFilesystemInfo::refresh_snaps(dataset_mount))
+    }
+
+    // metadata, then contents, then all, and around again, but, as at startup,
+    // contents are never read back over a high-latency link
+    fn next_uniqueness(uniqueness: &ListSnapsOfType) -> ListSnapsOfType {
+        match uniqueness {
+            ListSnapsOfType::UniqueMetadata if GLOBAL_CONFIG.opt_high_latency => {
+                ListSnapsOfType::All
+            }
+            ListSnapsOfType::UniqueMetadata => ListSnapsOfType::UniqueContents,
+            ListSnapsOfType::UniqueContents => ListSnapsOfType::All,
+            ListSnapsOfType::All => ListSnapsOfType::UniqueMetadata,
+        }
+    }
+
+    // "just give me that version", the most common restore, as a copy beside the live version,
+    // named like "<name>.<snapshot timestamp>.httm", without any of the dialogs of the full restore
+    fn restore_beside_live(snap_path: &Path) -> HttmResult<PathBuf> {
+        let snap_pathdata = PathData::from(snap_path);
+
+        let snap_path_metadata = snap_pathdata
+            .metadata
+            .ok_or_else(|| HttmError::localized("error-source-missing"))?;
+
+        Redaction::refuse_unless_forced(&snap_pathdata, GLOBAL_CONFIG.opt_force)?;
+
+        let live_path = snap_pathdata.clone().into_live_version().path_buf;
+
+        let mut new_file_name = live_path
+            .file_name()
+            .ok_or_else(|| HttmError::new("Could not obtain a file name for the live version."))?
+            .to_os_string();
+
+        // a version unchanged across many snapshots has the same modify time in each, so name the copy
+        // by when its snapshot was taken, where that is known
+        let snap_time =
+            Self::snapshot_time(&snap_pathdata).unwrap_or(snap_path_metadata.modify_time);
+
+        new_file_name.push(".");
+        new_file_name.push(date_string(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &snap_time,
+            DateFormat::Timestamp,
+        )?);
+        new_file_name.push(".httm");
+
+        let new_file_path_buf = live_path.with_file_name(new_file_name);
+
+        // as with any restore which is not an overwrite, never rewrite over an existing file,
+        // nor through a symlink, even one which dangles, as exists() would not see
+        if new_file_path_buf.symlink_metadata().is_ok() {
+            let msg = format!(
+                "httm will not restore to {new_file_path_buf:?}, as a file with the same path name already exists."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        InteractiveRestore::copy_and_verify(&snap_pathdata.path_buf, &new_file_path_buf, true)?;
+
+        SessionReport::add_restored(&new_file_path_buf);
+
+        Ok(new_file_path_buf)
+    }
+
+    // when the snapshot upon which a version resides was taken is only known for ZFS and snapper snapshots
+    fn snapshot_time(snap_pathdata: &PathData) -> Option<SystemTime> {
+        // btrfs-snapper: <mount>/.snapshots/<snap number>/info.xml is written as the snapshot is taken
+        let opt_snapper_info = snap_pathdata.path_buf.ancestors().find_map(|ancestor| {
+            let parent = ancestor.parent()?;
+
+            (ancestor.ends_with(BTRFS_SNAPPER_SUFFIX)
+                && parent.parent()?.ends_with(BTRFS_SNAPPER_HIDDEN_DIRECTORY))
+            .then(|| parent.join(SNAPPER_INFO_FILE))
+        });
+
+        if let Some(snapper_info) = opt_snapper_info {
+            return snapper_info.symlink_metadata().ok()?.modified().ok();
+        }
+
+        // ZFS: like "rpool/home@snap_1", whose creation time only "zfs" may tell us
+        let full_snap_name = LiveFilesystemInfo::with_current(|fs_info| {
+            let dataset_mount = fs_info
+                .map_of_datasets
+                .proximate_mount(&snap_pathdata.path_buf)?;
+
+            match fs_info.map_of_datasets.get(dataset_mount) {
+                Some(md)
+                    if md.fs_type == FilesystemType::Zfs && md.mount_type == MountType::Local =>
+                {
+                    snap_pathdata.dataset_label(&fs_info.map_of_datasets)
+                }
+                _ => None,
+            }
+        })?;
+
+        let (dataset_name, _snap_name) = full_snap_name.split_once('@')?;
+
+        let zfs_command = ExternalCommand::optional(
+            "zfs",
+            "a copy restored beside the live version is named by the modify time of the version",
+        )?;
+
+        AllSnaps::of_dataset(&zfs_command, dataset_name)
+            .ok()?
+            .into_iter()
+            .find(|snap_info| snap_info.name == full_snap_name)
+            .map(|snap_info| snap_info.creation)
+    }
+
+    // versions for the current selection, by the uniqueness chosen in this view,
+    // which may differ from that requested on the command line, and of the replica chosen, if any
+    fn lookup(
+        paths_selected: &[PathData],
+        opt_max_results: Option<usize>,
+        uniqueness: &ListSnapsOfType,
+        opt_replica: Option<&Path>,
+    ) -> HttmResult<VersionsMap> {
+        if *uniqueness == GLOBAL_CONFIG.uniqueness {
+            return VersionsMap::with_max_results(
+                &GLOBAL_CONFIG,
+                paths_selected,
+                opt_max_results,
+                opt_replica,
+            );
+        }
+
+        let mut config = Config::clone(&GLOBAL_CONFIG);
+        config.uniqueness = uniqueness.clone();
+
+        VersionsMap::with_max_results(&config, paths_selected, opt_max_results, opt_replica)
+    }
+
+    // the datasets upon which versions of the selection may reside, which, with ALT_REPLICATED, may be several
+    fn replicas(paths_selected: &[PathData]) -> Vec<PathBuf> {
+        let mut replicas: Vec<PathBuf> = LiveFilesystemInfo::with_current(|fs_info| {
+            paths_selected
+                .iter()
+                .filter_map(|pathdata| {
+                    ProximateDatasetAndOptAlts::with_fs_info(fs_info, pathdata).ok()
+                })
+                .flat_map(|prox_opt_alts| prox_opt_alts.datasets_of_interest)
+                .collect()
+        });
+
+        replicas.sort_unstable();
+        replicas.dedup();
+
+        replicas
+    }
+
+    fn snap_path_of<'a>(
+        selected_line: &'a str,
+        display_map: &VersionsDisplayWrapper,
+    ) -> Option<&'a str> {
+        // ... we want everything between the quotes
+        let broken_string: Vec<_> = selected_line.split_terminator('"').collect();
+
+        // ... and the file is the 2nd item or the indexed "1" object
+        let path_string = broken_string.get(1)?;
+
+        // and cannot select a 'live' version or other invalid value.
+        display_map
+            .map
+            .iter()
+            .all(|(live_version, _snaps)| Path::new(path_string) != live_version.path_buf.as_path())
+            .then_some(*path_string)
+    }
+
+    // a failed copy shouldn't end the session, the user may still select or copy another version
+    fn copy_to_clipboard(path_string: &str) {
+        let clipboard_mode = GLOBAL_CONFIG.opt_clipboard.unwrap_or(ClipboardMode::Path);
+
+        match Clipboard::copy(Path::new(path_string), &clipboard_mode) {
+            Ok(_) => print_notice(&format!("Copied to clipboard: \"{path_string}\"")),
+            Err(err) => eprintln!("WARNING: {err}"),
+        }
+    }
+
     fn print_selection(path_string: &str) -> HttmResult<()> {
+        if let Some(clipboard_mode) = &GLOBAL_CONFIG.opt_clipboard {
+            Clipboard::copy(Path::new(path_string), clipboard_mode)?;
+            OutputSink::commit()?;
+
+            std::process::exit(0)
+        }
+
+        // the contents of the version selected are streamed, so a huge version need never reside in memory
+        if GLOBAL_CONFIG.opt_select_contents {
+            Self::dump_contents(Path::new(path_string))?;
+            OutputSink::commit()?;
+
+            std::process::exit(0)
+        }
+
         let delimiter = delimiter();
 
+        // pick mode is for consumption by other programs, so never decorate or zero delimit
+        if matches!(
+            GLOBAL_CONFIG.exec_mode,
+            ExecMode::Interactive(InteractiveMode::Pick)
+        ) {
+            print_output_buf(format!("{path_string}\n"))?;
+            OutputSink::commit()?;
+
+            std::process::exit(0)
+        }
+
         let output_buf = if matches!(
             GLOBAL_CONFIG.print_mode,
             PrintMode::RawNewline | PrintMode::RawZero
@@ -191,10 +722,37 @@ impl InteractiveSelect {
         };
 
         print_output_buf(output_buf)?;
+        OutputSink::commit()?;
 
         std::process::exit(0)
     }
 
+    fn dump_contents(snap_path: &Path) -> HttmResult<()> {
+        let mut snap_file = std::fs::File::open(snap_path)?;
+        // to stdout, or to an OUTPUT file, if one was requested
+        let mut writer = OutputSink::writer();
+
+        match &GLOBAL_CONFIG.opt_compress {
+            Some(Compression::Zstd) => {
+                let mut encoder = zstd::stream::write::Encoder::new(writer, 0)?;
+                std::io::copy(&mut snap_file, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            Some(Compression::Gzip) => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+                std::io::copy(&mut snap_file, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            None => {
+                std::io::copy(&mut snap_file, &mut writer)?;
+                writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn last_snap(
         paths_selected_in_browse: &[PathData],
         versions_map: &VersionsMap,
@@ -204,19 +762,25 @@ impl InteractiveSelect {
             .get(0)
             .expect("ExecMode::LiveSnap should always have exactly one path.");
 
+        // the version against which ditto is determined, usually the live version
+        let reference = GLOBAL_CONFIG
+            .opt_compare_against
+            .as_ref()
+            .unwrap_or(live_version);
+
         let last_snap = versions_map
             .values()
             .flatten()
             .filter(|snap_version| {
                 if GLOBAL_CONFIG.opt_omit_ditto {
                     snap_version.md_infallible().modify_time
-                        != live_version.md_infallible().modify_time
+                        != reference.md_infallible().modify_time
                 } else {
                     true
                 }
             })
             .last()
-            .ok_or_else(|| HttmError::new("No last snapshot for the requested input file exists."))?
+            .ok_or_else(|| HttmError::localized("error-no-last-snap"))?
             .path_buf
             .to_string_lossy()
             .into_owned();
@@ -225,10 +789,259 @@ impl InteractiveSelect {
     }
 }
 
-struct InteractiveRestore;
+// the way back out of a snapshot dir opened from the select view, to the live dir it mirrors
+struct Breadcrumbs {
+    live_dir: PathData,
+    snap_dir: PathData,
+    snap_name: String,
+}
+
+impl Breadcrumbs {
+    const BACK_TO_LIVE_KEY: Key = Key::Ctrl('l');
+    const BACK_TO_LIVE_BINDING: &'static str = "ctrl-l:accept";
+
+    fn new(snap_path: &Path, display_map: &VersionsDisplayWrapper) -> Option<Self> {
+        let snap_version = PathData::from(snap_path);
+
+        // the live version of which this snap version is a version
+        let live_version = display_map.map.iter().find_map(|(live_version, snaps)| {
+            snaps.contains(&snap_version).then_some(live_version)
+        })?;
+
+        let snap_name = snap_version.snap_name()?.to_string_lossy().into_owned();
+
+        Some(Self {
+            live_dir: PathData::from(live_version.path_buf.parent()?),
+            snap_dir: PathData::from(snap_version.path_buf.parent()?),
+            snap_name,
+        })
+    }
+
+    fn print(&self) -> String {
+        [
+            format!(
+                "LIVE: {} > SNAPSHOT: {} > {}",
+                self.live_dir.path_buf.display(),
+                self.snap_name,
+                self.snap_dir.path_buf.display()
+            ),
+            "BACK TO LIVE: ctrl+l".to_owned(),
+        ]
+        .join("\n")
+    }
+}
+
+// the other names of a version, hard linked within the same snapshot
+struct SnapHardLinks {
+    links: Vec<PathBuf>,
+}
+
+impl SnapHardLinks {
+    const YES_WITH_LINKS: &'static str = "YES-WITH-HARD-LINKS";
+    // how many levels above the selection to search for its other names, as searching
+    // an entire snapshot for a single inode may take a very long time
+    const SEARCH_LEVELS: usize = 2;
+    // how many levels below that to descend, as the search runs on the UI thread, and
+    // a deep tree would otherwise stall the select view while each of its entries is read
+    const SEARCH_DEPTH: usize = 4;
+
+    fn new(snap_path: &Path) -> Option<Self> {
+        let snap_md = snap_path.symlink_metadata().ok()?;
+
+        // only a file with more than one link has any other name to find
+        if !snap_md.is_file() || snap_md.nlink() < 2 {
+            return None;
+        }
+
+        let search_root = Self::search_root(snap_path)?;
+        let mut links = Self::find_links(search_root, &snap_md);
+
+        links.sort_unstable();
+
+        (links.len() > 1).then_some(Self { links })
+    }
+
+    fn find_links(search_root: &Path, snap_md: &Metadata) -> Vec<PathBuf> {
+        let mut links: Vec<PathBuf> = Vec::new();
+        let mut queue: Vec<(PathBuf, usize)> = vec![(search_root.to_path_buf(), 0)];
+
+        // stop as soon as every one of the file's names has been found
+        while links.len() < snap_md.nlink() as usize {
+            let (dir, depth) = match queue.pop() {
+                Some(item) => item,
+                None => break,
+            };
+
+            // an unreadable dir is skipped, as other names may yet be found elsewhere
+            let entries = match read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            for dir_entry in entries.flatten() {
+                // a dir entry's file type never follows a symlink
+                match dir_entry.file_type() {
+                    Ok(file_type) if file_type.is_dir() => {
+                        if depth < Self::SEARCH_DEPTH {
+                            queue.push((dir_entry.path(), depth + 1));
+                        }
+                    }
+                    Ok(file_type) if file_type.is_file() => {
+                        // an inode number is only unique within its own device
+                        if let Ok(md) = dir_entry.metadata() {
+                            if md.dev() == snap_md.dev() && md.ino() == snap_md.ino() {
+                                links.push(dir_entry.path());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        links
+    }
+
+    fn search_root(snap_path: &Path) -> Option<&Path> {
+        let mut search_root = snap_path.parent()?;
+
+        // never above the root of the snapshot itself
+        for _level in 1..Self::SEARCH_LEVELS {
+            if Self::is_snap_root(search_root) {
+                break;
+            }
+
+            match search_root.parent() {
+                Some(parent) => search_root = parent,
+                None => break,
+            }
+        }
+
+        Some(search_root)
+    }
+
+    fn is_snap_root(path: &Path) -> bool {
+        match path.parent() {
+            // ZFS: <mount>/.zfs/snapshot/<snap name>
+            Some(parent) if parent.ends_with(ZFS_SNAPSHOT_DIRECTORY) => true,
+            // snapper: <mount>/.snapshots/<number>/snapshot
+            Some(parent) => match parent.parent() {
+                Some(grandparent) => {
+                    path.ends_with(BTRFS_SNAPPER_SUFFIX)
+                        && grandparent.ends_with(BTRFS_SNAPPER_HIDDEN_DIRECTORY)
+                }
+                None => false,
+            },
+            None => true,
+        }
+    }
+
+    // the name selected is listed first, so enter simply keeps it
+    fn choose(&self, selected: &str) -> HttmResult<String> {
+        let links_buffer: String = self
+            .links
+            .iter()
+            .filter(|link| link.as_path() == Path::new(selected))
+            .chain(
+                self.links
+                    .iter()
+                    .filter(|link| link.as_path() != Path::new(selected)),
+            )
+            .map(|link| format!("{}\n", link.to_string_lossy()))
+            .collect();
+
+        let preview_buffer = format!(
+            "This version has {} names, hard linked within the same snapshot.  \
+            Please select the name by which to act upon it:\n\n{links_buffer}",
+            self.links.len()
+        );
+
+        // loop until user selects a valid name
+        loop {
+            let view_mode = &ViewMode::HardLinks;
+            let selection = view_mode.select(&preview_buffer, false)?;
+
+            if let Some(link) = self
+                .links
+                .iter()
+                .find(|link| link.to_string_lossy() == selection[0].trim())
+            {
+                return Ok(link.to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    // recreate the other names of a version beside its restored live version, as they were linked on
+    // the snapshot, but never replace a live file of the same name which is not already such a link
+    fn restore_links(&self, snap_path: &Path, restored_path: &Path) -> HttmResult<()> {
+        let restored_ino = restored_path.symlink_metadata()?.ino();
+
+        self.links
+            .iter()
+            .filter(|link| link.as_path() != snap_path)
+            .map(|link| {
+                (
+                    link,
+                    PathData::from(link.as_path()).into_live_version().path_buf,
+                )
+            })
+            .filter(|(link, live_link)| link != &live_link)
+            .try_for_each(|(_link, live_link)| {
+                match live_link.symlink_metadata() {
+                    Ok(md) if md.ino() == restored_ino => {}
+                    Ok(_) => eprintln!(
+                        "WARNING: {:?} already exists, and is not a link of the version restored.  \
+                        It has not been replaced.",
+                        live_link
+                    ),
+                    Err(_) => {
+                        generate_dst_parent(&live_link)?;
+                        std::fs::hard_link(restored_path, &live_link)?;
+
+                        println!(
+                            "httm restored a hard link: {:?} -> {:?}",
+                            live_link, restored_path
+                        );
+                    }
+                }
+
+                Ok(())
+            })
+    }
+}
+
+pub struct InteractiveRestore;
+
+// each selection may be restored differently than the mode requested at the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RestoreChoice {
+    Mode(RestoreMode),
+    ToPath(PathBuf),
+}
+
+impl RestoreChoice {
+    const COPY: &'static str = "COPY";
+    const COPY_PRESERVE: &'static str = "COPY-PRESERVE";
+    const OVERWRITE: &'static str = "OVERWRITE";
+    const CLONE: &'static str = "CLONE";
+    const RESTORE_TO_PATH: &'static str = "RESTORE-TO-PATH";
+
+    fn label(restore_mode: &RestoreMode) -> &'static str {
+        match restore_mode {
+            RestoreMode::CopyOnly => Self::COPY,
+            RestoreMode::CopyAndPreserve => Self::COPY_PRESERVE,
+            RestoreMode::Overwrite(_) => Self::OVERWRITE,
+            RestoreMode::Clone => Self::CLONE,
+        }
+    }
+}
 
 impl InteractiveRestore {
-    fn exec(parsed_str: &str, paths_selected_in_browse: &[PathData]) -> HttmResult<()> {
+    fn exec(
+        parsed_str: &str,
+        paths_selected_in_browse: &[PathData],
+        opt_hard_links: Option<&SnapHardLinks>,
+    ) -> HttmResult<()> {
         // build pathdata from selection buffer parsed string
         //
         // request is also sanity check for snap path exists below when we check
@@ -238,27 +1051,65 @@ impl InteractiveRestore {
         // sanity check -- snap version has good metadata?
         let snap_path_metadata = snap_pathdata
             .metadata
-            .ok_or_else(|| HttmError::new("Source location does not exist on disk. Quitting."))?;
+            .ok_or_else(|| HttmError::localized("error-source-missing"))?;
 
-        // build new place to send file
-        let new_file_path_buf = Self::build_new_file_path(
-            paths_selected_in_browse,
-            &snap_pathdata,
-            &snap_path_metadata,
-        )?;
+        Redaction::refuse_unless_forced(&snap_pathdata, GLOBAL_CONFIG.opt_force)?;
+
+        let cli_restore_mode = Self::cli_restore_mode();
+
+        let (restore_mode, new_file_path_buf) =
+            match Self::choose(&snap_pathdata, &cli_restore_mode)? {
+                RestoreChoice::Mode(restore_mode) => {
+                    // build new place to send file
+                    let new_file_path_buf = Self::build_new_file_path(
+                        &restore_mode,
+                        paths_selected_in_browse,
+                        &snap_pathdata,
+                        &snap_path_metadata,
+                    )?;
+
+                    (restore_mode, new_file_path_buf)
+                }
+                // a restore to a path is simply a copy, which preserves attributes if the command line mode would have
+                RestoreChoice::ToPath(new_file_path_buf) => {
+                    let restore_mode = if Self::should_preserve_attributes(&cli_restore_mode) {
+                        RestoreMode::CopyAndPreserve
+                    } else {
+                        RestoreMode::CopyOnly
+                    };
+
+                    (restore_mode, new_file_path_buf)
+                }
+            };
 
-        let should_preserve = Self::should_preserve_attributes();
+        let should_preserve = Self::should_preserve_attributes(&restore_mode);
+
+        // the other names of a version can only be linked again beside the live version it overwrites
+        let opt_hard_links = match opt_hard_links {
+            Some(hard_links) if matches!(restore_mode, RestoreMode::Overwrite(_)) => {
+                Some(hard_links)
+            }
+            _ => None,
+        };
+
+        let opt_links_choice = match opt_hard_links {
+            Some(_) => format!("{}\n", SnapHardLinks::YES_WITH_LINKS),
+            None => String::new(),
+        };
 
         // tell the user what we're up to, and get consent
         let preview_buffer = format!(
-            "httm will copy a file from a snapshot:\n\n\
+            "{}\n\n\
             \tfrom: {:?}\n\
             \tto:   {new_file_path_buf:?}\n\n\
-            Before httm restores this file, it would like your consent. Continue? (YES/NO)\n\
+            {}\n\
             ──────────────────────────────────────────────────────────────────────────────\n\
             YES\n\
+            {opt_links_choice}\
             NO",
-            snap_pathdata.path_buf
+            Catalog::get("restore-consent-header"),
+            snap_pathdata.path_buf,
+            Catalog::get("restore-consent-question")
         );
 
         // loop until user consents or doesn't
@@ -267,83 +1118,244 @@ impl InteractiveRestore {
             let user_consent = view_mode.select(&preview_buffer, false)?[0].to_ascii_uppercase();
 
             match user_consent.as_ref() {
-                "YES" | "Y" => {
-                    if matches!(
-                        GLOBAL_CONFIG.exec_mode,
-                        ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Overwrite(
-                            RestoreSnapGuard::Guarded
-                        )))
+                "YES" | "Y" | SnapHardLinks::YES_WITH_LINKS => {
+                    if matches!(restore_mode, RestoreMode::Overwrite(_)) {
+                        if !Self::confirm_over_newer(&new_file_path_buf, &snap_path_metadata)? {
+                            break println!("{}", Catalog::get("restore-declined"));
+                        }
+
+                        PoolHealth::precheck_path(&new_file_path_buf)?;
+                    }
+
+                    if matches!(restore_mode, RestoreMode::Clone) {
+                        let clone_name =
+                            CloneRestore::exec(&snap_pathdata.path_buf, &new_file_path_buf)?;
+
+                        println!(
+                            "httm created a clone named: {clone_name}\n\
+                            Specify CLEANUP_CLONES to destroy it, and any other clones httm created, when finished."
+                        );
+                    } else if matches!(
+                        restore_mode,
+                        RestoreMode::Overwrite(RestoreSnapGuard::Guarded)
                     ) && (user_has_effective_root().is_ok()
-                        || user_has_zfs_allow_snap_priv(&new_file_path_buf).is_ok())
+                        || ZfsAllow::check_path(&new_file_path_buf, &SNAPSHOT_PRIVS).is_ok())
                     {
                         let snap_guard: SnapGuard =
                             SnapGuard::try_from(new_file_path_buf.as_path())?;
 
-                        if let Err(err) = copy_recursive(
+                        if let Err(err) = Self::copy_and_verify(
                             &snap_pathdata.path_buf,
                             &new_file_path_buf,
                             should_preserve,
                         ) {
-                            let msg = format!(
-                                "httm restore failed for the following reason: {}.\n\
-                            Attempting roll back to precautionary pre-execution snapshot.",
-                                err
+                            let msg = Catalog::format(
+                                "error-restore-rollback",
+                                &[("reason", &err.to_string())],
                             );
 
                             eprintln!("{}", msg);
 
                             snap_guard
                                 .rollback()
-                                .map(|_| println!("Rollback succeeded."))?;
+                                .map(|_| println!("{}", Catalog::get("rollback-succeeded")))?;
 
                             std::process::exit(1);
                         }
                     } else {
-                        copy_recursive(
+                        Self::copy_and_verify(
                             &snap_pathdata.path_buf,
                             &new_file_path_buf,
                             should_preserve,
                         )?
                     }
 
+                    if let Some(hard_links) = opt_hard_links {
+                        if user_consent == SnapHardLinks::YES_WITH_LINKS {
+                            hard_links
+                                .restore_links(&snap_pathdata.path_buf, &new_file_path_buf)?;
+                        }
+                    }
+
+                    SessionReport::add_restored(&new_file_path_buf);
+
                     let result_buffer = format!(
-                        "httm copied a file from a snapshot:\n\n\
+                        "{}\n\n\
                             \tfrom: {:?}\n\
                             \tto:   {new_file_path_buf:?}\n\n\
-                            Restore completed successfully.",
-                        snap_pathdata.path_buf
+                            {}",
+                        Catalog::get("restore-completed-header"),
+                        snap_pathdata.path_buf,
+                        Catalog::get("restore-completed")
                     );
 
                     break println!("{result_buffer}");
                 }
-                "NO" | "N" => break println!("User declined restore.  No files were restored."),
+                "NO" | "N" => break println!("{}", Catalog::get("restore-declined")),
                 // if not yes or no, then noop and continue to the next iter of loop
                 _ => {}
             }
         }
 
+        OutputSink::commit()?;
+
         std::process::exit(0)
     }
 
-    fn should_preserve_attributes() -> bool {
+    // overwriting recent work with an older version is most likely a mistake, so ask again, unless forced
+    fn confirm_over_newer(live_path: &Path, snap_path_metadata: &PathMetadata) -> HttmResult<bool> {
+        let newer_by = match live_newer_by(live_path, snap_path_metadata.modify_time) {
+            Some(newer_by) if !GLOBAL_CONFIG.opt_force => newer_by,
+            _ => return Ok(true),
+        };
+
+        let preview_buffer = format!(
+            "WARNING: The live version is newer than the snapshot version selected, by {}.\n\n\
+            \tlive: {live_path:?}\n\n\
+            Overwriting it will discard any changes made since.  Overwrite anyway? (Specify FORCE to skip this question.)\n\
+            ──────────────────────────────────────────────────────────────────────────────\n\
+            YES\n\
+            NO",
+            display_human_duration(newer_by)
+        );
+
+        loop {
+            let user_consent =
+                ViewMode::Restore.select(&preview_buffer, false)?[0].to_ascii_uppercase();
+
+            match user_consent.as_ref() {
+                "YES" | "Y" => return Ok(true),
+                "NO" | "N" => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn copy_and_verify(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+        copy_recursive(src, dst, should_preserve)?;
+
+        if GLOBAL_CONFIG.opt_verify_restores {
+            Checksum::verify_restored(src, dst)?;
+        }
+
+        Ok(())
+    }
+
+    fn cli_restore_mode() -> RestoreMode {
+        match &GLOBAL_CONFIG.exec_mode {
+            ExecMode::Interactive(InteractiveMode::Restore(restore_mode)) => restore_mode.clone(),
+            _ => unreachable!("InteractiveRestore should only ever be executed in Restore mode"),
+        }
+    }
+
+    fn choose(
+        snap_pathdata: &PathData,
+        cli_restore_mode: &RestoreMode,
+    ) -> HttmResult<RestoreChoice> {
+        let default_label = RestoreChoice::label(cli_restore_mode);
+
+        // select views are displayed in reverse, so the default, placed last, is the first item under the cursor
+        let labels: String = [
+            RestoreChoice::COPY,
+            RestoreChoice::COPY_PRESERVE,
+            RestoreChoice::OVERWRITE,
+            RestoreChoice::CLONE,
+            RestoreChoice::RESTORE_TO_PATH,
+        ]
+        .into_iter()
+        .filter(|label| *label != default_label)
+        .chain(std::iter::once(default_label))
+        .map(|label| format!("{label}\n"))
+        .collect();
+
+        let choice_buffer = format!(
+            "{}\n\n\
+            \tfrom: {:?}\n\n\
+            {}\n\
+            ──────────────────────────────────────────────────────────────────────────────\n\
+            {labels}",
+            Catalog::get("restore-choice-header"),
+            snap_pathdata.path_buf,
+            Catalog::format("restore-choice-default", &[("mode", default_label)])
+        );
+
+        // loop until user makes a valid choice
+        loop {
+            let view_mode = &ViewMode::Restore;
+            let user_choice = view_mode.select(&choice_buffer, false)?[0].to_ascii_uppercase();
+
+            let choice = match user_choice.as_ref() {
+                RestoreChoice::COPY => RestoreChoice::Mode(RestoreMode::CopyOnly),
+                RestoreChoice::COPY_PRESERVE => RestoreChoice::Mode(RestoreMode::CopyAndPreserve),
+                // keep the user's guard preference, else be careful and guard an overwrite
+                RestoreChoice::OVERWRITE => match cli_restore_mode {
+                    RestoreMode::Overwrite(_) => RestoreChoice::Mode(cli_restore_mode.clone()),
+                    _ => RestoreChoice::Mode(RestoreMode::Overwrite(RestoreSnapGuard::Guarded)),
+                },
+                RestoreChoice::CLONE => RestoreChoice::Mode(RestoreMode::Clone),
+                RestoreChoice::RESTORE_TO_PATH => match Self::restore_to_path(snap_pathdata)? {
+                    Some(new_file_path_buf) => RestoreChoice::ToPath(new_file_path_buf),
+                    None => continue,
+                },
+                // if not a valid choice, then noop and continue to the next iter of loop
+                _ => continue,
+            };
+
+            break Ok(choice);
+        }
+    }
+
+    fn restore_to_path(snap_pathdata: &PathData) -> HttmResult<Option<PathBuf>> {
+        let path_buffer = format!(
+            "Please type the path to which httm should restore this file, and press enter:\n\n\
+            \tfrom: {:?}\n\n\
+            A relative path is relative to the working directory.  \
+            Should the path be an existing directory, the file will be restored within that directory.",
+            snap_pathdata.path_buf
+        );
+
+        let view_mode = &ViewMode::Restore;
+        let requested = view_mode.query(&path_buffer, "path> ")?;
+        let requested = requested.trim();
+
+        if requested.is_empty() {
+            return Ok(None);
+        }
+
+        let mut new_file_path_buf = GLOBAL_CONFIG.pwd.path_buf.join(requested);
+
+        if new_file_path_buf.is_dir() {
+            if let Some(snap_filename) = snap_pathdata.path_buf.file_name() {
+                new_file_path_buf.push(snap_filename);
+            }
+        }
+
+        // don't let the user rewrite over an existing file, that's what overwrite mode is for
+        if new_file_path_buf.exists() {
+            eprintln!(
+                "WARNING: httm will not restore to {new_file_path_buf:?}, as a file with the same path name already exists."
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(new_file_path_buf))
+    }
+
+    fn should_preserve_attributes(restore_mode: &RestoreMode) -> bool {
         matches!(
-            GLOBAL_CONFIG.exec_mode,
-            ExecMode::Interactive(InteractiveMode::Restore(
-                RestoreMode::CopyAndPreserve | RestoreMode::Overwrite(_)
-            ))
+            restore_mode,
+            RestoreMode::CopyAndPreserve | RestoreMode::Overwrite(_)
         )
     }
 
     fn build_new_file_path(
+        restore_mode: &RestoreMode,
         paths_selected_in_browse: &[PathData],
         snap_pathdata: &PathData,
         snap_path_metadata: &PathMetadata,
     ) -> HttmResult<PathBuf> {
         // build new place to send file
-        if matches!(
-            GLOBAL_CONFIG.exec_mode,
-            ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Overwrite(_)))
-        ) {
+        if matches!(restore_mode, RestoreMode::Overwrite(_)) {
             // instead of just not naming the new file with extra info (date plus "httm_restored") and shoving that new file
             // into the pwd, here, we actually look for the original location of the file to make sure we overwrite it.
             // so, if you were in /etc and wanted to restore /etc/samba/smb.conf, httm will make certain to overwrite
@@ -368,13 +1380,20 @@ impl InteractiveRestore {
                 }
             });
 
-            match opt_original_live_pathdata {
-                Some(pathdata) => Ok(pathdata.path_buf),
-                None => Err(HttmError::new(
+            // a sibling, selected from within a snapshot dir, or another hard link name chosen for the version,
+            // is a version of no path selected in browse, but its live path is the same path, relative to the
+            // live dataset, and never the live path of the name first selected
+            let original_live_pathdata = opt_original_live_pathdata
+                .unwrap_or_else(|| snap_pathdata.clone().into_live_version());
+
+            if original_live_pathdata.path_buf == snap_pathdata.path_buf {
+                return Err(HttmError::new(
                     "httm unable to determine original file path in overwrite mode.  Quitting.",
                 )
-                .into()),
+                .into());
             }
+
+            Ok(original_live_pathdata.path_buf)
         } else {
             let snap_filename = snap_pathdata
                 .path_buf
@@ -389,15 +1408,16 @@ impl InteractiveRestore {
                     GLOBAL_CONFIG.requested_utc_offset,
                     &snap_path_metadata.modify_time,
                     DateFormat::Timestamp,
-                );
+                )?;
             let new_file_dir = GLOBAL_CONFIG.pwd.path_buf.clone();
             let new_file_path_buf: PathBuf = new_file_dir.join(new_filename);
 
-            // don't let the user rewrite one restore over another in non-overwrite mode
-            if new_file_path_buf.exists() {
-                Err(
-                    HttmError::new("httm will not restore to that file, as a file with the same path name already exists. Quitting.").into(),
-                )
+            // don't let the user rewrite one restore over another in non-overwrite mode,
+            // unless the user asks to resume a previous restore which was interrupted
+            if new_file_path_buf.exists()
+                && !(GLOBAL_CONFIG.opt_resume && has_resume_state(&new_file_path_buf))
+            {
+                Err(HttmError::localized("error-restore-exists").into())
             } else {
                 Ok(new_file_path_buf)
             }
@@ -410,14 +1430,55 @@ pub enum ViewMode {
     Select(Option<String>),
     Restore,
     Prune,
+    Recover,
+    Diff,
+    Snapshot,
+    HardLinks,
+}
+
+// besides selecting, the select view's own keybindings also accept, see ViewMode::select_or_act
+pub enum SelectAction {
+    Select,
+    Copy,
+    CycleUniqueness,
+    JumpToSnapshot,
+    MarkBase,
+    CompareToBase,
+    Snapshot,
+    RestoreBesideLive,
 }
 
 impl ViewMode {
+    const COPY_KEY: Key = Key::Ctrl('y');
+    const COPY_BINDING: &'static str = "ctrl-y:accept";
+    const UNIQUENESS_KEY: Key = Key::Ctrl('u');
+    const UNIQUENESS_BINDING: &'static str = "ctrl-u:accept";
+    const JUMP_TO_SNAPSHOT_KEY: Key = Key::Ctrl('o');
+    const JUMP_TO_SNAPSHOT_BINDING: &'static str = "ctrl-o:accept";
+    const MARK_BASE_KEY: Key = Key::Ctrl('b');
+    const MARK_BASE_BINDING: &'static str = "ctrl-b:accept";
+    const COMPARE_TO_BASE_KEY: Key = Key::Ctrl('x');
+    const COMPARE_TO_BASE_BINDING: &'static str = "ctrl-x:accept";
+    const SNAPSHOT_KEY: Key = Key::Alt('s');
+    const SNAPSHOT_BINDING: &'static str = "alt-s:accept";
+    const RESTORE_BESIDE_LIVE_KEY: Key = Key::Alt('r');
+    const RESTORE_BESIDE_LIVE_BINDING: &'static str = "alt-r:accept";
+    const PREVIEW_TOGGLE_BINDING: &'static str = "alt-p:toggle-preview";
+
     fn print_header(&self) -> String {
+        let opt_select_keys = match self {
+            ViewMode::Select(_) => {
+                "COPY:       ctrl+y   | UNIQUENESS:   ctrl+u     | JUMP TO SNAPSHOT DIR: ctrl+o\n\
+                MARK BASE:  ctrl+b   | COMPARE TO BASE: ctrl+x | SNAPSHOT DATASET:     alt+s\n\
+                RESTORE BESIDE LIVE: alt+r\n"
+            }
+            _ => "",
+        };
+
         format!(
             "PREVIEW UP: shift+up | PREVIEW DOWN: shift+down | {}\n\
-        PAGE UP:    page up  | PAGE DOWN:    page down \n\
-        EXIT:       esc      | SELECT:       enter      | SELECT, MULTIPLE: shift+tab\n\
+        PAGE UP:    page up  | PAGE DOWN:    page down  | PREVIEW, TOGGLE: alt+p\n\
+        {opt_select_keys}EXIT:       esc      | SELECT:       enter      | SELECT, MULTIPLE: shift+tab\n\
         ──────────────────────────────────────────────────────────────────────────────",
             self.print_mode()
         )
@@ -429,54 +1490,127 @@ impl ViewMode {
             ViewMode::Select(_) => "====> [ Select Mode ] <====",
             ViewMode::Restore => "====> [ Restore Mode ] <====",
             ViewMode::Prune => "====> [ Prune Mode ] <====",
+            ViewMode::Recover => "====> [ Recover Mode ] <====",
+            ViewMode::Diff => "====> [ Diff Mode ] <====",
+            ViewMode::Snapshot => "====> [ Snapshot Mode ] <====",
+            ViewMode::HardLinks => "====> [ Hard Links Mode ] <====",
         }
     }
 
-    fn browse(&self, requested_dir: &PathData) -> HttmResult<InteractiveBrowse> {
+    fn browse(
+        &self,
+        requested_dirs: &[PathData],
+        opt_breadcrumbs: Option<&Breadcrumbs>,
+        is_first_browse: bool,
+    ) -> HttmResult<InteractiveBrowse> {
         // prep thread spawn
-        let requested_dir_clone = requested_dir.path_buf.clone();
-        let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) = unbounded();
+        let requested_dirs_clone: Vec<PathBuf> = requested_dirs
+            .iter()
+            .map(|requested_dir| requested_dir.path_buf.clone())
+            .collect();
+        // several dirs merged into one view share the query of the first
+        let requested_dir_query = requested_dirs_clone
+            .first()
+            .cloned()
+            .ok_or_else(|| HttmError::new("httm requires a directory to browse."))?;
+        let (tx_item, rx_item): (SkimItemSender, SkimItemReceiver) =
+            bounded(EntrySender::interactive_capacity());
         let (hangup_tx, hangup_rx): (Sender<Never>, Receiver<Never>) = bounded(0);
 
         // thread spawn fn enumerate_directory - permits recursion into dirs without blocking
         let background_handle = thread::spawn(move || {
+            // each dir is walked in its own thread, so one large dir doesn't hold back the others.
             // no way to propagate error from closure so exit and explain error here
-            RecursiveSearch::exec(&requested_dir_clone, tx_item.clone(), hangup_rx.clone());
+            let dir_handles: Vec<JoinHandle<()>> = requested_dirs_clone
+                .into_iter()
+                .map(|requested_dir| {
+                    let tx_item = tx_item.clone();
+                    let hangup_rx = hangup_rx.clone();
+
+                    thread::spawn(move || {
+                        RecursiveSearch::exec(
+                            &requested_dir,
+                            EntrySender::Interactive(tx_item),
+                            hangup_rx,
+                        )
+                    })
+                })
+                .collect();
+
+            dir_handles.into_iter().for_each(|dir_handle| {
+                let _ = dir_handle.join();
+            });
         });
 
-        let header: String = self.print_header();
+        let header: String = match opt_breadcrumbs {
+            Some(breadcrumbs) => format!("{}\n{}", breadcrumbs.print(), self.print_header()),
+            None => self.print_header(),
+        };
+
+        let mut bindings = vec![Self::PREVIEW_TOGGLE_BINDING];
+
+        // files in a snapshot dir are already snapshot-ed, so only live files may be
+        if opt_breadcrumbs.is_some() {
+            bindings.push(Breadcrumbs::BACK_TO_LIVE_BINDING);
+        } else {
+            bindings.push(Self::SNAPSHOT_BINDING);
+        }
+
+        // one sibling is selected from a snapshot dir, as one version is selected in the select view
+        let opt_multi = opt_breadcrumbs.is_none()
+            && (GLOBAL_CONFIG.opt_last_snap.is_none() || GLOBAL_CONFIG.opt_preview.is_none());
 
         let display_handle = thread::spawn(move || {
-            let opt_multi =
-                GLOBAL_CONFIG.opt_last_snap.is_none() || GLOBAL_CONFIG.opt_preview.is_none();
+            // a query, or an auto select, requested on the command line applies only to the first browse,
+            // and not to any dir browsed again later, so begin with the query requested,
+            // else pick up where the user left off in this dir
+            let opt_query = match &GLOBAL_CONFIG.opt_query {
+                Some(query) if is_first_browse => Some(query.clone()),
+                _ => QueryCache::get(&requested_dir_query),
+            };
+            let query_history = QueryCache::history();
 
             // create the skim component for previews
             let skim_opts = SkimOptionsBuilder::default()
-                .preview_window(Some("up:50%"))
+                .preview_window(Some(GLOBAL_CONFIG.preview_window.as_str()))
                 .preview(Some(""))
+                .bind(bindings)
                 .nosort(true)
                 .exact(GLOBAL_CONFIG.opt_exact)
                 .header(Some(&header))
                 .multi(opt_multi)
                 .regex(false)
+                .query(opt_query.as_deref())
+                .query_history(&query_history)
+                // skim waits until the search is complete, before it selects the only match
+                .select1(GLOBAL_CONFIG.opt_auto_select && is_first_browse)
                 .build()
                 .expect("Could not initialized skim options for browse_view");
 
             // run_with() reads and shows items from the thread stream created above
             let res = match skim::Skim::run_with(&skim_opts, Some(rx_item)) {
                 Some(output) if output.is_abort => {
-                    eprintln!("httm interactive file browse session was aborted.  Quitting.");
+                    QueryCache::set(&requested_dir_query, &output.query);
+                    eprintln!("{}", Catalog::get("browse-aborted"));
                     std::process::exit(0)
                 }
                 Some(output) => {
+                    QueryCache::set(&requested_dir_query, &output.query);
+
                     // hangup the channel so the background recursive search can gracefully cleanup and exit
                     drop(hangup_tx);
 
-                    output
+                    if output.final_key == Breadcrumbs::BACK_TO_LIVE_KEY {
+                        return Ok((Vec::new(), output.final_key));
+                    }
+
+                    let selected: Vec<PathData> = output
                         .selected_items
                         .iter()
                         .map(|i| PathData::from(Path::new(&i.output().to_string())))
-                        .collect()
+                        .collect();
+
+                    (selected, output.final_key)
                 }
                 None => {
                     return Err(HttmError::new(
@@ -489,16 +1623,33 @@ impl ViewMode {
         });
 
         match display_handle.join() {
-            Ok(selected_pathdata) => {
+            Ok(browse_output) => {
                 Self::malloc_trim();
 
+                let (selected_pathdata, final_key) = browse_output?;
+
+                if final_key == Self::SNAPSHOT_KEY {
+                    let _ = background_handle.join();
+
+                    // a failure to snapshot should not end the session
+                    if let Err(err) = InteractiveSelect::snapshot(&selected_pathdata) {
+                        eprintln!("WARNING: {err}");
+                    }
+
+                    // browse again, and pick up where the user left off, now the new snapshot is read in
+                    return self.browse(requested_dirs, opt_breadcrumbs, false);
+                }
+
+                let is_back_to_live = final_key == Breadcrumbs::BACK_TO_LIVE_KEY;
+
                 let res = InteractiveBrowse {
-                    selected_pathdata: selected_pathdata?,
+                    selected_pathdata,
                     opt_background_handle: Some(background_handle),
+                    is_back_to_live,
                 };
                 Ok(res)
             }
-            Err(_) => Err(HttmError::new("Interactive browse thread panicked.").into()),
+            Err(_) => Err(HttmError::localized("error-browse-panicked").into()),
         }
     }
 
@@ -511,9 +1662,71 @@ impl ViewMode {
     }
 
     pub fn select(&self, preview_buffer: &str, multi: bool) -> HttmResult<Vec<String>> {
+        self.select_with_bindings(preview_buffer, multi, Vec::new(), None)
+            .map(|(selected, _final_key)| selected)
+    }
+
+    // like select, but the copy and uniqueness keybindings also accept, returning which the user pressed
+    pub fn select_or_act(
+        &self,
+        preview_buffer: &str,
+        uniqueness: &ListSnapsOfType,
+        opt_base: Option<&str>,
+        opt_notice: Option<&str>,
+    ) -> HttmResult<(Vec<String>, SelectAction)> {
+        let status = match opt_base {
+            Some(base) => format!("UNIQUENESS: {} | BASE: \"{base}\"", uniqueness.name()),
+            None => format!("UNIQUENESS: {}", uniqueness.name()),
+        };
+
+        let status = match opt_notice {
+            Some(notice) => format!("{status}\n{notice}"),
+            None => status,
+        };
+
+        self.select_with_bindings(
+            preview_buffer,
+            false,
+            vec![
+                Self::COPY_BINDING,
+                Self::UNIQUENESS_BINDING,
+                Self::JUMP_TO_SNAPSHOT_BINDING,
+                Self::MARK_BASE_BINDING,
+                Self::COMPARE_TO_BASE_BINDING,
+                Self::SNAPSHOT_BINDING,
+                Self::RESTORE_BESIDE_LIVE_BINDING,
+            ],
+            Some(&status),
+        )
+        .map(|(selected, final_key)| {
+            let action = match final_key {
+                key if key == Self::COPY_KEY => SelectAction::Copy,
+                key if key == Self::UNIQUENESS_KEY => SelectAction::CycleUniqueness,
+                key if key == Self::JUMP_TO_SNAPSHOT_KEY => SelectAction::JumpToSnapshot,
+                key if key == Self::MARK_BASE_KEY => SelectAction::MarkBase,
+                key if key == Self::COMPARE_TO_BASE_KEY => SelectAction::CompareToBase,
+                key if key == Self::SNAPSHOT_KEY => SelectAction::Snapshot,
+                key if key == Self::RESTORE_BESIDE_LIVE_KEY => SelectAction::RestoreBesideLive,
+                _ => SelectAction::Select,
+            };
+
+            (selected, action)
+        })
+    }
+
+    fn select_with_bindings(
+        &self,
+        preview_buffer: &str,
+        multi: bool,
+        bindings: Vec<&str>,
+        opt_status: Option<&str>,
+    ) -> HttmResult<(Vec<String>, Key)> {
         let preview_selection = PreviewSelection::new(self)?;
 
-        let header = self.print_header();
+        let header = match opt_status {
+            Some(status) => format!("{status}\n{}", self.print_header()),
+            None => self.print_header(),
+        };
 
         // build our browse view - less to do than before - no previews, looking through one 'lil buffer
         let skim_opts = SkimOptionsBuilder::default()
@@ -528,6 +1741,12 @@ impl ViewMode {
             .regex(false)
             .tiebreak(Some("length,index".to_string()))
             .header(Some(&header))
+            .bind(
+                [Self::PREVIEW_TOGGLE_BINDING]
+                    .into_iter()
+                    .chain(bindings)
+                    .collect(),
+            )
             .build()
             .expect("Could not initialized skim options for select_restore_view");
 
@@ -540,19 +1759,88 @@ impl ViewMode {
         // run_with() reads and shows items from the thread stream created above
         let res = match skim::Skim::run_with(&skim_opts, Some(items)) {
             Some(output) if output.is_abort => {
-                eprintln!("httm select/restore/prune session was aborted.  Quitting.");
+                eprintln!("{}", Catalog::get("select-aborted"));
+                // a cancelled pick must be distinguishable from a successful one
+                if matches!(
+                    GLOBAL_CONFIG.exec_mode,
+                    ExecMode::Interactive(InteractiveMode::Pick)
+                ) {
+                    std::process::exit(1);
+                }
                 std::process::exit(0);
             }
-            Some(output) => output
-                .selected_items
-                .iter()
-                .map(|i| i.output().into_owned())
-                .collect(),
+            Some(output) => (
+                output
+                    .selected_items
+                    .iter()
+                    .map(|i| i.output().into_owned())
+                    .collect(),
+                output.final_key,
+            ),
             None => {
-                return Err(HttmError::new("httm select/restore/prune session failed.").into());
+                return Err(HttmError::localized("error-select-failed").into());
             }
         };
 
         Ok(res)
     }
+
+    // only displays the buffer, and, unlike select, returns on either enter or esc, back to the view from which it was opened
+    pub fn page(&self, buffer: &str) -> HttmResult<()> {
+        let header = self.print_header();
+
+        let skim_opts = SkimOptionsBuilder::default()
+            .disabled(true)
+            .tac(true)
+            .nosort(true)
+            .tabstop(Some("4"))
+            .multi(false)
+            .regex(false)
+            .header(Some(&header))
+            .build()
+            .expect("Could not initialized skim options for page_view");
+
+        let item_reader_opts = SkimItemReaderOption::default().ansi(true);
+        let item_reader = SkimItemReader::new(item_reader_opts);
+
+        let (items, _opt_handle) =
+            item_reader.of_bufread(Box::new(Cursor::new(buffer.trim().to_owned())));
+
+        match skim::Skim::run_with(&skim_opts, Some(items)) {
+            Some(_output) => Ok(()),
+            None => Err(HttmError::localized("error-select-failed").into()),
+        }
+    }
+
+    // like select, but returns whatever the user typed at the prompt, instead of a selected item
+    pub fn query(&self, preview_buffer: &str, prompt: &str) -> HttmResult<String> {
+        let header = self.print_header();
+
+        let skim_opts = SkimOptionsBuilder::default()
+            .disabled(true)
+            .tac(true)
+            .nosort(true)
+            .tabstop(Some("4"))
+            .multi(false)
+            .regex(false)
+            .prompt(Some(prompt))
+            .header(Some(&header))
+            .build()
+            .expect("Could not initialized skim options for query_view");
+
+        let item_reader_opts = SkimItemReaderOption::default().ansi(true);
+        let item_reader = SkimItemReader::new(item_reader_opts);
+
+        let (items, _opt_handle) =
+            item_reader.of_bufread(Box::new(Cursor::new(preview_buffer.trim().to_owned())));
+
+        match skim::Skim::run_with(&skim_opts, Some(items)) {
+            Some(output) if output.is_abort => {
+                eprintln!("{}", Catalog::get("restore-aborted"));
+                std::process::exit(0);
+            }
+            Some(output) => Ok(output.query),
+            None => Err(HttmError::localized("error-restore-failed").into()),
+        }
+    }
 }
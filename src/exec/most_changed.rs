@@ -0,0 +1,193 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::config::generate::{MostChangedConfig, PrintMode, RankBy};
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::exec::recursive::SharedRecursive;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{delimiter, display_human_size, display_path, print_output_bytes};
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, VersionsMap};
+use crate::GLOBAL_CONFIG;
+
+struct Churn {
+    path: PathBuf,
+    num_versions: usize,
+    changed_bytes: u64,
+}
+
+impl Churn {
+    fn rank(&self, rank_by: &RankBy) -> u64 {
+        match rank_by {
+            RankBy::Versions => self.num_versions as u64,
+            RankBy::Bytes => self.changed_bytes,
+        }
+    }
+}
+
+pub struct MostChanged;
+
+impl MostChanged {
+    pub fn exec(config: &MostChangedConfig) -> HttmResult<()> {
+        let files: Vec<PathBuf> = GLOBAL_CONFIG
+            .paths
+            .iter()
+            .flat_map(|pathdata| Self::files_beneath(&pathdata.path_buf))
+            .collect();
+
+        let mut churn: Vec<Churn> = files
+            .par_iter()
+            .filter_map(|path| Self::churn(path))
+            // a file with a single version has never changed
+            .filter(|churn| churn.num_versions > 1)
+            .collect();
+
+        if churn.is_empty() {
+            return Err(HttmError::new(
+                "httm could not find any files with more than one version beneath the paths specified.",
+            )
+            .into());
+        }
+
+        // ties are broken by path, so the output is stable run to run
+        churn.sort_unstable_by(|a, b| {
+            b.rank(&config.rank_by)
+                .cmp(&a.rank(&config.rank_by))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        churn.truncate(config.top);
+
+        print_output_bytes(&Self::format(&churn, &config.rank_by))
+    }
+
+    fn files_beneath(requested_dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        let mut queue: Vec<PathBuf> = vec![requested_dir.to_path_buf()];
+
+        while let Some(dir) = queue.pop() {
+            // no errors will be propagated here, like any other recursive search
+            // far too likely to run into a dir we don't have permissions to view
+            let (vec_dirs, vec_files): (Vec<BasicDirEntryInfo>, Vec<BasicDirEntryInfo>) =
+                match SharedRecursive::entries_partitioned(&dir) {
+                    Ok(entries) => entries,
+                    Err(_) => continue,
+                };
+
+            files.extend(
+                vec_files
+                    .into_iter()
+                    .filter(
+                        |entry| matches!(entry.filetype(), Ok(file_type) if file_type.is_file()),
+                    )
+                    .map(|entry| entry.path),
+            );
+
+            queue.extend(vec_dirs.into_iter().map(|entry| entry.path));
+        }
+
+        files
+    }
+
+    fn churn(path: &Path) -> Option<Churn> {
+        let pathdata = PathData::from(path);
+
+        let mut versions: Vec<PathData> = LiveFilesystemInfo::with_current(|fs_info| {
+            let prox_opt_alts =
+                ProximateDatasetAndOptAlts::with_fs_info(fs_info, &pathdata).ok()?;
+
+            let versions: Vec<PathData> = prox_opt_alts
+                .into_search_bundles()
+                .flat_map(|relative_path_snap_mounts| {
+                    relative_path_snap_mounts.versions_processed(&GLOBAL_CONFIG.uniqueness)
+                })
+                .collect();
+
+            Some(versions)
+        })?;
+
+        if !VersionsMap::is_live_version_redundant(&pathdata, &versions) {
+            versions.push(pathdata.clone());
+        }
+
+        // every version after the first must have been written anew, so the sum of their
+        // sizes is an estimate of the bytes written, without reading any contents
+        let changed_bytes = versions
+            .iter()
+            .skip(1)
+            .map(|version| version.md_infallible().size)
+            .sum();
+
+        Some(Churn {
+            path: pathdata.path_buf,
+            num_versions: versions.len(),
+            changed_bytes,
+        })
+    }
+
+    fn format(churn: &[Churn], rank_by: &RankBy) -> Vec<u8> {
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            let delimiter = delimiter();
+
+            if GLOBAL_CONFIG.opt_escape {
+                return churn
+                    .iter()
+                    .map(|churn| format!("{}{delimiter}", display_path(&churn.path)))
+                    .collect::<String>()
+                    .into_bytes();
+            }
+
+            // raw paths are output exactly as they are on disk
+            let mut delimiter_buffer = [0u8; 4];
+            let delimiter = delimiter.encode_utf8(&mut delimiter_buffer).as_bytes();
+
+            return churn
+                .iter()
+                .flat_map(|churn| {
+                    let mut bytes = churn.path.as_os_str().as_bytes().to_vec();
+                    bytes.extend_from_slice(delimiter);
+                    bytes
+                })
+                .collect();
+        }
+
+        let ranks: Vec<String> = churn
+            .iter()
+            .map(|churn| match rank_by {
+                RankBy::Versions => format!("{} versions", churn.num_versions),
+                RankBy::Bytes => display_human_size(churn.changed_bytes),
+            })
+            .collect();
+
+        let padding = ranks.iter().map(String::len).max().unwrap_or_default();
+
+        churn
+            .iter()
+            .zip(ranks.iter())
+            .map(|(churn, rank)| format!("{rank:>padding$} : {}\n", display_path(&churn.path)))
+            .collect::<String>()
+            .into_bytes()
+    }
+}
@@ -15,14 +15,29 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
+use std::time::Duration;
 
 use which::which;
 
 use crate::exec::interactive::ViewMode;
+use crate::library::checksum::Checksum;
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::display_human_duration;
 use crate::GLOBAL_CONFIG;
 
+// like grep, peek at the head of a file for a NULL byte, or bytes which are not UTF-8, to decide whether it is binary
+const BINARY_DETECTION_LEN: usize = 8_192;
+// a preview pane only has room for so many lines of hexdump
+const HEXDUMP_HEAD_LEN: usize = 512;
+const HEXDUMP_ROW_LEN: usize = 16;
+// a forced preview in browse mode is drawn for each entry the cursor passes over, so contents
+// are only read when both versions are small enough that doing so won't stall the view
+const FORCED_CONTENTS_LEN: u64 = 1_048_576;
+
 pub struct PreviewSelection {
     pub opt_preview_window: Option<String>,
     pub opt_preview_command: Option<String>,
@@ -40,7 +55,7 @@ impl PreviewSelection {
                 };
 
                 PreviewSelection {
-                    opt_preview_window: Some("up:50%".to_owned()),
+                    opt_preview_window: Some(GLOBAL_CONFIG.preview_window.clone()),
                     opt_preview_command: Some(Self::parse_preview_command(
                         defined_command,
                         opt_live_version,
@@ -60,20 +75,32 @@ impl PreviewSelection {
         defined_command: &str,
         opt_live_version: &Option<String>,
     ) -> HttmResult<String> {
-        let command = if defined_command == "default" {
-            match opt_live_version {
-                Some(live_version) if PathBuf::from(live_version).exists() && which("bowie").is_ok() => {
-                    format!("bowie --direct \"$snap_file\" \"{live_version}\"")
-                },
-                _ => match which("cat") {
-                    Ok(_) => "cat \"$snap_file\"".to_string(),
+        let command = if defined_command == "default" || defined_command == "force" {
+            // the default preview is our own, so it can decide, per version, whether a text diff makes any sense
+            let httm_exe = match std::env::current_exe() {
+                Ok(httm_exe) => httm_exe,
+                Err(_) => match which("httm") {
+                    Ok(httm_exe) => httm_exe,
                     Err(_) => {
                         return Err(HttmError::new(
-                            "'cat' executable could not be found in the user's PATH. 'cat' is necessary for executing a bare preview command.",
+                            "httm could not determine the location of its own executable, which is necessary for executing the default preview command.",
                         )
                         .into())
                     }
                 },
+            };
+
+            match opt_live_version {
+                Some(live_version) if PathBuf::from(live_version).exists() => {
+                    format!(
+                        "\"{}\" --preview-compare \"$snap_file\" \"{live_version}\"",
+                        httm_exe.display()
+                    )
+                }
+                _ => format!(
+                    "\"{}\" --preview-compare \"$snap_file\"",
+                    httm_exe.display()
+                ),
             }
         } else {
             match defined_command.split_ascii_whitespace().next() {
@@ -133,3 +160,345 @@ impl PreviewSelection {
         Ok(res)
     }
 }
+
+// the default preview: a text diff, when both versions are text, else a comparison
+// which actually says something about binaries, instead of a screenful of garbage
+pub struct PreviewCompare;
+
+impl PreviewCompare {
+    pub fn exec(snap_file: &Path, opt_live_file: Option<&Path>) -> HttmResult<()> {
+        let opt_live_file = opt_live_file.filter(|live_file| live_file.exists());
+
+        let is_binary = Self::is_binary(snap_file)?
+            || match opt_live_file {
+                Some(live_file) => Self::is_binary(live_file)?,
+                None => false,
+            };
+
+        if !is_binary {
+            return Self::text(snap_file, opt_live_file);
+        }
+
+        let mut output_buf = Self::describe("Snapshot version", snap_file)?;
+
+        match opt_live_file {
+            Some(live_file) => {
+                output_buf += &Self::describe("Live version", live_file)?;
+                output_buf += &Self::compare(snap_file, live_file)?;
+            }
+            None => {
+                output_buf += &format!("\nFirst {HEXDUMP_HEAD_LEN} bytes:\n");
+                output_buf += &Self::hexdump_rows(&Self::head(snap_file)?)
+                    .into_iter()
+                    .map(|row| format!(" {row}\n"))
+                    .collect::<String>();
+            }
+        }
+
+        print!("{output_buf}");
+
+        Ok(())
+    }
+
+    // the browse preview, when forced, compares the newest snapshot version to the live version
+    // by metadata alone, unless both are small regular files, when their contents are compared too
+    pub fn forced(snap_file: &Path, live_file: &Path) -> HttmResult<String> {
+        let snap_md = snap_file.symlink_metadata()?;
+
+        let live_md = match live_file.symlink_metadata() {
+            Ok(live_md) => live_md,
+            Err(_) => {
+                return Ok(
+                    "\nNo live version exists to compare with the newest snapshot version.\n"
+                        .to_owned(),
+                )
+            }
+        };
+
+        let size_delta = match live_md.len() as i128 - snap_md.len() as i128 {
+            0 => "none".to_owned(),
+            delta => format!("{delta:+} bytes"),
+        };
+
+        let modify_delta = match (snap_md.modified(), live_md.modified()) {
+            (Ok(snap_time), Ok(live_time)) => match live_time.duration_since(snap_time) {
+                Ok(duration) if duration == Duration::ZERO => "none".to_owned(),
+                Ok(duration) => format!("live is newer by {}", display_human_duration(duration)),
+                Err(err) => format!(
+                    "live is older by {}",
+                    display_human_duration(err.duration())
+                ),
+            },
+            _ => "unknown".to_owned(),
+        };
+
+        let mut output_buf = format!(
+            "\nNewest snapshot version compared to the live version:\n  Size delta (live - snapshot): {size_delta}\n  Modify time delta: {modify_delta}\n"
+        );
+
+        let is_contents_comparable = snap_md.is_file()
+            && live_md.is_file()
+            && snap_md.len().max(live_md.len()) <= FORCED_CONTENTS_LEN;
+
+        if !is_contents_comparable {
+            output_buf += &format!(
+                "  Contents: not compared, as the versions are not both regular files of {FORCED_CONTENTS_LEN} bytes or less\n"
+            );
+            return Ok(output_buf);
+        }
+
+        if Self::is_binary(snap_file)? || Self::is_binary(live_file)? {
+            output_buf += &Self::compare(snap_file, live_file)?;
+            return Ok(output_buf);
+        }
+
+        let diff_command = match which("diff") {
+            Ok(diff_command) => diff_command,
+            Err(_) => {
+                output_buf += &Self::compare(snap_file, live_file)?;
+                return Ok(output_buf);
+            }
+        };
+
+        // diff exits 1 when the versions differ, but 2 (or by signal) when it was in trouble
+        let diff_output = ExecProcess::new(diff_command)
+            .arg("-u")
+            .arg(snap_file)
+            .arg(live_file)
+            .output()?;
+
+        if !matches!(diff_output.status.code(), Some(0 | 1)) {
+            let stderr_string = std::str::from_utf8(&diff_output.stderr)?.trim();
+            let msg = "httm was unable to compare versions. The 'diff' command issued the following error: ".to_owned() + stderr_string;
+            return Err(HttmError::new(&msg).into());
+        }
+
+        if diff_output.stdout.is_empty() {
+            output_buf += "  Contents: identical\n";
+        } else {
+            output_buf += &format!("\n{}", String::from_utf8_lossy(&diff_output.stdout));
+        }
+
+        Ok(output_buf)
+    }
+
+    fn text(snap_file: &Path, opt_live_file: Option<&Path>) -> HttmResult<()> {
+        let mut process = match opt_live_file {
+            Some(live_file) if which("bowie").is_ok() => {
+                let mut process = ExecProcess::new("bowie");
+                process.arg("--direct").arg(snap_file).arg(live_file);
+                process
+            }
+            _ => match which("cat") {
+                Ok(_) => {
+                    let mut process = ExecProcess::new("cat");
+                    process.arg(snap_file);
+                    process
+                }
+                Err(_) => {
+                    return Err(HttmError::new(
+                        "'cat' executable could not be found in the user's PATH. 'cat' is necessary for executing a bare preview command.",
+                    )
+                    .into())
+                }
+            },
+        };
+
+        // a diff exiting non-zero just means the versions differ
+        let _ = process.status()?;
+
+        Ok(())
+    }
+
+    fn is_binary(path: &Path) -> HttmResult<bool> {
+        if !path.is_file() {
+            return Ok(false);
+        }
+
+        let mut head = Vec::with_capacity(BINARY_DETECTION_LEN);
+
+        File::open(path)?
+            .take(BINARY_DETECTION_LEN as u64)
+            .read_to_end(&mut head)?;
+
+        if head.contains(&0) {
+            return Ok(true);
+        }
+
+        // a multibyte char cut off at the end of the head is not evidence of anything
+        match std::str::from_utf8(&head) {
+            Ok(_) => Ok(false),
+            Err(err) => Ok(err.error_len().is_some()),
+        }
+    }
+
+    fn describe(label: &str, path: &Path) -> HttmResult<String> {
+        let size = path.symlink_metadata()?.len();
+
+        let file_type = match which("file") {
+            Ok(file_command) => ExecProcess::new(file_command)
+                .arg("-b")
+                .arg("--")
+                .arg(path)
+                .output()
+                .ok()
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+                .filter(|file_type| !file_type.is_empty()),
+            Err(_) => None,
+        }
+        .unwrap_or_else(|| "unknown (the 'file' executable could not be found)".to_owned());
+
+        Ok(format!(
+            "{label}: \"{}\"\n  Size: {size} bytes\n  Type: {file_type}\n",
+            path.display()
+        ))
+    }
+
+    // the select view may also compare two snapshot versions, one marked as the base, with each other
+    pub fn between(base_file: &Path, compare_file: &Path) -> HttmResult<String> {
+        let mut output_buf = Self::describe("Base version", base_file)?;
+        output_buf += &Self::describe("Compare version", compare_file)?;
+
+        let is_text = base_file.is_file()
+            && compare_file.is_file()
+            && !Self::is_binary(base_file)?
+            && !Self::is_binary(compare_file)?;
+
+        match which("diff") {
+            Ok(diff_command) if is_text => {
+                // diff exits 1 when the versions differ, but 2 (or by signal) when it was in trouble
+                let diff_output = ExecProcess::new(diff_command)
+                    .arg("-u")
+                    .arg(base_file)
+                    .arg(compare_file)
+                    .output()?;
+
+                if !matches!(diff_output.status.code(), Some(0 | 1)) {
+                    let stderr_string = std::str::from_utf8(&diff_output.stderr)?.trim();
+                    let msg = "httm was unable to compare versions. The 'diff' command issued the following error: ".to_owned() + stderr_string;
+                    return Err(HttmError::new(&msg).into());
+                }
+
+                if diff_output.stdout.is_empty() {
+                    output_buf += "\nContents: identical\n";
+                } else {
+                    output_buf += &format!("\n{}", String::from_utf8_lossy(&diff_output.stdout));
+                }
+            }
+            _ if base_file.is_file() && compare_file.is_file() => {
+                output_buf += &Self::compare_as(base_file, compare_file, ("base", "compare"))?;
+            }
+            _ => {
+                output_buf +=
+                    "\nContents: not compared, as the versions are not both regular files\n";
+            }
+        }
+
+        Ok(output_buf)
+    }
+
+    fn compare(snap_file: &Path, live_file: &Path) -> HttmResult<String> {
+        Self::compare_as(snap_file, live_file, ("snapshot", "live"))
+    }
+
+    fn compare_as(
+        snap_file: &Path,
+        live_file: &Path,
+        (snap_label, live_label): (&str, &str),
+    ) -> HttmResult<String> {
+        let snap_size = snap_file.symlink_metadata()?.len() as i128;
+        let live_size = live_file.symlink_metadata()?.len() as i128;
+
+        let size_delta = match live_size - snap_size {
+            0 => "none".to_owned(),
+            delta => format!("{delta:+} bytes"),
+        };
+
+        let is_identical = snap_size == live_size
+            && Checksum::without_permit(snap_file)? == Checksum::without_permit(live_file)?;
+
+        let mut output_buf = format!(
+            "\nSize delta ({live_label} - {snap_label}): {size_delta}\nContents: {}\n",
+            if is_identical {
+                "identical (blake3 checksums match)"
+            } else {
+                "differ"
+            }
+        );
+
+        if is_identical {
+            return Ok(output_buf);
+        }
+
+        let snap_rows = Self::hexdump_rows(&Self::head(snap_file)?);
+        let live_rows = Self::hexdump_rows(&Self::head(live_file)?);
+
+        let row_count = snap_rows.len().max(live_rows.len());
+
+        let differing_rows: String = (0..row_count)
+            .filter(|idx| snap_rows.get(*idx) != live_rows.get(*idx))
+            .map(|idx| {
+                let mut rows = String::new();
+
+                if let Some(row) = snap_rows.get(idx) {
+                    rows += &format!("-{row}\n");
+                }
+
+                if let Some(row) = live_rows.get(idx) {
+                    rows += &format!("+{row}\n");
+                }
+
+                rows
+            })
+            .collect();
+
+        if differing_rows.is_empty() {
+            output_buf +=
+                &format!("\nThe first {HEXDUMP_HEAD_LEN} bytes of each version are identical.\n");
+        } else {
+            output_buf += &format!(
+                "\nRows which differ within the first {HEXDUMP_HEAD_LEN} bytes (- {snap_label}, + {live_label}):\n{differing_rows}"
+            );
+        }
+
+        Ok(output_buf)
+    }
+
+    fn head(path: &Path) -> HttmResult<Vec<u8>> {
+        let mut head = Vec::with_capacity(HEXDUMP_HEAD_LEN);
+
+        File::open(path)?
+            .take(HEXDUMP_HEAD_LEN as u64)
+            .read_to_end(&mut head)?;
+
+        Ok(head)
+    }
+
+    // like "hexdump -C"
+    fn hexdump_rows(bytes: &[u8]) -> Vec<String> {
+        bytes
+            .chunks(HEXDUMP_ROW_LEN)
+            .enumerate()
+            .map(|(idx, chunk)| {
+                let hex: String = chunk.iter().map(|byte| format!("{byte:02x} ")).collect();
+
+                let ascii: String = chunk
+                    .iter()
+                    .map(|byte| {
+                        if byte.is_ascii_graphic() || *byte == b' ' {
+                            *byte as char
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+
+                format!(
+                    "{:08x}  {hex:<width$} |{ascii}|",
+                    idx * HEXDUMP_ROW_LEN,
+                    width = HEXDUMP_ROW_LEN * 3
+                )
+            })
+            .collect()
+    }
+}
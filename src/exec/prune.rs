@@ -15,11 +15,17 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::process::Command as ExecProcess;
+use std::collections::BTreeSet;
 
 use crate::config::generate::ListSnapsFilters;
 use crate::exec::interactive::ViewMode;
+use crate::library::escalate::PrivilegeBroker;
+use crate::library::hooks::{Hook, HookEvent};
+use crate::library::messages::Catalog;
+use crate::library::output_sink::OutputSink;
+use crate::library::pool_health::PoolHealth;
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::zfs_allow::{ZfsAllow, DESTROY_PRIVS};
 use crate::lookup::snap_names::SnapNameMap;
 use crate::lookup::versions::VersionsMap;
 
@@ -32,6 +38,16 @@ impl PruneSnaps {
     ) -> HttmResult<()> {
         let snap_name_map: SnapNameMap = SnapNameMap::new(versions_map, opt_filters)?;
 
+        // check delegated permissions before asking the user anything
+        snap_name_map
+            .values()
+            .flatten()
+            .filter_map(|snap_name| snap_name.split_once('@'))
+            .map(|(dataset_name, _snap)| dataset_name)
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .try_for_each(|dataset_name| ZfsAllow::check_dataset(dataset_name, &DESTROY_PRIVS))?;
+
         let select_mode = if let Some(filters) = opt_filters {
             filters.select_mode
         } else {
@@ -65,13 +81,17 @@ impl PruneSnaps {
             .collect();
 
         let preview_buffer = format!(
-            "User has requested snapshots related to the following file/s be pruned:\n\n{}\n\
-            httm will destroy the following snapshot/s:\n\n{}\n\
-            Before httm destroys these snapshot/s, it would like your consent. Continue? (YES/NO)\n\
+            "{}\n\n{}\n\
+            {}\n\n{}\n\
+            {}\n\
             ─────────────────────────────────────────────────────────────────────────────\n\
             YES\n\
             NO",
-            file_names_string, snap_names_string
+            Catalog::get("prune-consent-files"),
+            file_names_string,
+            Catalog::get("prune-consent-snaps"),
+            snap_names_string,
+            Catalog::get("prune-consent-question")
         );
 
         // loop until user consents or doesn't
@@ -81,34 +101,51 @@ impl PruneSnaps {
 
             match user_consent.as_ref() {
                 "YES" | "Y" => {
+                    // check each pool only once, no matter how many snapshots reside upon it
+                    let pool_names: BTreeSet<&str> = snap_names
+                        .iter()
+                        .filter_map(|snap_name| snap_name.split(['/', '@']).next())
+                        .collect();
+
+                    pool_names
+                        .iter()
+                        .try_for_each(|pool_name| PoolHealth::precheck(pool_name))?;
+
                     Self::prune_snaps(snap_name_map)?;
 
                     let result_buffer = format!(
-                        "httm pruned snapshots related to the following file/s:\n\n{}\n\
-                        By destroying the following snapshot/s:\n\n{}\n\
-                        Prune completed successfully.",
-                        file_names_string, snap_names_string
+                        "{}\n\n{}\n\
+                        {}\n\n{}\n\
+                        {}",
+                        Catalog::get("prune-completed-files"),
+                        file_names_string,
+                        Catalog::get("prune-completed-snaps"),
+                        snap_names_string,
+                        Catalog::get("prune-completed")
                     );
 
                     break eprintln!("{result_buffer}");
                 }
-                "NO" | "N" => break eprintln!("User declined prune.  No files were pruned."),
+                "NO" | "N" => break eprintln!("{}", Catalog::get("prune-declined")),
                 // if not yes or no, then noop and continue to the next iter of loop
                 _ => {}
             }
         }
 
+        OutputSink::commit()?;
+
         std::process::exit(0)
     }
 
     fn prune_snaps(snap_name_map: &SnapNameMap) -> HttmResult<()> {
-        let zfs_command = which::which("zfs").map_err(|_err| {
-            HttmError::new("'zfs' command not found. Make sure the command 'zfs' is in your path.")
-        })?;
+        let snapshot_names: Vec<String> = snap_name_map.values().flatten().cloned().collect();
+
+        Hook::run(HookEvent::PreDestroy, &snapshot_names)?;
+
         snap_name_map.values().flatten().try_for_each( |snapshot_name| {
             let process_args = vec!["destroy".to_owned(), snapshot_name.clone()];
 
-            let process_output = ExecProcess::new(&zfs_command).args(&process_args).output()?;
+            let process_output = PrivilegeBroker::zfs()?.args(&process_args).output()?;
             let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
 
             // stderr_string is a string not an error, so here we build an err or output
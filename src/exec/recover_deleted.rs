@@ -0,0 +1,263 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use hashbrown::HashMap;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+
+use crate::config::generate::RecoverDeletedConfig;
+use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::exec::interactive::ViewMode;
+use crate::exec::recursive::SharedRecursive;
+use crate::library::messages::Catalog;
+use crate::library::output_sink::OutputSink;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{copy_recursive, date_string, DateFormat};
+use crate::lookup::deleted::{DeletedFiles, LastInTimeSet};
+use crate::GLOBAL_CONFIG;
+
+struct DeletedCandidate {
+    requested_dir: PathBuf,
+    live_path: PathBuf,
+    last_snap: PathBuf,
+    modify_time: SystemTime,
+}
+
+pub struct RecoverDeleted;
+
+impl RecoverDeleted {
+    pub fn exec(config: &RecoverDeletedConfig) -> HttmResult<()> {
+        let opt_restore_to = &config.opt_restore_to;
+
+        if let Some(restore_to) = opt_restore_to {
+            if !restore_to.is_dir() {
+                return Err(HttmError::new("RESTORE_TO must be an existing directory.").into());
+            }
+        }
+
+        let progress_bar = &config.progress_bar;
+        progress_bar.set_message("Searching for deleted files...");
+
+        let mut candidates: Vec<DeletedCandidate> = Vec::new();
+
+        for pathdata in GLOBAL_CONFIG.paths.iter() {
+            if !pathdata.path_buf.is_dir() {
+                let msg = format!(
+                    "RECOVER_DELETED requires a directory, but the path specified is not: {:?}",
+                    pathdata.path_buf
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+
+            candidates.extend(Self::deleted_beneath(&pathdata.path_buf, progress_bar));
+        }
+
+        progress_bar.finish_and_clear();
+
+        if candidates.is_empty() {
+            return Err(HttmError::localized("error-no-deleted").into());
+        }
+
+        candidates.sort_by(|a, b| a.live_path.cmp(&b.live_path));
+
+        let buffer: String = candidates
+            .iter()
+            .map(|candidate| {
+                Ok(format!(
+                    "{}\t\"{}\"\n",
+                    date_string(
+                        GLOBAL_CONFIG.requested_utc_offset,
+                        &candidate.modify_time,
+                        DateFormat::Display
+                    )?,
+                    candidate.live_path.display()
+                ))
+            })
+            .collect::<HttmResult<String>>()?;
+
+        let view_mode = &ViewMode::Recover;
+        let selected = view_mode.select(&buffer, true)?;
+
+        let candidates_map: HashMap<&Path, &DeletedCandidate> = candidates
+            .iter()
+            .map(|candidate| (candidate.live_path.as_path(), candidate))
+            .collect();
+
+        // ... we want everything between the quotes
+        let chosen: Vec<&DeletedCandidate> = selected
+            .iter()
+            .filter_map(|line| line.split_terminator('"').nth(1))
+            .filter_map(|live_path| candidates_map.get(Path::new(live_path)).copied())
+            .collect();
+
+        if chosen.is_empty() {
+            return Err(HttmError::localized("error-no-valid-selection").into());
+        }
+
+        let restores: Vec<(&DeletedCandidate, PathBuf)> = chosen
+            .into_iter()
+            .map(|candidate| {
+                let destination = Self::destination(candidate, opt_restore_to);
+                (candidate, destination)
+            })
+            .collect();
+
+        Self::interactive_recover(&restores)
+    }
+
+    fn interactive_recover(restores: &[(&DeletedCandidate, PathBuf)]) -> HttmResult<()> {
+        let restores_string: String = restores
+            .iter()
+            .map(|(candidate, destination)| {
+                format!(
+                    "\tfrom: {:?}\n\tto:   {:?}\n",
+                    candidate.last_snap, destination
+                )
+            })
+            .collect();
+
+        let preview_buffer = format!(
+            "{}\n\n{}\n\
+            {}\n\
+            ──────────────────────────────────────────────────────────────────────────────\n\
+            YES\n\
+            NO",
+            Catalog::get("recover-consent-header"),
+            restores_string,
+            Catalog::get("recover-consent-question")
+        );
+
+        // loop until user consents or doesn't
+        loop {
+            let view_mode = &ViewMode::Restore;
+            let user_consent = view_mode.select(&preview_buffer, false)?[0].to_ascii_uppercase();
+
+            match user_consent.as_ref() {
+                "YES" | "Y" => {
+                    let mut result_buffer =
+                        format!("{}\n\n", Catalog::get("recover-completed-header"));
+                    let mut num_failed = 0usize;
+
+                    restores.iter().for_each(|(candidate, destination)| {
+                        match Self::recover(&candidate.last_snap, destination) {
+                            Ok(_) => {
+                                result_buffer += &format!(
+                                    "\tfrom: {:?}\n\tto:   {:?}\n",
+                                    candidate.last_snap, destination
+                                )
+                            }
+                            Err(err) => {
+                                num_failed += 1;
+                                result_buffer += &format!("\tfailed: {:?}: {err}\n", destination)
+                            }
+                        }
+                    });
+
+                    // a partial recovery is still a failure, so scripts can tell from the exit status
+                    if num_failed > 0 {
+                        eprint!("{result_buffer}");
+
+                        let msg = Catalog::format(
+                            "error-recover-failed",
+                            &[
+                                ("failed", &num_failed.to_string()),
+                                ("total", &restores.len().to_string()),
+                            ],
+                        );
+                        return Err(HttmError::new(&msg).into());
+                    }
+
+                    result_buffer += &format!("\n{}", Catalog::get("recover-completed"));
+
+                    break eprintln!("{result_buffer}");
+                }
+                "NO" | "N" => break eprintln!("{}", Catalog::get("recover-declined")),
+                // if not yes or no, then noop and continue to the next iter of loop
+                _ => {}
+            }
+        }
+
+        OutputSink::commit()?;
+
+        std::process::exit(0)
+    }
+
+    // never overwrites, deleted files should, by definition, have nothing in their place
+    fn recover(last_snap: &Path, destination: &Path) -> HttmResult<()> {
+        if destination.symlink_metadata().is_ok() {
+            return Err(HttmError::localized("error-restore-exists").into());
+        }
+
+        // missing parents are created by the copy, which gives them the attributes of their snapshot counterparts
+        copy_recursive(last_snap, destination, true)
+    }
+
+    fn destination(candidate: &DeletedCandidate, opt_restore_to: &Option<PathBuf>) -> PathBuf {
+        match opt_restore_to {
+            Some(restore_to) => match candidate.live_path.strip_prefix(&candidate.requested_dir) {
+                Ok(relative_path) => restore_to.join(relative_path),
+                Err(_) => restore_to.join(candidate.live_path.file_name().unwrap_or_default()),
+            },
+            None => candidate.live_path.clone(),
+        }
+    }
+
+    // deleted dirs are restored whole, so we only descend into live dirs
+    fn deleted_beneath(requested_dir: &Path, progress_bar: &ProgressBar) -> Vec<DeletedCandidate> {
+        let mut candidates: Vec<DeletedCandidate> = Vec::new();
+        let mut queue: Vec<PathBuf> = vec![requested_dir.to_path_buf()];
+
+        while let Some(dir) = queue.pop() {
+            progress_bar.tick();
+
+            // no errors will be propagated here, like any other recursive search
+            // far too likely to run into a dir we don't have permissions to view
+            let vec_deleted: Vec<BasicDirEntryInfo> = match DeletedFiles::new(&dir) {
+                Ok(deleted) => deleted.into_inner(),
+                Err(_) => Vec::new(),
+            };
+
+            candidates.par_extend(vec_deleted.into_par_iter().filter_map(|entry| {
+                let live_path = dir.join(entry.filename());
+
+                // the deleted search finds at least one version, here we want the last in time
+                let last_snap = LastInTimeSet::new(vec![PathData::from(live_path.as_path())])
+                    .ok()?
+                    .first()?
+                    .to_owned();
+
+                let modify_time = last_snap.symlink_metadata().ok()?.modified().ok()?;
+
+                Some(DeletedCandidate {
+                    requested_dir: requested_dir.to_path_buf(),
+                    live_path,
+                    last_snap,
+                    modify_time,
+                })
+            }));
+
+            if let Ok((vec_dirs, _vec_files)) = SharedRecursive::entries_partitioned(&dir) {
+                queue.extend(vec_dirs.into_iter().map(|entry| entry.path));
+            }
+        }
+
+        candidates
+    }
+}
@@ -16,35 +16,60 @@
 // that was distributed with this source code.
 
 use std::os::unix::fs::MetadataExt;
-use std::{fs::read_dir, path::Path, sync::Arc};
+use std::sync::Mutex;
+use std::{fs::read_dir, path::Path, path::PathBuf, sync::Arc, thread};
 
+use hashbrown::HashMap;
 use once_cell::sync::Lazy;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use rayon::{Scope, ThreadPool};
 use skim::prelude::*;
 
 use crate::config::generate::{DeletedMode, ExecMode};
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::{BasicDirEntryInfo, PathData};
 use crate::data::selection::SelectionCandidate;
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
 use crate::exec::deleted::SpawnDeletedThread;
-use crate::library::results::{HttmError, HttmResult};
+use crate::library::results::{HttmError, HttmResult, SkippedPaths};
 use crate::library::utility::is_channel_closed;
-use crate::library::utility::{print_output_buf, HttmIsDir, Never};
+use crate::library::utility::{print_notice, print_output_bytes, HttmIsDir, Never};
 use crate::parse::mounts::MaxLen;
 use crate::VersionsMap;
 use crate::GLOBAL_CONFIG;
 use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, ZFS_HIDDEN_DIRECTORY};
 
-static OPT_REQUESTED_DIR_DEV: Lazy<u64> = Lazy::new(|| {
-    GLOBAL_CONFIG
-        .opt_requested_dir
-        .as_ref()
-        .expect("opt_requested_dir should be Some value at this point in execution")
-        .path_buf
-        .symlink_metadata()
-        .expect("Cannot read metadata for directory requested for search.")
-        .dev()
-});
+// number of entries per directory whose versions are looked up before display in high-latency mode
+const HIGH_LATENCY_LOOKAHEAD: usize = 64;
+
+// number of entries, per thread, which may be in flight between the directory readers and
+// the interactive view, before the readers block and wait for the view to catch up
+const ENTRIES_PER_THREAD: usize = 1024;
+
+// key: requested dir, val: its device, one per requested dir, as several dirs, browsed together,
+// may each reside upon a different filesystem
+static REQUESTED_DIR_DEVS: Lazy<Mutex<HashMap<PathBuf, u64>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// where the directory readers send what they find: the interactive view receives single entries,
+// while display recursive receives whole directories at a time, to look up and print as a batch
+#[derive(Clone)]
+pub enum EntrySender {
+    Interactive(SkimItemSender),
+    NonInteractive(Sender<Vec<BasicDirEntryInfo>>),
+}
+
+impl EntrySender {
+    // both channels are bounded, so memory use stays flat upon huge trees,
+    // as readers simply block when the consuming side falls behind
+    pub fn interactive_capacity() -> usize {
+        rayon::current_num_threads() * ENTRIES_PER_THREAD
+    }
+
+    pub fn non_interactive_capacity() -> usize {
+        rayon::current_num_threads()
+    }
+}
 
 #[derive(Clone, Copy)]
 pub enum PathProvenance {
@@ -55,16 +80,16 @@ pub enum PathProvenance {
 pub struct RecursiveSearch;
 
 impl RecursiveSearch {
-    pub fn exec(requested_dir: &Path, skim_tx: SkimItemSender, hangup_rx: Receiver<Never>) {
+    pub fn exec(requested_dir: &Path, entry_tx: EntrySender, hangup_rx: Receiver<Never>) {
         fn run_loop(
             requested_dir: &Path,
-            skim_tx: SkimItemSender,
+            entry_tx: EntrySender,
             hangup_rx: Receiver<Never>,
             opt_deleted_scope: Option<&Scope>,
         ) {
             // this runs the main loop for live file searches, see the referenced struct below
             // we are in our own detached system thread, so print error and exit if error trickles up
-            RecursiveMainLoop::exec(requested_dir, opt_deleted_scope, &skim_tx, &hangup_rx)
+            RecursiveMainLoop::exec(requested_dir, opt_deleted_scope, &entry_tx, &hangup_rx)
                 .unwrap_or_else(|error| {
                     eprintln!("Error: {error}");
                     std::process::exit(1)
@@ -77,14 +102,16 @@ impl RecursiveSearch {
             // for display recursive searches as the live enumeration will end before
             // all deleted threads have completed
             let pool: ThreadPool = rayon::ThreadPoolBuilder::new()
+                // zero threads is the rayon default of one thread per logical CPU
+                .num_threads(GLOBAL_CONFIG.opt_threads.unwrap_or(0))
                 .build()
                 .expect("Could not initialize rayon threadpool for recursive deleted search");
 
             pool.in_place_scope(|deleted_scope| {
-                run_loop(requested_dir, skim_tx, hangup_rx, Some(deleted_scope))
+                run_loop(requested_dir, entry_tx, hangup_rx, Some(deleted_scope))
             })
         } else {
-            run_loop(requested_dir, skim_tx, hangup_rx, None)
+            run_loop(requested_dir, entry_tx, hangup_rx, None)
         }
     }
 }
@@ -96,14 +123,14 @@ impl RecursiveMainLoop {
     fn exec(
         requested_dir: &Path,
         opt_deleted_scope: Option<&Scope>,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
         hangup_rx: &Receiver<Never>,
     ) -> HttmResult<()> {
         // runs once for non-recursive but also "primes the pump"
         // for recursive to have items available, also only place an
         // error can stop execution
         let mut queue: Vec<BasicDirEntryInfo> =
-            Self::enter_directory(requested_dir, opt_deleted_scope, skim_tx, hangup_rx)?;
+            Self::enter_directory(requested_dir, opt_deleted_scope, entry_tx, hangup_rx)?;
 
         if GLOBAL_CONFIG.opt_recursive {
             // condition kills iter when user has made a selection
@@ -118,10 +145,10 @@ impl RecursiveMainLoop {
 
                 // no errors will be propagated in recursive mode
                 // far too likely to run into a dir we don't have permissions to view
-                if let Ok(items) =
-                    Self::enter_directory(&item.path, opt_deleted_scope, skim_tx, hangup_rx)
-                {
-                    queue.extend(items)
+                // but, in STRICT mode, each is reported upon exit
+                match Self::enter_directory(&item.path, opt_deleted_scope, entry_tx, hangup_rx) {
+                    Ok(items) => queue.extend(items),
+                    Err(err) => SkippedPaths::record(&item.path, err.as_ref()),
                 }
             }
         }
@@ -132,7 +159,7 @@ impl RecursiveMainLoop {
     fn enter_directory(
         requested_dir: &Path,
         opt_deleted_scope: Option<&Scope>,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
         hangup_rx: &Receiver<Never>,
     ) -> HttmResult<Vec<BasicDirEntryInfo>> {
         // combined entries will be sent or printed, but we need the vec_dirs to recurse
@@ -144,11 +171,11 @@ impl RecursiveMainLoop {
             &vec_dirs,
             PathProvenance::FromLiveDataset,
             requested_dir,
-            skim_tx,
+            entry_tx,
         )?;
 
         if let Some(deleted_scope) = opt_deleted_scope {
-            SpawnDeletedThread::exec(requested_dir, deleted_scope, skim_tx, hangup_rx);
+            SpawnDeletedThread::exec(requested_dir, deleted_scope, entry_tx, hangup_rx);
         }
 
         Ok(vec_dirs)
@@ -163,7 +190,7 @@ impl SharedRecursive {
         vec_dirs: &[BasicDirEntryInfo],
         is_phantom: PathProvenance,
         requested_dir: &Path,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
     ) -> HttmResult<()> {
         let mut combined = vec_files;
         combined.extend_from_slice(vec_dirs);
@@ -191,7 +218,7 @@ impl SharedRecursive {
             }
         };
 
-        Self::display_or_transmit(entries, is_phantom, skim_tx)
+        Self::display_or_transmit(entries, is_phantom, entry_tx)
     }
 
     pub fn entries_partitioned(
@@ -215,9 +242,9 @@ impl SharedRecursive {
                 }
 
                 if GLOBAL_CONFIG.opt_one_filesystem {
-                    if let Some(requested_dir_dev) = Lazy::get(&OPT_REQUESTED_DIR_DEV) {
+                    if let Some(requested_dir_dev) = Self::requested_dir_dev(&entry.path) {
                         match entry.path.symlink_metadata() {
-                            Ok(path_md) if *requested_dir_dev != path_md.dev() => {
+                            Ok(path_md) if requested_dir_dev != path_md.dev() => {
                                 return false;
                             }
                             Ok(_) => {}
@@ -243,6 +270,42 @@ impl SharedRecursive {
         Ok((vec_dirs, vec_files))
     }
 
+    // the device of the requested dir nearest the path, as requested dirs may be nested one within another
+    fn requested_dir_dev(path: &Path) -> Option<u64> {
+        let requested_dir = GLOBAL_CONFIG
+            .opt_requested_dir
+            .iter()
+            .chain(GLOBAL_CONFIG.additional_requested_dirs.iter())
+            .map(|requested_dir| requested_dir.path_buf.as_path())
+            .filter(|requested_dir| path.starts_with(requested_dir))
+            .max_by_key(|requested_dir| requested_dir.components().count())?;
+
+        let mut cache = REQUESTED_DIR_DEVS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(dev) = cache.get(requested_dir) {
+            return Some(*dev);
+        }
+
+        let dev = requested_dir
+            .symlink_metadata()
+            .expect("Cannot read metadata for directory requested for search.")
+            .dev();
+
+        cache.insert(requested_dir.to_path_buf(), dev);
+
+        Some(dev)
+    }
+
+    // a filesystem may since have been mounted upon a requested dir
+    pub fn clear_cache() {
+        REQUESTED_DIR_DEVS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
     pub fn is_entry_dir(entry: &BasicDirEntryInfo) -> bool {
         // must do is_dir() look up on DirEntry file_type() as look up on Path will traverse links!
         if GLOBAL_CONFIG.opt_no_traverse {
@@ -267,10 +330,10 @@ impl SharedRecursive {
         }
 
         // is a common btrfs snapshot dir?
-        if let Some(common_snap_dir) = &GLOBAL_CONFIG.dataset_collection.opt_common_snap_dir {
-            if path == *common_snap_dir {
-                return true;
-            }
+        if LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info.opt_common_snap_dir.as_deref() == Some(path)
+        }) {
+            return true;
         }
 
         // check whether user requested this dir specifically, then we will show
@@ -280,13 +343,23 @@ impl SharedRecursive {
             }
         }
 
-        // finally : is a non-supported dataset?
-        // bailout easily if path is larger than max_filter_dir len
-        if path.components().count() > GLOBAL_CONFIG.dataset_collection.filter_dirs.max_len() {
+        if GLOBAL_CONFIG
+            .additional_requested_dirs
+            .iter()
+            .any(|user_requested_dir| user_requested_dir.path_buf.as_path() == path)
+        {
             return false;
         }
 
-        GLOBAL_CONFIG.dataset_collection.filter_dirs.contains(path)
+        // finally : is a non-supported dataset?
+        LiveFilesystemInfo::with_current(|fs_info| {
+            // bailout easily if path is larger than max_filter_dir len
+            if path.components().count() > fs_info.filter_dirs.max_len() {
+                return false;
+            }
+
+            fs_info.filter_dirs.contains(path)
+        })
     }
 
     // this function creates dummy "live versions" values to match deleted files
@@ -308,31 +381,30 @@ impl SharedRecursive {
     fn display_or_transmit(
         entries: Vec<BasicDirEntryInfo>,
         is_phantom: PathProvenance,
-        skim_tx: &SkimItemSender,
+        entry_tx: &EntrySender,
     ) -> HttmResult<()> {
-        // send to the interactive view, or print directly, never return back
-        match &GLOBAL_CONFIG.exec_mode {
-            ExecMode::Interactive(_) => Self::transmit(entries, is_phantom, skim_tx)?,
-            ExecMode::NonInteractiveRecursive(progress_bar) => {
-                if entries.is_empty() {
-                    if GLOBAL_CONFIG.opt_recursive {
+        // send to the interactive view, or to the display thread to be printed, never return back
+        match entry_tx {
+            EntrySender::Interactive(skim_tx) => Self::transmit(entries, is_phantom, skim_tx)?,
+            EntrySender::NonInteractive(display_tx) => {
+                if !entries.is_empty() {
+                    // blocks when the display thread has fallen behind
+                    return display_tx.send(entries).map_err(std::convert::Into::into);
+                }
+
+                if GLOBAL_CONFIG.opt_recursive {
+                    if let ExecMode::NonInteractiveRecursive(progress_bar) =
+                        &GLOBAL_CONFIG.exec_mode
+                    {
                         progress_bar.tick();
-                    } else {
-                        eprintln!(
-                            "NOTICE: httm could not find any deleted files at this directory level.  \
-                        Perhaps try specifying a deleted mode in combination with \"--recursive\"."
-                        )
                     }
                 } else {
-                    NonInteractiveRecursiveWrapper::print(entries)?;
-
-                    // keeps spinner from squashing last line of output
-                    if GLOBAL_CONFIG.opt_recursive {
-                        eprintln!();
-                    }
+                    print_notice(
+                        "NOTICE: httm could not find any deleted files at this directory level.  \
+                    Perhaps try specifying a deleted mode in combination with \"--recursive\".",
+                    )
                 }
             }
-            _ => unreachable!(),
         }
 
         Ok(())
@@ -343,13 +415,32 @@ impl SharedRecursive {
         is_phantom: PathProvenance,
         skim_tx: &SkimItemSender,
     ) -> HttmResult<()> {
+        let candidates: Vec<SelectionCandidate> = entries
+            .into_iter()
+            .map(|basic_info| SelectionCandidate::new(basic_info, is_phantom))
+            .collect();
+
+        if GLOBAL_CONFIG.opt_high_latency {
+            // one parallel batch of metadata calls per directory, rather than
+            // a call for each entry as it is displayed
+            candidates
+                .par_iter()
+                .for_each(SelectionCandidate::prefetch_metadata);
+
+            candidates
+                .iter()
+                .take(HIGH_LATENCY_LOOKAHEAD)
+                .for_each(SelectionCandidate::prefetch_version_summary);
+        }
+
         // don't want a par_iter here because it will block and wait for all
         // results, instead of printing and recursing into the subsequent dirs
-        entries
+        //
+        // send, rather than try_send, blocks when the bounded channel is full,
+        // which is our backpressure upon the directory readers
+        candidates
             .into_iter()
-            .try_for_each(|basic_info| {
-                skim_tx.try_send(Arc::new(SelectionCandidate::new(basic_info, is_phantom)))
-            })
+            .try_for_each(|candidate| skim_tx.send(Arc::new(candidate)))
             .map_err(std::convert::Into::into)
     }
 }
@@ -361,13 +452,28 @@ pub struct NonInteractiveRecursiveWrapper;
 impl NonInteractiveRecursiveWrapper {
     #[allow(unused_variables)]
     pub fn exec() -> HttmResult<()> {
-        // won't be sending anything anywhere, this just allows us to reuse enumerate_directory
-        let (dummy_skim_tx, _): (SkimItemSender, SkimItemReceiver) = unbounded();
+        // directory readers send batches of entries to a single display thread, which
+        // looks up and prints their versions, so lookups never hold up the readers
+        let (display_tx, display_rx): (
+            Sender<Vec<BasicDirEntryInfo>>,
+            Receiver<Vec<BasicDirEntryInfo>>,
+        ) = bounded(EntrySender::non_interactive_capacity());
         let (hangup_tx, hangup_rx): (Sender<Never>, Receiver<Never>) = bounded(0);
 
         match &GLOBAL_CONFIG.opt_requested_dir {
             Some(requested_dir) => {
-                RecursiveSearch::exec(&requested_dir.path_buf, dummy_skim_tx, hangup_rx);
+                let display_handle = thread::spawn(move || Self::display_loop(&display_rx));
+
+                // all senders are dropped once the search completes, which ends the display loop
+                RecursiveSearch::exec(
+                    &requested_dir.path_buf,
+                    EntrySender::NonInteractive(display_tx),
+                    hangup_rx,
+                );
+
+                display_handle
+                    .join()
+                    .map_err(|_| HttmError::new("Display thread for recursive search panicked"))?;
             }
             None => {
                 return Err(HttmError::new(
@@ -380,12 +486,29 @@ impl NonInteractiveRecursiveWrapper {
         Ok(())
     }
 
+    fn display_loop(display_rx: &Receiver<Vec<BasicDirEntryInfo>>) {
+        // we are in our own detached system thread, so print error and exit if error trickles up
+        display_rx.iter().for_each(|entries| {
+            Self::print(entries).unwrap_or_else(|error| {
+                eprintln!("Error: {error}");
+                std::process::exit(1)
+            });
+
+            // keeps spinner from squashing last line of output
+            if let ExecMode::NonInteractiveRecursive(progress_bar) = &GLOBAL_CONFIG.exec_mode {
+                if GLOBAL_CONFIG.opt_recursive && !progress_bar.is_hidden() {
+                    eprintln!();
+                }
+            }
+        })
+    }
+
     fn print(entries: Vec<BasicDirEntryInfo>) -> HttmResult<()> {
         let pseudo_live_set: Vec<PathData> = entries.into_iter().map(PathData::from).collect();
 
         let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &pseudo_live_set)?;
-        let output_buf = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_string();
+        let output_buf = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_bytes()?;
 
-        print_output_buf(output_buf)
+        print_output_bytes(&output_buf)
     }
 }
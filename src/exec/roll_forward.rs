@@ -20,27 +20,33 @@ use std::fs::read_dir;
 use std::io::{BufRead, Read};
 use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
+use std::process::ChildStderr;
 use std::process::Stdio;
 use std::process::{Child, ChildStdout};
-use std::process::{ChildStderr, Command as ExecProcess};
 use std::sync::atomic::AtomicBool;
 use std::thread::JoinHandle;
 
 use hashbrown::{HashMap, HashSet};
 use nu_ansi_term::Color::{Blue, Green, Red, Yellow};
+use proc_mounts::MountIter;
 use rayon::prelude::*;
-use which::which;
 
 use crate::config::generate::RollForwardConfig;
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::BasicDirEntryInfo;
 use crate::data::paths::PathData;
+use crate::library::checksum::Checksum;
+use crate::library::escalate::PrivilegeBroker;
 use crate::library::iter_extensions::HttmIter;
+use crate::library::pool_health::PoolHealth;
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::snap_guard::{PrecautionarySnapType, SnapGuard};
 use crate::library::utility::preserve_recursive;
 use crate::library::utility::{copy_attributes, generate_dst_parent};
-use crate::library::utility::{copy_direct, remove_recursive};
-use crate::library::utility::{is_metadata_same, user_has_effective_root};
+use crate::library::utility::{copy_direct, remove_recursive, user_has_effective_root};
+use crate::library::utility::{is_metadata_same, paint_notice, print_notice};
+use crate::library::zfs_allow::{ZfsAllow, ROLL_FORWARD_PRIVS};
+use crate::parse::aliases::FilesystemType;
 use crate::{GLOBAL_CONFIG, ZFS_SNAPSHOT_DIRECTORY};
 
 #[derive(Debug, Clone)]
@@ -131,13 +137,19 @@ impl RollForward {
             return Err(HttmError::new(&msg).into());
         };
 
-        let proximate_dataset_mount = GLOBAL_CONFIG
-            .dataset_collection
-            .map_of_datasets
-            .iter()
-            .find(|(_mount, md)| md.source == PathBuf::from(&dataset_name))
-            .map(|(mount, _)| mount.to_owned())
-            .ok_or_else(|| HttmError::new("Could not determine proximate dataset mount"))?;
+        let proximate_dataset_mount = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .iter()
+                .find(|(_mount, md)| md.source == PathBuf::from(&dataset_name))
+                .map(|(mount, _)| mount.to_owned())
+        })
+        .ok_or_else(|| {
+                let msg = format!(
+                    "Could not determine a mount for dataset {dataset_name:?}.  Is the dataset name correct, and is the dataset mounted?"
+                );
+                HttmError::new(&msg)
+            })?;
 
         Ok(Self {
             dataset_name: dataset_name.to_string(),
@@ -148,12 +160,53 @@ impl RollForward {
     }
 
     pub fn exec(&self) -> HttmResult<()> {
+        let descendants = if self.roll_config.recursive {
+            self.descendants()
+        } else {
+            Vec::new()
+        };
+
+        let roll_forwards: Vec<&RollForward> =
+            std::iter::once(self).chain(descendants.iter()).collect();
+
+        // delegated permissions suffice for the zfs operations, but not for the file phase, which
+        // copies, removes, and restores the ownership of files which may belong to any user
         user_has_effective_root()?;
 
-        let snap_guard: SnapGuard =
-            SnapGuard::new(&self.dataset_name, PrecautionarySnapType::PreRollForward)?;
+        roll_forwards.iter().try_for_each(|roll_forward| {
+            ZfsAllow::check_dataset(&roll_forward.dataset_name, &ROLL_FORWARD_PRIVS)
+        })?;
 
-        match self.roll_forward() {
+        // fail early, before any precautionary snapshot has been taken
+        roll_forwards
+            .iter()
+            .try_for_each(|roll_forward| roll_forward.preflight())?;
+
+        // descendants necessarily reside on the same pool
+        PoolHealth::precheck(&self.dataset_name)?;
+
+        // every dataset is guarded before any dataset is modified
+        let snap_guards: Vec<SnapGuard> = roll_forwards
+            .iter()
+            .map(|roll_forward| {
+                SnapGuard::new(
+                    &roll_forward.dataset_name,
+                    PrecautionarySnapType::PreRollForward,
+                )
+            })
+            .collect::<HttmResult<Vec<SnapGuard>>>()?;
+
+        // verification is only begun once every dataset has been rolled forward
+        let res = roll_forwards
+            .iter()
+            .try_for_each(|roll_forward| roll_forward.roll_forward())
+            .and_then(|_| {
+                roll_forwards
+                    .iter()
+                    .try_for_each(|roll_forward| roll_forward.verify())
+            });
+
+        match res {
             Ok(_) => {
                 println!("httm roll forward completed successfully.");
             }
@@ -165,19 +218,121 @@ impl RollForward {
                 );
                 eprintln!("{}", msg);
 
-                snap_guard
-                    .rollback()
-                    .map(|_| println!("Rollback succeeded."))?;
+                // attempt to roll back every dataset, even if one rollback fails
+                let rollback_errors: Vec<String> = snap_guards
+                    .iter()
+                    .filter_map(|snap_guard| snap_guard.rollback().err())
+                    .map(|err| err.to_string())
+                    .collect();
+
+                if !rollback_errors.is_empty() {
+                    return Err(HttmError::new(&rollback_errors.join("\n")).into());
+                }
+
+                println!("Rollback succeeded.");
 
                 std::process::exit(1)
             }
         };
 
-        SnapGuard::new(
-            &self.dataset_name,
-            PrecautionarySnapType::PostRollForward(self.snap_name.to_owned()),
-        )
-        .map(|_res| ())
+        roll_forwards.iter().try_for_each(|roll_forward| {
+            SnapGuard::new(
+                &roll_forward.dataset_name,
+                PrecautionarySnapType::PostRollForward(roll_forward.snap_name.to_owned()),
+            )
+            .map(|_res| ())
+        })
+    }
+
+    fn preflight(&self) -> HttmResult<()> {
+        // -H: no header, -t: only snapshots, -o: only print the name
+        let process_args = vec![
+            "list",
+            "-H",
+            "-t",
+            "snapshot",
+            "-o",
+            "name",
+            &self.roll_config.full_snap_name,
+        ];
+
+        let process_output = PrivilegeBroker::zfs()?.args(&process_args).output()?;
+        let stdout_string = std::str::from_utf8(&process_output.stdout)?;
+
+        if !process_output.status.success()
+            || stdout_string.trim() != self.roll_config.full_snap_name
+        {
+            let msg = format!(
+                "The snapshot specified, {:?}, does not exist.  Check the name with 'zfs list -t snapshot {}'.",
+                self.roll_config.full_snap_name, self.dataset_name
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let is_read_only = MountIter::new()?
+            .flatten()
+            .filter(|mount_info| mount_info.dest == self.proximate_dataset_mount)
+            // the last mount at a path is the one visible
+            .last()
+            .map(|mount_info| mount_info.options.iter().any(|option| option == "ro"))
+            .ok_or_else(|| {
+                let msg = format!(
+                    "The dataset specified, {:?}, does not appear to be mounted.",
+                    self.dataset_name
+                );
+                HttmError::new(&msg)
+            })?;
+
+        if is_read_only {
+            let msg = format!(
+                "The dataset specified, {:?}, is mounted read-only at {:?}, and cannot be rolled forward.",
+                self.dataset_name, self.proximate_dataset_mount
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    // descendant datasets, which are mounted, and have a snapshot of the same name,
+    // sorted so parents are always rolled forward before their children
+    fn descendants(&self) -> Vec<Self> {
+        let dataset_prefix = format!("{}/", self.dataset_name);
+
+        let mut descendants: Vec<Self> = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .iter()
+                .filter(|(_mount, md)| md.fs_type == FilesystemType::Zfs)
+                .filter(|(_mount, md)| md.source.to_string_lossy().starts_with(&dataset_prefix))
+                .filter(|(mount, _md)| {
+                    mount
+                        .join(ZFS_SNAPSHOT_DIRECTORY)
+                        .join(&self.snap_name)
+                        .exists()
+                })
+                .map(|(mount, md)| {
+                    let dataset_name = md.source.to_string_lossy().into_owned();
+
+                    let roll_config = RollForwardConfig {
+                        full_snap_name: format!("{}@{}", dataset_name, self.snap_name),
+                        progress_bar: self.roll_config.progress_bar.clone(),
+                        recursive: self.roll_config.recursive,
+                    };
+
+                    Self {
+                        dataset_name,
+                        snap_name: self.snap_name.clone(),
+                        roll_config,
+                        proximate_dataset_mount: mount.to_owned(),
+                    }
+                })
+                .collect();
+        });
+
+        descendants.sort_by(|a, b| a.dataset_name.cmp(&b.dataset_name));
+
+        descendants
     }
 
     fn zfs_diff_std_err(opt_stderr: Option<ChildStderr>) -> HttmResult<String> {
@@ -206,6 +361,15 @@ impl RollForward {
             let msg = Self::zfs_diff_std_err(opt_stderr)?;
 
             if msg.is_empty() {
+                // in recursive mode, some datasets may simply not have changed
+                if self.roll_config.recursive {
+                    print_notice(&format!(
+                        "NOTICE: 'zfs diff' reported no changes to dataset: {}",
+                        self.dataset_name
+                    ));
+                    return Ok(());
+                }
+
                 return Err(HttmError::new("'zfs diff' reported no changes to dataset").into());
             }
 
@@ -213,7 +377,7 @@ impl RollForward {
         }
 
         // zfs-diff can return multiple file actions for a single inode, here we dedup
-        eprintln!("Building a map of ZFS filesystem events since the specified snapshot.");
+        print_notice("Building a map of ZFS filesystem events since the specified snapshot.");
         let mut parse_errors = vec![];
         let group_map = stream_peekable
             .map(|event| {
@@ -229,7 +393,7 @@ impl RollForward {
         // Here, we print only as NOTICE
         if let Ok(buf) = Self::zfs_diff_std_err(opt_stderr) {
             if !buf.is_empty() {
-                eprintln!("NOTICE: 'zfs diff' reported an error.  At this point of execution, these are usually inconsequential: {}", buf.trim());
+                print_notice(&format!("NOTICE: 'zfs diff' reported an error.  At this point of execution, these are usually inconsequential: {}", buf.trim()));
             }
         }
 
@@ -251,7 +415,7 @@ impl RollForward {
         let exclusions = preserve_hard_links.exec()?;
 
         // into iter and reverse because we want to go largest first
-        eprintln!("Reversing 'zfs diff' actions.");
+        print_notice("Reversing 'zfs diff' actions.");
         group_map
             .par_iter()
             .filter(|(key, _values)| !exclusions.contains(key.as_path()))
@@ -259,9 +423,7 @@ impl RollForward {
             .try_for_each(|event| match &event.diff_type {
                 DiffType::Renamed(new_file) if exclusions.contains(new_file) => Ok(()),
                 _ => self.diff_action(event),
-            })?;
-
-        self.verify()
+            })
     }
 
     fn verify(&self) -> HttmResult<()> {
@@ -270,11 +432,15 @@ impl RollForward {
         let mut first_pass: Vec<PathBuf> = vec![snap_dataset.clone()];
         let mut second_pass = Vec::new();
 
-        eprint!("Verifying files and symlinks: ");
+        if !GLOBAL_CONFIG.opt_quiet {
+            eprint!("Verifying files and symlinks: ");
+        }
         while let Some(item) = first_pass.pop() {
             let (vec_dirs, vec_files): (Vec<PathBuf>, Vec<PathBuf>) = read_dir(&item)?
                 .flatten()
                 .map(|dir_entry| dir_entry.path())
+                // the mount point of a nested dataset belongs to that dataset, not to this snapshot
+                .filter(|path| !self.is_nested_mount(path))
                 .partition(|path| path.is_dir());
 
             // change attrs on dir when at the top of a dir tree, so not over written from above
@@ -296,13 +462,21 @@ impl RollForward {
                     .live_path(&path)
                     .ok_or_else(|| HttmError::new("Could not generate live path"))?;
 
-                is_metadata_same(&path, &live_path)
+                is_metadata_same(&path, &live_path)?;
+
+                if GLOBAL_CONFIG.opt_verify_restores {
+                    Checksum::verify_restored(&path, &live_path)?;
+                }
+
+                Ok(())
             })?;
         }
         self.roll_config.progress_bar.finish_and_clear();
-        eprintln!("OK");
+        print_notice("OK");
 
-        eprint!("Verifying directories: ");
+        if !GLOBAL_CONFIG.opt_quiet {
+            eprint!("Verifying directories: ");
+        }
         // copy attributes for base dataset, our recursive attr copy does stops
         // before including the base dataset
         let live_dataset = self
@@ -321,11 +495,23 @@ impl RollForward {
             is_metadata_same(&path, &live_path)
         })?;
         self.roll_config.progress_bar.finish_and_clear();
-        eprintln!("OK");
+        print_notice("OK");
 
         Ok(())
     }
 
+    fn is_nested_mount(&self, snap_path: &Path) -> bool {
+        self.live_path(snap_path)
+            .map(|live_path| {
+                LiveFilesystemInfo::with_current(|fs_info| {
+                    fs_info
+                        .map_of_datasets
+                        .is_nested_mount(&live_path, &self.proximate_dataset_mount)
+                })
+            })
+            .unwrap_or(false)
+    }
+
     fn live_path(&self, snap_path: &Path) -> Option<PathBuf> {
         snap_path
             .strip_prefix(&self.proximate_dataset_mount)
@@ -340,14 +526,10 @@ impl RollForward {
     }
 
     fn zfs_diff_cmd(&self) -> HttmResult<Child> {
-        let zfs_command = which("zfs").map_err(|_err| {
-            HttmError::new("'zfs' command not found. Make sure the command 'zfs' is in your path.")
-        })?;
-
         // -H: tab separated, -t: Specify time, -h: Normalize paths (don't use escape codes)
         let process_args = vec!["diff", "-H", "-t", "-h", &self.roll_config.full_snap_name];
 
-        let process_handle = ExecProcess::new(zfs_command)
+        let process_handle = PrivilegeBroker::zfs()?
             .args(&process_args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -459,9 +641,18 @@ impl RollForward {
                     HttmError::new("Could not obtain snap file path for live version.")
                 })?;
 
+                // a file only renamed since the snapshot need only be renamed back, not copied
+                let is_renamed_back =
+                    if Self::is_pure_rename(&snap_file_path, new_file_name, &event.path_buf) {
+                        Self::rename(new_file_name, &event.path_buf)?;
+                        true
+                    } else {
+                        false
+                    };
+
                 Self::overwrite_or_remove(&snap_new_file_name, new_file_name)?;
 
-                if snap_file_path.exists() {
+                if !is_renamed_back && snap_file_path.exists() {
                     Self::copy(&snap_file_path, &event.path_buf)?
                 }
 
@@ -470,6 +661,51 @@ impl RollForward {
         }
     }
 
+    // the live file is the very object on the snapshot, and unmodified, when it has the same inode and
+    // the same metadata.
+    //
+    // directories are never renamed back: the events of a roll forward are reversed in parallel, one
+    // path at a time, and zfs-diff reports the contents of a renamed directory by their new paths.
+    // renaming the directory would move those paths out from under the threads reversing them.
+    // and a directory's own metadata says nothing of whether its contents are unmodified, so the
+    // directory is instead recreated at its old path, and its contents restored by their own events
+    fn is_pure_rename(snap_old_path: &Path, live_new_path: &Path, live_old_path: &Path) -> bool {
+        let (snap_md, live_md) = match (
+            snap_old_path.symlink_metadata(),
+            live_new_path.symlink_metadata(),
+        ) {
+            (Ok(snap_md), Ok(live_md)) => (snap_md, live_md),
+            _ => return false,
+        };
+
+        if snap_md.is_dir() || snap_md.ino() != live_md.ino() {
+            return false;
+        }
+
+        // never rename over whatever may now reside at the old path, and never into a dir not yet restored
+        let is_destination_free = live_old_path.symlink_metadata().is_err()
+            && matches!(live_old_path.parent(), Some(parent) if parent.is_dir());
+
+        is_destination_free && is_metadata_same(snap_old_path, live_new_path).is_ok()
+    }
+
+    fn rename(src: &Path, dst: &Path) -> HttmResult<()> {
+        if let Err(err) = std::fs::rename(src, dst) {
+            eprintln!("Error: {}", err);
+            let msg = format!("Could not rename {:?} to {:?}", src, dst);
+            return Err(HttmError::new(&msg).into());
+        }
+
+        print_notice(&format!(
+            "{}: {:?} -> {:?}",
+            paint_notice(Yellow, "Renamed  "),
+            src,
+            dst
+        ));
+
+        Ok(())
+    }
+
     fn copy(src: &Path, dst: &Path) -> HttmResult<()> {
         if let Err(err) = copy_direct(src, dst, true) {
             eprintln!("Error: {}", err);
@@ -480,7 +716,12 @@ impl RollForward {
             return Err(HttmError::new(&msg).into());
         }
 
-        eprintln!("{}: {:?} -> {:?}", Blue.paint("Restored "), src, dst);
+        print_notice(&format!(
+            "{}: {:?} -> {:?}",
+            paint_notice(Blue, "Restored "),
+            src,
+            dst
+        ));
         Ok(())
     }
 
@@ -524,7 +765,11 @@ impl RollForward {
             }
         }
 
-        eprintln!("{}: {:?} -> 🗑️", Red.paint("Removed  "), dst);
+        print_notice(&format!(
+            "{}: {:?} -> 🗑️",
+            paint_notice(Red, "Removed  "),
+            dst
+        ));
 
         Ok(())
     }
@@ -557,6 +802,15 @@ impl HardLinkMap {
                     // checking file_type on dir entries is always preferable
                     // as it is much faster than a metadata call on the path
                     .map(|dir_entry| BasicDirEntryInfo::from(&dir_entry))
+                    // child datasets have their own snapshots, and inode numbers which may
+                    // collide with our own, so never descend into a nested dataset's mount
+                    .filter(|dir_entry| {
+                        !LiveFilesystemInfo::with_current(|fs_info| {
+                            fs_info
+                                .map_of_datasets
+                                .is_nested_mount(&dir_entry.path, requested_path)
+                        })
+                    })
                     .partition(|dir_entry| dir_entry.path.is_dir());
 
             let mut combined = vec_files;
@@ -619,14 +873,14 @@ impl<'a> PreserveHardLinks<'a> {
     }
 
     fn exec(&self) -> HttmResult<HashSet<PathBuf>> {
-        eprintln!("Removing and preserving the difference between live and snap orphans.");
+        print_notice("Removing and preserving the difference between live and snap orphans.");
         let mut exclusions = self.diff_orphans()?;
 
-        eprintln!("Removing the intersection of the live and snap hard link maps to generate snap orphans.");
+        print_notice("Removing the intersection of the live and snap hard link maps to generate snap orphans.");
         let intersection = self.remove_map_intersection()?;
         exclusions.extend(intersection);
 
-        eprintln!("Removing additional unnecessary links on the live dataset.");
+        print_notice("Removing additional unnecessary links on the live dataset.");
         self.remove_live_links()?;
         exclusions.extend(
             self.live_map
@@ -637,7 +891,7 @@ impl<'a> PreserveHardLinks<'a> {
                 .map(|entry| entry.path),
         );
 
-        eprintln!("Preserving necessary links from the snapshot dataset.");
+        print_notice("Preserving necessary links from the snapshot dataset.");
         self.preserve_snap_links()?;
         exclusions.extend(
             self.snap_map
@@ -674,7 +928,7 @@ impl<'a> PreserveHardLinks<'a> {
             })?;
 
         if none_removed.load(std::sync::atomic::Ordering::Relaxed) {
-            eprintln!("No hard links found which require removal.");
+            print_notice("No hard links found which require removal.");
             return Ok(());
         }
 
@@ -725,7 +979,7 @@ impl<'a> PreserveHardLinks<'a> {
             })?;
 
         if none_preserved.load(std::sync::atomic::Ordering::Relaxed) {
-            println!("No hard links found which require preservation.");
+            print_notice("No hard links found which require preservation.");
             return Ok(());
         }
 
@@ -846,7 +1100,12 @@ impl<'a> PreserveHardLinks<'a> {
             return Err(HttmError::new("Could not obtain snap path").into());
         }
 
-        eprintln!("{}: {:?} -> {:?}", Yellow.paint("Linked  "), original, link);
+        print_notice(&format!(
+            "{}: {:?} -> {:?}",
+            paint_notice(Yellow, "Linked  "),
+            original,
+            link
+        ));
 
         Ok(())
     }
@@ -868,7 +1127,11 @@ impl<'a> PreserveHardLinks<'a> {
             }
         }
 
-        eprintln!("{}: {:?} -> 🗑️", Green.paint("Unlinked  "), link);
+        print_notice(&format!(
+            "{}: {:?} -> 🗑️",
+            paint_notice(Green, "Unlinked  "),
+            link
+        ));
 
         Ok(())
     }
@@ -0,0 +1,347 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::io::{BufRead, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::config::generate::{Config, ListSnapsOfType, RestoreMode, RestoreSnapGuard};
+use crate::data::paths::PathData;
+use crate::exec::interactive::InteractiveRestore;
+use crate::library::pool_health::PoolHealth;
+use crate::library::redaction::Redaction;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::session_report::SessionReport;
+use crate::library::snap_guard::SnapGuard;
+use crate::library::utility::{
+    date_string, display_human_duration, into_input_pathdata, live_newer_by, print_output_buf,
+    DateFormat,
+};
+use crate::lookup::versions::VersionsMap;
+use crate::GLOBAL_CONFIG;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for RpcError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self::new(SERVER_ERROR, &err.to_string())
+    }
+}
+
+pub struct Rpc;
+
+impl Rpc {
+    // one JSON-RPC request per line on stdin, one response per line on stdout, until stdin is closed,
+    // so a file manager plugin may keep a single httm warm for the life of its process
+    pub fn exec() -> HttmResult<()> {
+        let stdin = std::io::stdin();
+        let mut stdin = stdin.lock();
+
+        loop {
+            let mut line = String::new();
+
+            let bytes_read = match stdin.read_line(&mut line) {
+                Ok(bytes_read) => bytes_read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            if bytes_read == 0 {
+                return Ok(());
+            }
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // a notification, a request without an id, expects no response, per JSON-RPC 2.0
+            if let Some(response) = Self::respond(line.trim()) {
+                print_output_buf(format!("{response}\n"))?;
+            }
+        }
+    }
+
+    fn respond(line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(err) => {
+                return Some(Self::error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, &err.to_string()),
+                ))
+            }
+        };
+
+        let opt_id = request.get("id").cloned();
+        let id = opt_id.clone().unwrap_or(Value::Null);
+
+        let method = match request.get("method").and_then(Value::as_str) {
+            Some(method) => method,
+            None => {
+                return Some(Self::error_response(
+                    id,
+                    RpcError::new(INVALID_REQUEST, "Request has no method."),
+                ))
+            }
+        };
+
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let res = match method {
+            "list_versions" => Self::list_versions(&params),
+            "restore" => Self::restore(&params),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                &format!("Method not found: {method}"),
+            )),
+        };
+
+        // the request is still carried out, only its response is withheld
+        if opt_id.is_none() {
+            return None;
+        }
+
+        match res {
+            Ok(result) => Some(json!({ "jsonrpc": "2.0", "id": id, "result": result })),
+            Err(err) => Some(Self::error_response(id, err)),
+        }
+    }
+
+    fn error_response(id: Value, err: RpcError) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": err.code, "message": err.message }
+        })
+    }
+
+    fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+        params.get(name).and_then(Value::as_str).ok_or_else(|| {
+            RpcError::new(
+                INVALID_PARAMS,
+                &format!("Missing or invalid string parameter: {name}"),
+            )
+        })
+    }
+
+    // params: { "path": <live path> }
+    fn list_versions(params: &Value) -> Result<Value, RpcError> {
+        let live_version = into_input_pathdata(Self::string_param(params, "path")?);
+
+        let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &[live_version.clone()])?;
+
+        let versions: Vec<Value> = versions_map
+            .values()
+            .flatten()
+            .map(Self::version_value)
+            .collect::<Result<Vec<Value>, RpcError>>()?;
+
+        Ok(json!({ "live": live_version, "versions": versions }))
+    }
+
+    // a version is described as is the JSON output of display mode, plus the name of its snapshot,
+    // which is all a client need specify in a later restore
+    fn version_value(version: &PathData) -> Result<Value, RpcError> {
+        let mut value = serde_json::to_value(version)
+            .map_err(|err| RpcError::new(SERVER_ERROR, &err.to_string()))?;
+
+        if let Value::Object(object) = &mut value {
+            let opt_snap_name = version
+                .snap_name()
+                .map(|snap_name| snap_name.to_string_lossy().into_owned());
+
+            object.insert("snap_name".to_owned(), json!(opt_snap_name));
+        }
+
+        Ok(value)
+    }
+
+    // params: { "path": <live path>, "snap": <snapshot name or version path>, "mode": <restore mode> }
+    // where mode is any value which RESTORE would accept, and defaults to "copy"
+    fn restore(params: &Value) -> Result<Value, RpcError> {
+        let live_version = into_input_pathdata(Self::string_param(params, "path")?);
+        let snap = Self::string_param(params, "snap")?;
+
+        let restore_mode = match params.get("mode").and_then(Value::as_str) {
+            None | Some("copy") => RestoreMode::CopyOnly,
+            Some("copy-and-preserve") => RestoreMode::CopyAndPreserve,
+            Some("overwrite" | "yolo") => RestoreMode::Overwrite(RestoreSnapGuard::NotGuarded),
+            Some("guard") => RestoreMode::Overwrite(RestoreSnapGuard::Guarded),
+            // a clone would outlive the request, with no one present to clean it up
+            Some("clone") => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    "Restore by clone is not available via RPC.",
+                ))
+            }
+            Some(other) => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    &format!("Invalid restore mode: {other}"),
+                ))
+            }
+        };
+
+        let snap_version = Self::find_version(&live_version, snap)?;
+
+        Redaction::refuse_unless_forced(&snap_version, Self::is_forced(params))?;
+
+        let dst = match restore_mode {
+            RestoreMode::Overwrite(_) => {
+                Self::refuse_over_newer(params, &live_version, &snap_version)?;
+                PoolHealth::precheck_path(&live_version.path_buf)?;
+                live_version.path_buf.clone()
+            }
+            RestoreMode::CopyOnly | RestoreMode::CopyAndPreserve => {
+                Self::new_file_path(&live_version, &snap_version)?
+            }
+            RestoreMode::Clone => unreachable!("restore by clone is refused above"),
+        };
+
+        let should_preserve = !matches!(restore_mode, RestoreMode::CopyOnly);
+
+        if matches!(
+            restore_mode,
+            RestoreMode::Overwrite(RestoreSnapGuard::Guarded)
+        ) {
+            let snap_guard = SnapGuard::try_from(dst.as_path())?;
+
+            if let Err(err) =
+                InteractiveRestore::copy_and_verify(&snap_version.path_buf, &dst, should_preserve)
+            {
+                snap_guard.rollback()?;
+                return Err(err.into());
+            }
+        } else {
+            InteractiveRestore::copy_and_verify(&snap_version.path_buf, &dst, should_preserve)?;
+        }
+
+        SessionReport::add_restored(&dst);
+
+        Ok(json!({ "from": snap_version.path_buf, "to": dst }))
+    }
+
+    // no one is present to confirm an overwrite of recent work, so the request must ask for it explicitly
+    fn refuse_over_newer(
+        params: &Value,
+        live_version: &PathData,
+        snap_version: &PathData,
+    ) -> Result<(), RpcError> {
+        let opt_newer_by = snap_version
+            .metadata
+            .and_then(|snap_md| live_newer_by(&live_version.path_buf, snap_md.modify_time));
+
+        match opt_newer_by {
+            Some(newer_by) if !Self::is_forced(params) => Err(RpcError::new(
+                INVALID_PARAMS,
+                &format!(
+                    "The live version is newer than the snapshot version, by {}.  Specify \"force\" to overwrite it anyway.",
+                    display_human_duration(newer_by)
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn is_forced(params: &Value) -> bool {
+        GLOBAL_CONFIG.opt_force
+            || matches!(params.get("force").and_then(Value::as_bool), Some(true))
+    }
+
+    // every version is a candidate for restore, not just those unique by the uniqueness requested
+    fn find_version(live_version: &PathData, snap: &str) -> Result<PathData, RpcError> {
+        let mut config = Config::clone(&GLOBAL_CONFIG);
+        config.uniqueness = ListSnapsOfType::All;
+
+        let versions_map = VersionsMap::new(&config, &[live_version.clone()])?;
+
+        versions_map
+            .values()
+            .flatten()
+            .find(|version| {
+                version.path_buf.as_path() == Path::new(snap)
+                    || version
+                        .snap_name()
+                        .map(|snap_name| snap_name == snap)
+                        .unwrap_or(false)
+            })
+            .cloned()
+            .ok_or_else(|| {
+                RpcError::new(
+                    INVALID_PARAMS,
+                    &format!(
+                        "No version of {:?} found on snapshot: {snap}",
+                        live_version.path_buf
+                    ),
+                )
+            })
+    }
+
+    // as in interactive restore, a copy is given a new name, but here resides beside the live version,
+    // as a file manager would expect
+    fn new_file_path(live_version: &PathData, snap_version: &PathData) -> HttmResult<PathBuf> {
+        let snap_path_metadata = snap_version
+            .metadata
+            .ok_or_else(|| HttmError::localized("error-source-missing"))?;
+
+        let file_name = live_version
+            .path_buf
+            .file_name()
+            .ok_or_else(|| HttmError::new("Could not obtain a file name for the path given."))?
+            .to_string_lossy()
+            .into_owned();
+
+        let new_file_name = file_name
+            + ".httm_restored."
+            + &date_string(
+                GLOBAL_CONFIG.requested_utc_offset,
+                &snap_path_metadata.modify_time,
+                DateFormat::Timestamp,
+            )?;
+
+        let new_file_path = match live_version.path_buf.parent() {
+            Some(parent) => parent.join(new_file_name),
+            None => PathBuf::from(new_file_name),
+        };
+
+        if new_file_path.exists() {
+            return Err(HttmError::localized("error-restore-exists").into());
+        }
+
+        Ok(new_file_path)
+    }
+}
@@ -15,18 +15,29 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::{collections::BTreeMap, time::SystemTime};
-
-use std::process::Command as ExecProcess;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::{Command as ExecProcess, Stdio};
+use std::time::SystemTime;
 
 use crate::config::generate::{MountDisplay, PrintMode};
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::PathData;
+use crate::library::escalate::PrivilegeBroker;
+use crate::library::hooks::Hook;
 use crate::library::iter_extensions::HttmIter;
 use crate::library::results::{HttmError, HttmResult};
+use crate::library::session_report::{SessionReport, Stat};
 use crate::library::utility::{date_string, delimiter, print_output_buf, DateFormat};
+use crate::library::zfs_allow::{ZfsAllow, SNAPSHOT_PRIVS};
 use crate::lookup::file_mounts::MountsForFiles;
-use crate::parse::aliases::FilesystemType;
+use crate::parse::aliases::{FilesystemType, RemoteSnapCommand};
 use crate::GLOBAL_CONFIG;
 
+const SUFFIX_PLACEHOLDERS: [&str; 3] = ["{date}", "{user}", "{hostname}"];
+
+pub const DEFAULT_SNAPSHOT_SUFFIX: &str = "httmSnapFileMount";
+
 pub struct SnapshotMounts;
 
 impl SnapshotMounts {
@@ -36,87 +47,182 @@ impl SnapshotMounts {
         Self::snapshot_mounts(&mounts_for_files, requested_snapshot_suffix)
     }
 
+    // snapshot only the mounts of the paths given, as when requested mid-session from an interactive view
+    pub fn exec_for(paths: &[PathData], requested_snapshot_suffix: &str) -> HttmResult<()> {
+        let mounts_for_files: MountsForFiles =
+            MountsForFiles::from_paths(paths, &MountDisplay::Target);
+
+        Self::snapshot_mounts(&mounts_for_files, requested_snapshot_suffix)
+    }
+
     fn snapshot_mounts(
         mounts_for_files: &MountsForFiles,
         requested_snapshot_suffix: &str,
     ) -> HttmResult<()> {
-        let zfs_command = which::which("zfs").map_err(|_err| {
-            HttmError::new("'zfs' command not found. Make sure the command 'zfs' is in your path.")
-        })?;
-        let map_snapshot_names = Self::snapshot_names(mounts_for_files, requested_snapshot_suffix)?;
+        // all snapshots should have the same timestamp
+        let timestamp = date_string(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &SystemTime::now(),
+            DateFormat::Timestamp,
+        )?;
+
+        let snapshot_suffix = Self::expand_suffix(requested_snapshot_suffix, &timestamp)?;
+
+        let snap_name = format!("snap_{}_{}", timestamp, snapshot_suffix);
+
+        // aliases with a remote snapshot command are snapshot-ed by the machine which hosts the dataset
+        let (remote_mounts, local_mounts): (Vec<&PathData>, Vec<&PathData>) = mounts_for_files
+            .iter()
+            .flat_map(|(_pathdata, datasets)| datasets)
+            .partition(|mount| Self::remote_snap_command(mount).is_some());
+
+        if !local_mounts.is_empty() || remote_mounts.is_empty() {
+            Self::snapshot_local(&local_mounts, &snap_name)?;
+        }
+
+        Self::snapshot_remote(&remote_mounts, &snap_name)
+    }
+
+    fn remote_snap_command(
+        mount: &PathData,
+    ) -> Option<(&'static Path, &'static RemoteSnapCommand)> {
+        GLOBAL_CONFIG
+            .dataset_collection
+            .opt_map_of_aliases
+            .as_ref()
+            .and_then(|map_of_aliases| map_of_aliases.remote_snap_command(&mount.path_buf))
+    }
+
+    fn snapshot_local(local_mounts: &[&PathData], snap_name: &str) -> HttmResult<()> {
+        let map_snapshot_names = Self::snapshot_names(local_mounts, snap_name)?;
+
+        // check delegated permissions before taking any snapshots
+        map_snapshot_names
+            .values()
+            .flatten()
+            .filter_map(|snapshot_name| snapshot_name.split_once('@'))
+            .map(|(dataset_name, _snap)| dataset_name)
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .try_for_each(|dataset_name| ZfsAllow::check_dataset(dataset_name, &SNAPSHOT_PRIVS))?;
 
         map_snapshot_names.iter().try_for_each( |(_pool_name, snapshot_names)| {
-            let mut process_args = vec!["snapshot".to_owned()];
-            process_args.extend_from_slice(snapshot_names);
+            Hook::around_snapshot(snapshot_names, || {
+                let mut process_args = vec!["snapshot".to_owned()];
+                process_args.extend_from_slice(snapshot_names);
 
-            let process_output = ExecProcess::new(&zfs_command).args(&process_args).output()?;
-            let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+                let process_output = PrivilegeBroker::zfs()?.args(&process_args).output()?;
+                let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
 
-            // stderr_string is a string not an error, so here we build an err or output
-            if !stderr_string.is_empty() {
-                let msg = if stderr_string.contains("cannot create snapshots : permission denied") {
-                    "httm must have root privileges to snapshot a filesystem".to_owned()
-                } else {
-                    "httm was unable to take snapshots. The 'zfs' command issued the following error: ".to_owned() + stderr_string
-                };
+                // stderr_string is a string not an error, so here we build an err or output
+                if !stderr_string.is_empty() {
+                    let msg = if stderr_string.contains("cannot create snapshots : permission denied") {
+                        "httm must have root privileges to snapshot a filesystem".to_owned()
+                    } else {
+                        "httm was unable to take snapshots. The 'zfs' command issued the following error: ".to_owned() + stderr_string
+                    };
 
-                Err(HttmError::new(&msg).into())
-            } else {
-                let output_buf = snapshot_names
-                    .iter()
-                    .map(|snap_name| {
-                        if matches!(GLOBAL_CONFIG.print_mode, PrintMode::RawNewline | PrintMode::RawZero)  {
-                            let delimiter = delimiter();
-                            format!("{}{delimiter}", &snap_name)
-                        } else {
-                            format!("httm took a snapshot named: {}\n", &snap_name)
-                        }
-                    })
-                    .collect();
-                print_output_buf(output_buf)
-            }
+                    Err(HttmError::new(&msg).into())
+                } else {
+                    Self::print_snapshot_names(snapshot_names)
+                }
+            })
         })?;
 
         Ok(())
     }
 
+    fn snapshot_remote(remote_mounts: &[&PathData], snap_name: &str) -> HttmResult<()> {
+        // many input files may share a single alias, but each alias should only be snapshot-ed once
+        let map_remote_commands: BTreeMap<&Path, (&Path, &RemoteSnapCommand)> = remote_mounts
+            .iter()
+            .filter_map(|mount| {
+                Self::remote_snap_command(mount)
+                    .map(|local_and_command| (mount.path_buf.as_path(), local_and_command))
+            })
+            .collect();
+
+        map_remote_commands
+            .iter()
+            .try_for_each(|(remote_dir, (local_dir, remote_snap))| {
+                // there is no dataset name we know of locally, so name the snapshot by its remote dir
+                let snapshot_names = vec![format!("{}@{}", remote_dir.display(), snap_name)];
+
+                Hook::around_snapshot(&snapshot_names, || {
+                    let process_output = ExecProcess::new("sh")
+                        .arg("-c")
+                        .arg(remote_snap.command(snap_name))
+                        .stdin(Stdio::null())
+                        .output()
+                        .map_err(|err| {
+                            HttmError::with_context(
+                                "Could not execute the remote snapshot command",
+                                &err,
+                            )
+                        })?;
+
+                    if !process_output.status.success() {
+                        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+                        let msg = format!(
+                            "httm was unable to take a snapshot for the alias {:?}.  The remote snapshot command exited with {}: {stderr_string}",
+                            local_dir, process_output.status
+                        );
+                        return Err(HttmError::new(&msg).into());
+                    }
+
+                    Self::print_snapshot_names(&snapshot_names)
+                })
+            })
+    }
+
+    fn print_snapshot_names(snapshot_names: &[String]) -> HttmResult<()> {
+        let output_buf = snapshot_names
+            .iter()
+            .map(|snap_name| {
+                if matches!(
+                    GLOBAL_CONFIG.print_mode,
+                    PrintMode::RawNewline | PrintMode::RawZero
+                ) {
+                    let delimiter = delimiter();
+                    format!("{}{delimiter}", &snap_name)
+                } else {
+                    format!("httm took a snapshot named: {}\n", &snap_name)
+                }
+            })
+            .collect();
+
+        print_output_buf(output_buf)?;
+
+        SessionReport::add(Stat::SnapshotsCreated, snapshot_names.len() as u64);
+
+        Ok(())
+    }
+
     fn snapshot_names(
-        mounts_for_files: &MountsForFiles,
-        requested_snapshot_suffix: &str,
+        local_mounts: &[&PathData],
+        snap_name: &str,
     ) -> HttmResult<BTreeMap<String, Vec<String>>> {
-        // all snapshots should have the same timestamp
-        let timestamp = date_string(
-            GLOBAL_CONFIG.requested_utc_offset,
-            &SystemTime::now(),
-            DateFormat::Timestamp,
-        );
-
-        let vec_snapshot_names: Vec<String> = mounts_for_files
+        let vec_snapshot_names: Vec<String> = local_mounts
             .iter()
-            .flat_map(|(_pathdata, datasets)| datasets)
             .map(|mount| {
             let dataset = match &GLOBAL_CONFIG.dataset_collection.opt_map_of_aliases {
-                None => {
-                    match GLOBAL_CONFIG.dataset_collection.map_of_datasets.get(&mount.path_buf) {
+                None => LiveFilesystemInfo::with_current(|fs_info| {
+                    match fs_info.map_of_datasets.get(&mount.path_buf) {
                         Some(dataset_info) => {
                             if let FilesystemType::Zfs = dataset_info.fs_type {
-                                Ok(dataset_info.source.to_string_lossy())
+                                Ok(dataset_info.source.to_string_lossy().into_owned())
                             } else {
                                 Err(HttmError::new("httm does not currently support snapshot-ing non-ZFS filesystems."))
                             }
                         }
-                        None => return Err(HttmError::new("httm was unable to parse dataset from mount!")),
+                        None => Err(HttmError::new("httm was unable to parse dataset from mount!")),
                     }
-                }
-                Some(_) => return Err(HttmError::new("httm does not currently support snapshot-ing user defined mount points.")),
+                }),
+                Some(_) => return Err(HttmError::new("httm does not currently support snapshot-ing user defined mount points, unless a REMOTE_SNAP_COMMAND is specified for the alias.")),
             }?;
 
-            let snapshot_name = format!(
-                "{}@snap_{}_{}",
-                dataset,
-                timestamp,
-                requested_snapshot_suffix,
-            );
+            let snapshot_name = format!("{}@{}", dataset, snap_name);
 
             Ok(snapshot_name)
         }).collect::<Result<Vec<String>, HttmError>>()?;
@@ -154,6 +260,93 @@ impl SnapshotMounts {
         Ok(map_snapshot_names)
     }
 
+    // expands the placeholders within a snapshot suffix template, like "pre_deploy_{date}_{user}"
+    pub fn expand_suffix(template: &str, timestamp: &str) -> HttmResult<String> {
+        let mut expanded = String::with_capacity(template.len());
+        let mut remainder = template;
+
+        while let Some(start) = remainder.find('{') {
+            expanded.push_str(&remainder[..start]);
+
+            let placeholder_and_rest = &remainder[start..];
+
+            let end = placeholder_and_rest.find('}').ok_or_else(|| {
+                HttmError::new("Snapshot suffix contains an unclosed placeholder.  Quitting.")
+            })?;
+
+            let value = match &placeholder_and_rest[..=end] {
+                "{date}" => timestamp.to_owned(),
+                "{user}" => Self::user_name()?,
+                "{hostname}" => Self::host_name()?,
+                unknown => {
+                    let msg = format!(
+                        "Unknown placeholder in snapshot suffix: {unknown}.  Valid placeholders are: {}",
+                        SUFFIX_PLACEHOLDERS.join(", ")
+                    );
+                    return Err(HttmError::new(&msg).into());
+                }
+            };
+
+            expanded.push_str(&value);
+            remainder = &placeholder_and_rest[end + 1..];
+        }
+
+        expanded.push_str(remainder);
+
+        // placeholders may expand to characters ZFS will not accept, so check the expanded suffix
+        if expanded.is_empty()
+            || !expanded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        {
+            let msg = format!(
+                "The snapshot suffix \"{expanded}\" is not valid.  httm will only accept snapshot suffixes which don't contain whitespace, \
+                and which contain only alphanumeric characters, or the characters '_', '-', '.' and ':'."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(expanded)
+    }
+
+    fn user_name() -> HttmResult<String> {
+        // if run via sudo, the user who is interesting is the user who ran sudo
+        if let Ok(sudo_user) = std::env::var("SUDO_USER") {
+            return Ok(sudo_user);
+        }
+
+        match nix::unistd::User::from_uid(nix::unistd::getuid()) {
+            Ok(Some(user)) => Ok(user.name),
+            _ => Err(
+                HttmError::new("Could not determine the user name for the snapshot suffix.").into(),
+            ),
+        }
+    }
+
+    fn host_name() -> HttmResult<String> {
+        let mut buffer = [0u8; 256];
+
+        let res =
+            unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+
+        if res != 0 {
+            return Err(HttmError::new(
+                "Could not determine the host name for the snapshot suffix.",
+            )
+            .into());
+        }
+
+        let len = buffer
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(buffer.len());
+
+        // prefer the short host name, "kimono", to the FQDN, "kimono.example.com"
+        let host_name = std::str::from_utf8(&buffer[..len])?;
+
+        Ok(host_name.split('.').next().unwrap_or(host_name).to_owned())
+    }
+
     fn pool_from_snap_name(snapshot_name: &str) -> HttmResult<String> {
         // split on "/" why?  because a snap looks like: rpool/kimono@snap...
         // splits according to pool name, then the rest of the snap name
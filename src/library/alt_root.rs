@@ -0,0 +1,144 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io::{Error, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+
+use crate::library::results::{HttmError, HttmResult};
+
+// same as the Linux kernel's limit
+const MAX_SYMLINK_HOPS: usize = 40;
+
+// the alt root must be available before the GLOBAL_CONFIG is, because PathData relies upon
+// it as the config is built, so it cannot simply be a field of the config
+static ALT_ROOT: OnceCell<PathBuf> = OnceCell::new();
+
+pub struct AltRoot;
+
+impl AltRoot {
+    pub fn set(requested_root: &Path) -> HttmResult<()> {
+        let root = requested_root.canonicalize().map_err(|err| {
+            HttmError::with_context("ROOT does not exist or is not accessible: ", &err)
+        })?;
+
+        if !root.is_dir() {
+            return Err(HttmError::new("ROOT must be a directory.").into());
+        }
+
+        // a root of "/" is no alt root at all
+        if root == Path::new("/") {
+            return Ok(());
+        }
+
+        ALT_ROOT
+            .set(root)
+            .map_err(|_err| HttmError::new("ROOT may only be set once.").into())
+    }
+
+    pub fn get() -> Option<&'static Path> {
+        ALT_ROOT.get().map(PathBuf::as_path)
+    }
+
+    // "/etc/fstab" becomes "/mnt/image/etc/fstab", paths which are relative or which are
+    // already within the alt root are left as they are
+    pub fn into_root<P: AsRef<Path>>(path: P) -> PathBuf {
+        let path = path.as_ref();
+
+        match Self::get() {
+            Some(root) if path.is_absolute() && !path.starts_with(root) => {
+                root.join(path.strip_prefix("/").unwrap_or(path))
+            }
+            _ => path.to_path_buf(),
+        }
+    }
+
+    // is this path either within the alt root, or an ancestor of the alt root, and therefore
+    // may contain files within the alt root?
+    pub fn is_relevant(path: &Path) -> bool {
+        match Self::get() {
+            Some(root) => path.starts_with(root) || root.starts_with(path),
+            None => true,
+        }
+    }
+
+    // resolves symlinks as if the alt root were "/", so an absolute link within a container
+    // or system image does not escape to the host's files
+    pub fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+        let root = match Self::get() {
+            Some(root) if path.starts_with(root) => root,
+            _ => return path.canonicalize(),
+        };
+
+        let mut resolved = root.to_path_buf();
+        let mut pending: VecDeque<OsString> =
+            Self::components(path.strip_prefix(root).unwrap_or(path));
+        let mut hops = 0usize;
+
+        while let Some(component) = pending.pop_front() {
+            if component == ".." {
+                // as with chroot, one cannot ascend above the root
+                if resolved != root {
+                    resolved.pop();
+                }
+                continue;
+            }
+
+            let candidate = resolved.join(&component);
+
+            if !candidate.symlink_metadata()?.file_type().is_symlink() {
+                resolved = candidate;
+                continue;
+            }
+
+            hops += 1;
+
+            if hops > MAX_SYMLINK_HOPS {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    "Too many levels of symbolic links",
+                ));
+            }
+
+            let link_target = candidate.read_link()?;
+
+            if link_target.is_absolute() {
+                resolved = root.to_path_buf();
+            }
+
+            Self::components(&link_target)
+                .into_iter()
+                .rev()
+                .for_each(|component| pending.push_front(component));
+        }
+
+        Ok(resolved)
+    }
+
+    fn components(path: &Path) -> VecDeque<OsString> {
+        path.components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_os_string()),
+                Component::ParentDir => Some(OsString::from("..")),
+                Component::CurDir | Component::RootDir | Component::Prefix(_) => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,162 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::ffi::{OsStr, OsString};
+use std::fs::read_dir;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
+use std::sync::Mutex;
+
+use hashbrown::{HashMap, HashSet};
+use once_cell::sync::Lazy;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::library::external_commands::ExternalCommand;
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
+
+// key: dataset mount, val: whether path lookups upon that dataset ignore case
+static CASE_INSENSITIVE: Lazy<Mutex<HashMap<PathBuf, bool>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// on a case-insensitive dataset (ZFS casesensitivity=insensitive, ciopfs, SMB mounts), the live path may
+// differ in case from the path stored within a snapshot, so joins of relative paths may miss versions
+pub struct CaseSensitivity;
+
+impl CaseSensitivity {
+    pub fn is_insensitive(dataset_mount: &Path) -> bool {
+        if let Some(is_insensitive) = CASE_INSENSITIVE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(dataset_mount)
+        {
+            return *is_insensitive;
+        }
+
+        // detection may wait upon zfs, so others, perhaps asking after another dataset, must not wait upon us
+        let is_insensitive = Self::detect(dataset_mount);
+
+        CASE_INSENSITIVE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(dataset_mount.to_path_buf(), is_insensitive);
+
+        is_insensitive
+    }
+
+    // the name, folded, for comparisons which ignore case
+    pub fn fold(name: &OsStr) -> OsString {
+        match name.to_str() {
+            Some(name) => OsString::from(name.to_lowercase()),
+            None => OsString::from_vec(name.as_bytes().to_ascii_lowercase()),
+        }
+    }
+
+    // resolves the relative path within the snap mount, one component at a time,
+    // preferring an exact match, but otherwise accepting any entry which differs only in case
+    pub fn resolve(snap_mount: &Path, relative_path: &Path) -> Option<PathBuf> {
+        relative_path
+            .components()
+            .try_fold(snap_mount.to_path_buf(), |snap_parent, component| {
+                let by_name = snap_parent.join(component);
+
+                if by_name.symlink_metadata().is_ok() {
+                    return Some(by_name);
+                }
+
+                let folded = Self::fold(component.as_os_str());
+
+                read_dir(&snap_parent)
+                    .ok()?
+                    .flatten()
+                    .find(|dir_entry| Self::fold(&dir_entry.file_name()) == folded)
+                    .map(|dir_entry| dir_entry.path())
+            })
+    }
+
+    fn detect(dataset_mount: &Path) -> bool {
+        let opt_zfs_dataset = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .get(dataset_mount)
+                .filter(|md| md.fs_type == FilesystemType::Zfs && md.mount_type == MountType::Local)
+                .map(|md| md.source.to_string_lossy().into_owned())
+        });
+
+        // ZFS tells us directly.  a mixed sensitivity dataset is case-sensitive when accessed locally,
+        // and only insensitive when accessed as via SMB, so its paths within snapshots match exactly
+        if let Some(dataset_name) = opt_zfs_dataset {
+            if let Some(value) = Self::zfs_case_sensitivity(&dataset_name) {
+                return value == "insensitive";
+            }
+        }
+
+        Self::probe(dataset_mount)
+    }
+
+    fn zfs_case_sensitivity(dataset_name: &str) -> Option<String> {
+        let zfs_command = ExternalCommand::optional(
+            "zfs",
+            "the case sensitivity of ZFS datasets is probed instead",
+        )?;
+
+        let process_args = vec!["get", "-H", "-o", "value", "casesensitivity", dataset_name];
+
+        let process_output = ExecProcess::new(zfs_command)
+            .args(&process_args)
+            .output()
+            .ok()?;
+
+        std::str::from_utf8(&process_output.stdout)
+            .ok()
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty() && value != "-")
+    }
+
+    // for any other filesystem, look up an entry of the dataset's root by a name which differs only in case
+    fn probe(dataset_mount: &Path) -> bool {
+        let names: HashSet<OsString> = match read_dir(dataset_mount) {
+            Ok(read_dir) => read_dir
+                .flatten()
+                .map(|dir_entry| dir_entry.file_name())
+                .collect(),
+            Err(_) => return false,
+        };
+
+        let opt_variant = names
+            .iter()
+            .filter_map(|name| name.to_str())
+            .find_map(|name| {
+                let upper = name.to_uppercase();
+                let variant = if upper != name {
+                    upper
+                } else {
+                    name.to_lowercase()
+                };
+
+                (variant != name).then_some(variant)
+            });
+
+        match opt_variant {
+            // if both names exist, the dataset is surely case sensitive
+            Some(variant) if names.contains(OsStr::new(&variant)) => false,
+            Some(variant) => dataset_mount.join(variant).symlink_metadata().is_ok(),
+            None => false,
+        }
+    }
+}
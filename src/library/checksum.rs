@@ -0,0 +1,121 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::fs::{read_dir, File};
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::results::{HttmError, HttmResult};
+
+// a cryptographic checksum of a file's contents, for when size and mtime are not enough
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    hash: blake3::Hash,
+}
+
+impl Checksum {
+    pub fn new(path: &Path) -> HttmResult<Self> {
+        let _opt_permit = DatasetPermit::acquire(path);
+
+        Self::without_permit(path)
+    }
+
+    // for a preview, which is executed before the config exists, and so can hold no dataset permit
+    pub fn without_permit(path: &Path) -> HttmResult<Self> {
+        const IN_BUFFER_SIZE: usize = 131_072;
+
+        let file = File::open(path)?;
+
+        let mut reader = BufReader::with_capacity(IN_BUFFER_SIZE, file);
+
+        let mut hasher = blake3::Hasher::new();
+
+        loop {
+            let consumed = match reader.fill_buf() {
+                Ok(buf) if buf.is_empty() => {
+                    return Ok(Self {
+                        hash: hasher.finalize(),
+                    })
+                }
+                Ok(buf) => {
+                    hasher.update(buf);
+                    buf.len()
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            reader.consume(consumed);
+        }
+    }
+
+    // verifies that the restored destination has the same contents as the snapshot version source,
+    // recursing into directories, and reporting every mismatch found, not just the first
+    pub fn verify_restored(src: &Path, dst: &Path) -> HttmResult<()> {
+        let mut mismatches: Vec<String> = Vec::new();
+
+        Self::collect_mismatches(src, dst, &mut mismatches)?;
+
+        if !mismatches.is_empty() {
+            let msg = format!(
+                "Restore verification failed.  The following restored files do not match their snapshot versions:\n{}",
+                mismatches.join("\n")
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    fn collect_mismatches(src: &Path, dst: &Path, mismatches: &mut Vec<String>) -> HttmResult<()> {
+        let src_file_type = src.symlink_metadata()?.file_type();
+
+        let dst_file_type = match dst.symlink_metadata() {
+            Ok(md) => md.file_type(),
+            Err(_) => {
+                mismatches.push(format!("{src:?} !-> {dst:?} (destination does not exist)"));
+                return Ok(());
+            }
+        };
+
+        if src_file_type != dst_file_type {
+            mismatches.push(format!("{src:?} !-> {dst:?} (file types differ)"));
+            return Ok(());
+        }
+
+        if src_file_type.is_symlink() {
+            if src.read_link().ok() != dst.read_link().ok() {
+                mismatches.push(format!("{src:?} !-> {dst:?} (symlink targets differ)"));
+            }
+
+            return Ok(());
+        }
+
+        if src_file_type.is_dir() {
+            return read_dir(src)?.flatten().try_for_each(|entry| {
+                let entry_dst: PathBuf = dst.join(entry.file_name());
+                Self::collect_mismatches(&entry.path(), &entry_dst, mismatches)
+            });
+        }
+
+        if src_file_type.is_file() && Self::new(src)? != Self::new(dst)? {
+            mismatches.push(format!("{src:?} !-> {dst:?} (checksums differ)"));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command as ExecProcess, Stdio};
+
+use which::which;
+
+use crate::config::generate::ClipboardMode;
+use crate::library::results::{HttmError, HttmResult};
+
+// tmux only passes escape sequences through to the outer terminal when wrapped
+const TMUX_PASSTHROUGH_START: &str = "\x1bPtmux;\x1b";
+const TMUX_PASSTHROUGH_END: &str = "\x1b\\";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub struct Clipboard;
+
+impl Clipboard {
+    pub fn copy(snap_path: &Path, clipboard_mode: &ClipboardMode) -> HttmResult<()> {
+        let bytes = match clipboard_mode {
+            ClipboardMode::Path => snap_path.as_os_str().as_bytes().to_vec(),
+            ClipboardMode::Contents => std::fs::read(snap_path)?,
+        };
+
+        if let Some((command, args)) = Self::native_command() {
+            return Self::copy_with(&command, &args, &bytes);
+        }
+
+        Self::copy_with_osc52(&bytes)
+    }
+
+    // a native clipboard tool is preferred, as terminals may limit the length of, or disallow, OSC 52 sequences
+    fn native_command() -> Option<(PathBuf, Vec<&'static str>)> {
+        let is_wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+        let is_x11 = std::env::var_os("DISPLAY").is_some();
+
+        let candidates: [(&str, Vec<&'static str>, bool); 4] = [
+            ("wl-copy", Vec::new(), is_wayland),
+            ("xclip", vec!["-selection", "clipboard"], is_x11),
+            ("xsel", vec!["--clipboard", "--input"], is_x11),
+            ("pbcopy", Vec::new(), cfg!(target_os = "macos")),
+        ];
+
+        candidates
+            .into_iter()
+            .filter(|(_name, _args, is_available)| *is_available)
+            .find_map(|(name, args, _is_available)| which(name).ok().map(|command| (command, args)))
+    }
+
+    fn copy_with(command: &Path, args: &[&str], bytes: &[u8]) -> HttmResult<()> {
+        let mut child = ExecProcess::new(command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(bytes)?;
+        }
+
+        if !child.wait()?.success() {
+            let msg = format!("httm could not copy to the clipboard using: {command:?}");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    // stdout may be redirected, so the sequence is written directly to the controlling terminal
+    fn copy_with_osc52(bytes: &[u8]) -> HttmResult<()> {
+        let mut tty = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
+
+        let sequence = format!("\x1b]52;c;{}\x07", Self::base64(bytes));
+
+        let sequence = if std::env::var_os("TMUX").is_some() {
+            format!("{TMUX_PASSTHROUGH_START}{sequence}{TMUX_PASSTHROUGH_END}")
+        } else {
+            sequence
+        };
+
+        tty.write_all(sequence.as_bytes())?;
+        tty.flush()?;
+
+        Ok(())
+    }
+
+    fn base64(bytes: &[u8]) -> String {
+        let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+        bytes.chunks(3).for_each(|chunk| {
+            let triple = chunk.iter().enumerate().fold(0u32, |acc, (idx, byte)| {
+                acc | ((*byte as u32) << (16 - idx * 8))
+            });
+
+            (0..4).for_each(|idx| {
+                if idx <= chunk.len() {
+                    let sextet = (triple >> (18 - idx * 6)) & 0x3f;
+                    encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            });
+        });
+
+        encoded
+    }
+}
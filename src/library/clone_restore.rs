@@ -0,0 +1,287 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::PathData;
+use crate::library::escalate::PrivilegeBroker;
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult};
+use crate::library::utility::{formatted_date, print_output_buf};
+use crate::library::xdg::BaseDir;
+use crate::parse::aliases::FilesystemType;
+use crate::{GLOBAL_CONFIG, ZFS_SNAPSHOT_DIRECTORY};
+
+// each line: the clone's dataset name, a tab, the snapshot of which it is a clone, a tab,
+// and the path of the link to a file within the clone
+const CLONES_RECORD: &str = "clones";
+
+// every clone name carries the marker, and cleanup will destroy no dataset without it
+const CLONE_MARKER: &str = "_httmClone_";
+
+// clone names must remain valid dataset names, whatever date format the user may prefer for display
+const CLONE_TIMESTAMP_FORMAT: &str = "[year]-[month]-[day]-[hour]:[minute]:[second]";
+
+// a very large file, like a VM image, may be accessed at once from a clone of its snapshot,
+// rather than waiting on a copy, with the clone recorded so it may be destroyed later
+pub struct CloneRestore;
+
+impl CloneRestore {
+    // returns the name of the clone created
+    pub fn exec(snap_path: &Path, link_path: &Path) -> HttmResult<String> {
+        let snap_pathdata = PathData::from(snap_path);
+
+        let snap_name = snap_pathdata
+            .snap_name()
+            .ok_or_else(|| {
+                HttmError::with_kind(
+                    HttmErrorKind::UnsupportedFilesystem,
+                    "Restore by clone is only available for ZFS snapshot versions.",
+                )
+            })?
+            .to_string_lossy()
+            .into_owned();
+
+        let dataset_mount = LiveFilesystemInfo::with_current(|fs_info| {
+            snap_pathdata.proximate_dataset(&fs_info.map_of_datasets)
+        })?;
+
+        let opt_dataset_name = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .get(dataset_mount)
+                .filter(|md| md.fs_type == FilesystemType::Zfs)
+                .map(|md| md.source.to_string_lossy().into_owned())
+        });
+
+        let dataset_name = match opt_dataset_name {
+            Some(dataset_name) => dataset_name,
+            None => {
+                return Err(HttmError::with_kind(
+                    HttmErrorKind::UnsupportedFilesystem,
+                    "Restore by clone is only available for ZFS snapshot versions.",
+                )
+                .into())
+            }
+        };
+
+        let relative_path = snap_path
+            .strip_prefix(dataset_mount.join(ZFS_SNAPSHOT_DIRECTORY).join(&snap_name))
+            .map_err(|_err| {
+                HttmError::new("Could not determine the path of the version within its snapshot.")
+            })?;
+
+        let timestamp = formatted_date(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &SystemTime::now(),
+            CLONE_TIMESTAMP_FORMAT,
+            None,
+        )?;
+
+        let full_snap_name = format!("{dataset_name}@{snap_name}");
+        // a clone is created beside its origin, but the root dataset of a pool has nothing beside it,
+        // "tank_httmClone_..." would name another pool, so its clone is created as its child
+        let clone_name = if dataset_name.contains('/') {
+            format!("{dataset_name}{CLONE_MARKER}{timestamp}")
+        } else {
+            format!("{dataset_name}/{dataset_name}{CLONE_MARKER}{timestamp}")
+        };
+
+        Self::zfs(&["clone", &full_snap_name, &clone_name])?;
+
+        // a clone may be created, but not mounted, for instance, when delegated the clone permission
+        // but not the privilege to mount, and a link into an unmounted clone would lead nowhere
+        let clone_path =
+            match Self::mounted(&clone_name).and_then(|_| Self::mountpoint(&clone_name)) {
+                Ok(mountpoint) => mountpoint.join(relative_path),
+                Err(err) => {
+                    let _ = Self::zfs(&["destroy", &clone_name]);
+                    return Err(err);
+                }
+            };
+
+        if let Err(err) = std::os::unix::fs::symlink(&clone_path, link_path) {
+            let _ = Self::zfs(&["destroy", &clone_name]);
+            return Err(err.into());
+        }
+
+        Self::record(&clone_name, &full_snap_name, link_path)?;
+
+        Ok(clone_name)
+    }
+
+    // destroy every clone recorded, and remove the links into each, keeping a record of any which fail
+    pub fn cleanup() -> HttmResult<()> {
+        let record_path = Self::record_path()?;
+
+        let record = match std::fs::read_to_string(&record_path) {
+            Ok(record) => record,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return print_output_buf("httm has no clones to clean up.\n".to_owned())
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let remaining: String = record
+            .lines()
+            .filter(|line| {
+                let mut fields = line.splitn(3, '\t');
+
+                let (clone_name, origin, link_path) =
+                    match (fields.next(), fields.next(), fields.next()) {
+                        (Some(clone_name), Some(origin), Some(link_path)) => {
+                            (clone_name, origin, Path::new(link_path))
+                        }
+                        _ => {
+                            eprintln!("WARNING: Could not parse clone record: {line:?}");
+                            return true;
+                        }
+                    };
+
+                // the record is only a file in the user's state dir, so before destroying anything,
+                // be certain the dataset named is still a clone httm created, of the snapshot recorded
+                if let Err(err) = Self::verify_clone(clone_name, origin) {
+                    eprintln!("WARNING: {err}");
+                    return true;
+                }
+
+                // don't remove whatever the user may have since put in the link's place
+                if link_path.is_symlink() {
+                    if let Err(err) = std::fs::remove_file(link_path) {
+                        eprintln!("WARNING: Could not remove link {link_path:?}: {err}");
+                        return true;
+                    }
+                }
+
+                match Self::zfs(&["destroy", clone_name]) {
+                    Ok(_) => {
+                        println!("httm destroyed clone: {clone_name}");
+                        false
+                    }
+                    Err(err) => {
+                        eprintln!("WARNING: {err}");
+                        true
+                    }
+                }
+            })
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        if remaining.is_empty() {
+            std::fs::remove_file(&record_path)?;
+        } else {
+            std::fs::write(&record_path, remaining)?;
+        }
+
+        Ok(())
+    }
+
+    fn verify_clone(clone_name: &str, origin: &str) -> HttmResult<()> {
+        if !clone_name.contains(CLONE_MARKER) {
+            let msg = format!(
+                "Refusing to destroy {clone_name}, as its name does not mark it as a clone httm created."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let stdout = Self::zfs(&["get", "-H", "-o", "value", "origin", clone_name])?;
+        let actual_origin = stdout.trim();
+
+        if actual_origin == "-" || actual_origin.is_empty() {
+            let msg = format!("Refusing to destroy {clone_name}, as it is not a clone.");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        if actual_origin != origin {
+            let msg = format!(
+                "Refusing to destroy {clone_name}, as it is a clone of {actual_origin}, not of {origin}, as recorded."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    fn mounted(clone_name: &str) -> HttmResult<()> {
+        let stdout = Self::zfs(&["get", "-H", "-o", "value", "mounted", clone_name])?;
+
+        if stdout.trim() != "yes" {
+            let msg = format!("The clone {clone_name} was created, but is not mounted.");
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(())
+    }
+
+    fn mountpoint(clone_name: &str) -> HttmResult<PathBuf> {
+        let stdout = Self::zfs(&["get", "-H", "-o", "value", "mountpoint", clone_name])?;
+        let mountpoint = stdout.trim();
+
+        if !mountpoint.starts_with('/') {
+            let msg = format!(
+                "The clone {clone_name} has no mountpoint, \"{mountpoint}\", at which its files may be accessed."
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(PathBuf::from(mountpoint))
+    }
+
+    fn zfs(process_args: &[&str]) -> HttmResult<String> {
+        let process_output = PrivilegeBroker::zfs()?.args(process_args).output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to clone or destroy a clone. The 'zfs' command issued the following error: ".to_owned()
+                + stderr_string;
+
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(std::str::from_utf8(&process_output.stdout)?.to_owned())
+    }
+
+    fn record(clone_name: &str, origin: &str, link_path: &Path) -> HttmResult<()> {
+        let record_path = Self::record_path()?;
+
+        if let Some(parent) = record_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut record = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(record_path)?;
+
+        writeln!(record, "{clone_name}\t{origin}\t{}", link_path.display())?;
+
+        Ok(())
+    }
+
+    fn record_path() -> HttmResult<PathBuf> {
+        BaseDir::State
+            .httm_dir()
+            .map(|httm_dir| httm_dir.join(CLONES_RECORD))
+            .ok_or_else(|| {
+                HttmError::new("Could not determine a state directory to record clones.").into()
+            })
+    }
+}
@@ -0,0 +1,224 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
+use std::sync::{Arc, Condvar, Mutex};
+
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+use proc_mounts::MountIter;
+
+use crate::config::generate::DatasetThreads;
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::library::external_commands::ExternalCommand;
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
+use crate::GLOBAL_CONFIG;
+
+// spinning rust does best with very little concurrency, but not none
+pub const ROTATIONAL_DATASET_THREADS: usize = 2;
+
+// key: dataset mount, val: the semaphore for that dataset, or None if the dataset is not limited
+static DATASET_SEMAPHORES: Lazy<Mutex<HashMap<PathBuf, Option<Arc<Semaphore>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Semaphore {
+    permits: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Self {
+            permits: Mutex::new(permits),
+            condvar: Condvar::new(),
+        }
+    }
+}
+
+// a permit is held for as long as the lookup or copy needs it, and is returned
+// to its dataset's semaphore on drop
+pub struct DatasetPermit {
+    semaphore: Arc<Semaphore>,
+}
+
+impl Drop for DatasetPermit {
+    fn drop(&mut self) {
+        let mut permits = self
+            .semaphore
+            .permits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        *permits += 1;
+        self.semaphore.condvar.notify_one();
+    }
+}
+
+impl DatasetPermit {
+    // blocks until a permit is available for the dataset upon which the path resides,
+    // returns None immediately when no limit applies to that dataset
+    pub fn acquire(path: &Path) -> Option<Self> {
+        GLOBAL_CONFIG.opt_dataset_threads.as_ref()?;
+
+        let semaphore = Self::semaphore(&Self::dataset_mount(path)?)?;
+
+        let mut permits = semaphore
+            .permits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        while *permits == 0 {
+            permits = semaphore
+                .condvar
+                .wait(permits)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        *permits -= 1;
+        drop(permits);
+
+        Some(Self { semaphore })
+    }
+
+    fn dataset_mount(path: &Path) -> Option<PathBuf> {
+        // paths here are already absolute, so no need to build a PathData, which would canonicalize
+        LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .proximate_mount(path)
+                .map(Path::to_path_buf)
+        })
+    }
+
+    // a dataset now mounted where another once was may reside upon other devices
+    pub fn clear_cache() {
+        DATASET_SEMAPHORES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    fn semaphore(dataset_mount: &Path) -> Option<Arc<Semaphore>> {
+        let mut semaphores = DATASET_SEMAPHORES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(opt_semaphore) = semaphores.get(dataset_mount) {
+            return opt_semaphore.clone();
+        }
+
+        let opt_limit = match &GLOBAL_CONFIG.opt_dataset_threads {
+            Some(DatasetThreads::Fixed(limit)) => Some(*limit),
+            Some(DatasetThreads::Auto) if is_rotational(dataset_mount) => {
+                Some(ROTATIONAL_DATASET_THREADS)
+            }
+            Some(DatasetThreads::Auto) | None => None,
+        };
+
+        let opt_semaphore = opt_limit.map(|limit| Arc::new(Semaphore::new(limit)));
+
+        semaphores.insert(dataset_mount.to_path_buf(), opt_semaphore.clone());
+
+        opt_semaphore
+    }
+}
+
+// is any device backing this dataset a spinning disk? only Linux exposes this info via sysfs
+pub fn is_rotational(dataset_mount: &Path) -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+
+    let opt_dataset_md = LiveFilesystemInfo::with_current(|fs_info| {
+        fs_info.map_of_datasets.get(dataset_mount).cloned()
+    });
+
+    let dataset_md = match opt_dataset_md {
+        Some(md) if md.mount_type == MountType::Local => md,
+        _ => return false,
+    };
+
+    let devices: Vec<PathBuf> = match dataset_md.fs_type {
+        FilesystemType::Zfs => zpool_devices(&dataset_md.source.to_string_lossy()),
+        // btrfs sources may be parsed as subvols, so we need the original source device from the mount table
+        FilesystemType::Btrfs | FilesystemType::Nilfs2 => match MountIter::new() {
+            Ok(mount_iter) => mount_iter
+                .flatten()
+                .filter(|mount_info| mount_info.dest == dataset_mount)
+                .map(|mount_info| mount_info.source)
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+    };
+
+    devices
+        .iter()
+        .filter_map(|device| is_device_rotational(device))
+        .any(|is_rotational| is_rotational)
+}
+
+fn zpool_devices(dataset_name: &str) -> Vec<PathBuf> {
+    let pool_name = match dataset_name.split_once('/') {
+        Some((pool_name, _the_rest)) => pool_name,
+        None => dataset_name,
+    };
+
+    let zpool_command = match ExternalCommand::optional(
+        "zpool",
+        "whether a ZFS pool resides upon spinning disks cannot be determined",
+    ) {
+        Some(zpool_command) => zpool_command,
+        None => return Vec::new(),
+    };
+
+    // -H: tab separated, -P: full device paths, -v: include the vdevs
+    let process_args = vec!["list", "-H", "-P", "-v", pool_name];
+
+    let stdout = match ExecProcess::new(zpool_command).args(&process_args).output() {
+        Ok(process_output) => process_output.stdout,
+        Err(_) => return Vec::new(),
+    };
+
+    std::str::from_utf8(&stdout)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_ascii_whitespace().next())
+        .filter(|vdev| vdev.starts_with('/'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+fn is_device_rotational(device: &Path) -> Option<bool> {
+    // resolve links like /dev/disk/by-id/... to the kernel name, e.g. /dev/sda1
+    let device = device.canonicalize().ok()?;
+    let kernel_name = device.file_name()?;
+
+    let sys_block_path = Path::new("/sys/class/block")
+        .join(kernel_name)
+        .canonicalize()
+        .ok()?;
+
+    // partitions have no queue dir of their own, but their parent block device does
+    sys_block_path
+        .ancestors()
+        .take(2)
+        .map(|ancestor| ancestor.join("queue/rotational"))
+        .find_map(|rotational| std::fs::read_to_string(rotational).ok())
+        .map(|value| value.trim() == "1")
+}
@@ -0,0 +1,260 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use time::OffsetDateTime;
+
+// month and weekday names for those locales we know, everything else is formatted by the time crate in English
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateLocale {
+    short_months: [&'static str; 12],
+    long_months: [&'static str; 12],
+    short_weekdays: [&'static str; 7],
+    long_weekdays: [&'static str; 7],
+}
+
+impl DateLocale {
+    // takes a locale like "de_DE.UTF-8", or just the language, like "de"
+    pub fn new(locale: &str) -> Option<Self> {
+        let language = locale
+            .split(['_', '.', '@', '-'])
+            .next()
+            .unwrap_or(locale)
+            .to_ascii_lowercase();
+
+        let res = match language.as_str() {
+            "de" => Self {
+                short_months: [
+                    "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov",
+                    "Dez",
+                ],
+                long_months: [
+                    "Januar",
+                    "Februar",
+                    "März",
+                    "April",
+                    "Mai",
+                    "Juni",
+                    "Juli",
+                    "August",
+                    "September",
+                    "Oktober",
+                    "November",
+                    "Dezember",
+                ],
+                short_weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+                long_weekdays: [
+                    "Montag",
+                    "Dienstag",
+                    "Mittwoch",
+                    "Donnerstag",
+                    "Freitag",
+                    "Samstag",
+                    "Sonntag",
+                ],
+            },
+            "es" => Self {
+                short_months: [
+                    "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov",
+                    "dic",
+                ],
+                long_months: [
+                    "enero",
+                    "febrero",
+                    "marzo",
+                    "abril",
+                    "mayo",
+                    "junio",
+                    "julio",
+                    "agosto",
+                    "septiembre",
+                    "octubre",
+                    "noviembre",
+                    "diciembre",
+                ],
+                short_weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+                long_weekdays: [
+                    "lunes",
+                    "martes",
+                    "miércoles",
+                    "jueves",
+                    "viernes",
+                    "sábado",
+                    "domingo",
+                ],
+            },
+            "fr" => Self {
+                short_months: [
+                    "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct",
+                    "nov", "déc",
+                ],
+                long_months: [
+                    "janvier",
+                    "février",
+                    "mars",
+                    "avril",
+                    "mai",
+                    "juin",
+                    "juillet",
+                    "août",
+                    "septembre",
+                    "octobre",
+                    "novembre",
+                    "décembre",
+                ],
+                short_weekdays: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+                long_weekdays: [
+                    "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+                ],
+            },
+            "it" => Self {
+                short_months: [
+                    "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov",
+                    "dic",
+                ],
+                long_months: [
+                    "gennaio",
+                    "febbraio",
+                    "marzo",
+                    "aprile",
+                    "maggio",
+                    "giugno",
+                    "luglio",
+                    "agosto",
+                    "settembre",
+                    "ottobre",
+                    "novembre",
+                    "dicembre",
+                ],
+                short_weekdays: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+                long_weekdays: [
+                    "lunedì",
+                    "martedì",
+                    "mercoledì",
+                    "giovedì",
+                    "venerdì",
+                    "sabato",
+                    "domenica",
+                ],
+            },
+            "nl" => Self {
+                short_months: [
+                    "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov",
+                    "dec",
+                ],
+                long_months: [
+                    "januari",
+                    "februari",
+                    "maart",
+                    "april",
+                    "mei",
+                    "juni",
+                    "juli",
+                    "augustus",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                short_weekdays: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+                long_weekdays: [
+                    "maandag",
+                    "dinsdag",
+                    "woensdag",
+                    "donderdag",
+                    "vrijdag",
+                    "zaterdag",
+                    "zondag",
+                ],
+            },
+            "pt" => Self {
+                short_months: [
+                    "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov",
+                    "dez",
+                ],
+                long_months: [
+                    "janeiro",
+                    "fevereiro",
+                    "março",
+                    "abril",
+                    "maio",
+                    "junho",
+                    "julho",
+                    "agosto",
+                    "setembro",
+                    "outubro",
+                    "novembro",
+                    "dezembro",
+                ],
+                short_weekdays: ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+                long_weekdays: [
+                    "segunda-feira",
+                    "terça-feira",
+                    "quarta-feira",
+                    "quinta-feira",
+                    "sexta-feira",
+                    "sábado",
+                    "domingo",
+                ],
+            },
+            _ => return None,
+        };
+
+        Some(res)
+    }
+
+    // the first of these env vars which is set wins, as with libc
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+            .and_then(|locale| Self::new(&locale))
+    }
+
+    // replace the name components of a format description with literal localized names,
+    // names are padded to a uniform width so columns of dates remain aligned
+    pub fn localize(&self, format: &str, date_time: &OffsetDateTime) -> String {
+        let month_idx = u8::from(date_time.month()) as usize - 1;
+        let weekday_idx = date_time.weekday().number_days_from_monday() as usize;
+
+        let replacements: [(&str, &[&str], usize); 5] = [
+            ("[weekday repr:short]", &self.short_weekdays, weekday_idx),
+            ("[weekday repr:long]", &self.long_weekdays, weekday_idx),
+            ("[weekday]", &self.long_weekdays, weekday_idx),
+            ("[month repr:short]", &self.short_months, month_idx),
+            ("[month repr:long]", &self.long_months, month_idx),
+        ];
+
+        replacements
+            .iter()
+            .fold(format.to_owned(), |acc, (component, names, idx)| {
+                if !acc.contains(component) {
+                    return acc;
+                }
+
+                let width = names
+                    .iter()
+                    .map(|name| name.chars().count())
+                    .max()
+                    .unwrap_or_default();
+
+                // a literal "[" must be escaped in a format description
+                let literal = format!("{:<width$}", names[*idx]).replace('[', "[[");
+
+                acc.replace(component, &literal)
+            })
+    }
+}
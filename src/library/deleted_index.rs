@@ -0,0 +1,323 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::OsStr;
+use std::hash::{Hash, Hasher};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use hashbrown::HashMap;
+use indicatif::ProgressBar;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::exec::recursive::SharedRecursive;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{make_tmp_path, print_notice};
+use crate::library::xdg::BaseDir;
+use crate::lookup::deleted::DeletedFiles;
+use crate::lookup::versions::ProximateDatasetAndOptAlts;
+use crate::GLOBAL_CONFIG;
+
+const DELETED_INDEX_DIR: &str = "deleted_index";
+const DELETED_INDEX_MAGIC: &[u8] = b"httm-deleted-index-v1";
+const DIR_RECORD_TAG: &[u8] = b"D";
+const ENTRY_RECORD_TAG: &[u8] = b"F";
+
+// key: a dir which may be the root of an index, val: that index, or None if no index exists
+static DELETED_INDICES: Lazy<Mutex<HashMap<PathBuf, Option<Arc<DeletedIndex>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct IndexedDir {
+    // deleting a file updates its parent dir's mtime, so a dir whose mtime has changed
+    // may have new deleted files, and a dir whose mtime has not, does not
+    modify_time: SystemTime,
+    // taking or destroying a snapshot changes the generation, and every dir upon the dataset is stale
+    generation: u64,
+    deleted: Vec<PathBuf>,
+}
+
+// a catalog, on disk, of the deleted files beneath a dir, so repeated deleted searches of
+// huge datasets need not read every snapshot dir again
+pub struct DeletedIndex {
+    inner: HashMap<PathBuf, IndexedDir>,
+}
+
+impl DeletedIndex {
+    pub fn exec(progress_bar: &ProgressBar) -> HttmResult<()> {
+        for pathdata in GLOBAL_CONFIG.paths.iter() {
+            if !pathdata.path_buf.is_dir() {
+                let msg = format!(
+                    "INDEX requires a directory, but the path specified is not: {:?}",
+                    pathdata.path_buf
+                );
+                return Err(HttmError::new(&msg).into());
+            }
+
+            progress_bar.set_message(format!(
+                "Indexing deleted files beneath {:?}...",
+                pathdata.path_buf
+            ));
+
+            let index = Self::build(&pathdata.path_buf, progress_bar);
+            let indexed_dirs = index.inner.len();
+            let indexed_deleted: usize = index.inner.values().map(|dir| dir.deleted.len()).sum();
+
+            index.save(&pathdata.path_buf)?;
+
+            progress_bar.finish_and_clear();
+
+            print_notice(&format!(
+                "httm indexed {indexed_deleted} deleted files, within {indexed_dirs} directories, beneath {:?}.",
+                pathdata.path_buf
+            ));
+        }
+
+        Ok(())
+    }
+
+    // consulted by every deleted search, returns None whenever the index is not fresh for this dir,
+    // so that the caller falls back to scanning the snapshots
+    pub fn get(requested_dir: &Path) -> Option<Vec<BasicDirEntryInfo>> {
+        let index = requested_dir
+            .ancestors()
+            .find_map(|ancestor| Self::load_cached(ancestor))?;
+
+        let indexed_dir = index.inner.get(requested_dir)?;
+
+        if Self::modify_time(requested_dir)? != indexed_dir.modify_time
+            || Self::generation(requested_dir)? != indexed_dir.generation
+        {
+            return None;
+        }
+
+        let deleted = indexed_dir
+            .deleted
+            .iter()
+            .map(|path| BasicDirEntryInfo {
+                path: path.clone(),
+                file_type: path.symlink_metadata().ok().map(|md| md.file_type()),
+            })
+            .collect();
+
+        Some(deleted)
+    }
+
+    fn build(root: &Path, progress_bar: &ProgressBar) -> Self {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        let mut queue: Vec<PathBuf> = vec![root.to_path_buf()];
+
+        while let Some(dir) = queue.pop() {
+            progress_bar.tick();
+
+            if let Ok((vec_dirs, _vec_files)) = SharedRecursive::entries_partitioned(&dir) {
+                queue.extend(vec_dirs.into_iter().map(|entry| entry.path));
+            }
+
+            dirs.push(dir);
+        }
+
+        // no errors will be propagated here, like any other recursive search
+        // far too likely to run into a dir we don't have permissions to view
+        let inner = dirs
+            .into_par_iter()
+            .filter_map(|dir| {
+                // the mtime must be read before the scan, else a deletion during the scan might be missed
+                let modify_time = Self::modify_time(&dir)?;
+                let generation = Self::generation(&dir)?;
+
+                let deleted = DeletedFiles::scan(&dir)
+                    .ok()?
+                    .into_inner()
+                    .into_iter()
+                    .map(|entry| entry.path)
+                    .collect();
+
+                progress_bar.tick();
+
+                Some((
+                    dir,
+                    IndexedDir {
+                        modify_time,
+                        generation,
+                        deleted,
+                    },
+                ))
+            })
+            .collect();
+
+        Self { inner }
+    }
+
+    fn modify_time(dir: &Path) -> Option<SystemTime> {
+        dir.symlink_metadata().ok()?.modified().ok()
+    }
+
+    // a hash of every snap mount which might contain a version of this dir.  the hasher is not
+    // guaranteed to be stable across Rust releases, but an unstable hash simply makes for a stale index
+    fn generation(dir: &Path) -> Option<u64> {
+        let pathdata = PathData::from(dir);
+
+        LiveFilesystemInfo::with_current(|fs_info| {
+            let mut snap_mounts: Vec<&PathBuf> = Vec::new();
+
+            ProximateDatasetAndOptAlts::with_fs_info(fs_info, &pathdata)
+                .ok()?
+                .into_search_bundles()
+                .for_each(|search_bundle| snap_mounts.extend(search_bundle.snap_mounts.iter()));
+
+            snap_mounts.sort_unstable();
+
+            let mut hasher = DefaultHasher::new();
+            snap_mounts.hash(&mut hasher);
+
+            Some(hasher.finish())
+        })
+    }
+
+    fn index_file(root: &Path) -> Option<PathBuf> {
+        let cache_dir = BaseDir::Cache.httm_dir()?;
+
+        let mut hasher = DefaultHasher::new();
+        root.hash(&mut hasher);
+
+        Some(
+            cache_dir
+                .join(DELETED_INDEX_DIR)
+                .join(format!("{:016x}", hasher.finish())),
+        )
+    }
+
+    fn load_cached(root: &Path) -> Option<Arc<Self>> {
+        let mut indices = DELETED_INDICES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(opt_index) = indices.get(root) {
+            return opt_index.clone();
+        }
+
+        let opt_index = Self::load(root).map(Arc::new);
+
+        indices.insert(root.to_path_buf(), opt_index.clone());
+
+        opt_index
+    }
+
+    fn load(root: &Path) -> Option<Self> {
+        let buffer = std::fs::read(Self::index_file(root)?).ok()?;
+
+        // records are NULL delimited, as paths may contain newlines
+        let mut fields = buffer.split(|byte| *byte == b'\0');
+
+        // two roots might share a hash, so the root itself is recorded too
+        if fields.next()? != DELETED_INDEX_MAGIC || fields.next()? != root.as_os_str().as_bytes() {
+            return None;
+        }
+
+        let mut inner: HashMap<PathBuf, IndexedDir> = HashMap::new();
+        let mut opt_current: Option<(PathBuf, IndexedDir)> = None;
+
+        while let Some(tag) = fields.next() {
+            match tag {
+                DIR_RECORD_TAG => {
+                    let dir = PathBuf::from(OsStr::from_bytes(fields.next()?));
+                    let secs: u64 = std::str::from_utf8(fields.next()?).ok()?.parse().ok()?;
+                    let nanos: u32 = std::str::from_utf8(fields.next()?).ok()?.parse().ok()?;
+                    let generation: u64 = std::str::from_utf8(fields.next()?).ok()?.parse().ok()?;
+
+                    if let Some((dir, indexed_dir)) = opt_current.replace((
+                        dir,
+                        IndexedDir {
+                            modify_time: SystemTime::UNIX_EPOCH + Duration::new(secs, nanos),
+                            generation,
+                            deleted: Vec::new(),
+                        },
+                    )) {
+                        inner.insert(dir, indexed_dir);
+                    }
+                }
+                ENTRY_RECORD_TAG => {
+                    let (_dir, indexed_dir) = opt_current.as_mut()?;
+                    indexed_dir
+                        .deleted
+                        .push(PathBuf::from(OsStr::from_bytes(fields.next()?)));
+                }
+                // the buffer ends with a delimiter
+                b"" => break,
+                // a corrupt index is no index at all
+                _ => return None,
+            }
+        }
+
+        if let Some((dir, indexed_dir)) = opt_current {
+            inner.insert(dir, indexed_dir);
+        }
+
+        Some(Self { inner })
+    }
+
+    fn save(&self, root: &Path) -> HttmResult<()> {
+        let index_file = Self::index_file(root).ok_or_else(|| {
+            HttmError::new("httm could not determine a cache directory for the index.  Please set the HOME, XDG_CACHE_HOME, or HTTM_CACHE_DIR environment variable.")
+        })?;
+
+        if let Some(parent) = index_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        let mut push_field = |field: &[u8]| {
+            buffer.extend_from_slice(field);
+            buffer.push(b'\0');
+        };
+
+        push_field(DELETED_INDEX_MAGIC);
+        push_field(root.as_os_str().as_bytes());
+
+        self.inner.iter().for_each(|(dir, indexed_dir)| {
+            let since_epoch = indexed_dir
+                .modify_time
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default();
+
+            push_field(DIR_RECORD_TAG);
+            push_field(dir.as_os_str().as_bytes());
+            push_field(since_epoch.as_secs().to_string().as_bytes());
+            push_field(since_epoch.subsec_nanos().to_string().as_bytes());
+            push_field(indexed_dir.generation.to_string().as_bytes());
+
+            indexed_dir.deleted.iter().for_each(|path| {
+                push_field(ENTRY_RECORD_TAG);
+                push_field(path.as_os_str().as_bytes());
+            });
+        });
+
+        // write to a tmp file and rename, so a concurrent search never reads a partial index
+        let tmp_file = make_tmp_path(&index_file);
+        std::fs::write(&tmp_file, buffer)?;
+        std::fs::rename(&tmp_file, &index_file)?;
+
+        Ok(())
+    }
+}
@@ -15,23 +15,37 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
+use std::ffi::OsString;
 use std::fs::File;
+use std::fs::Metadata;
 use std::fs::OpenOptions;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::BufWriter;
 use std::io::ErrorKind;
+use std::io::Read;
 use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
-use std::path::Path;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 
 use simd_adler32::Adler32;
 
 use crate::library::results::HttmResult;
+use crate::library::utility::print_notice;
+use crate::GLOBAL_CONFIG;
 
 const CHUNK_SIZE: usize = 65_536;
 
+// a checkpoint is recorded once each segment is written and synced, so an interruption loses at most one segment,
+// segments must be a multiple of the chunk size, so that no read need be split across a segment boundary
+const SEGMENT_SIZE: u64 = 1_024 * CHUNK_SIZE as u64;
+// smaller files are quicker to copy once more than to checkpoint
+const RESUMABLE_MIN_LEN: u64 = 4 * SEGMENT_SIZE;
+const RESUME_SUFFIX: &str = ".httm_resume";
+const RESUME_MAGIC: &str = "httm-resume-v1";
+
 enum DstFileState {
     Exists,
     DoesNotExist,
@@ -54,7 +68,8 @@ pub fn diff_copy(src: &Path, dst: &Path) -> HttmResult<()> {
         .read(true)
         .create(true)
         .open(dst)?;
-    let src_len = src_file.metadata()?.len();
+    let src_metadata = src_file.metadata()?;
+    let src_len = src_metadata.len();
     dst_file.set_len(src_len)?;
 
     // create destination file writer and maybe reader
@@ -66,10 +81,32 @@ pub fn diff_copy(src: &Path, dst: &Path) -> HttmResult<()> {
     // cur pos - byte offset in file,
     let mut cur_pos = 0u64;
 
+    let mut opt_resume = if src_len >= RESUMABLE_MIN_LEN {
+        let (resume_state, verified_len) = ResumeState::new(dst, &src_metadata)?;
+
+        if verified_len > 0 {
+            let _seek_pos = src_reader.seek(SeekFrom::Start(verified_len))?;
+            let _seek_pos = dst_reader.seek(SeekFrom::Start(verified_len))?;
+            cur_pos = verified_len;
+        }
+
+        Some((resume_state, blake3::Hasher::new()))
+    } else {
+        None
+    };
+
     loop {
         match src_reader.fill_buf() {
             Ok(src_read) => {
                 // read (size of buffer amt) from src, and dst if it exists
+                let src_read = match opt_resume {
+                    Some(_) => {
+                        let to_boundary = SEGMENT_SIZE - cur_pos % SEGMENT_SIZE;
+                        &src_read[..src_read.len().min(to_boundary as usize)]
+                    }
+                    None => src_read,
+                };
+
                 let src_amt_read = src_read.len();
 
                 if src_amt_read == 0 {
@@ -87,9 +124,9 @@ pub fn diff_copy(src: &Path, dst: &Path) -> HttmResult<()> {
                         // read same amt from dst file, if it exists, to compare
                         match dst_reader.fill_buf() {
                             Ok(dst_read) => {
-                                let dst_amt_read = dst_read.len();
+                                let dst_amt_read = dst_read.len().min(src_amt_read);
 
-                                if !is_same_bytes(src_read, dst_read) {
+                                if !is_same_bytes(src_read, &dst_read[..dst_amt_read]) {
                                     // seek to current byte offset in dst writer
                                     let _seek_pos = dst_writer.seek(SeekFrom::Start(cur_pos))?;
 
@@ -109,9 +146,24 @@ pub fn diff_copy(src: &Path, dst: &Path) -> HttmResult<()> {
                     }
                 };
 
+                if let Some((_resume_state, hasher)) = opt_resume.as_mut() {
+                    hasher.update(src_read);
+                }
+
                 cur_pos += src_amt_read as u64;
 
                 src_reader.consume(src_amt_read);
+
+                if let Some((resume_state, hasher)) = opt_resume.as_mut() {
+                    if cur_pos % SEGMENT_SIZE == 0 {
+                        // the segment must be durable before we may say it is
+                        dst_writer.flush()?;
+                        dst_file.sync_data()?;
+
+                        resume_state.checkpoint(cur_pos / SEGMENT_SIZE - 1, &hasher.finalize())?;
+                        hasher.reset();
+                    }
+                }
             }
             Err(err) => match err.kind() {
                 ErrorKind::Interrupted => continue,
@@ -127,9 +179,130 @@ pub fn diff_copy(src: &Path, dst: &Path) -> HttmResult<()> {
     dst_writer.flush()?;
     dst_file.sync_data()?;
 
+    if let Some((resume_state, _hasher)) = opt_resume {
+        resume_state.finish();
+    }
+
     Ok(())
 }
 
+// a sidecar to the destination file, recording the checksum of each segment known to be written,
+// so that, with RESUME, an interrupted copy of a very large file need not begin again from the start
+struct ResumeState {
+    path: PathBuf,
+    file: File,
+}
+
+impl ResumeState {
+    fn path_for(dst: &Path) -> PathBuf {
+        let mut path: OsString = dst.as_os_str().to_os_string();
+        path.push(RESUME_SUFFIX);
+        PathBuf::from(path)
+    }
+
+    // returns the new state, and the length of the dst prefix which need not be copied again
+    fn new(dst: &Path, src_metadata: &Metadata) -> HttmResult<(Self, u64)> {
+        let path = Self::path_for(dst);
+        let identity = Self::identity(src_metadata);
+
+        let verified = if GLOBAL_CONFIG.opt_resume {
+            Self::verified_segments(&path, dst, &identity)
+        } else {
+            Vec::new()
+        };
+
+        if GLOBAL_CONFIG.opt_resume && !verified.is_empty() {
+            print_notice(&format!(
+                "Resuming the copy to {:?} after {} verified segment(s).",
+                dst,
+                verified.len()
+            ));
+        }
+
+        // rewritten from scratch, so any segments which didn't verify are forgotten
+        let mut file = File::create(&path)?;
+        writeln!(file, "{RESUME_MAGIC}")?;
+        writeln!(file, "{identity}")?;
+        verified
+            .iter()
+            .try_for_each(|line| writeln!(file, "{line}"))?;
+        file.sync_data()?;
+
+        let verified_len = verified.len() as u64 * SEGMENT_SIZE;
+
+        Ok((Self { path, file }, verified_len))
+    }
+
+    // a copy may only resume from the very same source version
+    fn identity(src_metadata: &Metadata) -> String {
+        format!(
+            "{} {} {} {} {}",
+            src_metadata.dev(),
+            src_metadata.ino(),
+            src_metadata.len(),
+            src_metadata.mtime(),
+            src_metadata.mtime_nsec()
+        )
+    }
+
+    // each segment recorded is checked against the dst as it is now, in order, until one fails to match
+    fn verified_segments(path: &Path, dst: &Path, identity: &str) -> Vec<String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut lines = contents.lines();
+
+        if lines.next() != Some(RESUME_MAGIC) || lines.next() != Some(identity) {
+            print_notice(&format!(
+                "The source of the interrupted copy to {:?} has changed, or its state is unreadable.  Copying from the start.",
+                dst
+            ));
+            return Vec::new();
+        }
+
+        let mut dst_file = match File::open(dst) {
+            Ok(dst_file) => dst_file,
+            Err(_) => return Vec::new(),
+        };
+
+        lines
+            .enumerate()
+            .map_while(|(expected_idx, line)| {
+                let (idx, recorded_hash) = line.split_once(' ')?;
+
+                if idx.parse::<usize>().ok()? != expected_idx {
+                    return None;
+                }
+
+                let mut hasher = blake3::Hasher::new();
+                let num_read =
+                    std::io::copy(&mut (&mut dst_file).take(SEGMENT_SIZE), &mut hasher).ok()?;
+
+                (num_read == SEGMENT_SIZE && hasher.finalize().to_hex().as_str() == recorded_hash)
+                    .then(|| line.to_owned())
+            })
+            .collect()
+    }
+
+    fn checkpoint(&mut self, segment_idx: u64, hash: &blake3::Hash) -> HttmResult<()> {
+        writeln!(self.file, "{segment_idx} {}", hash.to_hex())?;
+        self.file.sync_data()?;
+
+        Ok(())
+    }
+
+    fn finish(self) {
+        // a leftover sidecar is harmless, so there is no need to error here
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+pub fn has_resume_state(dst: &Path) -> bool {
+    ResumeState::path_for(dst).exists()
+}
+
 #[inline]
 fn is_same_bytes(a_bytes: &[u8], b_bytes: &[u8]) -> bool {
     let (a_hash, b_hash): (u32, u32) = rayon::join(|| hash(a_bytes), || hash(b_bytes));
@@ -0,0 +1,84 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::process::Command as ExecProcess;
+
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmResult};
+use crate::GLOBAL_CONFIG;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationTool {
+    Sudo,
+    Doas,
+    Pkexec,
+}
+
+impl EscalationTool {
+    pub fn new(value: &str) -> HttmResult<Self> {
+        match value {
+            "" | "sudo" => Ok(Self::Sudo),
+            "doas" => Ok(Self::Doas),
+            "pkexec" => Ok(Self::Pkexec),
+            _ => Err(HttmError::new(
+                "ESCALATE requires one of the following values: \"sudo\", \"doas\", or \"pkexec\".",
+            )
+            .into()),
+        }
+    }
+
+    fn command_name(&self) -> &'static str {
+        match self {
+            Self::Sudo => "sudo",
+            Self::Doas => "doas",
+            Self::Pkexec => "pkexec",
+        }
+    }
+}
+
+// the main process remains unprivileged, and only the privileged zfs sub-operations,
+// like snapshot, destroy, rollback, and diff, are executed through the escalation tool
+pub struct PrivilegeBroker;
+
+impl PrivilegeBroker {
+    pub fn can_escalate() -> bool {
+        GLOBAL_CONFIG.opt_escalate.is_some()
+    }
+
+    pub fn zfs() -> HttmResult<ExecProcess> {
+        let zfs_command = ExternalCommand::require("zfs")?;
+
+        let escalation_tool = match GLOBAL_CONFIG.opt_escalate {
+            // no need to escalate if we are already root
+            Some(escalation_tool) if !nix::unistd::geteuid().is_root() => escalation_tool,
+            _ => return Ok(ExecProcess::new(zfs_command)),
+        };
+
+        let escalation_command = ExternalCommand::require(escalation_tool.command_name())?;
+
+        let mut process = ExecProcess::new(escalation_command);
+
+        // pkexec does not understand "--", but will not interpret the args of the command either
+        if escalation_tool != EscalationTool::Pkexec {
+            process.arg("--");
+        }
+
+        process.arg(zfs_command);
+
+        Ok(process)
+    }
+}
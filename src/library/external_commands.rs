@@ -0,0 +1,231 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use hashbrown::HashSet;
+use once_cell::sync::Lazy;
+use which::which;
+
+use crate::config::generate::{
+    ExecMode, InteractiveMode, ListSnapsFilters, RestoreMode, RestoreSnapGuard,
+};
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::print_notice;
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
+use crate::GLOBAL_CONFIG;
+
+// the policy for every external command httm executes is decided here:
+//
+// a mode which cannot work at all without a command, like SNAPSHOT, PRUNE, or ROLL_FORWARD without "zfs",
+// fails before it has done anything, with a hint as to how to install the command, whereas a mode which
+// only works better with a command, like browsing the versions found via ".zfs/snapshot", continues,
+// with a notice as to what is missing
+//
+// preview and clipboard commands, like "diff", "cat" or "wl-copy", already have their own fallbacks,
+// and are not the concern of this policy
+
+// key: command name, so each notice is printed only once per session
+static NOTICES_GIVEN: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+pub struct ExternalCommand;
+
+impl ExternalCommand {
+    // for a command without which the operation requested cannot proceed
+    pub fn require(command: &str) -> HttmResult<PathBuf> {
+        which(command).map_err(|_err| HttmError::command_missing(command).into())
+    }
+
+    // for a command without which httm may continue, only with less information
+    pub fn optional(command: &str, degraded: &str) -> Option<PathBuf> {
+        match which(command) {
+            Ok(path) => Some(path),
+            Err(_) => {
+                Self::notice_once(command, degraded);
+                None
+            }
+        }
+    }
+
+    // fail early, before any mode which cannot work without a command has begun
+    pub fn preflight() -> HttmResult<()> {
+        let exec_mode = &GLOBAL_CONFIG.exec_mode;
+
+        Self::required_by(exec_mode)
+            .iter()
+            .try_for_each(|command| Self::require(command).map(|_path| ()))?;
+
+        // browsing versions via ".zfs/snapshot" requires no command, but the user should know what is unavailable
+        if matches!(exec_mode, ExecMode::Interactive(_)) && Self::has_local_zfs() {
+            let _ = Self::optional(
+                "zfs",
+                "versions are found only via \".zfs/snapshot\", and snapshot-ing a dataset from the select view is unavailable",
+            );
+        }
+
+        Ok(())
+    }
+
+    fn has_local_zfs() -> bool {
+        LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .values()
+                .any(|md| md.fs_type == FilesystemType::Zfs && md.mount_type == MountType::Local)
+        })
+    }
+
+    fn required_by(exec_mode: &ExecMode) -> &'static [&'static str] {
+        match exec_mode {
+            ExecMode::Prune(_)
+            | ExecMode::RollForward(_)
+            | ExecMode::ListSnapsAll(_)
+            | ExecMode::CleanupClones
+            | ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Clone)) => &["zfs"],
+            // an alias may instead be snapshot-ed via its REMOTE_SNAP_COMMAND, which is checked as each is
+            ExecMode::SnapFileMount(_)
+                if GLOBAL_CONFIG
+                    .dataset_collection
+                    .opt_map_of_aliases
+                    .is_none() =>
+            {
+                &["zfs"]
+            }
+            // creation times, by which snapshots are filtered by age, are listed via "zfs",
+            // though only for local ZFS datasets, as there are no others to list
+            ExecMode::SnapsForFiles(Some(ListSnapsFilters {
+                opt_older_than: Some(_),
+                ..
+            }))
+            | ExecMode::AssertSnapshotted(Some(_))
+                if Self::has_local_zfs() =>
+            {
+                &["zfs"]
+            }
+            // a guarded restore snapshots the dataset before overwriting, which, for a ZFS dataset, is via "zfs"
+            ExecMode::Interactive(InteractiveMode::Restore(RestoreMode::Overwrite(
+                RestoreSnapGuard::Guarded,
+            ))) if Self::has_local_zfs() => &["zfs"],
+            _ => &[],
+        }
+    }
+
+    fn notice_once(command: &str, degraded: &str) {
+        let mut notices_given = NOTICES_GIVEN
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if notices_given.insert(command.to_owned()) {
+            print_notice(&format!(
+                "NOTICE: '{command}' command not found, so {degraded}.  {}",
+                Self::install_hint(command)
+            ));
+        }
+    }
+
+    // a hint for the distribution upon which we are running, as named by os-release, if we know it
+    pub fn install_hint(command: &str) -> String {
+        let package_name = match command {
+            "zfs" | "zpool" => "zfsutils-linux",
+            "btrfs" => "btrfs-progs",
+            _ => return format!("Make sure the command '{command}' is in your path."),
+        };
+
+        let opt_hint = Self::os_release_ids()
+            .iter()
+            .find_map(|id| Self::distro_hint(command, id));
+
+        match opt_hint {
+            Some(hint) => format!("To install '{command}': {hint}"),
+            None if cfg!(target_os = "macos") && package_name == "zfsutils-linux" => {
+                format!("To install '{command}', see: https://openzfsonosx.org")
+            }
+            None => format!(
+                "Make sure the command '{command}' is in your path, or install the package which provides it, \
+                usually named \"{package_name}\", or similar, via your package manager."
+            ),
+        }
+    }
+
+    fn distro_hint(command: &str, id: &str) -> Option<&'static str> {
+        let is_zfs = matches!(command, "zfs" | "zpool");
+
+        let hint = match id {
+            "debian" | "ubuntu" if is_zfs => "sudo apt install zfsutils-linux",
+            "debian" | "ubuntu" => "sudo apt install btrfs-progs",
+            "fedora" | "rhel" | "centos" if is_zfs => {
+                "add the OpenZFS repository, see https://openzfs.github.io/openzfs-docs/, then: sudo dnf install zfs"
+            }
+            "fedora" | "rhel" | "centos" => "sudo dnf install btrfs-progs",
+            "arch" if is_zfs => "add the archzfs repository, then: sudo pacman -S zfs-utils",
+            "arch" => "sudo pacman -S btrfs-progs",
+            "opensuse" | "suse" if is_zfs => {
+                "add the filesystems repository, then: sudo zypper install zfs"
+            }
+            "opensuse" | "suse" => "sudo zypper install btrfsprogs",
+            "alpine" if is_zfs => "sudo apk add zfs",
+            "alpine" => "sudo apk add btrfs-progs",
+            "gentoo" if is_zfs => "sudo emerge sys-fs/zfs",
+            "gentoo" => "sudo emerge sys-fs/btrfs-progs",
+            "nixos" if is_zfs => "add \"zfs\" to boot.supportedFilesystems in your configuration",
+            "nixos" => "add \"btrfs\" to boot.supportedFilesystems in your configuration",
+            "freebsd" if is_zfs => "'zfs' is part of the FreeBSD base system, is /sbin in your path?",
+            _ => return None,
+        };
+
+        Some(hint)
+    }
+
+    // the ID, and then any ID_LIKE, of this distribution, like "ubuntu", then "debian"
+    fn os_release_ids() -> Vec<String> {
+        let os_release = match std::fs::read_to_string("/etc/os-release")
+            .or_else(|_err| std::fs::read_to_string("/usr/lib/os-release"))
+        {
+            Ok(os_release) => os_release,
+            Err(_) if cfg!(target_os = "freebsd") => return vec!["freebsd".to_owned()],
+            Err(_) => return Vec::new(),
+        };
+
+        let value_of = |key: &str| -> Vec<String> {
+            os_release
+                .lines()
+                .filter_map(|line| line.strip_prefix(key))
+                .filter_map(|rest| rest.strip_prefix('='))
+                .flat_map(|value| {
+                    value
+                        .trim_matches('"')
+                        .split_ascii_whitespace()
+                        .map(|id| id.to_ascii_lowercase())
+                        .collect::<Vec<String>>()
+                })
+                .collect()
+        };
+
+        let mut ids = value_of("ID");
+        ids.extend(value_of("ID_LIKE"));
+
+        // opensuse IDs are versioned, like "opensuse-tumbleweed"
+        ids.iter_mut()
+            .filter(|id| id.starts_with("opensuse"))
+            .for_each(|id| *id = "opensuse".to_owned());
+
+        ids
+    }
+}
@@ -0,0 +1,172 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use proc_mounts::MountIter;
+
+use crate::data::paths::PathMetadata;
+use crate::library::utility::print_notice;
+
+// FAT stores modify times in 2 second increments, exFAT in 10 millisecond increments
+const FAT_MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+const EXFAT_MTIME_GRANULARITY: Duration = Duration::from_millis(10);
+
+// key: mount point, val: fs type, longest mount points first, so the first prefix match is the most proximate,
+// read again whenever the mount table changes, see MountWatch
+static MOUNT_TYPES: Lazy<RwLock<Arc<Vec<(PathBuf, String)>>>> =
+    Lazy::new(|| RwLock::new(Arc::new(FsCapabilities::mount_types())));
+
+static HAS_WARNED_XATTRS: AtomicBool = AtomicBool::new(false);
+
+// what the filesystem upon which a path resides can faithfully store, such that
+// comparisons and preserve operations can degrade gracefully when it can't
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsCapabilities {
+    pub opt_mtime_granularity: Option<Duration>,
+    pub supports_xattrs: bool,
+    pub supports_ownership: bool,
+}
+
+impl FsCapabilities {
+    const FULL: Self = Self {
+        opt_mtime_granularity: None,
+        supports_xattrs: true,
+        supports_ownership: true,
+    };
+
+    pub fn of(path: &Path) -> Self {
+        // only Linux exposes a mount table at /proc/mounts, elsewhere assume the filesystem is capable
+        if !cfg!(target_os = "linux") {
+            return Self::FULL;
+        }
+
+        let mount_types = MOUNT_TYPES
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+
+        let opt_fs_type = mount_types
+            .iter()
+            .find(|(dest, _fs_type)| path.starts_with(dest))
+            .map(|(_dest, fs_type)| fs_type.as_str());
+
+        match opt_fs_type {
+            Some("vfat" | "msdos" | "fat" | "umsdos") => Self {
+                opt_mtime_granularity: Some(FAT_MTIME_GRANULARITY),
+                supports_xattrs: false,
+                supports_ownership: false,
+            },
+            Some("exfat") => Self {
+                opt_mtime_granularity: Some(EXFAT_MTIME_GRANULARITY),
+                supports_xattrs: false,
+                supports_ownership: false,
+            },
+            _ => Self::FULL,
+        }
+    }
+
+    pub fn reload() {
+        // nothing has asked what a filesystem can store, so there is nothing to read again
+        if let Some(mount_types) = Lazy::get(&MOUNT_TYPES) {
+            *mount_types
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(Self::mount_types());
+        }
+    }
+
+    fn mount_types() -> Vec<(PathBuf, String)> {
+        let mut mount_types: Vec<(PathBuf, String)> = match MountIter::new() {
+            Ok(mount_iter) => mount_iter
+                .flatten()
+                .map(|mount_info| (mount_info.dest, mount_info.fstype))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        mount_types.sort_by_key(|(dest, _fs_type)| std::cmp::Reverse(dest.components().count()));
+
+        mount_types
+    }
+
+    // the larger of a user specified tolerance and the coarsest granularity of the paths given
+    pub fn effective_tolerance(
+        opt_tolerance: Option<Duration>,
+        paths: &[&Path],
+    ) -> Option<Duration> {
+        paths
+            .iter()
+            .filter_map(|path| Self::of(path).opt_mtime_granularity)
+            .chain(opt_tolerance)
+            .max()
+    }
+
+    // metadata is the same, or would be the same, if both were stored upon the coarser of the two filesystems,
+    // or, if a tolerance is specified, if the modify times differ by less than that tolerance
+    pub fn is_metadata_equivalent(
+        md_a: &PathMetadata,
+        path_a: &Path,
+        md_b: &PathMetadata,
+        path_b: &Path,
+        opt_tolerance: Option<Duration>,
+    ) -> bool {
+        if md_a == md_b {
+            return true;
+        }
+
+        match Self::effective_tolerance(opt_tolerance, &[path_a, path_b]) {
+            Some(tolerance) => Self::is_within_tolerance(md_a, md_b, tolerance),
+            None => false,
+        }
+    }
+
+    pub fn is_within_tolerance(
+        md_a: &PathMetadata,
+        md_b: &PathMetadata,
+        tolerance: Duration,
+    ) -> bool {
+        if md_a.size != md_b.size {
+            return false;
+        }
+
+        let difference = md_a
+            .modify_time
+            .duration_since(md_b.modify_time)
+            .unwrap_or_else(|err| err.duration());
+
+        difference < tolerance
+    }
+
+    // xattrs and ACLs are skipped when either side can't hold them, with a notice, but only once per run
+    pub fn can_preserve_xattrs(src: &Path, dst: &Path) -> bool {
+        if Self::of(src).supports_xattrs && Self::of(dst).supports_xattrs {
+            return true;
+        }
+
+        if !HAS_WARNED_XATTRS.swap(true, Ordering::Relaxed) {
+            print_notice(
+                "NOTICE: Extended attributes and ACLs are not supported by the filesystem at the source or destination, and will not be preserved.",
+            );
+        }
+
+        false
+    }
+}
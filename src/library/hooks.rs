@@ -0,0 +1,174 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::process::{Command as ExecProcess, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::config::generate::HookFailure;
+use crate::library::results::{HttmError, HttmResult};
+use crate::GLOBAL_CONFIG;
+
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreSnap,
+    PostSnap,
+    PreDestroy,
+}
+
+impl HookEvent {
+    fn label(&self) -> &'static str {
+        match self {
+            HookEvent::PreSnap => "pre-snap",
+            HookEvent::PostSnap => "post-snap",
+            HookEvent::PreDestroy => "pre-destroy",
+        }
+    }
+}
+
+pub struct Hook;
+
+impl Hook {
+    // runs the user's hook, if any, for this event, around the snapshots named, which are passed to
+    // the hook, space separated, via the HTTM_SNAPSHOTS environment variable
+    pub fn run(event: HookEvent, snapshot_names: &[String]) -> HttmResult<()> {
+        Self::run_with_status(event, snapshot_names, None)
+    }
+
+    // takes the snapshots named, via the snapshot fn, between the pre-snap and post-snap hooks --
+    // once the pre-snap hook has succeeded, the post-snap hook always runs, even when the snapshot
+    // fails, so whatever the pre-snap hook quiesced is always resumed, and the hook learns which,
+    // "success" or "failure", via the HTTM_SNAPSHOT_STATUS environment variable
+    pub fn around_snapshot<T>(
+        snapshot_names: &[String],
+        snapshot: impl FnOnce() -> HttmResult<T>,
+    ) -> HttmResult<T> {
+        Self::run(HookEvent::PreSnap, snapshot_names)?;
+
+        let snapshot_res = snapshot();
+
+        let status = match snapshot_res {
+            Ok(_) => "success",
+            Err(_) => "failure",
+        };
+
+        let post_snap_res =
+            Self::run_with_status(HookEvent::PostSnap, snapshot_names, Some(status));
+
+        // the snapshot's own error is the more important, so report it before the hook's
+        let value = snapshot_res?;
+        post_snap_res?;
+
+        Ok(value)
+    }
+
+    fn run_with_status(
+        event: HookEvent,
+        snapshot_names: &[String],
+        opt_status: Option<&str>,
+    ) -> HttmResult<()> {
+        let hook_config = match &GLOBAL_CONFIG.opt_hooks {
+            Some(hook_config) => hook_config,
+            None => return Ok(()),
+        };
+
+        let opt_command = match event {
+            HookEvent::PreSnap => hook_config.opt_pre_snap.as_ref(),
+            HookEvent::PostSnap => hook_config.opt_post_snap.as_ref(),
+            HookEvent::PreDestroy => hook_config.opt_pre_destroy.as_ref(),
+        };
+
+        let command = match opt_command {
+            Some(command) => command,
+            None => return Ok(()),
+        };
+
+        match Self::exec(
+            command,
+            event,
+            snapshot_names,
+            opt_status,
+            hook_config.timeout,
+        ) {
+            Ok(_) => Ok(()),
+            Err(err) => match hook_config.on_failure {
+                HookFailure::Abort => {
+                    let msg = format!(
+                        "The {} hook failed, so httm will not proceed: {err}",
+                        event.label()
+                    );
+                    Err(HttmError::new(&msg).into())
+                }
+                HookFailure::Continue => {
+                    eprintln!(
+                        "WARNING: The {} hook failed, but httm will continue: {err}",
+                        event.label()
+                    );
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn exec(
+        command: &str,
+        event: HookEvent,
+        snapshot_names: &[String],
+        opt_status: Option<&str>,
+        timeout: Duration,
+    ) -> HttmResult<()> {
+        let mut process = ExecProcess::new("sh");
+
+        process
+            .arg("-c")
+            .arg(command)
+            .env("HTTM_HOOK_EVENT", event.label())
+            .env("HTTM_SNAPSHOTS", snapshot_names.join(" "));
+
+        if let Some(status) = opt_status {
+            process.env("HTTM_SNAPSHOT_STATUS", status);
+        }
+
+        // hooks may be chatty, but stdout is reserved for httm's own output
+        let mut child = process
+            .stdin(Stdio::null())
+            .stdout(Stdio::from(std::io::stderr()))
+            .spawn()
+            .map_err(|err| HttmError::with_context("Could not execute hook", &err))?;
+
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match child.try_wait()? {
+                Some(status) if status.success() => return Ok(()),
+                Some(status) => {
+                    let msg = format!("hook exited with {status}");
+                    return Err(HttmError::new(&msg).into());
+                }
+                None if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+
+                    let msg = format!("hook timed out after {:?}", timeout);
+                    return Err(HttmError::new(&msg).into());
+                }
+                None => std::thread::sleep(HOOK_POLL_INTERVAL),
+            }
+        }
+    }
+}
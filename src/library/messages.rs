@@ -0,0 +1,120 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::path::PathBuf;
+
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+
+// the English baseline is always compiled in, so every key has a value, even where no translation is installed
+const ENGLISH_BASELINE: &str = include_str!("../../translations/en.ftl");
+const DEFAULT_TRANSLATIONS_DIR: &str = "/usr/share/httm/translations";
+
+// never reads GLOBAL_CONFIG, as help texts are needed before the config is generated
+static CATALOG: Lazy<Catalog> = Lazy::new(Catalog::from_env);
+
+// a message catalog, in a small subset of the Fluent syntax, see translations/en.ftl
+#[derive(Debug)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    // returns the message for the key, in the user's language, if a translation is installed,
+    // else in English, or, if the key is unknown, the key itself
+    pub fn get(key: &'static str) -> &'static str {
+        CATALOG.messages.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    // like get, but replaces placeables, like "{ $name }", with the values given
+    pub fn format(key: &'static str, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(Self::get(key).to_owned(), |acc, (name, value)| {
+                acc.replace(&format!("{{ ${name} }}"), value)
+            })
+    }
+
+    fn from_env() -> Self {
+        let mut catalog = Self::parse(ENGLISH_BASELINE);
+
+        if let Some(translation) = Self::translation() {
+            catalog.messages.extend(translation.messages);
+        }
+
+        catalog
+    }
+
+    fn translation() -> Option<Self> {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))?;
+
+        // "pt_BR.UTF-8" is looked up as "pt_BR", and then as "pt"
+        let territory = locale.split(['.', '@']).next().unwrap_or(&locale);
+        let language = territory.split(['_', '-']).next().unwrap_or(territory);
+
+        if matches!(language, "C" | "POSIX" | "en") {
+            return None;
+        }
+
+        let translations_dir = std::env::var_os("HTTM_TRANSLATIONS_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_TRANSLATIONS_DIR));
+
+        [territory, language].iter().find_map(|name| {
+            std::fs::read_to_string(translations_dir.join(format!("{name}.ftl")))
+                .ok()
+                .map(|contents| Self::parse(&contents))
+        })
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut messages: HashMap<String, String> = HashMap::new();
+        let mut opt_current_key: Option<String> = None;
+
+        contents.lines().for_each(|line| {
+            // indented lines continue the value of the previous key
+            if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+                if let Some(value) = opt_current_key
+                    .as_ref()
+                    .and_then(|key| messages.get_mut(key))
+                {
+                    if !value.is_empty() {
+                        value.push('\n');
+                    }
+                    value.push_str(line.trim());
+                }
+
+                return;
+            }
+
+            opt_current_key = None;
+
+            if line.starts_with('#') || line.trim().is_empty() {
+                return;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim().to_owned();
+
+                messages.insert(key.clone(), value.trim().to_owned());
+                opt_current_key = Some(key);
+            }
+        });
+
+        Self { messages }
+    }
+}
@@ -0,0 +1,107 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::os::unix::io::AsRawFd;
+use std::thread;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::{GLOBAL_CONFIG, ZFS_SNAPSHOT_DIRECTORY};
+
+const PROC_SELF_MOUNTS: &str = "/proc/self/mounts";
+
+// the mount table is parsed once, at startup, so, in a long interactive session, a dataset mounted later,
+// like a backup drive plugged in, would simply be missing from the results, and an unmounted dataset would
+// simply be searched in vain, so we watch for changes, and parse the mount table again whenever it changes
+pub struct MountWatch;
+
+impl MountWatch {
+    // on systems without "/proc/self/mounts" there is nothing to watch, and we keep the mount table parsed at startup
+    pub fn spawn() {
+        let mut mounts_file = match File::open(PROC_SELF_MOUNTS) {
+            Ok(mounts_file) => mounts_file,
+            Err(_) => return,
+        };
+
+        let mut last_seen = match Self::mount_table(&mut mounts_file) {
+            Some(mount_table) => mount_table,
+            None => return,
+        };
+
+        thread::spawn(move || loop {
+            if !Self::wait_for_change(&mounts_file) {
+                return;
+            }
+
+            let mount_table = match Self::mount_table(&mut mounts_file) {
+                Some(mount_table) => mount_table,
+                None => return,
+            };
+
+            if mount_table == last_seen {
+                continue;
+            }
+
+            last_seen = mount_table;
+
+            // the interactive view owns the terminal, so, if the mount table cannot be parsed again,
+            // we quietly continue with the mount table we have, and try again upon the next change
+            let _ = LiveFilesystemInfo::reparse(&GLOBAL_CONFIG.reparse_options);
+        });
+    }
+
+    // the kernel marks the mount table with POLLPRI whenever a mount changes, until it is read again
+    fn wait_for_change(mounts_file: &File) -> bool {
+        let mut poll_fd = libc::pollfd {
+            fd: mounts_file.as_raw_fd(),
+            events: libc::POLLPRI,
+            revents: 0,
+        };
+
+        loop {
+            if unsafe { libc::poll(&mut poll_fd, 1, -1) } >= 0 {
+                return true;
+            }
+
+            if std::io::Error::last_os_error().kind() != ErrorKind::Interrupted {
+                return false;
+            }
+        }
+    }
+
+    // ZFS automounts a snapshot when httm first reads it, and expires it later, so snapshot mounts
+    // come and go with every lookup, and are no change to the datasets which concern us
+    fn mount_table(mounts_file: &mut File) -> Option<Vec<String>> {
+        let mut contents = String::new();
+
+        mounts_file.seek(SeekFrom::Start(0)).ok()?;
+        mounts_file.read_to_string(&mut contents).ok()?;
+
+        let snapshot_dir = format!("/{ZFS_SNAPSHOT_DIRECTORY}/");
+
+        let mount_table = contents
+            .lines()
+            .filter(|line| {
+                !matches!(line.split_whitespace().nth(1), Some(mount_point) if mount_point.contains(&snapshot_dir))
+            })
+            .map(str::to_owned)
+            .collect();
+
+        Some(mount_table)
+    }
+}
@@ -0,0 +1,347 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use hashbrown::HashMap;
+use rayon::prelude::*;
+
+use crate::config::generate::Config;
+use crate::data::filesystem_info::{FilesystemInfo, LiveFilesystemInfo};
+use crate::data::paths::{PathData, PathMetadata};
+use crate::library::results::HttmResult;
+use crate::library::utility::make_tmp_path;
+use crate::library::xdg::BaseDir;
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, VersionsMap};
+
+const NUM_VERSIONS_CACHE_FILE: &str = "num_versions";
+// bump whenever the layout of the cache file, or what goes into a key, changes
+const NUM_VERSIONS_CACHE_VERSION: u32 = 2;
+
+struct CachedVersions {
+    key: blake3::Hash,
+    versions: Vec<PathData>,
+}
+
+// key: live path, val: the versions found the last time the live path was looked up
+pub struct NumVersionsCache {
+    inner: HashMap<PathBuf, CachedVersions>,
+}
+
+impl NumVersionsCache {
+    // paths whose live version and snapshots are unchanged since the last run are not looked up again
+    pub fn versions_map(
+        config: &Config,
+        path_set: &[PathData],
+        opt_replica: Option<&Path>,
+    ) -> HttmResult<VersionsMap> {
+        let mut cache = Self::load();
+
+        let keys: HashMap<PathBuf, blake3::Hash> = path_set
+            .par_iter()
+            .filter_map(|pathdata| {
+                Self::key(config, pathdata, opt_replica).map(|key| (pathdata.path_buf.clone(), key))
+            })
+            .collect();
+
+        let (hits, misses): (Vec<&PathData>, Vec<&PathData>) =
+            path_set.iter().partition(|pathdata| {
+                match (
+                    keys.get(&pathdata.path_buf),
+                    cache.inner.get(&pathdata.path_buf),
+                ) {
+                    (Some(key), Some(cached)) => *key == cached.key,
+                    _ => false,
+                }
+            });
+
+        let hits: BTreeMap<PathData, Vec<PathData>> = hits
+            .into_iter()
+            .filter_map(|pathdata| {
+                cache
+                    .inner
+                    .get(&pathdata.path_buf)
+                    .map(|cached| (pathdata.clone(), cached.versions.clone()))
+            })
+            .collect();
+
+        let misses: Vec<PathData> = misses.into_iter().cloned().collect();
+
+        let mut versions_map: VersionsMap = if misses.is_empty() {
+            VersionsMap::from(BTreeMap::new())
+        } else {
+            match VersionsMap::with_max_results(
+                config,
+                &misses,
+                config.opt_max_results,
+                opt_replica,
+            ) {
+                Ok(versions_map) => versions_map,
+                Err(err) if hits.is_empty() => return Err(err),
+                // only the misses are missing, which is no reason to discard the hits,
+                // so display them as the ordinary lookup would a path which does not exist
+                Err(_) => misses
+                    .into_iter()
+                    .map(|pathdata| (pathdata, Vec::new()))
+                    .collect::<BTreeMap<PathData, Vec<PathData>>>()
+                    .into(),
+            }
+        };
+
+        versions_map.iter().for_each(|(live_version, versions)| {
+            if let Some(key) = keys.get(&live_version.path_buf) {
+                cache.inner.insert(
+                    live_version.path_buf.clone(),
+                    CachedVersions {
+                        key: *key,
+                        versions: versions.clone(),
+                    },
+                );
+            }
+        });
+
+        versions_map.extend(hits);
+
+        // a failure to remember is not worth failing the audit
+        if let Err(err) = cache.save() {
+            eprintln!("WARNING: httm could not save the num versions cache: {err}");
+        }
+
+        Ok(versions_map)
+    }
+
+    // a key changes whenever the live version, the snapshots of its datasets, or the options
+    // which determine which versions are found, change.  it is persisted, so it must be stable
+    // across runs and builds, which a DefaultHasher is not guaranteed to be
+    fn key(
+        config: &Config,
+        pathdata: &PathData,
+        opt_replica: Option<&Path>,
+    ) -> Option<blake3::Hash> {
+        LiveFilesystemInfo::with_current(|fs_info| {
+            Self::key_in(fs_info, config, pathdata, opt_replica)
+        })
+    }
+
+    fn key_in(
+        fs_info: &FilesystemInfo,
+        config: &Config,
+        pathdata: &PathData,
+        opt_replica: Option<&Path>,
+    ) -> Option<blake3::Hash> {
+        let md = pathdata.metadata?;
+
+        let mut prox_opt_alts = ProximateDatasetAndOptAlts::with_fs_info(fs_info, pathdata).ok()?;
+
+        // versions scoped to one replica are not the versions of every replica
+        prox_opt_alts.scope_to_replica(opt_replica);
+
+        let nanos = md
+            .modify_time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        Self::push_field(
+            &mut buffer,
+            NUM_VERSIONS_CACHE_VERSION.to_string().as_bytes(),
+        );
+        Self::push_field(&mut buffer, config.uniqueness.name().as_bytes());
+        Self::push_field(&mut buffer, md.size.to_string().as_bytes());
+        Self::push_field(&mut buffer, nanos.to_string().as_bytes());
+
+        // the options which include or exclude versions, or snapshots
+        [
+            format!("{:?}", config.opt_omit_ditto),
+            format!("{:?}", config.opt_of_snap),
+            format!("{:?}", config.opt_max_results),
+            format!("{:?}", config.opt_mtime_tolerance),
+            format!("{:?}", config.opt_last_snap),
+            format!("{:?}", config.opt_bulk_exclusion),
+        ]
+        .iter()
+        .for_each(|option| Self::push_field(&mut buffer, option.as_bytes()));
+
+        prox_opt_alts
+            .datasets_of_interest
+            .iter()
+            .for_each(|dataset| Self::push_field(&mut buffer, dataset.as_os_str().as_bytes()));
+
+        // snap mounts are not read in any particular order, so sort them, to hash the same in any order
+        let mut snap_mounts: Vec<&PathBuf> = prox_opt_alts
+            .datasets_of_interest
+            .iter()
+            .flat_map(|dataset| fs_info.map_of_snaps.get(dataset))
+            .flatten()
+            .collect();
+
+        snap_mounts.sort_unstable();
+
+        snap_mounts.iter().for_each(|snap_mount| {
+            Self::push_field(&mut buffer, snap_mount.as_os_str().as_bytes())
+        });
+
+        Some(blake3::hash(&buffer))
+    }
+
+    fn cache_file() -> Option<PathBuf> {
+        Some(BaseDir::Cache.httm_dir()?.join(NUM_VERSIONS_CACHE_FILE))
+    }
+
+    fn load() -> Self {
+        let mut cache = Self {
+            inner: HashMap::new(),
+        };
+
+        let buffer = match Self::cache_file().and_then(|file| std::fs::read(file).ok()) {
+            Some(buffer) => buffer,
+            None => return cache,
+        };
+
+        // records are NULL delimited, as paths may contain newlines: the live path, its key,
+        // the number of versions, and then the path, size and modify time of each version
+        let mut fields = buffer.split(|byte| *byte == b'\0').map(OsStr::from_bytes);
+
+        if fields.next().and_then(OsStr::to_str)
+            != Some(NUM_VERSIONS_CACHE_VERSION.to_string().as_str())
+        {
+            return cache;
+        }
+
+        while let Some(live_path) = fields.next() {
+            if live_path.is_empty() {
+                continue;
+            }
+
+            let opt_key: Option<blake3::Hash> = fields
+                .next()
+                .and_then(OsStr::to_str)
+                .and_then(|key| blake3::Hash::from_hex(key).ok());
+            let opt_num_versions: Option<usize> = fields
+                .next()
+                .and_then(OsStr::to_str)
+                .and_then(|num| num.parse().ok());
+
+            let (key, num_versions) = match (opt_key, opt_num_versions) {
+                (Some(key), Some(num_versions)) => (key, num_versions),
+                // a cache we can't parse is a cache we don't have
+                _ => {
+                    return Self {
+                        inner: HashMap::new(),
+                    }
+                }
+            };
+
+            let opt_versions: Option<Vec<PathData>> = (0..num_versions)
+                .map(|_| {
+                    let path = fields.next()?;
+                    let size: u64 = fields.next()?.to_str()?.parse().ok()?;
+                    let nanos: u64 = fields.next()?.to_str()?.parse().ok()?;
+
+                    Some(PathData {
+                        path_buf: PathBuf::from(path),
+                        metadata: Some(PathMetadata {
+                            size,
+                            modify_time: SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos),
+                        }),
+                    })
+                })
+                .collect();
+
+            match opt_versions {
+                Some(versions) => {
+                    cache
+                        .inner
+                        .insert(PathBuf::from(live_path), CachedVersions { key, versions });
+                }
+                None => {
+                    return Self {
+                        inner: HashMap::new(),
+                    }
+                }
+            }
+        }
+
+        cache
+    }
+
+    fn save(&self) -> HttmResult<()> {
+        let cache_file = match Self::cache_file() {
+            Some(cache_file) => cache_file,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = cache_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        Self::push_field(
+            &mut buffer,
+            NUM_VERSIONS_CACHE_VERSION.to_string().as_bytes(),
+        );
+
+        self.inner
+            .iter()
+            // a version with a modify time before the epoch, or without metadata, can't be written
+            .filter_map(|(live_path, cached)| {
+                let versions: Option<Vec<(&PathData, u64, u64)>> = cached
+                    .versions
+                    .iter()
+                    .map(|version| {
+                        let md = version.metadata?;
+                        let since_epoch =
+                            md.modify_time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+                        let nanos = u64::try_from(since_epoch.as_nanos()).ok()?;
+                        Some((version, md.size, nanos))
+                    })
+                    .collect();
+
+                versions.map(|versions| (live_path, cached.key, versions))
+            })
+            .for_each(|(live_path, key, versions)| {
+                Self::push_field(&mut buffer, live_path.as_os_str().as_bytes());
+                Self::push_field(&mut buffer, key.to_hex().as_bytes());
+                Self::push_field(&mut buffer, versions.len().to_string().as_bytes());
+
+                versions.iter().for_each(|(version, size, nanos)| {
+                    Self::push_field(&mut buffer, version.path_buf.as_os_str().as_bytes());
+                    Self::push_field(&mut buffer, size.to_string().as_bytes());
+                    Self::push_field(&mut buffer, nanos.to_string().as_bytes());
+                });
+            });
+
+        // write to a tmp file and rename, so a concurrent run never reads a partial cache
+        let tmp_file = make_tmp_path(&cache_file);
+        std::fs::write(&tmp_file, buffer)?;
+        std::fs::rename(&tmp_file, &cache_file)?;
+
+        Ok(())
+    }
+
+    fn push_field(buffer: &mut Vec<u8>, field: &[u8]) {
+        buffer.extend_from_slice(field);
+        buffer.push(b'\0');
+    }
+}
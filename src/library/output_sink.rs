@@ -0,0 +1,170 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::config::generate::OutputFile;
+use crate::library::results::{HttmError, HttmResult};
+
+// output is written to a temporary file beside the requested output file, which is only renamed
+// into place once httm completes successfully, so a reader, like the consumer of a cron job's output,
+// never sees a partial output, and a failed run leaves any previous output as it was
+static PENDING_OUTPUT: Mutex<Option<PendingOutput>> = Mutex::new(None);
+
+// the exit handler discards whatever output is pending, so one registration serves every open
+static IS_DISCARD_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+struct PendingOutput {
+    tmp_path: PathBuf,
+    dst_path: PathBuf,
+    file: File,
+}
+
+pub struct OutputSink;
+
+impl OutputSink {
+    pub fn open(output_file: &OutputFile) -> HttmResult<()> {
+        let dst_path = &output_file.path;
+
+        let file_name = match dst_path.file_name() {
+            Some(file_name) if !dst_path.is_dir() => file_name,
+            _ => {
+                let msg = format!("OUTPUT must be the path of a file: {:?}", dst_path);
+                return Err(HttmError::new(&msg).into());
+            }
+        };
+
+        // must reside upon the same filesystem as the output file for the rename to be atomic
+        let tmp_path = dst_path.with_file_name(format!(
+            ".{}.httm_output.{}",
+            file_name.to_string_lossy(),
+            std::process::id()
+        ));
+
+        // an append is atomic too, as we append to a copy of the existing output file
+        if output_file.should_append && dst_path.exists() {
+            std::fs::copy(dst_path, &tmp_path).map_err(|err| {
+                HttmError::with_context("Could not copy OUTPUT file for append", &err)
+            })?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(output_file.should_append)
+            .truncate(!output_file.should_append)
+            .open(&tmp_path)
+            .map_err(|err| HttmError::with_context("Could not create OUTPUT file", &err))?;
+
+        // a replaced output file keeps its mode, as it would with shell redirection
+        if let Ok(dst_md) = dst_path.metadata() {
+            let _ = file.set_permissions(dst_md.permissions());
+        }
+
+        *Self::lock() = Some(PendingOutput {
+            tmp_path,
+            dst_path: dst_path.to_path_buf(),
+            file,
+        });
+
+        // many modes end by exiting the process from wherever they finish, often upon an error,
+        // so any output still pending at exit was never committed, and is removed
+        if !IS_DISCARD_REGISTERED.swap(true, Ordering::SeqCst) {
+            // SAFETY: discard_at_exit is an extern "C" fn which takes no arguments, and never unwinds,
+            // as discard recovers a poisoned lock and ignores any error removing the file.  the flag
+            // above ensures it is registered only once, however many times output is opened
+            unsafe {
+                let _ = libc::atexit(Self::discard_at_exit);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_all(output_buf: &[u8]) -> HttmResult<()> {
+        let mut opt_pending = Self::lock();
+
+        match opt_pending.as_mut() {
+            Some(pending) => pending
+                .file
+                .write_all(output_buf)
+                .map_err(std::convert::Into::into),
+            None => {
+                // mutex keeps threads from writing over each other
+                let out = std::io::stdout();
+                let mut out_locked = out.lock();
+                out_locked.write_all(output_buf)?;
+                out_locked.flush().map_err(std::convert::Into::into)
+            }
+        }
+    }
+
+    // rename the output written into place, a no-op when no OUTPUT file was requested
+    pub fn commit() -> HttmResult<()> {
+        let pending = match Self::lock().take() {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+
+        pending.file.sync_all()?;
+
+        std::fs::rename(&pending.tmp_path, &pending.dst_path).map_err(|err| {
+            let _ = std::fs::remove_file(&pending.tmp_path);
+            HttmError::with_context("Could not rename OUTPUT file into place", &err).into()
+        })
+    }
+
+    // any further output, like an error, is written to stdout
+    pub fn discard() {
+        if let Some(pending) = Self::lock().take() {
+            let _ = std::fs::remove_file(pending.tmp_path);
+        }
+    }
+
+    extern "C" fn discard_at_exit() {
+        Self::discard()
+    }
+
+    // for output streamed, like the contents of a version, rather than written in one buffer
+    pub fn writer() -> OutputWriter {
+        OutputWriter
+    }
+
+    fn lock() -> std::sync::MutexGuard<'static, Option<PendingOutput>> {
+        PENDING_OUTPUT
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub struct OutputWriter;
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        OutputSink::write_all(buf)
+            .map(|_| buf.len())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::Path;
+use std::process::Command as ExecProcess;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::PathData;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::print_notice;
+use crate::parse::aliases::FilesystemType;
+use crate::GLOBAL_CONFIG;
+
+// before we mutate a dataset, via prune, overwrite restore, or roll forward, we check
+// whether its pool is in a state where mutation might worsen recovery prospects
+pub struct PoolHealth {
+    pool_name: String,
+    opt_state: Option<String>,
+    opt_scan: Option<String>,
+}
+
+impl PoolHealth {
+    pub fn precheck_path(path: &Path) -> HttmResult<()> {
+        let pathdata = PathData::from(path);
+
+        let opt_dataset_name = LiveFilesystemInfo::with_current(|fs_info| {
+            let dataset_mount = pathdata.proximate_dataset(&fs_info.map_of_datasets).ok()?;
+
+            fs_info
+                .map_of_datasets
+                .get(dataset_mount)
+                .filter(|md| md.fs_type == FilesystemType::Zfs)
+                .map(|md| md.source.to_string_lossy().into_owned())
+        });
+
+        match opt_dataset_name {
+            Some(dataset_name) => Self::precheck(&dataset_name),
+            None => Ok(()),
+        }
+    }
+
+    // takes any dataset or snapshot name, like "rpool/kimono@snap", and checks its pool
+    pub fn precheck(dataset_name: &str) -> HttmResult<()> {
+        let pool_name = dataset_name
+            .split(['/', '@'])
+            .next()
+            .unwrap_or(dataset_name);
+
+        let pool_health = match Self::new(pool_name) {
+            Ok(pool_health) => pool_health,
+            Err(err) => {
+                if GLOBAL_CONFIG.opt_strict {
+                    return Err(err);
+                }
+
+                eprintln!("WARNING: Could not determine the health of pool \"{pool_name}\": {err}");
+                return Ok(());
+            }
+        };
+
+        if let Some(msg) = pool_health.is_unhealthy() {
+            if GLOBAL_CONFIG.opt_strict {
+                let msg =
+                    format!("{msg}  httm will not modify this pool in STRICT mode.  Quitting.");
+                return Err(HttmError::new(&msg).into());
+            }
+
+            eprintln!("WARNING: {msg}  Proceeding anyway.");
+        } else if let Some(msg) = pool_health.is_scrubbing() {
+            print_notice(&format!("NOTICE: {msg}"));
+        }
+
+        Ok(())
+    }
+
+    fn new(pool_name: &str) -> HttmResult<Self> {
+        let zpool_command = ExternalCommand::require("zpool")?;
+
+        // -x: only print status for pools which are exhibiting errors or are otherwise unavailable
+        let process_args = vec!["status", "-x", pool_name];
+
+        let process_output = ExecProcess::new(zpool_command)
+            .args(&process_args)
+            .output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = "The 'zpool' command issued the following error: ".to_owned() + stderr_string;
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let stdout_string = std::str::from_utf8(&process_output.stdout)?;
+
+        // a healthy pool prints only "pool 'rpool' is healthy", otherwise we get the full status
+        let value_of = |key: &str| {
+            stdout_string.lines().find_map(|line| {
+                line.trim_start()
+                    .strip_prefix(key)
+                    .map(|value| value.trim().to_owned())
+            })
+        };
+
+        Ok(Self {
+            pool_name: pool_name.to_owned(),
+            opt_state: value_of("state:"),
+            opt_scan: value_of("scan:"),
+        })
+    }
+
+    fn is_unhealthy(&self) -> Option<String> {
+        if let Some(scan) = &self.opt_scan {
+            if scan.starts_with("resilver in progress") {
+                return Some(format!(
+                    "Pool \"{}\" is resilvering ({scan}).",
+                    self.pool_name
+                ));
+            }
+        }
+
+        match &self.opt_state {
+            Some(state) if state != "ONLINE" => Some(format!(
+                "Pool \"{}\" is in a {state} state.",
+                self.pool_name
+            )),
+            _ => None,
+        }
+    }
+
+    fn is_scrubbing(&self) -> Option<String> {
+        match &self.opt_scan {
+            Some(scan) if scan.starts_with("scrub in progress") => Some(format!(
+                "Pool \"{}\" is being scrubbed ({scan}).",
+                self.pool_name
+            )),
+            _ => None,
+        }
+    }
+}
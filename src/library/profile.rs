@@ -0,0 +1,132 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::OnceCell;
+
+// as with the alt root, profiling must be enabled before the GLOBAL_CONFIG is available,
+// because mount parsing takes place as the config is built
+static PROFILE_ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILE_START: OnceCell<Instant> = OnceCell::new();
+
+// nanoseconds spent in each phase, indexed by Phase
+static PHASE_NANOS: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    MountParsing,
+    Lookup,
+    Uniqueness,
+    Display,
+}
+
+impl Phase {
+    const ALL: [Phase; 4] = [
+        Phase::MountParsing,
+        Phase::Lookup,
+        Phase::Uniqueness,
+        Phase::Display,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Phase::MountParsing => "mount parsing",
+            Phase::Lookup => "lookup",
+            // uniqueness is determined for each file in parallel, so its time is summed across threads,
+            // and is also included within the lookup time
+            Phase::Uniqueness => "uniqueness filtering (all threads)",
+            Phase::Display => "display",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Phase::MountParsing => 0,
+            Phase::Lookup => 1,
+            Phase::Uniqueness => 2,
+            Phase::Display => 3,
+        }
+    }
+}
+
+pub struct Profile;
+
+impl Profile {
+    pub fn enable() {
+        let _ = PROFILE_START.set(Instant::now());
+        PROFILE_ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled() -> bool {
+        PROFILE_ENABLED.load(Ordering::Relaxed)
+    }
+
+    // when profiling is disabled, this costs no more than an atomic load
+    pub fn time<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+        if !Self::is_enabled() {
+            return f();
+        }
+
+        let start = Instant::now();
+        let res = f();
+        let elapsed = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+
+        PHASE_NANOS[phase.index()].fetch_add(elapsed, Ordering::Relaxed);
+
+        res
+    }
+
+    // report to stderr, so as not to disturb any output meant for other programs
+    pub fn report() {
+        if !Self::is_enabled() {
+            return;
+        }
+
+        let mut buffer = String::from("httm profile:\n");
+
+        Phase::ALL.iter().for_each(|phase| {
+            let nanos = PHASE_NANOS[phase.index()].load(Ordering::Relaxed);
+
+            buffer += &format!(
+                "  {:<36}{:>12.3} ms\n",
+                phase.label(),
+                Self::millis(Duration::from_nanos(nanos))
+            );
+        });
+
+        if let Some(start) = PROFILE_START.get() {
+            buffer += &format!(
+                "  {:<36}{:>12.3} ms\n",
+                "total",
+                Self::millis(start.elapsed())
+            );
+        }
+
+        eprint!("{buffer}");
+    }
+
+    fn millis(duration: Duration) -> f64 {
+        duration.as_secs_f64() * 1000f64
+    }
+}
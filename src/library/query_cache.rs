@@ -0,0 +1,163 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+
+use crate::library::results::HttmResult;
+use crate::library::utility::make_tmp_path;
+use crate::library::xdg::BaseDir;
+use crate::GLOBAL_CONFIG;
+
+const QUERY_CACHE_FILE: &str = "interactive_queries";
+// no need to remember every dir ever visited
+const QUERY_CACHE_MAX_ENTRIES: usize = 256;
+
+// key: requested dir, val: the last query string entered while browsing that dir
+static QUERY_CACHE: Lazy<Mutex<QueryCache>> = Lazy::new(|| Mutex::new(QueryCache::load()));
+
+pub struct QueryCache {
+    inner: HashMap<PathBuf, String>,
+    // most recently used last, so we know what to forget first
+    order: Vec<PathBuf>,
+}
+
+impl QueryCache {
+    pub fn get(requested_dir: &Path) -> Option<String> {
+        let cache = QUERY_CACHE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        cache
+            .inner
+            .get(requested_dir)
+            .filter(|query| !query.is_empty())
+            .cloned()
+    }
+
+    // all previous queries, oldest first, for the query history keybindings (ctrl+p, ctrl+n)
+    pub fn history() -> Vec<String> {
+        let cache = QUERY_CACHE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        cache
+            .order
+            .iter()
+            .filter_map(|dir| cache.inner.get(dir))
+            .filter(|query| !query.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    pub fn set(requested_dir: &Path, query: &str) {
+        let mut cache = QUERY_CACHE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        cache.insert(requested_dir.to_path_buf(), query.to_owned());
+
+        if GLOBAL_CONFIG.opt_remember_query {
+            // a failure to remember is not worth interrupting the user
+            if let Err(err) = cache.save() {
+                eprintln!("WARNING: httm could not save the interactive query cache: {err}");
+            }
+        }
+    }
+
+    fn insert(&mut self, requested_dir: PathBuf, query: String) {
+        self.order.retain(|dir| dir != &requested_dir);
+        self.order.push(requested_dir.clone());
+        self.inner.insert(requested_dir, query);
+
+        while self.order.len() > QUERY_CACHE_MAX_ENTRIES {
+            let oldest = self.order.remove(0);
+            self.inner.remove(&oldest);
+        }
+    }
+
+    fn cache_file() -> Option<PathBuf> {
+        Some(BaseDir::Cache.httm_dir()?.join(QUERY_CACHE_FILE))
+    }
+
+    fn load() -> Self {
+        let mut cache = Self {
+            inner: HashMap::new(),
+            order: Vec::new(),
+        };
+
+        if !GLOBAL_CONFIG.opt_remember_query {
+            return cache;
+        }
+
+        let buffer = match Self::cache_file().and_then(|file| std::fs::read(file).ok()) {
+            Some(buffer) => buffer,
+            None => return cache,
+        };
+
+        // records are NULL delimited pairs of dir and query, as paths may contain newlines
+        let mut fields = buffer.split(|byte| *byte == b'\0');
+
+        while let (Some(dir), Some(query)) = (fields.next(), fields.next()) {
+            if dir.is_empty() {
+                continue;
+            }
+
+            cache.insert(
+                PathBuf::from(OsStr::from_bytes(dir)),
+                String::from_utf8_lossy(query).to_string(),
+            );
+        }
+
+        cache
+    }
+
+    fn save(&self) -> HttmResult<()> {
+        let cache_file = match Self::cache_file() {
+            Some(cache_file) => cache_file,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = cache_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        self.order
+            .iter()
+            .filter_map(|dir| self.inner.get(dir).map(|query| (dir, query)))
+            .for_each(|(dir, query)| {
+                buffer.extend_from_slice(dir.as_os_str().as_bytes());
+                buffer.push(b'\0');
+                buffer.extend_from_slice(query.as_bytes());
+                buffer.push(b'\0');
+            });
+
+        // write to a tmp file and rename, so a concurrent session never reads a partial cache
+        let tmp_file = make_tmp_path(&cache_file);
+        std::fs::write(&tmp_file, buffer)?;
+        std::fs::rename(&tmp_file, &cache_file)?;
+
+        Ok(())
+    }
+}
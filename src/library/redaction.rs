@@ -0,0 +1,219 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::Command as ExecProcess;
+use std::sync::{Arc, Mutex};
+
+use hashbrown::{HashMap, HashSet};
+use once_cell::sync::Lazy;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::PathData;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmResult};
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
+use crate::ZFS_SNAPSHOT_DIRECTORY;
+
+// key: dataset mount, val: the names of the snapshots of that dataset which are redacted
+static REDACTED_SNAPS: Lazy<Mutex<HashMap<PathBuf, Arc<HashSet<OsString>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// key: pool name, val: whether the pool holds any redacted dataset at all
+static REDACTED_POOLS: Lazy<Mutex<HashMap<String, bool>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// a dataset received from a redacted send ("zfs send --redact") is missing the blocks of the files redacted,
+// and reading such a file fails with a confusing error, so a version within a redacted snapshot is annotated,
+// is never read to compare contents, and is never restored, unless forced
+pub struct Redaction;
+
+impl Redaction {
+    pub const ANNOTATION: &'static str = "redacted/not fully present";
+
+    pub fn is_redacted(pathdata: &PathData) -> bool {
+        let snap_name = match pathdata.snap_name() {
+            Some(snap_name) => snap_name,
+            None => return false,
+        };
+
+        // the dataset mount is the parent of the hidden ".zfs" directory
+        let opt_dataset_mount = pathdata
+            .path_buf
+            .ancestors()
+            .find(|ancestor| ancestor.ends_with(ZFS_SNAPSHOT_DIRECTORY))
+            .and_then(Path::parent)
+            .and_then(Path::parent);
+
+        match opt_dataset_mount {
+            Some(dataset_mount) => Self::redacted_snaps(dataset_mount).contains(snap_name),
+            None => false,
+        }
+    }
+
+    pub fn refuse_unless_forced(pathdata: &PathData, is_forced: bool) -> HttmResult<()> {
+        if is_forced || !Self::is_redacted(pathdata) {
+            return Ok(());
+        }
+
+        let msg = format!(
+            "The version selected resides within a redacted snapshot, and may be missing some or all of its contents: {:?}  \
+            Specify FORCE to restore it anyway.",
+            pathdata.path_buf
+        );
+
+        Err(HttmError::new(&msg).into())
+    }
+
+    // a dataset now mounted where another once was may have its own redacted snapshots, or its own pool
+    pub fn clear_cache() {
+        REDACTED_SNAPS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+
+        REDACTED_POOLS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    fn redacted_snaps(dataset_mount: &Path) -> Arc<HashSet<OsString>> {
+        if let Some(redacted_snaps) = REDACTED_SNAPS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(dataset_mount)
+        {
+            return redacted_snaps.clone();
+        }
+
+        // detection waits upon zfs, so others, perhaps asking after another dataset, must not wait upon us
+        let detected = Arc::new(Self::detect(dataset_mount));
+
+        REDACTED_SNAPS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(dataset_mount.to_path_buf())
+            .or_insert(detected)
+            .clone()
+    }
+
+    fn detect(dataset_mount: &Path) -> HashSet<OsString> {
+        let opt_dataset_name = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .get(dataset_mount)
+                .filter(|md| md.fs_type == FilesystemType::Zfs && md.mount_type == MountType::Local)
+                .map(|md| md.source.to_string_lossy().into_owned())
+        });
+
+        let dataset_name = match opt_dataset_name {
+            Some(dataset_name) => dataset_name,
+            None => return HashSet::new(),
+        };
+
+        // almost no pool holds a redacted dataset, so ask once of each pool, before asking after each dataset
+        let pool_name = match dataset_name.split_once('/') {
+            Some((pool_name, _the_rest)) => pool_name,
+            None => dataset_name.as_str(),
+        };
+
+        if !Self::is_pool_redacted(pool_name) {
+            return HashSet::new();
+        }
+
+        let zfs_command = match ExternalCommand::optional(
+            "zfs",
+            "versions within redacted snapshots cannot be detected",
+        ) {
+            Some(zfs_command) => zfs_command,
+            None => return HashSet::new(),
+        };
+
+        // the redact_snaps of a snapshot are those snapshots it is redacted with respect to, "-" if it is not redacted
+        let process_args = vec![
+            "list",
+            "-H",
+            "-t",
+            "snapshot",
+            "-d",
+            "1",
+            "-o",
+            "name,redact_snaps",
+            &dataset_name,
+        ];
+
+        // a pool or a release which knows nothing of redaction simply fails here
+        let process_output = match ExecProcess::new(zfs_command).args(&process_args).output() {
+            Ok(process_output) if process_output.status.success() => process_output,
+            _ => return HashSet::new(),
+        };
+
+        String::from_utf8_lossy(&process_output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(_full_snap_name, redact_snaps)| *redact_snaps != "-")
+            .filter_map(|(full_snap_name, _redact_snaps)| full_snap_name.split_once('@'))
+            .map(|(_dataset_name, snap_name)| OsStr::new(snap_name).to_os_string())
+            .collect()
+    }
+
+    fn is_pool_redacted(pool_name: &str) -> bool {
+        if let Some(is_redacted) = REDACTED_POOLS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(pool_name)
+        {
+            return *is_redacted;
+        }
+
+        let zpool_command = match ExternalCommand::optional(
+            "zpool",
+            "versions within redacted snapshots cannot be detected",
+        ) {
+            Some(zpool_command) => zpool_command,
+            None => return false,
+        };
+
+        // the feature is "active" only while the pool holds a redacted dataset, and a pool or a release
+        // which knows nothing of redaction simply fails here
+        let process_args = vec![
+            "get",
+            "-H",
+            "-o",
+            "value",
+            "feature@redacted_datasets",
+            pool_name,
+        ];
+
+        let is_redacted = match ExecProcess::new(zpool_command).args(&process_args).output() {
+            Ok(process_output) if process_output.status.success() => {
+                String::from_utf8_lossy(&process_output.stdout).trim() == "active"
+            }
+            _ => false,
+        };
+
+        REDACTED_POOLS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(pool_name.to_owned(), is_redacted);
+
+        is_redacted
+    }
+}
@@ -15,21 +15,128 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::{error::Error, fmt, io::Error as IoError};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{error::Error, fmt, io::Error as IoError, io::ErrorKind as IoErrorKind};
+
+use once_cell::sync::Lazy;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::library::external_commands::ExternalCommand;
+use crate::library::messages::Catalog;
+use crate::GLOBAL_CONFIG;
 
 // wrap this complex looking error type, which is used everywhere,
 // into something more simple looking. This error, FYI, is really easy to use with rayon.
 pub type HttmResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+// paths skipped, which, in STRICT mode, are reported as failures upon exit
+static SKIPPED_PATHS: Lazy<Mutex<Vec<(PathBuf, HttmErrorKind, String)>>> =
+    Lazy::new(|| Mutex::new(Vec::new()));
+
+// the kind of an error, so that a script may tell one failure from another,
+// by exit code, or by the "kind" of a JSON error response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttmErrorKind {
+    NotFound,
+    PermissionDenied,
+    UnsupportedFilesystem,
+    CommandMissing,
+    ParseError,
+    Other,
+}
+
+impl HttmErrorKind {
+    // see sysexits.h, and, for a missing command, the shell's own convention
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            HttmErrorKind::Other => 1,
+            HttmErrorKind::ParseError => 65,
+            HttmErrorKind::NotFound => 66,
+            HttmErrorKind::UnsupportedFilesystem => 69,
+            HttmErrorKind::PermissionDenied => 77,
+            HttmErrorKind::CommandMissing => 127,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttmErrorKind::NotFound => "not_found",
+            HttmErrorKind::PermissionDenied => "permission_denied",
+            HttmErrorKind::UnsupportedFilesystem => "unsupported_filesystem",
+            HttmErrorKind::CommandMissing => "command_missing",
+            HttmErrorKind::ParseError => "parse_error",
+            HttmErrorKind::Other => "other",
+        }
+    }
+
+    // our own errors know their kind, and the kinds of some others may be inferred
+    pub fn of(err: &(dyn Error + 'static)) -> Self {
+        if let Some(httm_err) = err.downcast_ref::<HttmError>() {
+            return httm_err.kind;
+        }
+
+        if let Some(io_err) = err.downcast_ref::<IoError>() {
+            return io_err.kind().into();
+        }
+
+        if err.is::<std::num::ParseIntError>()
+            || err.is::<std::num::ParseFloatError>()
+            || err.is::<std::str::Utf8Error>()
+            || err.is::<serde_json::Error>()
+        {
+            return HttmErrorKind::ParseError;
+        }
+
+        HttmErrorKind::Other
+    }
+}
+
+impl From<IoErrorKind> for HttmErrorKind {
+    fn from(kind: IoErrorKind) -> Self {
+        match kind {
+            IoErrorKind::NotFound => HttmErrorKind::NotFound,
+            IoErrorKind::PermissionDenied => HttmErrorKind::PermissionDenied,
+            IoErrorKind::InvalidData => HttmErrorKind::ParseError,
+            _ => HttmErrorKind::Other,
+        }
+    }
+}
+
+impl Serialize for HttmErrorKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 #[derive(Debug)]
 pub struct HttmError {
     pub details: String,
+    pub kind: HttmErrorKind,
 }
 
 impl HttmError {
     pub fn new(msg: &str) -> Self {
         HttmError {
             details: msg.to_owned(),
+            kind: HttmErrorKind::Other,
+        }
+    }
+    pub fn with_kind(kind: HttmErrorKind, msg: &str) -> Self {
+        HttmError {
+            details: msg.to_owned(),
+            kind,
+        }
+    }
+    // constructs an error from a message catalog key, see library::messages
+    pub fn localized(key: &'static str) -> Self {
+        HttmError {
+            details: Catalog::get(key).to_owned(),
+            kind: HttmErrorKind::Other,
         }
     }
     pub fn with_context(msg: &str, err: &dyn Error) -> Self {
@@ -37,6 +144,18 @@ impl HttmError {
 
         HttmError {
             details: msg_plus_context,
+            kind: HttmErrorKind::Other,
+        }
+    }
+    pub fn command_missing(command: &str) -> Self {
+        let msg = format!(
+            "'{command}' command not found.  {}",
+            ExternalCommand::install_hint(command)
+        );
+
+        HttmError {
+            details: msg,
+            kind: HttmErrorKind::CommandMissing,
         }
     }
 }
@@ -56,13 +175,78 @@ impl Error for HttmError {
 impl From<&dyn Error> for HttmError {
     fn from(err: &dyn Error) -> Self {
         let context = format!("{err:?}");
-        HttmError { details: context }
+        HttmError {
+            details: context,
+            kind: HttmErrorKind::Other,
+        }
     }
 }
 
 impl From<IoError> for HttmError {
     fn from(err: IoError) -> Self {
         let context = format!("{err:?}");
-        HttmError { details: context }
+        HttmError {
+            details: context,
+            kind: err.kind().into(),
+        }
+    }
+}
+
+// the body of a JSON error response: {"kind": "not_found", "message": "..."}
+pub struct JsonError<'a> {
+    pub kind: HttmErrorKind,
+    pub message: &'a str,
+}
+
+impl<'a> Serialize for JsonError<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("JsonError", 2)?;
+
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field("message", self.message)?;
+        state.end()
+    }
+}
+
+// an unreadable dir, or a path upon no supported dataset, is usually skipped silently,
+// but, in STRICT mode, each is recorded, and reported as a failure upon exit
+pub struct SkippedPaths;
+
+impl SkippedPaths {
+    pub fn record(path: &Path, err: &(dyn Error + 'static)) {
+        if !GLOBAL_CONFIG.opt_strict {
+            return;
+        }
+
+        SKIPPED_PATHS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((path.to_path_buf(), HttmErrorKind::of(err), err.to_string()));
+    }
+
+    pub fn report() -> HttmResult<()> {
+        let skipped = SKIPPED_PATHS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // the kind of the whole is that of the first skip
+        let kind = match skipped.first() {
+            Some((_path, kind, _msg)) => *kind,
+            None => return Ok(()),
+        };
+
+        skipped
+            .iter()
+            .for_each(|(path, _kind, msg)| eprintln!("Error: {path:?}: {msg}"));
+
+        let msg = format!(
+            "httm could not process {} path(s), listed above, in STRICT mode.",
+            skipped.len()
+        );
+
+        Err(HttmError::with_kind(kind, &msg).into())
     }
 }
@@ -0,0 +1,105 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+
+// attempts include the first, the backoff doubles after each transient failure: 20ms, 40ms, 80ms
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(20);
+// once a dataset has persistently failed this many times, it is probably gone, not busy, so stop retrying
+// reads upon it, lest every lookup of every file wait out every backoff
+const MAX_PERSISTENT_FAILURES: usize = 3;
+
+// key: dataset mount, val: the number of reads which failed even after every retry
+static PERSISTENT_FAILURES: Lazy<Mutex<HashMap<PathBuf, usize>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// automounted and NFS snapshot dirs may fail transiently, as while they are being mounted,
+// and a transient failure should not be mistaken for the absence of a snapshot version
+pub struct Retry;
+
+impl Retry {
+    pub fn io<T>(dataset: &Path, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut opt_max_attempts: Option<u32> = None;
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 1;
+
+        loop {
+            match op() {
+                Err(err) if Self::is_transient(&err) => {
+                    // only a failure consults the persistent failures, so a read which succeeds takes no lock
+                    let max_attempts = *opt_max_attempts.get_or_insert_with(|| {
+                        if Self::is_exhausted(dataset) {
+                            1
+                        } else {
+                            MAX_ATTEMPTS
+                        }
+                    });
+
+                    if attempt >= max_attempts {
+                        Self::record_persistent_failure(dataset, &err);
+                        return Err(err);
+                    }
+
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    fn is_transient(err: &Error) -> bool {
+        matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::Interrupted)
+            || matches!(
+                err.raw_os_error(),
+                Some(libc::EAGAIN | libc::ESTALE | libc::EBUSY)
+            )
+    }
+
+    fn is_exhausted(dataset: &Path) -> bool {
+        let failures = PERSISTENT_FAILURES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        matches!(failures.get(dataset), Some(count) if *count >= MAX_PERSISTENT_FAILURES)
+    }
+
+    // warn only once per dataset, the first time, as one warning per file would be a flood
+    fn record_persistent_failure(dataset: &Path, err: &Error) {
+        let mut failures = PERSISTENT_FAILURES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let count = failures.entry(dataset.to_path_buf()).or_insert(0);
+        *count += 1;
+
+        if *count == 1 {
+            eprintln!(
+                "WARNING: Reads of the snapshot directories of dataset {:?} failed repeatedly, even after retrying, and results for this dataset may be incomplete: {err}",
+                dataset
+            );
+        }
+    }
+}
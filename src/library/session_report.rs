@@ -0,0 +1,190 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use crate::library::utility::{date_string, DateFormat};
+use crate::library::xdg::BaseDir;
+use crate::GLOBAL_CONFIG;
+
+const SESSION_REPORT_FILE: &str = "report.log";
+
+// counts for this session, indexed by Stat
+static STAT_COUNTS: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    FilesViewed,
+    VersionsRestored,
+    BytesCopied,
+    SnapshotsCreated,
+}
+
+impl Stat {
+    const ALL: [Stat; 4] = [
+        Stat::FilesViewed,
+        Stat::VersionsRestored,
+        Stat::BytesCopied,
+        Stat::SnapshotsCreated,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Stat::FilesViewed => "files viewed",
+            Stat::VersionsRestored => "versions restored",
+            Stat::BytesCopied => "bytes copied",
+            Stat::SnapshotsCreated => "snapshots created",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Stat::FilesViewed => 0,
+            Stat::VersionsRestored => 1,
+            Stat::BytesCopied => 2,
+            Stat::SnapshotsCreated => 3,
+        }
+    }
+}
+
+pub struct SessionReport;
+
+impl SessionReport {
+    // interactive sessions end by exiting the process from wherever the user finishes,
+    // so the report is written by an exit handler, rather than by each of those paths
+    pub fn register() {
+        // SAFETY: report_at_exit is an extern "C" fn which takes no arguments, and never unwinds, as
+        // it ignores every error, even those writing to stderr, rather than panic across the FFI boundary.
+        // register is called only once, from exec, so the report is only ever written once
+        unsafe {
+            let _ = libc::atexit(Self::report_at_exit);
+        }
+    }
+
+    pub fn add(stat: Stat, count: u64) {
+        if !GLOBAL_CONFIG.opt_report {
+            return;
+        }
+
+        STAT_COUNTS[stat.index()].fetch_add(count, Ordering::Relaxed);
+    }
+
+    // the bytes of regular files restored, which, for a directory, are those of its contents
+    pub fn add_restored(path: &Path) {
+        if !GLOBAL_CONFIG.opt_report {
+            return;
+        }
+
+        Self::add(Stat::VersionsRestored, 1);
+        Self::add(Stat::BytesCopied, Self::bytes_of(path));
+    }
+
+    fn bytes_of(path: &Path) -> u64 {
+        let md = match path.symlink_metadata() {
+            Ok(md) => md,
+            Err(_) => return 0,
+        };
+
+        if !md.is_dir() {
+            return md.len();
+        }
+
+        match std::fs::read_dir(path) {
+            Ok(read_dir) => read_dir
+                .flatten()
+                .map(|entry| Self::bytes_of(&entry.path()))
+                .sum(),
+            Err(_) => 0,
+        }
+    }
+
+    extern "C" fn report_at_exit() {
+        let summary = Self::summary();
+
+        // report to stderr, so as not to disturb any output meant for other programs, and
+        // never via eprintln!, which panics should stderr be closed
+        let mut stderr = std::io::stderr();
+
+        let _ = writeln!(stderr, "httm session report:\n{summary}");
+
+        if let Err(err) = Self::append_to_log(&summary) {
+            let _ = writeln!(
+                stderr,
+                "WARNING: httm could not write the session report to its log: {err}"
+            );
+        }
+    }
+
+    fn summary() -> String {
+        Stat::ALL
+            .iter()
+            .map(|stat| {
+                format!(
+                    "  {:<20}{:>12}\n",
+                    stat.label(),
+                    STAT_COUNTS[stat.index()].load(Ordering::Relaxed)
+                )
+            })
+            .collect()
+    }
+
+    // one line per session, kept locally, for the user's own records
+    fn append_to_log(summary: &str) -> std::io::Result<()> {
+        let log_path = Self::log_path().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "could not determine a state directory",
+            )
+        })?;
+
+        if let Some(parent) = log_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let timestamp = date_string(
+            GLOBAL_CONFIG.requested_utc_offset,
+            &SystemTime::now(),
+            DateFormat::Display,
+        )
+        .map_err(std::io::Error::other)?;
+
+        let fields: Vec<String> = summary
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+            .collect();
+
+        let mut log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path)?;
+
+        writeln!(log_file, "{timestamp}\t{}", fields.join("\t"))
+    }
+
+    fn log_path() -> Option<PathBuf> {
+        Some(BaseDir::State.httm_dir()?.join(SESSION_REPORT_FILE))
+    }
+}
@@ -16,13 +16,14 @@
 // that was distributed with this source code.
 
 use std::path::Path;
-use std::process::Command as ExecProcess;
 use std::time::SystemTime;
 
-use which::which;
-
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::PathData;
-use crate::library::results::{HttmError, HttmResult};
+use crate::library::escalate::PrivilegeBroker;
+use crate::library::hooks::Hook;
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult};
+use crate::library::session_report::{SessionReport, Stat};
 use crate::library::utility::{date_string, DateFormat};
 use crate::print_output_buf;
 use crate::GLOBAL_CONFIG;
@@ -38,24 +39,18 @@ impl TryFrom<&Path> for SnapGuard {
 
     fn try_from(path: &Path) -> HttmResult<Self> {
         let pathdata = PathData::from(path);
-        let dataset_mount =
-            pathdata.proximate_dataset(&GLOBAL_CONFIG.dataset_collection.map_of_datasets)?;
-
-        let dataset_name = match GLOBAL_CONFIG
-            .dataset_collection
-            .map_of_datasets
-            .get(dataset_mount)
-        {
-            Some(md) => &md.source,
-            None => {
-                return Err(HttmError::new("Could not obtain source dataset for mount: ").into())
+
+        // the live table, as the path may have been restored into a dataset mounted since startup
+        let dataset_name = LiveFilesystemInfo::with_current(|fs_info| {
+            let dataset_mount = pathdata.proximate_dataset(&fs_info.map_of_datasets)?;
+
+            match fs_info.map_of_datasets.get(dataset_mount) {
+                Some(md) => HttmResult::Ok(md.source.to_string_lossy().into_owned()),
+                None => Err(HttmError::new("Could not obtain source dataset for mount: ").into()),
             }
-        };
+        })?;
 
-        SnapGuard::new(
-            &dataset_name.to_string_lossy(),
-            PrecautionarySnapType::PreRestore,
-        )
+        SnapGuard::new(&dataset_name, PrecautionarySnapType::PreRestore)
     }
 }
 
@@ -65,13 +60,11 @@ pub struct SnapGuard {
 
 impl SnapGuard {
     pub fn new(dataset_name: &str, snap_type: PrecautionarySnapType) -> HttmResult<Self> {
-        let zfs_command = which("zfs")?;
-
         let timestamp = date_string(
             GLOBAL_CONFIG.requested_utc_offset,
             &SystemTime::now(),
             DateFormat::Timestamp,
-        );
+        )?;
 
         let new_snap_name = match &snap_type {
             PrecautionarySnapType::PreRollForward => {
@@ -100,23 +93,31 @@ impl SnapGuard {
             }
         };
 
-        let process_args = vec!["snapshot".to_owned(), new_snap_name.clone()];
+        Hook::around_snapshot(&[new_snap_name.clone()], || {
+            let process_args = vec!["snapshot".to_owned(), new_snap_name.clone()];
 
-        let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
-        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+            let process_output = PrivilegeBroker::zfs()?.args(&process_args).output()?;
+            let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
 
-        // stderr_string is a string not an error, so here we build an err or output
-        if !stderr_string.is_empty() {
-            let msg = if stderr_string.contains("cannot create snapshots : permission denied") {
-                "httm must have root privileges to snapshot a filesystem".to_owned()
-            } else {
-                "httm was unable to take snapshots. The 'zfs' command issued the following error: "
-                    .to_owned()
-                    + stderr_string
-            };
+            // stderr_string is a string not an error, so here we build an err or output
+            if !stderr_string.is_empty() {
+                let (kind, msg) = if stderr_string
+                    .contains("cannot create snapshots : permission denied")
+                {
+                    (
+                        HttmErrorKind::PermissionDenied,
+                        "httm must have root privileges to snapshot a filesystem".to_owned(),
+                    )
+                } else {
+                    let msg = "httm was unable to take snapshots. The 'zfs' command issued the following error: ".to_owned()
+                        + stderr_string;
+
+                    (HttmErrorKind::Other, msg)
+                };
+
+                return Err(HttmError::with_kind(kind, &msg).into());
+            }
 
-            Err(HttmError::new(&msg).into())
-        } else {
             let output_buf = match &snap_type {
                 PrecautionarySnapType::PreRollForward | PrecautionarySnapType::PreRestore => {
                     format!(
@@ -134,17 +135,20 @@ impl SnapGuard {
 
             print_output_buf(output_buf)?;
 
-            Ok(SnapGuard {
-                inner: new_snap_name,
-            })
-        }
+            SessionReport::add(Stat::SnapshotsCreated, 1);
+
+            Ok(())
+        })?;
+
+        Ok(SnapGuard {
+            inner: new_snap_name,
+        })
     }
 
     pub fn rollback(&self) -> HttmResult<()> {
-        let zfs_command = which("zfs")?;
         let process_args = vec!["rollback", "-r", &self.inner];
 
-        let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
+        let process_output = PrivilegeBroker::zfs()?.args(&process_args).output()?;
         let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
 
         // stderr_string is a string not an error, so here we build an err or output
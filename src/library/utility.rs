@@ -17,80 +17,51 @@
 
 use std::{
     borrow::Cow,
+    ffi::OsString,
     fs::{create_dir_all, read_dir, set_permissions, FileType},
-    io::{self, Read, Write},
+    io::{self, IsTerminal, Read},
     iter::Iterator,
-    os::unix::fs::MetadataExt,
+    os::unix::ffi::OsStrExt,
+    os::unix::fs::{MetadataExt, PermissionsExt},
     path::{Path, PathBuf},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use crossbeam_channel::{Receiver, TryRecvError};
 use lscolors::{Colorable, LsColors, Style};
-use nu_ansi_term::Style as AnsiTermStyle;
+use nu_ansi_term::{Color, Style as AnsiTermStyle};
 use number_prefix::NumberPrefix;
 use once_cell::sync::Lazy;
 use time::{format_description, OffsetDateTime, UtcOffset};
-use which::which;
 
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::{BasicDirEntryInfo, PathData, PHANTOM_DATE};
 use crate::data::selection::SelectionCandidate;
+use crate::library::alt_root::AltRoot;
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::date_locale::DateLocale;
 use crate::library::diff_copy::diff_copy;
-use crate::library::results::{HttmError, HttmResult};
+use crate::library::fs_capabilities::FsCapabilities;
+use crate::library::output_sink::OutputSink;
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult};
 use crate::parse::aliases::FilesystemType;
 use crate::GLOBAL_CONFIG;
-use crate::{config::generate::PrintMode, data::paths::PathMetadata};
+use crate::{
+    config::generate::{PrintMode, SizeFormat},
+    data::paths::PathMetadata,
+};
 use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, ZFS_SNAPSHOT_DIRECTORY};
-use std::process::Command as ExecProcess;
+
+// file capabilities, "security.capability", and SELinux contexts, "security.selinux", among others
+const SECURITY_XATTR_PREFIX: &[u8] = b"security.";
 
 pub fn user_has_effective_root() -> HttmResult<()> {
     if !nix::unistd::geteuid().is_root() {
-        return Err(HttmError::new("Superuser privileges are require to execute.").into());
-    }
-
-    Ok(())
-}
-
-pub fn user_has_zfs_allow_snap_priv(new_file_path: &Path) -> HttmResult<()> {
-    let zfs_command = which("zfs")?;
-
-    let pathdata = PathData::from(new_file_path);
-
-    let dataset_mount =
-        pathdata.proximate_dataset(&GLOBAL_CONFIG.dataset_collection.map_of_datasets)?;
-
-    let dataset_name = match GLOBAL_CONFIG
-        .dataset_collection
-        .map_of_datasets
-        .get(dataset_mount)
-    {
-        Some(md) => &md.source,
-        None => return Err(HttmError::new("Could not obtain source dataset for mount: ").into()),
-    };
-
-    let dataset_name = &dataset_name.to_string_lossy();
-    let process_args = vec!["allow", dataset_name];
-
-    let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
-    let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
-    let stdout_string = std::str::from_utf8(&process_output.stdout)?.trim();
-
-    // stderr_string is a string not an error, so here we build an err or output
-    if !stderr_string.is_empty() {
-        let msg = "httm was unable to determine 'zfs allow' for the path given. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
-
-        return Err(HttmError::new(&msg).into());
-    }
-
-    let user_name = std::env::var("USER")?;
-
-    if !stdout_string.contains(&user_name)
-        || !stdout_string.contains("mount")
-        || !stdout_string.contains("snapshot")
-    {
-        let msg = "User does not have 'zfs allow' privileges for the path given.";
-
-        return Err(HttmError::new(msg).into());
+        return Err(HttmError::with_kind(
+            HttmErrorKind::PermissionDenied,
+            "Superuser privileges are require to execute.",
+        )
+        .into());
     }
 
     Ok(())
@@ -116,6 +87,28 @@ pub fn is_channel_closed(chan: &Receiver<Never>) -> bool {
 
 const TMP_SUFFIX: &str = ".tmp";
 
+// setting the umask to read it would, for a moment, change the mode of any file another thread creates,
+// so read it from the kernel's status of this process, and only set it right back where that is unavailable
+static UMASK: Lazy<u32> = Lazy::new(|| {
+    let opt_status_umask = std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status.lines().find_map(|line| {
+                line.strip_prefix("Umask:")
+                    .and_then(|value| u32::from_str_radix(value.trim(), 8).ok())
+            })
+        });
+
+    match opt_status_umask {
+        Some(umask) => umask,
+        None => {
+            let umask = nix::sys::stat::umask(nix::sys::stat::Mode::empty());
+            nix::sys::stat::umask(umask);
+            umask.bits() as u32
+        }
+    }
+});
+
 pub fn make_tmp_path(path: &Path) -> PathBuf {
     let path_string = path.to_string_lossy().to_string();
     let res = path_string + TMP_SUFFIX;
@@ -124,6 +117,7 @@ pub fn make_tmp_path(path: &Path) -> PathBuf {
 
 pub fn copy_attributes(src: &Path, dst: &Path) -> HttmResult<()> {
     let src_metadata = src.symlink_metadata()?;
+    let can_preserve_xattrs = FsCapabilities::can_preserve_xattrs(src, dst);
 
     // Mode
     {
@@ -132,15 +126,15 @@ pub fn copy_attributes(src: &Path, dst: &Path) -> HttmResult<()> {
 
     // ACLs - requires libacl1-dev to build
     #[cfg(feature = "acls")]
-    {
+    if can_preserve_xattrs {
         if let Ok(acls) = exacl::getfacl(src, None) {
             acls.into_iter()
                 .try_for_each(|acl| exacl::setfacl(&[dst], &[acl], None))?;
         }
     }
 
-    // Ownership
-    {
+    // Ownership - FAT and exFAT have no owners to set
+    if FsCapabilities::of(dst).supports_ownership {
         let dst_uid = src_metadata.uid();
         let dst_gid = src_metadata.gid();
 
@@ -148,12 +142,20 @@ pub fn copy_attributes(src: &Path, dst: &Path) -> HttmResult<()> {
     }
 
     // XAttrs
-    {
+    if can_preserve_xattrs {
         if let Ok(xattrs) = xattr::list(src) {
+            let (security_xattrs, xattrs): (Vec<OsString>, Vec<OsString>) =
+                xattrs.partition(|attr| attr.as_bytes().starts_with(SECURITY_XATTR_PREFIX));
+
             xattrs
-                .flat_map(|attr| xattr::get(src, attr.clone()).map(|opt_value| (attr, opt_value)))
+                .into_iter()
+                .flat_map(|attr| xattr::get(src, &attr).map(|opt_value| (attr, opt_value)))
                 .filter_map(|(attr, opt_value)| opt_value.map(|value| (attr, value)))
-                .try_for_each(|(attr, value)| xattr::set(dst, attr, value.as_slice()))?
+                .try_for_each(|(attr, value)| xattr::set(dst, attr, value.as_slice()))?;
+
+            if !GLOBAL_CONFIG.opt_no_security_attrs {
+                copy_security_xattrs(src, dst, &security_xattrs);
+            }
         }
     }
 
@@ -171,11 +173,33 @@ pub fn copy_attributes(src: &Path, dst: &Path) -> HttmResult<()> {
     Ok(())
 }
 
+// capabilities and security labels are restored verbatim, and only after the chown, which
+// would otherwise clear any capabilities, and after any other xattrs.  setting these requires
+// privileges, or permission from the security policy, which a user may not have, and the rest
+// of the file is still worth restoring, so a failure here is only a warning
+fn copy_security_xattrs(src: &Path, dst: &Path, security_xattrs: &[OsString]) {
+    security_xattrs
+        .iter()
+        .filter_map(|attr| match xattr::get(src, attr) {
+            Ok(Some(value)) => Some((attr, value)),
+            _ => None,
+        })
+        .for_each(|(attr, value)| {
+            if let Err(err) = xattr::set(dst, attr, value.as_slice()) {
+                eprintln!(
+                    "WARNING: Could not restore the security attribute {:?} of {:?}: {}",
+                    attr, dst, err
+                );
+            }
+        });
+}
+
 pub fn preserve_recursive(src: &Path, dst: &Path) -> HttmResult<()> {
     let dst_pathdata: PathData = dst.into();
 
-    let proximate_dataset_mount =
-        dst_pathdata.proximate_dataset(&GLOBAL_CONFIG.dataset_collection.map_of_datasets)?;
+    let proximate_dataset_mount = LiveFilesystemInfo::with_current(|fs_info| {
+        dst_pathdata.proximate_dataset(&fs_info.map_of_datasets)
+    })?;
 
     let relative_path_components_len = dst_pathdata
         .relative_path(proximate_dataset_mount)?
@@ -190,6 +214,13 @@ pub fn preserve_recursive(src: &Path, dst: &Path) -> HttmResult<()> {
 }
 
 pub fn copy_direct(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+    // must be determined before the copy creates them
+    let new_ancestors = if should_preserve {
+        missing_ancestors(src, dst)
+    } else {
+        Vec::new()
+    };
+
     if src.is_dir() {
         create_dir_all(dst)?;
     } else {
@@ -201,17 +232,70 @@ pub fn copy_direct(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<
         }
 
         if src.is_file() {
+            let _opt_permit = DatasetPermit::acquire(dst);
             diff_copy(src, dst)?;
         }
     }
 
     if should_preserve {
-        preserve_recursive(src, dst)?;
+        copy_attributes(src, dst)?;
+
+        // parents created for the copy would otherwise be left with the defaults of create_dir_all,
+        // parents which already existed are the user's, and are left alone
+        new_ancestors
+            .iter()
+            .try_for_each(|(src_ancestor, dst_ancestor)| {
+                copy_attributes(src_ancestor, dst_ancestor)
+            })?;
+    }
+
+    set_restored_mode(src, dst, should_preserve)
+}
+
+// a file restored by a copy which preserves attributes has the snapshot version's mode, and by a plain copy,
+// like cp, the snapshot version's mode less the umask, even over an existing file, unless CHMOD overrides either
+fn set_restored_mode(src: &Path, dst: &Path, should_preserve: bool) -> HttmResult<()> {
+    let dst_metadata = dst.symlink_metadata()?;
+
+    // setting the mode of a link would set the mode of its target, and a dir is created writable,
+    // with the umask, so that its entries may be copied, and is only given its snapshot mode when preserving
+    if dst_metadata.file_type().is_symlink() || dst_metadata.is_dir() {
+        return Ok(());
     }
 
+    let mode = match GLOBAL_CONFIG.opt_chmod {
+        Some(chmod) => chmod,
+        None if should_preserve => return Ok(()),
+        None => src.symlink_metadata()?.mode() & 0o777 & !*UMASK,
+    };
+
+    if dst_metadata.mode() & 0o7777 == mode {
+        return Ok(());
+    }
+
+    set_permissions(dst, std::fs::Permissions::from_mode(mode))?;
+
     Ok(())
 }
 
+// pairs each of the destination's missing parent directories with its counterpart on the snapshot
+fn missing_ancestors(src: &Path, dst: &Path) -> Vec<(PathBuf, PathBuf)> {
+    src.ancestors()
+        .zip(dst.ancestors())
+        .skip(1)
+        // a restore may give the file itself a new name, but never its parents, so a parent
+        // with another name is not a counterpart, as when recovering to another directory
+        .take_while(|(src_ancestor, dst_ancestor)| {
+            dst_ancestor.symlink_metadata().is_err()
+                && src_ancestor.file_name() == dst_ancestor.file_name()
+                && src_ancestor.is_dir()
+        })
+        .map(|(src_ancestor, dst_ancestor)| {
+            (src_ancestor.to_path_buf(), dst_ancestor.to_path_buf())
+        })
+        .collect()
+}
+
 pub fn generate_dst_parent(dst: &Path) -> HttmResult<()> {
     if let Some(dst_parent) = dst.parent() {
         create_dir_all(dst_parent)?;
@@ -237,10 +321,15 @@ pub fn copy_recursive(src: &Path, dst: &Path, should_preserve: bool) -> HttmResu
                 if file_type.is_dir() {
                     copy_recursive(&entry_src, &entry_dst, should_preserve)?;
                 } else {
-                    copy_direct(src, dst, should_preserve)?;
+                    copy_direct(&entry_src, &entry_dst, should_preserve)?;
                 }
             }
         }
+
+        // copying the entries has since modified the directory, so set its timestamps once more
+        if should_preserve {
+            copy_attributes(src, dst)?;
+        }
     } else {
         copy_direct(src, dst, should_preserve)?;
     }
@@ -282,14 +371,41 @@ pub fn read_stdin() -> HttmResult<Vec<PathData>> {
     let mut buffer = Vec::new();
     stdin.read_to_end(&mut buffer)?;
 
-    let buffer_string = std::str::from_utf8(&buffer)?;
+    parse_input_buffer(&buffer)
+}
+
+pub fn read_files_from(files_from: &Path) -> HttmResult<Vec<PathData>> {
+    // "-" explicitly requests stdin, as with many other unix utilities
+    if files_from == Path::new("-") {
+        return read_stdin();
+    }
+
+    let buffer = std::fs::read(files_from).map_err(|err| {
+        HttmError::with_context(
+            "Reading the FILES_FROM file failed for the following reason: ",
+            &err,
+        )
+    })?;
+
+    parse_input_buffer(&buffer)
+}
+
+// every input path, however it is read, must be converted the same way
+pub fn into_input_pathdata(input: &str) -> PathData {
+    PathData::from(AltRoot::into_root(input))
+        .into_live_version()
+        .into_bind_source()
+}
+
+fn parse_input_buffer(buffer: &[u8]) -> HttmResult<Vec<PathData>> {
+    let buffer_string = std::str::from_utf8(buffer)?;
 
     let broken_string = if buffer_string.contains(['\n', '\0']) {
         // always split on newline or null char, if available
         buffer_string
             .split(&['\n', '\0'])
             .filter(|s| !s.is_empty())
-            .map(PathData::from)
+            .map(into_input_pathdata)
             .collect()
     } else if buffer_string.contains('\"') {
         buffer_string
@@ -298,13 +414,13 @@ pub fn read_stdin() -> HttmResult<Vec<PathData>> {
             .map(str::trim)
             // remove any empty strings
             .filter(|s| !s.is_empty())
-            .map(PathData::from)
+            .map(into_input_pathdata)
             .collect()
     } else {
         buffer_string
             .split_ascii_whitespace()
             .filter(|s| !s.is_empty())
-            .map(PathData::from)
+            .map(into_input_pathdata)
             .collect()
     };
 
@@ -341,11 +457,71 @@ fn cmp_path<A: AsRef<Path>, B: AsRef<Path>>(a: A, b: B) -> Option<PathBuf> {
 }
 
 pub fn print_output_buf(output_buf: String) -> HttmResult<()> {
-    // mutex keeps threads from writing over each other
-    let out = std::io::stdout();
-    let mut out_locked = out.lock();
-    out_locked.write_all(output_buf.as_bytes())?;
-    out_locked.flush().map_err(std::convert::Into::into)
+    print_output_bytes(output_buf.as_bytes())
+}
+
+// paths need not be UTF-8, so raw output is written as bytes, exactly as the paths are on disk
+pub fn print_output_bytes(output_buf: &[u8]) -> HttmResult<()> {
+    // to stdout, or to an OUTPUT file, if one was requested
+    OutputSink::write_all(output_buf)
+}
+
+// paths for display are escaped if requested, else converted lossily, as before
+pub fn display_path(path: &Path) -> Cow<str> {
+    if GLOBAL_CONFIG.opt_escape {
+        return Cow::Owned(escape_path(path));
+    }
+
+    path.to_string_lossy()
+}
+
+// like "ls -b", escapes backslashes, control chars, and any byte which is not part of a valid UTF-8 sequence,
+// in C-style, so that every path has exactly one unambiguous representation
+pub fn escape_path(path: &Path) -> String {
+    let mut escaped = String::new();
+    let mut remainder = path.as_os_str().as_bytes();
+
+    loop {
+        let (valid, invalid) = match std::str::from_utf8(remainder) {
+            Ok(valid) => (valid, &[][..]),
+            Err(err) => {
+                let valid_up_to = err.valid_up_to();
+                let invalid_len = err.error_len().unwrap_or(remainder.len() - valid_up_to);
+
+                // from_utf8 has just told us these bytes up to valid_up_to are valid
+                let valid = std::str::from_utf8(&remainder[..valid_up_to]).unwrap_or_default();
+
+                (valid, &remainder[valid_up_to..valid_up_to + invalid_len])
+            }
+        };
+
+        valid.chars().for_each(|c| match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if c.is_control() => {
+                let mut buffer = [0u8; 4];
+                c.encode_utf8(&mut buffer)
+                    .as_bytes()
+                    .iter()
+                    .for_each(|byte| escaped.push_str(&format!("\\{byte:03o}")));
+            }
+            c => escaped.push(c),
+        });
+
+        invalid
+            .iter()
+            .for_each(|byte| escaped.push_str(&format!("\\{byte:03o}")));
+
+        remainder = &remainder[valid.len() + invalid.len()..];
+
+        if remainder.is_empty() {
+            break;
+        }
+    }
+
+    escaped
 }
 
 // is this path/dir_entry something we should count as a directory for our purposes?
@@ -428,10 +604,14 @@ static PHANTOM_STYLE: Lazy<AnsiTermStyle> = Lazy::new(|| {
     )
 });
 
-pub fn paint_string<T>(path: T, display_name: &str) -> Cow<str>
+pub fn paint_string<T>(path: T, display_name: &str, is_colored: bool) -> Cow<str>
 where
     T: PaintString,
 {
+    if !is_colored {
+        return Cow::Borrowed(display_name);
+    }
+
     if path.is_phantom() {
         // paint all other phantoms/deleted files the same color, light pink
         return Cow::Owned(PHANTOM_STYLE.paint(display_name).to_string());
@@ -503,25 +683,119 @@ pub fn date_string(
     utc_offset: UtcOffset,
     system_time: &SystemTime,
     date_format: DateFormat,
-) -> String {
-    let date_time: OffsetDateTime = (*system_time).into();
+) -> HttmResult<String> {
+    // timestamps name snapshots and files, so are never localized, as localized names, padded to
+    // a uniform width, may contain spaces, or any other char not valid within a snapshot name
+    let (opt_custom_format, opt_locale) = match &date_format {
+        DateFormat::Display => (
+            GLOBAL_CONFIG.opt_date_format.as_deref(),
+            GLOBAL_CONFIG.opt_date_locale.as_ref(),
+        ),
+        DateFormat::Timestamp => (GLOBAL_CONFIG.opt_timestamp_format.as_deref(), None),
+    };
 
-    let parsed_format = format_description::parse(date_string_format(&date_format))
-        .expect("timestamp date format is invalid");
+    // a user may format however they like, so we only append a UTC suffix to our own formats
+    if let Some(custom_format) = opt_custom_format {
+        return formatted_date(utc_offset, system_time, custom_format, opt_locale);
+    }
 
-    let raw_string = date_time
-        .to_offset(utc_offset)
-        .format(&parsed_format)
-        .expect("timestamp date format could not be applied to the date supplied");
+    let raw_string = formatted_date(
+        utc_offset,
+        system_time,
+        date_string_format(&date_format),
+        opt_locale,
+    )?;
 
     if utc_offset == UtcOffset::UTC {
         return match &date_format {
-            DateFormat::Timestamp => raw_string + "_UTC",
-            DateFormat::Display => raw_string + " UTC",
+            DateFormat::Timestamp => Ok(raw_string + "_UTC"),
+            DateFormat::Display => Ok(raw_string + " UTC"),
+        };
+    }
+
+    Ok(raw_string)
+}
+
+pub fn formatted_date(
+    utc_offset: UtcOffset,
+    system_time: &SystemTime,
+    format: &str,
+    opt_locale: Option<&DateLocale>,
+) -> HttmResult<String> {
+    let date_time: OffsetDateTime = OffsetDateTime::from(*system_time).to_offset(utc_offset);
+
+    let format: Cow<str> = match opt_locale {
+        Some(locale) => Cow::Owned(locale.localize(format, &date_time)),
+        None => Cow::Borrowed(format),
+    };
+
+    let parsed_format = format_description::parse(&format)
+        .map_err(|err| HttmError::with_context("Date format description is invalid: ", &err))?;
+
+    let res = date_time.format(&parsed_format).map_err(|err| {
+        HttmError::with_context(
+            "Date format could not be applied to the date supplied: ",
+            &err,
+        )
+    })?;
+
+    Ok(res)
+}
+
+// users are more likely to know strftime than the time crate's format descriptions, so we
+// translate the most common strftime conversions, and other format descriptions pass through untouched
+pub fn parse_date_format(format: &str) -> HttmResult<String> {
+    if !format.contains('%') {
+        format_description::parse(format)
+            .map_err(|err| HttmError::with_context("Date format description is invalid: ", &err))?;
+
+        return Ok(format.to_owned());
+    }
+
+    let mut res = String::new();
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            if c == '[' {
+                res.push_str("[[");
+            } else {
+                res.push(c);
+            }
+            continue;
+        }
+
+        let component = match chars.next() {
+            Some('Y') => "[year]",
+            Some('y') => "[year repr:last_two]",
+            Some('m') => "[month]",
+            Some('b' | 'h') => "[month repr:short]",
+            Some('B') => "[month repr:long]",
+            Some('d') => "[day]",
+            Some('e') => "[day padding:space]",
+            Some('j') => "[ordinal]",
+            Some('a') => "[weekday repr:short]",
+            Some('A') => "[weekday repr:long]",
+            Some('H') => "[hour]",
+            Some('I') => "[hour repr:12]",
+            Some('p') => "[period]",
+            Some('M') => "[minute]",
+            Some('S') => "[second]",
+            Some('z') => "[offset_hour sign:mandatory][offset_minute]",
+            Some('F') => "[year]-[month]-[day]",
+            Some('T') => "[hour]:[minute]:[second]",
+            Some('%') => "%",
+            Some(other) => {
+                let msg = format!("Date format conversion \"%{other}\" is not supported.");
+                return Err(HttmError::new(&msg).into());
+            }
+            None => return Err(HttmError::new("Date format may not end with a \"%\".").into()),
         };
+
+        res.push_str(component);
     }
 
-    raw_string
+    Ok(res)
 }
 
 fn date_string_format<'a>(format: &DateFormat) -> &'a str {
@@ -531,15 +805,130 @@ fn date_string_format<'a>(format: &DateFormat) -> &'a str {
     }
 }
 
+// notices are printed to stderr, so whether to paint them depends upon stderr
+pub fn paint_notice(color: Color, label: &str) -> Cow<str> {
+    if GLOBAL_CONFIG
+        .color_mode
+        .is_enabled(std::io::stderr().is_terminal())
+    {
+        return Cow::Owned(color.paint(label).to_string());
+    }
+
+    Cow::Borrowed(label)
+}
+
+// chatter, like per-file notices, which is suppressed in QUIET mode, errors and summaries are always printed
+pub fn print_notice(msg: &str) {
+    if !GLOBAL_CONFIG.opt_quiet {
+        eprintln!("{msg}")
+    }
+}
+
 pub fn display_human_size(size: u64) -> String {
-    let size = size as f64;
+    let prefixed = match GLOBAL_CONFIG.size_format {
+        SizeFormat::Bytes => return size.to_string(),
+        SizeFormat::Binary => NumberPrefix::binary(size as f64),
+        SizeFormat::Decimal => NumberPrefix::decimal(size as f64),
+    };
 
-    match NumberPrefix::binary(size) {
+    match prefixed {
         NumberPrefix::Standalone(bytes) => format!("{bytes} bytes"),
         NumberPrefix::Prefixed(prefix, n) => format!("{n:.1} {prefix}B"),
     }
 }
 
+// how much newer the live version is than the version which would overwrite it, if newer at all
+pub fn live_newer_by(live_path: &Path, version_modify_time: SystemTime) -> Option<Duration> {
+    let live_modify_time = live_path.symlink_metadata().ok()?.modified().ok()?;
+
+    live_modify_time
+        .duration_since(version_modify_time)
+        .ok()
+        .filter(|duration| !duration.is_zero())
+}
+
+pub fn display_human_duration(duration: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = MINUTE * 60;
+    const DAY: u64 = HOUR * 24;
+    const YEAR: u64 = DAY * 365;
+
+    match duration.as_secs() {
+        secs if secs >= YEAR => format!("{}y", secs / YEAR),
+        secs if secs >= DAY => format!("{}d", secs / DAY),
+        secs if secs >= HOUR => format!("{}h", secs / HOUR),
+        secs if secs >= MINUTE => format!("{}m", secs / MINUTE),
+        secs => format!("{secs}s"),
+    }
+}
+
+// a version's age spelled out, like "3 hours ago", a time in the future, say, due to clock skew, is "just now"
+pub fn display_relative_date(system_time: &SystemTime) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = MINUTE * 60;
+    const DAY: u64 = HOUR * 24;
+    const WEEK: u64 = DAY * 7;
+    const MONTH: u64 = DAY * 30;
+    const YEAR: u64 = DAY * 365;
+
+    let secs = SystemTime::now()
+        .duration_since(*system_time)
+        .unwrap_or_default()
+        .as_secs();
+
+    let (count, unit) = match secs {
+        secs if secs >= YEAR => (secs / YEAR, "year"),
+        secs if secs >= MONTH => (secs / MONTH, "month"),
+        secs if secs >= WEEK => (secs / WEEK, "week"),
+        secs if secs >= DAY => (secs / DAY, "day"),
+        secs if secs >= HOUR => (secs / HOUR, "hour"),
+        secs if secs >= MINUTE => (secs / MINUTE, "minute"),
+        _ => return "just now".to_owned(),
+    };
+
+    match count {
+        1 => format!("1 {unit} ago"),
+        count => format!("{count} {unit}s ago"),
+    }
+}
+
+// parses durations like "2s", "500ms", "3h" or "7d", a bare number is understood as seconds
+pub fn parse_human_duration(input: &str) -> HttmResult<Duration> {
+    let input = input.trim();
+
+    let unit_idx = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+
+    let (value, unit) = input.split_at(unit_idx);
+
+    let value: f64 = match value.parse() {
+        Ok(value) if value >= 0f64 => value,
+        _ => {
+            let msg = format!("Could not parse duration from value given: {input:?}");
+            return Err(HttmError::with_kind(HttmErrorKind::ParseError, &msg).into());
+        }
+    };
+
+    let multiplier = match unit.trim() {
+        "ms" => 0.001,
+        "" | "s" | "sec" | "secs" => 1f64,
+        "m" | "min" | "mins" => 60f64,
+        "h" | "hr" | "hrs" => 3_600f64,
+        "d" | "day" | "days" => 86_400f64,
+        "w" | "wk" | "wks" => 604_800f64,
+        "y" | "yr" | "yrs" => 31_536_000f64,
+        _ => {
+            let msg = format!(
+                "Could not parse duration unit from value given: {input:?}.  Units available are: ms, s, m, h, d, w, and y."
+            );
+            return Err(HttmError::with_kind(HttmErrorKind::ParseError, &msg).into());
+        }
+    };
+
+    Ok(Duration::from_secs_f64(value * multiplier))
+}
+
 pub fn is_metadata_same<T>(src: T, dst: T) -> HttmResult<()>
 where
     T: ComparePathMetadata,
@@ -554,7 +943,14 @@ where
         return Err(HttmError::new(&msg).into());
     }
 
-    if src.opt_metadata() != dst.opt_metadata() {
+    let is_equivalent = match (src.opt_metadata(), dst.opt_metadata()) {
+        (Some(src_md), Some(dst_md)) => {
+            FsCapabilities::is_metadata_equivalent(&src_md, src.path(), &dst_md, dst.path(), None)
+        }
+        _ => false,
+    };
+
+    if !is_equivalent {
         let msg = format!(
             "WARNING: Metadata mismatch: {:?} !-> {:?}",
             src.path(),
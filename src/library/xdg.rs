@@ -0,0 +1,83 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::PathBuf;
+
+// the files httm keeps for itself, like caches and installed scripts, live within
+// one of these base directories, see the XDG Base Directory Specification
+#[derive(Debug, Clone, Copy)]
+pub enum BaseDir {
+    // disposable: query history, the deleted index
+    Cache,
+    // installed files, like the zsh hot keys script
+    Data,
+    // kept between sessions, but not worth backing up: the session report log
+    State,
+}
+
+impl BaseDir {
+    // where httm keeps files of this kind, in order of preference: httm's own override
+    // environment variable, which names the directory itself, then the XDG variable,
+    // then the XDG default beneath $HOME, each within an "httm" subdirectory
+    pub fn httm_dir(&self) -> Option<PathBuf> {
+        if let Some(override_dir) = Self::absolute_var(self.override_var()) {
+            return Some(override_dir);
+        }
+
+        let base_dir = match Self::absolute_var(self.xdg_var()) {
+            Some(base_dir) => base_dir,
+            None => Self::home()?.join(self.home_default()),
+        };
+
+        Some(base_dir.join("httm"))
+    }
+
+    pub fn home() -> Option<PathBuf> {
+        Self::absolute_var("HOME")
+    }
+
+    fn override_var(&self) -> &'static str {
+        match self {
+            BaseDir::Cache => "HTTM_CACHE_DIR",
+            BaseDir::Data => "HTTM_DATA_DIR",
+            BaseDir::State => "HTTM_STATE_DIR",
+        }
+    }
+
+    fn xdg_var(&self) -> &'static str {
+        match self {
+            BaseDir::Cache => "XDG_CACHE_HOME",
+            BaseDir::Data => "XDG_DATA_HOME",
+            BaseDir::State => "XDG_STATE_HOME",
+        }
+    }
+
+    fn home_default(&self) -> &'static str {
+        match self {
+            BaseDir::Cache => ".cache",
+            BaseDir::Data => ".local/share",
+            BaseDir::State => ".local/state",
+        }
+    }
+
+    // the spec says relative paths are invalid and should be ignored, as should empty values
+    fn absolute_var(var: &str) -> Option<PathBuf> {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    }
+}
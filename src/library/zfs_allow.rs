@@ -0,0 +1,208 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::Path;
+use std::process::Command as ExecProcess;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::PathData;
+use crate::library::escalate::PrivilegeBroker;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZfsAllowPriv {
+    Snapshot,
+    Destroy,
+    Diff,
+    Mount,
+    Rollback,
+}
+
+impl ZfsAllowPriv {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Snapshot => "snapshot",
+            Self::Destroy => "destroy",
+            Self::Diff => "diff",
+            Self::Mount => "mount",
+            Self::Rollback => "rollback",
+        }
+    }
+}
+
+// ZFS requires the mount permission for nearly every operation which modifies a dataset
+pub const SNAPSHOT_PRIVS: [ZfsAllowPriv; 2] = [ZfsAllowPriv::Snapshot, ZfsAllowPriv::Mount];
+pub const DESTROY_PRIVS: [ZfsAllowPriv; 2] = [ZfsAllowPriv::Destroy, ZfsAllowPriv::Mount];
+pub const ROLL_FORWARD_PRIVS: [ZfsAllowPriv; 4] = [
+    ZfsAllowPriv::Diff,
+    ZfsAllowPriv::Snapshot,
+    ZfsAllowPriv::Rollback,
+    ZfsAllowPriv::Mount,
+];
+
+enum PermissionScope {
+    Local,
+    Descendent,
+    LocalAndDescendent,
+    CreateTime,
+}
+
+pub struct ZfsAllow;
+
+impl ZfsAllow {
+    pub fn check_path(path: &Path, privs: &[ZfsAllowPriv]) -> HttmResult<()> {
+        let pathdata = PathData::from(path);
+
+        let dataset_name = LiveFilesystemInfo::with_current(|fs_info| {
+            let dataset_mount = pathdata.proximate_dataset(&fs_info.map_of_datasets)?;
+
+            match fs_info.map_of_datasets.get(dataset_mount) {
+                Some(md) => HttmResult::Ok(md.source.to_string_lossy().into_owned()),
+                None => Err(HttmError::new("Could not obtain source dataset for mount: ").into()),
+            }
+        })?;
+
+        Self::check_dataset(&dataset_name, privs)
+    }
+
+    // superusers, and those who have asked to escalate, need no delegated permissions
+    pub fn check_dataset(dataset_name: &str, privs: &[ZfsAllowPriv]) -> HttmResult<()> {
+        if nix::unistd::geteuid().is_root() || PrivilegeBroker::can_escalate() {
+            return Ok(());
+        }
+
+        let user = match nix::unistd::User::from_uid(nix::unistd::getuid()) {
+            Ok(Some(user)) => user,
+            _ => return Err(HttmError::new("Could not determine the current user.").into()),
+        };
+
+        let user_groups: Vec<String> = nix::unistd::getgroups()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|gid| nix::unistd::Group::from_gid(gid).ok().flatten())
+            .map(|group| group.name)
+            .collect();
+
+        let granted = Self::granted(dataset_name, &user.name, &user_groups)?;
+
+        let missing: Vec<&str> = privs
+            .iter()
+            .filter(|allow_priv| !granted.iter().any(|granted| granted == allow_priv.as_str()))
+            .map(ZfsAllowPriv::as_str)
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let missing = missing.join(",");
+
+        let msg = format!(
+            "User '{}' does not have the 'zfs allow' permissions required for this operation on {dataset_name}: {missing}.  \
+            Perhaps ask an administrator to execute: zfs allow -u {} {missing} {dataset_name}",
+            user.name, user.name
+        );
+
+        Err(HttmError::new(&msg).into())
+    }
+
+    // parses the output of "zfs allow <dataset>", which lists permissions on the dataset itself,
+    // and those inherited from its ancestors, like so:
+    //
+    // ---- Permissions on rpool/home ----------------------------------------
+    // Local+Descendent permissions:
+    //         user kimono destroy,mount,snapshot
+    //         group staff diff
+    //         everyone rollback
+    fn granted(
+        dataset_name: &str,
+        user_name: &str,
+        user_groups: &[String],
+    ) -> HttmResult<Vec<String>> {
+        let zfs_command = ExternalCommand::require("zfs")?;
+
+        let process_args = vec!["allow", dataset_name];
+
+        let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+        let stdout_string = std::str::from_utf8(&process_output.stdout)?;
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to determine 'zfs allow' for the dataset given. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let mut granted: Vec<String> = Vec::new();
+        let mut is_on_dataset = false;
+        let mut opt_scope: Option<PermissionScope> = None;
+
+        for line in stdout_string.lines().map(str::trim) {
+            if let Some(header) = line.strip_prefix("---- Permissions on ") {
+                is_on_dataset = header.split_ascii_whitespace().next() == Some(dataset_name);
+                opt_scope = None;
+                continue;
+            }
+
+            if let Some(scope) = line.strip_suffix(" permissions:") {
+                opt_scope = match scope {
+                    "Local" => Some(PermissionScope::Local),
+                    "Descendent" => Some(PermissionScope::Descendent),
+                    "Local+Descendent" => Some(PermissionScope::LocalAndDescendent),
+                    "Create time" => Some(PermissionScope::CreateTime),
+                    _ => None,
+                };
+                continue;
+            }
+
+            // local permissions apply only to the dataset itself, descendent permissions only
+            // to its children, and create time permissions only to datasets the user creates
+            let applies = match opt_scope {
+                Some(PermissionScope::Local) => is_on_dataset,
+                Some(PermissionScope::Descendent) => !is_on_dataset,
+                Some(PermissionScope::LocalAndDescendent) => true,
+                Some(PermissionScope::CreateTime) | None => false,
+            };
+
+            if !applies {
+                continue;
+            }
+
+            let mut fields = line.split_ascii_whitespace();
+
+            let is_relevant = match (fields.next(), fields.next()) {
+                (Some("user"), Some(name)) => name == user_name,
+                (Some("group"), Some(name)) => user_groups.iter().any(|group| group == name),
+                (Some("everyone"), Some(_)) => true,
+                _ => false,
+            };
+
+            if !is_relevant {
+                continue;
+            }
+
+            // "everyone" lines have no name, so the permissions are the last field
+            if let Some(perms) = line.split_ascii_whitespace().last() {
+                granted.extend(perms.split(',').map(str::to_owned));
+            }
+        }
+
+        Ok(granted)
+    }
+}
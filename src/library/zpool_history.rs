@@ -0,0 +1,166 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+use std::path::Path;
+use std::process::Command as ExecProcess;
+use std::sync::{Arc, Mutex};
+
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::data::paths::PathData;
+use crate::library::external_commands::ExternalCommand;
+use crate::parse::aliases::FilesystemType;
+use crate::ZFS_SNAPSHOT_DIRECTORY;
+
+// key: pool name, val: the history of that pool, or None if the history could not be read
+static POOL_HISTORIES: Lazy<Mutex<HashMap<String, Option<Arc<ZpoolHistory>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// user initiated commands, as recorded by 'zpool history', used to annotate
+// snapshot versions with the command, and perhaps the job, which created them
+pub struct ZpoolHistory {
+    entries: Vec<HistoryEntry>,
+}
+
+struct HistoryEntry {
+    line: String,
+    is_recursive: bool,
+    snap_names: Vec<String>,
+}
+
+impl ZpoolHistory {
+    // takes a full snapshot name, like "rpool/home@snap", and returns the history entry which created it
+    pub fn annotate(full_snap_name: &str) -> Option<String> {
+        let (dataset_name, snap_name) = full_snap_name.split_once('@')?;
+        let pool_name = dataset_name.split('/').next()?;
+
+        let history = Self::for_pool(pool_name)?;
+
+        history
+            .entries
+            .iter()
+            .rev()
+            .find(|entry| entry.created(dataset_name, snap_name))
+            .map(|entry| entry.line.clone())
+    }
+
+    // takes a snapshot version path, like "/home/.zfs/snapshot/snap/file"
+    pub fn annotate_path(pathdata: &PathData) -> Option<String> {
+        let snap_name = pathdata.snap_name()?;
+
+        // the dataset mount is the parent of the hidden ".zfs" directory
+        let dataset_mount = pathdata
+            .path_buf
+            .ancestors()
+            .find(|ancestor| ancestor.ends_with(ZFS_SNAPSHOT_DIRECTORY))
+            .and_then(Path::parent)
+            .and_then(Path::parent)?;
+
+        let dataset_name = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .get(dataset_mount)
+                .filter(|md| md.fs_type == FilesystemType::Zfs)
+                .map(|md| md.source.to_string_lossy().into_owned())
+        })?;
+
+        let full_snap_name = format!("{}@{}", dataset_name, snap_name.to_string_lossy());
+
+        Self::annotate(&full_snap_name)
+    }
+
+    fn for_pool(pool_name: &str) -> Option<Arc<Self>> {
+        let mut histories = POOL_HISTORIES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        histories
+            .entry(pool_name.to_owned())
+            .or_insert_with(|| Self::new(pool_name).map(Arc::new))
+            .clone()
+    }
+
+    fn new(pool_name: &str) -> Option<Self> {
+        let zpool_command = ExternalCommand::optional(
+            "zpool",
+            "snapshots cannot be annotated with the commands which created them",
+        )?;
+
+        // -l: long format, which includes the user, hostname and zone
+        let process_output = ExecProcess::new(zpool_command)
+            .args(["history", "-l", pool_name])
+            .output()
+            .ok()?;
+
+        if !process_output.status.success() {
+            return None;
+        }
+
+        let entries = std::str::from_utf8(&process_output.stdout)
+            .ok()?
+            .lines()
+            .filter_map(HistoryEntry::new)
+            .collect();
+
+        Some(Self { entries })
+    }
+}
+
+impl HistoryEntry {
+    // lines are of the form: "2023-05-01.12:00:01 zfs snapshot -r rpool/home@snap [user 0 (root) on host:linux]"
+    fn new(line: &str) -> Option<Self> {
+        let mut tokens = line.split_ascii_whitespace().skip(1);
+
+        if tokens.next()? != "zfs" || !matches!(tokens.next()?, "snapshot" | "snap") {
+            return None;
+        }
+
+        let (snap_names, options): (Vec<&str>, Vec<&str>) = tokens
+            // everything after the command proper is the long format's info block
+            .take_while(|token| !token.starts_with('['))
+            .partition(|token| token.contains('@'));
+
+        let is_recursive = options.iter().any(|option| {
+            option.starts_with('-') && !option.starts_with("--") && option.contains('r')
+        });
+
+        Some(Self {
+            line: line.to_owned(),
+            is_recursive,
+            snap_names: snap_names.into_iter().map(str::to_owned).collect(),
+        })
+    }
+
+    fn created(&self, dataset_name: &str, snap_name: &str) -> bool {
+        self.snap_names.iter().any(|full_snap_name| {
+            let (entry_dataset, entry_snap) = match full_snap_name.split_once('@') {
+                Some(res) => res,
+                None => return false,
+            };
+
+            if entry_snap != snap_name {
+                return false;
+            }
+
+            // a recursive snapshot of an ancestor also created a snapshot of each descendant
+            entry_dataset == dataset_name
+                || (self.is_recursive
+                    && Path::new(dataset_name).starts_with(Path::new(entry_dataset)))
+        })
+    }
+}
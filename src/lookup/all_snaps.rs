@@ -0,0 +1,205 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::path::Path;
+use std::process::Command as ExecProcess;
+use std::time::{Duration, SystemTime};
+
+use crate::config::generate::PrintMode;
+use crate::data::filesystem_info::LiveFilesystemInfo;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmResult};
+use crate::library::utility::{
+    date_string, delimiter, display_human_size, print_output_buf, DateFormat,
+};
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
+use crate::GLOBAL_CONFIG;
+
+#[derive(Debug, Clone)]
+pub struct SnapInfo {
+    pub name: String,
+    pub creation: SystemTime,
+    pub used: u64,
+}
+
+// every snapshot upon every local ZFS dataset we know about, no input files required
+pub struct AllSnaps {
+    inner: Vec<SnapInfo>,
+}
+
+impl AllSnaps {
+    pub fn exec(opt_pattern: &Option<String>) -> HttmResult<()> {
+        let all_snaps = Self::new(opt_pattern)?;
+
+        print_output_buf(all_snaps.format()?)
+    }
+
+    pub fn new(opt_pattern: &Option<String>) -> HttmResult<Self> {
+        let mut dataset_names: Vec<String> = LiveFilesystemInfo::with_current(|fs_info| {
+            fs_info
+                .map_of_datasets
+                .values()
+                .filter(|md| md.fs_type == FilesystemType::Zfs && md.mount_type == MountType::Local)
+                .map(|md| md.source.to_string_lossy().into_owned())
+                .collect()
+        });
+
+        if dataset_names.is_empty() {
+            return Err(HttmError::new(
+                "httm could not find any local ZFS datasets.  Note: LIST_SNAPS_ALL is a ZFS only option.",
+            )
+            .into());
+        }
+
+        dataset_names.sort_unstable();
+        dataset_names.dedup();
+
+        let zfs_command = ExternalCommand::require("zfs")?;
+
+        let mut inner: Vec<SnapInfo> = Self::list(&zfs_command, &dataset_names)?
+            .into_iter()
+            .filter(|snap_info| match opt_pattern {
+                Some(pattern) => snap_info
+                    .name
+                    .split_once('@')
+                    .map(|(_dataset, snap_name)| snap_name.contains(pattern.as_str()))
+                    .unwrap_or(false),
+                None => true,
+            })
+            .collect();
+
+        inner.sort_by(|a, b| {
+            a.creation
+                .cmp(&b.creation)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        if inner.is_empty() {
+            return Err(HttmError::new(
+                "httm could not find any snapshots which match the pattern specified.",
+            )
+            .into());
+        }
+
+        Ok(Self { inner })
+    }
+
+    // the snapshots of a single dataset, unsorted, for when only their creation times are required
+    pub fn of_dataset(zfs_command: &Path, dataset_name: &str) -> HttmResult<Vec<SnapInfo>> {
+        Self::list(zfs_command, &[dataset_name.to_owned()])
+    }
+
+    fn list(zfs_command: &Path, dataset_names: &[String]) -> HttmResult<Vec<SnapInfo>> {
+        // -H: tab separated, no header, -p: parsable numbers, -d 1: only the snapshots of the datasets given
+        let mut process_args: Vec<String> = [
+            "list",
+            "-H",
+            "-p",
+            "-t",
+            "snapshot",
+            "-o",
+            "name,creation,used",
+            "-d",
+            "1",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        process_args.extend_from_slice(dataset_names);
+
+        let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        // stderr_string is a string not an error, so here we build an err or output
+        if !stderr_string.is_empty() {
+            let msg =
+                "httm was unable to list snapshots. The 'zfs' command issued the following error: "
+                    .to_owned()
+                    + stderr_string;
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let stdout_string = std::str::from_utf8(&process_output.stdout)?;
+
+        Ok(stdout_string.lines().filter_map(Self::parse_line).collect())
+    }
+
+    pub fn into_inner(self) -> Vec<SnapInfo> {
+        self.inner
+    }
+
+    fn parse_line(line: &str) -> Option<SnapInfo> {
+        let mut fields = line.split('\t');
+
+        let name = fields.next()?.to_owned();
+        let creation = SystemTime::UNIX_EPOCH + Duration::from_secs(fields.next()?.parse().ok()?);
+        let used = fields.next()?.parse().ok()?;
+
+        Some(SnapInfo {
+            name,
+            creation,
+            used,
+        })
+    }
+
+    fn format(&self) -> HttmResult<String> {
+        let delimiter = delimiter();
+
+        // names only, so the output may be used as input elsewhere, for instance as a list of snapshots to prune
+        if matches!(
+            GLOBAL_CONFIG.print_mode,
+            PrintMode::RawNewline | PrintMode::RawZero
+        ) {
+            return Ok(self
+                .inner
+                .iter()
+                .map(|snap_info| format!("{}{delimiter}", snap_info.name))
+                .collect());
+        }
+
+        let size_padding_len = self
+            .inner
+            .iter()
+            .map(|snap_info| display_human_size(snap_info.used).len())
+            .max()
+            .unwrap_or_default();
+
+        let padding = match GLOBAL_CONFIG.print_mode {
+            PrintMode::FormattedNotPretty => "\t",
+            _ => "  ",
+        };
+
+        self.inner
+            .iter()
+            .map(|snap_info| {
+                let date = date_string(
+                    GLOBAL_CONFIG.requested_utc_offset,
+                    &snap_info.creation,
+                    DateFormat::Display,
+                )?;
+
+                Ok(format!(
+                    "{date}{padding}{:>width$}{padding}{}{delimiter}",
+                    display_human_size(snap_info.used),
+                    snap_info.name,
+                    width = size_padding_len
+                ))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,164 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+use std::time::Duration;
+
+use hashbrown::HashSet;
+use rayon::prelude::*;
+
+use crate::config::generate::ListSnapsOfType;
+use crate::data::filesystem_info::{FilesystemInfo, LiveFilesystemInfo};
+use crate::data::paths::{PathData, PathMetadata};
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::fs_capabilities::FsCapabilities;
+use crate::library::results::HttmResult;
+use crate::library::utility::{delimiter, print_output_buf};
+use crate::lookup::versions::{ProximateDatasetAndOptAlts, RelativePathAndSnapMounts, VersionsMap};
+use crate::GLOBAL_CONFIG;
+
+// the fast path for "is there any history?", which never builds a PathData for a snap version,
+// never reads file contents (unless requested via UNIQUENESS), and never formats a version for display
+pub struct CountVersions;
+
+impl CountVersions {
+    pub fn exec() -> HttmResult<()> {
+        let delimiter = delimiter();
+
+        let output_buf: String = GLOBAL_CONFIG
+            .paths
+            .par_iter()
+            .map(|pathdata| format!("{}{delimiter}", Self::count(pathdata)))
+            .collect();
+
+        print_output_buf(output_buf)
+    }
+
+    fn count(pathdata: &PathData) -> usize {
+        LiveFilesystemInfo::with_current(|fs_info| Self::count_in(fs_info, pathdata))
+    }
+
+    fn count_in(fs_info: &FilesystemInfo, pathdata: &PathData) -> usize {
+        let prox_opt_alts = match ProximateDatasetAndOptAlts::with_fs_info(fs_info, pathdata) {
+            Ok(prox_opt_alts) => prox_opt_alts,
+            // no dataset, no snapshots, just the live version, if it exists
+            Err(_) => return pathdata.metadata.iter().count(),
+        };
+
+        // contents uniqueness requires we read every version, so there is no shortcut
+        if let ListSnapsOfType::UniqueContents = GLOBAL_CONFIG.uniqueness {
+            let snaps: Vec<PathData> = prox_opt_alts
+                .into_search_bundles()
+                .flat_map(|relative_path_snap_mounts| {
+                    relative_path_snap_mounts.versions_processed(&ListSnapsOfType::UniqueContents)
+                })
+                .collect();
+
+            let live_count = usize::from(
+                pathdata.metadata.is_some()
+                    && !VersionsMap::is_live_version_redundant(pathdata, &snaps),
+            );
+
+            return snaps.len() + live_count;
+        }
+
+        let search_bundles: Vec<RelativePathAndSnapMounts> =
+            prox_opt_alts.into_search_bundles().collect();
+
+        // as elsewhere, the larger of a user specified tolerance and the coarsest granularity of the datasets searched
+        let opt_tolerance = search_bundles
+            .iter()
+            .filter_map(RelativePathAndSnapMounts::mtime_tolerance)
+            .max();
+
+        let mut snap_versions: Vec<PathMetadata> = search_bundles
+            .iter()
+            .flat_map(|relative_path_snap_mounts| {
+                relative_path_snap_mounts
+                    .snap_mounts
+                    .par_iter()
+                    .filter_map(|snap_mount| {
+                        let joined_path = snap_mount.join(relative_path_snap_mounts.relative_path);
+
+                        let _opt_permit = DatasetPermit::acquire(&joined_path);
+
+                        let md = joined_path.symlink_metadata().ok()?;
+
+                        md.modified().ok().map(|modify_time| PathMetadata {
+                            size: md.len(),
+                            modify_time,
+                        })
+                    })
+                    .collect::<Vec<PathMetadata>>()
+            })
+            .collect();
+
+        let num_snaps = match (&GLOBAL_CONFIG.uniqueness, opt_tolerance) {
+            (ListSnapsOfType::All, _) => snap_versions.len(),
+            (_, Some(tolerance)) => Self::count_within_tolerance(&mut snap_versions, tolerance),
+            (_, None) => snap_versions
+                .iter()
+                .collect::<HashSet<&PathMetadata>>()
+                .len(),
+        };
+
+        // same as elsewhere, the live version is redundant if it is the same as the last snap version
+        let opt_last_snap = snap_versions.iter().max_by_key(|md| md.modify_time);
+
+        let live_count = match (&pathdata.metadata, opt_last_snap) {
+            (Some(live_md), Some(last_snap_md)) => {
+                let opt_live_tolerance = FsCapabilities::effective_tolerance(
+                    opt_tolerance,
+                    &[pathdata.path_buf.as_path()],
+                );
+
+                let is_redundant = match opt_live_tolerance {
+                    Some(tolerance) => {
+                        FsCapabilities::is_within_tolerance(live_md, last_snap_md, tolerance)
+                    }
+                    None => live_md == last_snap_md,
+                };
+
+                usize::from(!is_redundant)
+            }
+            (Some(_live_md), None) => 1,
+            (None, _) => 0,
+        };
+
+        num_snaps + live_count
+    }
+
+    // just as versions are deduplicated for display, each version is compared to the last version retained
+    fn count_within_tolerance(snap_versions: &mut [PathMetadata], tolerance: Duration) -> usize {
+        snap_versions.sort_by_key(|md| (md.modify_time, md.size));
+
+        let mut opt_last_retained: Option<&PathMetadata> = None;
+
+        snap_versions
+            .iter()
+            .filter(|md| {
+                if let Some(last_retained) = opt_last_retained {
+                    if FsCapabilities::is_within_tolerance(last_retained, md, tolerance) {
+                        return false;
+                    }
+                }
+
+                opt_last_retained = Some(*md);
+                true
+            })
+            .count()
+    }
+}
@@ -19,14 +19,21 @@ use std::{
     ffi::OsString,
     fs::read_dir,
     ops::Deref,
+    os::unix::fs::{DirEntryExt, MetadataExt},
     path::{Path, PathBuf},
 };
 
 use hashbrown::{HashMap, HashSet};
+use rayon::prelude::*;
 
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::{BasicDirEntryInfo, PathData};
+use crate::library::case_sensitivity::CaseSensitivity;
+use crate::library::deleted_index::DeletedIndex;
 use crate::library::results::HttmResult;
+use crate::library::retry::Retry;
 use crate::lookup::versions::{ProximateDatasetAndOptAlts, RelativePathAndSnapMounts};
+use crate::GLOBAL_CONFIG;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct DeletedFiles {
@@ -38,6 +45,15 @@ pub struct DeletedFiles {
 // this, believe it or not, will be faster
 impl DeletedFiles {
     pub fn new(requested_dir: &Path) -> HttmResult<Self> {
+        // an index, when fresh, saves us reading every snapshot version of this dir
+        if let Some(inner) = DeletedIndex::get(requested_dir) {
+            return Ok(Self { inner });
+        }
+
+        Self::scan(requested_dir)
+    }
+
+    pub fn scan(requested_dir: &Path) -> HttmResult<Self> {
         // we always need a requesting dir because we are comparing the files in the
         // requesting dir to those of their relative dirs on snapshots
         let requested_dir_pathdata = PathData::from(requested_dir);
@@ -48,14 +64,22 @@ impl DeletedFiles {
         // as these will be the filenames that populate our interactive views, so deduplicate
         // by filename and latest file version here
         let basic_info_map: HashMap<OsString, BasicDirEntryInfo> =
-            ProximateDatasetAndOptAlts::new(&requested_dir_pathdata)?
-                .into_search_bundles()
-                .flat_map(|search_bundle| {
-                    Self::unique_deleted_for_dir(&requested_dir_pathdata.path_buf, &search_bundle)
-                })
-                .flatten()
-                .map(|basic_info| (basic_info.filename().to_os_string(), basic_info))
-                .collect();
+            LiveFilesystemInfo::with_current(|fs_info| {
+                let basic_info_map =
+                    ProximateDatasetAndOptAlts::with_fs_info(fs_info, &requested_dir_pathdata)?
+                        .into_search_bundles()
+                        .flat_map(|search_bundle| {
+                            Self::unique_deleted_for_dir(
+                                &requested_dir_pathdata.path_buf,
+                                &search_bundle,
+                            )
+                        })
+                        .flatten()
+                        .map(|basic_info| (basic_info.filename().to_os_string(), basic_info))
+                        .collect();
+
+                HttmResult::Ok(basic_info_map)
+            })?;
 
         Ok(Self {
             inner: basic_info_map.into_values().collect(),
@@ -74,41 +98,140 @@ impl DeletedFiles {
         // what is a deleted file
         //
         // create a collection of local file names
+        // on a case-insensitive dataset, a file whose name differs only in case is not deleted
+        let is_case_insensitive =
+            CaseSensitivity::is_insensitive(search_bundle.dataset_of_interest);
+
         let local_filenames_set: HashSet<OsString> = read_dir(requested_dir)?
             .flatten()
-            .map(|dir_entry| dir_entry.file_name())
+            .map(|dir_entry| Self::fold(dir_entry.file_name(), is_case_insensitive))
             .collect();
 
-        let unique_snap_filenames: HashMap<OsString, BasicDirEntryInfo> =
-            Self::unique_snap_filenames(search_bundle.snap_mounts, search_bundle.relative_path);
-
-        // compare local filenames to all unique snap filenames - none values are unique, here
-        let all_deleted_versions = unique_snap_filenames
-            .into_iter()
-            .filter_map(move |(file_name, basic_info)| {
-                if local_filenames_set.contains(&file_name) {
-                    None
-                } else {
-                    Some(basic_info)
-                }
-            })
-            .collect();
+        // inodes are only comparable within the same dataset, so never for alt replicated datasets or aliases
+        let opt_live_inodes = if GLOBAL_CONFIG.opt_follow_renames
+            && requested_dir.starts_with(search_bundle.dataset_of_interest)
+        {
+            Self::live_inodes(requested_dir, search_bundle.relative_path)
+        } else {
+            None
+        };
+
+        // local filenames are excluded as each snap dir is read - none values are unique, here
+        let all_deleted_versions = Self::unique_snap_filenames(
+            search_bundle.dataset_of_interest,
+            search_bundle.snap_mounts,
+            search_bundle.relative_path,
+            opt_live_inodes.as_deref(),
+            &local_filenames_set,
+            is_case_insensitive,
+        )
+        .into_values()
+        .collect();
 
         Ok(all_deleted_versions)
     }
 
+    fn fold(file_name: OsString, is_case_insensitive: bool) -> OsString {
+        if is_case_insensitive {
+            CaseSensitivity::fold(&file_name)
+        } else {
+            file_name
+        }
+    }
+
     fn unique_snap_filenames(
+        dataset_of_interest: &Path,
         mounts: &[PathBuf],
         relative_path: &Path,
+        opt_live_inodes: Option<&[u64]>,
+        local_filenames_set: &HashSet<OsString>,
+        is_case_insensitive: bool,
     ) -> HashMap<OsString, BasicDirEntryInfo> {
+        // each snap dir is read in parallel, and any name which is live, or which has already been
+        // found upon another snap, is dropped as soon as it is read, so, for a dir with hundreds
+        // of snapshots, which mostly contain the same files, the maps to be merged remain small
         mounts
-            .iter()
-            .map(|path| path.join(relative_path))
-            .flat_map(read_dir)
-            .flatten()
-            .flatten()
-            .map(|dir_entry| (dir_entry.file_name(), BasicDirEntryInfo::from(&dir_entry)))
-            .collect::<HashMap<OsString, BasicDirEntryInfo>>()
+            .par_iter()
+            .flat_map_iter(|mount| {
+                let by_name = match mount.join(relative_path) {
+                    joined if is_case_insensitive && !joined.exists() => {
+                        CaseSensitivity::resolve(mount, relative_path).unwrap_or(joined)
+                    }
+                    joined => joined,
+                };
+
+                let opt_renamed = opt_live_inodes
+                    .and_then(|live_inodes| Self::renamed_dir(mount, relative_path, live_inodes))
+                    .filter(|renamed| renamed != &by_name);
+
+                std::iter::once(by_name).chain(opt_renamed)
+            })
+            .filter_map(|dir| Retry::io(dataset_of_interest, || read_dir(&dir)).ok())
+            .fold(HashMap::new, |mut unique, snap_dir| {
+                snap_dir.flatten().for_each(|dir_entry| {
+                    let file_name = dir_entry.file_name();
+
+                    let is_live = if is_case_insensitive {
+                        local_filenames_set.contains(&CaseSensitivity::fold(&file_name))
+                    } else {
+                        local_filenames_set.contains(&file_name)
+                    };
+
+                    if is_live || unique.contains_key(&file_name) {
+                        return;
+                    }
+
+                    unique.insert(file_name, BasicDirEntryInfo::from(&dir_entry));
+                });
+
+                unique
+            })
+            .reduce(HashMap::new, |mut unique, other| {
+                other.into_iter().for_each(|(file_name, basic_info)| {
+                    unique.entry(file_name).or_insert(basic_info);
+                });
+
+                unique
+            })
+    }
+
+    // the inode of each dir along the relative path, from the dataset mount down to the requested dir
+    fn live_inodes(requested_dir: &Path, relative_path: &Path) -> Option<Vec<u64>> {
+        let num_components = relative_path.components().count();
+
+        let mut live_inodes: Vec<u64> = requested_dir
+            .ancestors()
+            .take(num_components)
+            .map(|ancestor| ancestor.symlink_metadata().map(|md| md.ino()))
+            .collect::<Result<Vec<u64>, std::io::Error>>()
+            .ok()?;
+
+        live_inodes.reverse();
+
+        Some(live_inodes)
+    }
+
+    // a renamed dir keeps its inode, so, at each level, we look for the dir of the same name, and,
+    // failing that, for whichever dir in the same snapshot parent has the live dir's inode
+    fn renamed_dir(mount: &Path, relative_path: &Path, live_inodes: &[u64]) -> Option<PathBuf> {
+        relative_path.components().zip(live_inodes).try_fold(
+            mount.to_path_buf(),
+            |snap_parent, (component, live_inode)| {
+                let by_name = snap_parent.join(component);
+
+                if matches!(by_name.symlink_metadata(), Ok(md) if md.is_dir() && md.ino() == *live_inode)
+                {
+                    return Some(by_name);
+                }
+
+                read_dir(&snap_parent)
+                    .ok()?
+                    .flatten()
+                    .filter(|dir_entry| dir_entry.ino() == *live_inode)
+                    .find(|dir_entry| matches!(dir_entry.file_type(), Ok(file_type) if file_type.is_dir()))
+                    .map(|dir_entry| dir_entry.path())
+            },
+        )
     }
 }
 
@@ -134,18 +257,20 @@ impl LastInTimeSet {
     // this fn is also missing parallel iter fns, to make the searches more responsive
     // by leaving parallel search for the interactive views
     pub fn new(path_set: Vec<PathData>) -> HttmResult<Self> {
-        let res = path_set
-            .iter()
-            .flat_map(ProximateDatasetAndOptAlts::new)
-            .map(|prox_opt_alts| prox_opt_alts.into_search_bundles())
-            .filter_map(|relative_paths_snap_mounts| {
-                relative_paths_snap_mounts
-                    .into_iter()
-                    .filter_map(|search_bundle| search_bundle.last_version())
-                    .max_by_key(|pathdata| pathdata.md_infallible().modify_time)
-                    .map(|pathdata| pathdata.path_buf)
-            })
-            .collect();
+        let res: Vec<PathBuf> = LiveFilesystemInfo::with_current(|fs_info| {
+            path_set
+                .iter()
+                .flat_map(|pathdata| ProximateDatasetAndOptAlts::with_fs_info(fs_info, pathdata))
+                .map(|prox_opt_alts| prox_opt_alts.into_search_bundles())
+                .filter_map(|relative_paths_snap_mounts| {
+                    relative_paths_snap_mounts
+                        .into_iter()
+                        .filter_map(|search_bundle| search_bundle.last_version())
+                        .max_by_key(|pathdata| pathdata.md_infallible().modify_time)
+                        .map(|pathdata| pathdata.path_buf)
+                })
+                .collect()
+        });
 
         Ok(Self { inner: res })
     }
@@ -21,6 +21,7 @@ use std::ops::Deref;
 use rayon::prelude::*;
 
 use crate::config::generate::MountDisplay;
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::PathData;
 use crate::lookup::versions::ProximateDatasetAndOptAlts;
 use crate::GLOBAL_CONFIG;
@@ -45,29 +46,34 @@ impl<'a> MountsForFiles<'a> {
     }
 
     pub fn new(mount_display: &'a MountDisplay) -> Self {
+        Self::from_paths(&GLOBAL_CONFIG.paths, mount_display)
+    }
+
+    pub fn from_paths(paths: &'a [PathData], mount_display: &'a MountDisplay) -> Self {
         // we only check for phantom files in "mount for file" mode because
         // people should be able to search for deleted files in other modes
-        let map: BTreeMap<&PathData, Vec<PathData>> = GLOBAL_CONFIG
-            .paths
-            .par_iter()
-            .filter(|pathdata| {
-                if pathdata.metadata.is_none() {
-                    eprintln!("Error: Input file may not exist: {:?}", pathdata.path_buf);
-                    return false;
-                }
+        let map: BTreeMap<&PathData, Vec<PathData>> = LiveFilesystemInfo::with_current(|fs_info| {
+            paths
+                .par_iter()
+                .filter(|pathdata| {
+                    if pathdata.metadata.is_none() {
+                        eprintln!("Error: Input file may not exist: {:?}", pathdata.path_buf);
+                        return false;
+                    }
 
-                true
-            })
-            .flat_map(ProximateDatasetAndOptAlts::new)
-            .map(|prox_opt_alts| {
-                let vec = prox_opt_alts
-                    .datasets_of_interest
-                    .iter()
-                    .map(PathData::from)
-                    .collect();
-                (prox_opt_alts.pathdata, vec)
-            })
-            .collect();
+                    true
+                })
+                .flat_map(|pathdata| ProximateDatasetAndOptAlts::new_or_skip(fs_info, pathdata))
+                .map(|prox_opt_alts| {
+                    let vec = prox_opt_alts
+                        .datasets_of_interest
+                        .iter()
+                        .map(PathData::from)
+                        .collect();
+                    (prox_opt_alts.pathdata, vec)
+                })
+                .collect()
+        });
 
         Self {
             inner: map,
@@ -16,16 +16,20 @@
 // that was distributed with this source code.
 
 use std::path::Path;
+use std::time::SystemTime;
 use std::{collections::BTreeMap, ops::Deref};
 
+use hashbrown::HashMap;
 use rayon::prelude::*;
 
 use crate::config::generate::ListSnapsFilters;
+use crate::data::filesystem_info::LiveFilesystemInfo;
 use crate::data::paths::PathData;
 use crate::library::results::{HttmError, HttmResult};
+use crate::lookup::all_snaps::AllSnaps;
 use crate::lookup::versions::VersionsMap;
 use crate::parse::aliases::FilesystemType;
-use crate::{GLOBAL_CONFIG, ZFS_SNAPSHOT_DIRECTORY};
+use crate::ZFS_SNAPSHOT_DIRECTORY;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SnapNameMap {
@@ -51,6 +55,28 @@ impl SnapNameMap {
         versions_map: VersionsMap,
         opt_filters: &Option<ListSnapsFilters>,
     ) -> HttmResult<Self> {
+        // key: full snapshot name, val: creation time, only necessary when filtering by age
+        let opt_creation_times: Option<(SystemTime, HashMap<String, SystemTime>)> =
+            match opt_filters
+                .as_ref()
+                .and_then(|filters| filters.opt_older_than)
+            {
+                Some(older_than) => {
+                    let cutoff = SystemTime::now()
+                        .checked_sub(older_than)
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+                    let creation_times = AllSnaps::new(&None)?
+                        .into_inner()
+                        .into_iter()
+                        .map(|snap_info| (snap_info.name, snap_info.creation))
+                        .collect();
+
+                    Some((cutoff, creation_times))
+                }
+                None => None,
+            };
+
         let inner: BTreeMap<PathData, Vec<String>> = versions_map
             .into_inner()
             .into_par_iter()
@@ -79,6 +105,13 @@ impl SnapNameMap {
                         }
                         true
                     })
+                    .filter(|snap| match &opt_creation_times {
+                        // a snapshot without a known creation time is never old enough to destroy
+                        Some((cutoff, creation_times)) => {
+                            matches!(creation_times.get(snap), Some(creation) if creation < cutoff)
+                        }
+                        None => true,
+                    })
                     .collect();
 
                 (pathdata, snap_names)
@@ -121,20 +154,17 @@ impl SnapNameMap {
             return None;
         };
 
-        let opt_dataset_md = GLOBAL_CONFIG
-            .dataset_collection
-            .map_of_datasets
-            .get(dataset_path);
-
-        match opt_dataset_md {
-            Some(md) if md.fs_type == FilesystemType::Zfs => {
-                Some(format!("{}@{snap}", md.source.to_string_lossy()))
-            }
-            Some(_md) => {
-                eprintln!("WARNING: {pathdata:?} is located on a non-ZFS dataset.  httm can only list snapshot names for ZFS datasets.");
-                None
+        LiveFilesystemInfo::with_current(|fs_info| {
+            match fs_info.map_of_datasets.get(dataset_path) {
+                Some(md) if md.fs_type == FilesystemType::Zfs => {
+                    Some(format!("{}@{snap}", md.source.to_string_lossy()))
+                }
+                Some(_md) => {
+                    eprintln!("WARNING: {pathdata:?} is located on a non-ZFS dataset.  httm can only list snapshot names for ZFS datasets.");
+                    None
+                }
+                _ => None,
             }
-            _ => None,
-        }
+        })
     }
 }
@@ -16,25 +16,86 @@
 // that was distributed with this source code.
 
 use std::{
+    collections::hash_map::DefaultHasher,
     collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    hash::{Hash, Hasher},
     io::ErrorKind,
     ops::Deref,
     ops::DerefMut,
+    os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
 };
 
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 
-use crate::library::results::{HttmError, HttmResult};
+use crate::data::filesystem_info::{FilesystemInfo, LiveFilesystemInfo};
+use crate::library::case_sensitivity::CaseSensitivity;
+use crate::library::dataset_permit::DatasetPermit;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::fs_capabilities::FsCapabilities;
+use crate::library::iter_extensions::HttmIter;
+use crate::library::profile::{Phase, Profile};
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult, SkippedPaths};
+use crate::library::retry::Retry;
+use crate::library::utility::HttmIsDir;
+use crate::lookup::all_snaps::AllSnaps;
+use crate::lookup::deleted::DeletedFiles;
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::MountType;
 use crate::{
     config::generate::ListSnapsOfType,
     data::paths::{CompareVersionsContainer, PathData},
 };
 use crate::{
-    config::generate::{BulkExclusion, Config, LastSnapMode},
-    GLOBAL_CONFIG,
+    config::generate::{BulkExclusion, Config, ExecMode, LastSnapMode},
+    BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, GLOBAL_CONFIG, ZFS_HIDDEN_DIRECTORY,
 };
 
+// path sets at least this large are grouped by dataset before lookup
+const SHARD_THRESHOLD: usize = 4_096;
+
+// when limiting results, snap mounts are searched, newest first, at least this many at a time
+const MAX_RESULTS_MIN_BATCH: usize = 32;
+
+// key: dataset of interest, val: its snap mounts ordered newest first
+static SNAP_MOUNTS_NEWEST_FIRST: Lazy<Mutex<HashMap<PathBuf, Arc<Vec<PathBuf>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// key: version retained, val: identical versions omitted in its favor, only kept for SHOW_DUPLICATES
+static DUPLICATE_VERSIONS: Lazy<Mutex<HashMap<PathBuf, Vec<PathData>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub struct DuplicateVersions;
+
+impl DuplicateVersions {
+    pub fn of(retained: &Path) -> Vec<PathData> {
+        DUPLICATE_VERSIONS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(retained)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn record(retained: &PathData, duplicate: PathData) {
+        if !GLOBAL_CONFIG.opt_show_duplicates {
+            return;
+        }
+
+        DUPLICATE_VERSIONS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(retained.path_buf.clone())
+            .or_default()
+            .push(duplicate);
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VersionsMap {
     inner: BTreeMap<PathData, Vec<PathData>>,
@@ -66,26 +127,57 @@ impl VersionsMap {
     }
 
     pub fn new(config: &Config, path_set: &[PathData]) -> HttmResult<VersionsMap> {
-        let all_snap_versions: BTreeMap<PathData, Vec<PathData>> = path_set
-            .par_iter()
-            .flat_map(ProximateDatasetAndOptAlts::new)
-            .map(|prox_opt_alts| {
-                // don't want to flatten this iter here b/c
-                // we want to keep these values with this key
-                let key = prox_opt_alts.pathdata.clone();
-                let values = prox_opt_alts
-                    .into_search_bundles()
-                    .par_bridge()
-                    .flat_map(|relative_path_snap_mounts| {
-                        relative_path_snap_mounts.versions_processed(&config.uniqueness)
+        Self::with_max_results(config, path_set, config.opt_max_results, None)
+    }
+
+    // interactive modes may ask again for more results than the command line requested, and may
+    // scope versions to a single replica of an alt replicated dataset -- scoped before versions are
+    // deduplicated and limited, so a version found upon several replicas is retained upon each
+    pub fn with_max_results(
+        config: &Config,
+        path_set: &[PathData],
+        opt_max_results: Option<usize>,
+        opt_replica: Option<&Path>,
+    ) -> HttmResult<VersionsMap> {
+        Profile::time(Phase::Lookup, || {
+            Self::lookup(config, path_set, opt_max_results, opt_replica)
+        })
+    }
+
+    fn lookup(
+        config: &Config,
+        path_set: &[PathData],
+        opt_max_results: Option<usize>,
+        opt_replica: Option<&Path>,
+    ) -> HttmResult<VersionsMap> {
+        // in the interactive modes, the mount table may be replaced mid-session, see MountWatch,
+        // so a lookup takes one snapshot of the table, and uses it throughout
+        let opt_live_fs_info = LiveFilesystemInfo::current();
+        let fs_info: &FilesystemInfo = opt_live_fs_info
+            .as_deref()
+            .unwrap_or(&config.dataset_collection);
+
+        let all_snap_versions: BTreeMap<PathData, Vec<PathData>> =
+            if path_set.len() >= SHARD_THRESHOLD {
+                Self::lookup_sharded(config, fs_info, path_set, opt_max_results, opt_replica)
+            } else {
+                path_set
+                    .par_iter()
+                    .flat_map(|pathdata| ProximateDatasetAndOptAlts::new_or_skip(fs_info, pathdata))
+                    .map(|prox_opt_alts| {
+                        Self::versions_of(config, prox_opt_alts, opt_max_results, opt_replica)
                     })
-                    .collect();
-                (key, values)
-            })
-            .collect();
+                    .collect()
+            };
 
         let mut versions_map: VersionsMap = all_snap_versions.into();
 
+        // a file given which no longer exists may have been deleted from a dir since renamed,
+        // so, when requested, a deleted search of its parent may find versions the exact path would not
+        if config.opt_deleted_mode.is_some() && matches!(config.exec_mode, ExecMode::Display) {
+            versions_map.deleted_from_parents(config, fs_info, opt_max_results, opt_replica);
+        }
+
         // check if all files (snap and live) do not exist, if this is true, then user probably messed up
         // and entered a file that never existed (that is, perhaps a wrong file name)?
         if versions_map.values().all(std::vec::Vec::is_empty)
@@ -94,7 +186,8 @@ impl VersionsMap {
                 .all(|pathdata| pathdata.metadata.is_none())
             && !matches!(config.opt_bulk_exclusion, Some(BulkExclusion::NoSnap))
         {
-            return Err(HttmError::new(
+            return Err(HttmError::with_kind(
+                HttmErrorKind::NotFound,
                 "httm could not find either a live copy or a snapshot copy of any specified file, so, umm, 🤷? Please try another file.",
             )
             .into());
@@ -102,44 +195,229 @@ impl VersionsMap {
 
         // process last snap mode after omit_ditto
         if config.opt_omit_ditto {
-            versions_map.omit_ditto()
+            versions_map.omit_ditto(config.opt_compare_against.as_ref())
         }
 
         if let Some(last_snap_mode) = &config.opt_last_snap {
-            versions_map.last_snap(last_snap_mode)
+            versions_map.last_snap(last_snap_mode, config.opt_compare_against.as_ref())
         }
 
         Ok(versions_map)
     }
 
+    fn deleted_from_parents(
+        &mut self,
+        config: &Config,
+        fs_info: &FilesystemInfo,
+        opt_max_results: Option<usize>,
+        opt_replica: Option<&Path>,
+    ) {
+        let phantoms: Vec<PathData> = self
+            .iter()
+            .filter(|(live_version, snaps)| live_version.metadata.is_none() && snaps.is_empty())
+            .map(|(live_version, _snaps)| live_version.clone())
+            .collect();
+
+        phantoms.into_iter().for_each(|phantom| {
+            let snaps =
+                Self::versions_of_deleted(config, fs_info, &phantom, opt_max_results, opt_replica);
+
+            if !snaps.is_empty() {
+                self.insert(phantom, snaps);
+            }
+        });
+    }
+
+    // the deleted file of the same name, found in the parent, was last live at some path,
+    // perhaps beneath a dir's previous name, and its versions are those of that path
+    fn versions_of_deleted(
+        config: &Config,
+        fs_info: &FilesystemInfo,
+        phantom: &PathData,
+        opt_max_results: Option<usize>,
+        opt_replica: Option<&Path>,
+    ) -> Vec<PathData> {
+        let (parent, file_name) = match (phantom.path_buf.parent(), phantom.path_buf.file_name()) {
+            (Some(parent), Some(file_name)) => (parent, file_name),
+            _ => return Vec::new(),
+        };
+
+        let deleted = match DeletedFiles::new(parent) {
+            Ok(deleted) => deleted.into_inner(),
+            Err(_) => return Vec::new(),
+        };
+
+        deleted
+            .into_iter()
+            .find(|basic_info| basic_info.filename() == file_name)
+            .map(|basic_info| PathData::from(basic_info.path.as_path()).into_live_version())
+            // the exact path has already been searched
+            .filter(|former_live| former_live.path_buf != phantom.path_buf)
+            .and_then(|former_live| {
+                ProximateDatasetAndOptAlts::with_fs_info(fs_info, &former_live)
+                    .ok()
+                    .map(|prox_opt_alts| {
+                        Self::versions_of(config, prox_opt_alts, opt_max_results, opt_replica)
+                    })
+            })
+            .map(|(_former_live, snaps)| snaps)
+            .unwrap_or_default()
+    }
+
+    // many paths piped in at once likely share only a few datasets, so paths are grouped by dataset,
+    // each dataset's snapshot mounts are read once for its shard, and whole shards are distributed across the pool
+    //
+    // and those paths are likely, for the most part, siblings, so, before they are grouped, a dataset is
+    // resolved once per parent dir, rather than once per path
+    fn lookup_sharded(
+        config: &Config,
+        fs_info: &FilesystemInfo,
+        path_set: &[PathData],
+        opt_max_results: Option<usize>,
+        opt_replica: Option<&Path>,
+    ) -> BTreeMap<PathData, Vec<PathData>> {
+        let siblings_by_parent: HashMap<Option<&Path>, Vec<&PathData>> = path_set
+            .iter()
+            .into_group_map_by(|pathdata| pathdata.path_buf.parent());
+
+        let shards: HashMap<&Path, Vec<ProximateDatasetAndOptAlts>> = siblings_by_parent
+            .into_par_iter()
+            .flat_map_iter(|(_parent, siblings)| {
+                ProximateDatasetAndOptAlts::of_siblings(fs_info, siblings)
+            })
+            .collect::<Vec<ProximateDatasetAndOptAlts>>()
+            .into_iter()
+            .into_group_map_by(|prox_opt_alts| prox_opt_alts.proximate_dataset_mount);
+
+        shards
+            .into_par_iter()
+            .flat_map(|(_proximate_dataset_mount, shard)| {
+                if let Some(first) = shard.first() {
+                    Self::warm_snap_mounts(config, first, opt_max_results);
+                }
+
+                shard.into_par_iter().map(move |prox_opt_alts| {
+                    Self::versions_of(config, prox_opt_alts, opt_max_results, opt_replica)
+                })
+            })
+            .collect()
+    }
+
+    // otherwise, every path of the shard may race to order the same snap mounts, newest first
+    fn warm_snap_mounts(
+        config: &Config,
+        prox_opt_alts: &ProximateDatasetAndOptAlts,
+        opt_max_results: Option<usize>,
+    ) {
+        if config.opt_of_snap.is_some()
+            || (opt_max_results.is_none() && matches!(config.uniqueness, ListSnapsOfType::All))
+        {
+            return;
+        }
+
+        prox_opt_alts
+            .clone()
+            .into_search_bundles()
+            .for_each(|relative_path_snap_mounts| {
+                relative_path_snap_mounts.snap_mounts_newest_first();
+            });
+    }
+
+    fn versions_of(
+        config: &Config,
+        mut prox_opt_alts: ProximateDatasetAndOptAlts,
+        opt_max_results: Option<usize>,
+        opt_replica: Option<&Path>,
+    ) -> (PathData, Vec<PathData>) {
+        prox_opt_alts.scope_to_replica(opt_replica);
+
+        // don't want to flatten this iter here b/c
+        // we want to keep these values with this key
+        let key = prox_opt_alts.pathdata.clone();
+        let mut values: Vec<PathData> = prox_opt_alts
+            .into_search_bundles()
+            .par_bridge()
+            .flat_map(
+                |relative_path_snap_mounts| match (&config.opt_of_snap, opt_max_results) {
+                    (Some(snap_name), _) => relative_path_snap_mounts.versions_of_snap(snap_name),
+                    (None, Some(max_results)) => {
+                        relative_path_snap_mounts.versions_newest(&config.uniqueness, max_results)
+                    }
+                    (None, None) => {
+                        relative_path_snap_mounts.versions_processed(&config.uniqueness)
+                    }
+                },
+            )
+            .collect();
+
+        // alt replicated datasets may each have contributed their newest versions
+        if let Some(max_results) = opt_max_results {
+            if values.len() > max_results {
+                values.sort_by_key(|pathdata| pathdata.md_infallible().modify_time);
+                values.drain(..values.len() - max_results);
+            }
+        }
+
+        (key, values)
+    }
+
     pub fn is_live_version_redundant(live_pathdata: &PathData, snaps: &[PathData]) -> bool {
         if let Some(last_snap) = snaps.last() {
-            return last_snap.md_infallible() == live_pathdata.md_infallible();
+            return FsCapabilities::is_metadata_equivalent(
+                &last_snap.md_infallible(),
+                &last_snap.path_buf,
+                &live_pathdata.md_infallible(),
+                &live_pathdata.path_buf,
+                GLOBAL_CONFIG.opt_mtime_tolerance,
+            );
         }
 
         false
     }
 
-    fn omit_ditto(&mut self) {
+    fn omit_ditto(&mut self, opt_compare_against: Option<&PathData>) {
         self.iter_mut().for_each(|(pathdata, snaps)| {
             // process omit_ditto before last snap
-            if Self::is_live_version_redundant(pathdata, snaps) {
-                snaps.pop();
+            match opt_compare_against {
+                // a reference other than the live version may be identical to any snap version, not just the last
+                Some(reference) => snaps.retain(|snap| {
+                    !FsCapabilities::is_metadata_equivalent(
+                        &snap.md_infallible(),
+                        &snap.path_buf,
+                        &reference.md_infallible(),
+                        &reference.path_buf,
+                        GLOBAL_CONFIG.opt_mtime_tolerance,
+                    )
+                }),
+                None => {
+                    if Self::is_live_version_redundant(pathdata, snaps) {
+                        snaps.pop();
+                    }
+                }
             }
         });
     }
 
-    fn last_snap(&mut self, last_snap_mode: &LastSnapMode) {
+    fn last_snap(&mut self, last_snap_mode: &LastSnapMode, opt_compare_against: Option<&PathData>) {
         self.iter_mut().for_each(|(pathdata, snaps)| {
+            // the version against which ditto is determined, usually the live version
+            let reference = opt_compare_against.unwrap_or(pathdata);
+
+            // a directory's own metadata says little about what has changed beneath it
+            if pathdata.httm_is_dir() {
+                *snaps = Self::last_snap_dir(pathdata, reference, last_snap_mode);
+                return;
+            }
+
             *snaps = match snaps.last() {
                 // if last() is some, then should be able to unwrap pop()
                 Some(last) => match last_snap_mode {
                     LastSnapMode::Any => vec![last.to_owned()],
-                    LastSnapMode::DittoOnly if pathdata.md_infallible() == last.md_infallible() => {
+                    LastSnapMode::DittoOnly if Self::is_ditto(reference, last) => {
                         vec![last.to_owned()]
                     }
                     LastSnapMode::NoDittoExclusive | LastSnapMode::NoDittoInclusive
-                        if pathdata.md_infallible() != last.md_infallible() =>
+                        if !Self::is_ditto(reference, last) =>
                     {
                         vec![last.to_owned()]
                     }
@@ -154,17 +432,156 @@ impl VersionsMap {
             };
         });
     }
+
+    fn is_ditto(reference: &PathData, version: &PathData) -> bool {
+        FsCapabilities::is_metadata_equivalent(
+            &reference.md_infallible(),
+            &reference.path_buf,
+            &version.md_infallible(),
+            &version.path_buf,
+            GLOBAL_CONFIG.opt_mtime_tolerance,
+        )
+    }
+
+    fn last_snap_dir(
+        live_dir: &PathData,
+        reference: &PathData,
+        last_snap_mode: &LastSnapMode,
+    ) -> Vec<PathData> {
+        // unique metadata for a dir may hide changes to its contents, so begin with every snapshot,
+        // ordered as the snapshots were taken, as the snapshots of a dir frequently share a modify time
+        let snap_dirs: Vec<PathData> = LiveFilesystemInfo::with_current(|fs_info| {
+            ProximateDatasetAndOptAlts::with_fs_info(fs_info, live_dir)
+                .map(|prox_opt_alts| {
+                    prox_opt_alts
+                        .into_search_bundles()
+                        .flat_map(|relative_path_snap_mounts| {
+                            relative_path_snap_mounts.snap_dirs_newest_first()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+
+        let opt_last = snap_dirs.first();
+
+        // each tree is walked only as needed, and snap dirs newest first, only until one differs
+        let live_summary = || DirTreeSummary::new(&reference.path_buf);
+
+        // the most recent snap dir in which anything beneath differs from the live dir
+        let last_differs = || {
+            let live_summary = live_summary();
+
+            snap_dirs
+                .iter()
+                .find(|snap_dir| DirTreeSummary::new(&snap_dir.path_buf) != live_summary)
+                .cloned()
+        };
+
+        let opt_last_snap = match last_snap_mode {
+            LastSnapMode::Any => opt_last.cloned(),
+            LastSnapMode::DittoOnly => opt_last
+                .filter(|snap_dir| DirTreeSummary::new(&snap_dir.path_buf) == live_summary())
+                .cloned(),
+            LastSnapMode::NoDittoExclusive => last_differs(),
+            LastSnapMode::NoDittoInclusive => {
+                Some(last_differs().unwrap_or_else(|| live_dir.clone()))
+            }
+            LastSnapMode::Without if opt_last.is_none() => Some(live_dir.clone()),
+            LastSnapMode::Without => None,
+        };
+
+        opt_last_snap.into_iter().collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct DirTreeSummary {
+    fingerprint: u64,
+}
+
+impl DirTreeSummary {
+    // walks the tree beneath a dir, without traversing symlinks, or into any other filesystem, like a nested
+    // dataset, which is only an empty dir within a snapshot, and summarizes every entry's relative path,
+    // and each file's size and modify time, as an order independent fingerprint.  a dir's own size and
+    // modify time only change when its entries change, which is already summarized.
+    fn new(dir: &Path) -> Self {
+        let mut summary = Self { fingerprint: 0u64 };
+
+        let root_dev = match dir.symlink_metadata() {
+            Ok(md) => md.dev(),
+            Err(_) => return summary,
+        };
+
+        let mut queue: Vec<PathBuf> = vec![dir.to_path_buf()];
+
+        while let Some(item) = queue.pop() {
+            let read_dir = match std::fs::read_dir(&item) {
+                Ok(read_dir) => read_dir,
+                Err(_) => continue,
+            };
+
+            read_dir.flatten().for_each(|entry| {
+                let file_name = entry.file_name();
+
+                // don't descend into snapshots of snapshots
+                if file_name == ZFS_HIDDEN_DIRECTORY || file_name == BTRFS_SNAPPER_HIDDEN_DIRECTORY
+                {
+                    return;
+                }
+
+                let md = match entry.metadata() {
+                    Ok(md) => md,
+                    Err(_) => return,
+                };
+
+                let path = entry.path();
+
+                let mut hasher = DefaultHasher::new();
+                path.strip_prefix(dir)
+                    .unwrap_or(path.as_path())
+                    .hash(&mut hasher);
+
+                if md.is_dir() {
+                    if md.dev() == root_dev {
+                        queue.push(path);
+                    }
+                } else {
+                    md.len().hash(&mut hasher);
+                    md.modified()
+                        .unwrap_or(SystemTime::UNIX_EPOCH)
+                        .hash(&mut hasher);
+                }
+
+                summary.fingerprint = summary.fingerprint.wrapping_add(hasher.finish());
+            });
+        }
+
+        summary
+    }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProximateDatasetAndOptAlts<'a> {
     pub pathdata: &'a PathData,
     pub proximate_dataset_mount: &'a Path,
     pub datasets_of_interest: Vec<PathBuf>,
+    fs_info: &'a FilesystemInfo,
 }
 
 impl<'a> ProximateDatasetAndOptAlts<'a> {
-    pub fn new(pathdata: &'a PathData) -> HttmResult<Self> {
+    // a path upon no supported dataset is skipped, but, in STRICT mode, the skip is reported upon exit
+    pub fn new_or_skip(fs_info: &'a FilesystemInfo, pathdata: &'a PathData) -> Option<Self> {
+        match Self::with_fs_info(fs_info, pathdata) {
+            Ok(prox_opt_alts) => Some(prox_opt_alts),
+            Err(err) => {
+                SkippedPaths::record(&pathdata.path_buf, err.as_ref());
+                None
+            }
+        }
+    }
+
+    pub fn with_fs_info(fs_info: &'a FilesystemInfo, pathdata: &'a PathData) -> HttmResult<Self> {
         // here, we take our file path and get back possibly multiple ZFS dataset mountpoints
         // and our most proximate dataset mount point (which is always the same) for
         // a single file
@@ -178,20 +595,16 @@ impl<'a> ProximateDatasetAndOptAlts<'a> {
         // will compare the most proximate dataset to our our canonical path and the difference
         // between ZFS mount point and the canonical path is the path we will use to search the
         // hidden snapshot dirs
-        let proximate_dataset_mount: &Path = match GLOBAL_CONFIG
-            .dataset_collection
+        let proximate_dataset_mount: &Path = match fs_info
             .opt_map_of_aliases
             .as_ref()
             .and_then(|map_of_aliases| pathdata.alias_dataset(map_of_aliases))
         {
             Some(alias_dataset) => alias_dataset,
-            None => {
-                pathdata.proximate_dataset(&GLOBAL_CONFIG.dataset_collection.map_of_datasets)?
-            }
+            None => pathdata.proximate_dataset(&fs_info.map_of_datasets)?,
         };
 
-        let res: Self = match GLOBAL_CONFIG
-            .dataset_collection
+        let res: Self = match fs_info
             .opt_map_of_alts
             .as_ref()
             .and_then(|map_of_alts| map_of_alts.get(proximate_dataset_mount))
@@ -204,6 +617,7 @@ impl<'a> ProximateDatasetAndOptAlts<'a> {
                     pathdata,
                     proximate_dataset_mount,
                     datasets_of_interest,
+                    fs_info,
                 }
             }
 
@@ -211,17 +625,66 @@ impl<'a> ProximateDatasetAndOptAlts<'a> {
                 pathdata,
                 proximate_dataset_mount,
                 datasets_of_interest: vec![proximate_dataset_mount.to_path_buf()],
+                fs_info,
             },
         };
 
         Ok(res)
     }
 
+    // siblings share their parent dir's dataset, so it need only be resolved once for all of them,
+    // except for a sibling which is itself a dataset's mount, or an alias, which is resolved on its own
+    pub fn of_siblings(fs_info: &'a FilesystemInfo, siblings: Vec<&'a PathData>) -> Vec<Self> {
+        let mut opt_shared: Option<Self> = None;
+
+        siblings
+            .into_iter()
+            .filter_map(|pathdata| {
+                if Self::is_mount_or_alias(fs_info, pathdata) {
+                    return Self::new_or_skip(fs_info, pathdata);
+                }
+
+                match &opt_shared {
+                    Some(shared) => Some(Self {
+                        pathdata,
+                        proximate_dataset_mount: shared.proximate_dataset_mount,
+                        datasets_of_interest: shared.datasets_of_interest.clone(),
+                        fs_info,
+                    }),
+                    None => {
+                        let prox_opt_alts = Self::new_or_skip(fs_info, pathdata)?;
+                        opt_shared = Some(prox_opt_alts.clone());
+                        Some(prox_opt_alts)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn is_mount_or_alias(fs_info: &FilesystemInfo, pathdata: &PathData) -> bool {
+        fs_info.map_of_datasets.contains_key(&pathdata.path_buf)
+            || fs_info
+                .opt_map_of_aliases
+                .as_ref()
+                .map_or(false, |map_of_aliases| {
+                    map_of_aliases.contains_key(&pathdata.path_buf)
+                })
+    }
+
+    // of the datasets of interest, keeps only the replica requested, if any
+    pub fn scope_to_replica(&mut self, opt_replica: Option<&Path>) {
+        if let Some(replica) = opt_replica {
+            self.datasets_of_interest
+                .retain(|dataset_of_interest| dataset_of_interest == replica);
+        }
+    }
+
     pub fn into_search_bundles(self) -> impl Iterator<Item = RelativePathAndSnapMounts<'a>> {
         self.datasets_of_interest
             .into_iter()
             .flat_map(|dataset_of_interest| {
                 RelativePathAndSnapMounts::new(
+                    self.fs_info,
                     self.pathdata,
                     self.proximate_dataset_mount,
                     &dataset_of_interest,
@@ -234,11 +697,14 @@ impl<'a> ProximateDatasetAndOptAlts<'a> {
 pub struct RelativePathAndSnapMounts<'a> {
     pub pathdata: &'a PathData,
     pub relative_path: &'a Path,
+    pub dataset_of_interest: &'a Path,
     pub snap_mounts: &'a Vec<PathBuf>,
+    fs_info: &'a FilesystemInfo,
 }
 
 impl<'a> RelativePathAndSnapMounts<'a> {
     fn new(
+        fs_info: &'a FilesystemInfo,
         pathdata: &'a PathData,
         proximate_dataset_mount: &'a Path,
         dataset_of_interest: &Path,
@@ -249,10 +715,9 @@ impl<'a> RelativePathAndSnapMounts<'a> {
         // for user specified dirs/aliases these are specified by the user
         let relative_path = pathdata.relative_path(proximate_dataset_mount)?;
 
-        let snap_mounts = GLOBAL_CONFIG
-            .dataset_collection
+        let (dataset_of_interest, snap_mounts) = fs_info
             .map_of_snaps
-            .get(dataset_of_interest)
+            .get_key_value(dataset_of_interest)
             .ok_or_else(|| {
                 HttmError::new(
                     "httm could find no snap mount for your files.  \
@@ -263,14 +728,235 @@ impl<'a> RelativePathAndSnapMounts<'a> {
         Ok(Self {
             pathdata,
             relative_path,
+            dataset_of_interest,
             snap_mounts,
+            fs_info,
         })
     }
 
     pub fn versions_processed(&'a self, uniqueness: &ListSnapsOfType) -> Vec<PathData> {
-        let all_versions = self.versions_unprocessed(uniqueness);
+        // of identical versions, the first found is retained, so, when we filter for unique versions,
+        // search newest first, so that the version retained is always the one from the newest snapshot
+        let newest_first;
+        let snap_mounts: &[PathBuf] = match uniqueness {
+            ListSnapsOfType::All => self.snap_mounts,
+            ListSnapsOfType::UniqueContents | ListSnapsOfType::UniqueMetadata => {
+                newest_first = self.snap_mounts_newest_first();
+                &newest_first
+            }
+        };
+
+        let all_versions = self.versions_unprocessed(snap_mounts, uniqueness);
+        let tolerance = self.mtime_tolerance();
+
+        // the version iter is lazy, so when profiling we exhaust it first, else the time spent reading
+        // metadata from every snapshot would be counted as time spent filtering for unique versions
+        if Profile::is_enabled() {
+            let all_versions: Vec<CompareVersionsContainer> = all_versions.collect();
+
+            return Profile::time(Phase::Uniqueness, || {
+                Self::sort_dedup_versions(all_versions.into_par_iter(), uniqueness, tolerance)
+            });
+        }
+
+        Self::sort_dedup_versions(all_versions, uniqueness, tolerance)
+    }
+
+    // searches snap mounts in batches, newest first, and stops once enough versions have been found,
+    // so that older snapshots of a file with thousands of versions need never be read
+    #[allow(clippy::mutable_key_type)]
+    pub fn versions_newest(
+        &self,
+        uniqueness: &ListSnapsOfType,
+        max_results: usize,
+    ) -> Vec<PathData> {
+        let snap_mounts_newest_first = self.snap_mounts_newest_first();
+        let batch_size = max_results.max(MAX_RESULTS_MIN_BATCH);
+
+        let mut all: Vec<PathData> = Vec::new();
+        let mut unique: BTreeSet<CompareVersionsContainer> = BTreeSet::new();
 
-        Self::sort_dedup_versions(all_versions, uniqueness)
+        for batch in snap_mounts_newest_first.chunks(batch_size) {
+            let found = self.versions_unprocessed(batch, uniqueness);
+
+            let num_found = match uniqueness {
+                ListSnapsOfType::All => {
+                    all.par_extend(found.map(PathData::from));
+                    all.len()
+                }
+                ListSnapsOfType::UniqueContents | ListSnapsOfType::UniqueMetadata => {
+                    Self::insert_unique(&mut unique, found.collect());
+                    unique.len()
+                }
+            };
+
+            if num_found >= max_results {
+                break;
+            }
+        }
+
+        let mut versions: Vec<PathData> = match uniqueness {
+            ListSnapsOfType::All => {
+                all.sort_by(|a, b| {
+                    a.md_infallible()
+                        .modify_time
+                        .cmp(&b.md_infallible().modify_time)
+                        .then_with(|| a.cmp(b))
+                });
+                all
+            }
+            ListSnapsOfType::UniqueContents => unique.into_iter().map(PathData::from).collect(),
+            ListSnapsOfType::UniqueMetadata => {
+                let versions = unique.into_iter().map(PathData::from);
+
+                match self.mtime_tolerance() {
+                    Some(tolerance) => Self::dedup_within_tolerance(versions, tolerance),
+                    None => versions.collect(),
+                }
+            }
+        };
+
+        if versions.len() > max_results {
+            versions.drain(..versions.len() - max_results);
+        }
+
+        versions
+    }
+
+    pub fn newest_snap_mount(&self) -> Option<PathBuf> {
+        self.snap_mounts_newest_first().first().cloned()
+    }
+
+    // snap mounts are not ordered by creation, so, for ZFS, we order them by each snapshot's creation property,
+    // and, for btrfs, or if ZFS can't be asked, by the modify time of each snapshot's root, which is the modify
+    // time of the dataset's root when the snapshot was taken, and which many snapshots may share
+    fn snap_mounts_newest_first(&self) -> Arc<Vec<PathBuf>> {
+        {
+            let cached = SNAP_MOUNTS_NEWEST_FIRST
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if let Some(snap_mounts) = cached.get(self.dataset_of_interest) {
+                return snap_mounts.clone();
+            }
+        }
+
+        let creation_times = self.snap_creation_times();
+
+        // don't hold the lock during the parallel iter, as this thread may be asked to do other work meanwhile
+        let mut with_times: Vec<(Option<SystemTime>, SystemTime, &PathBuf)> = self
+            .snap_mounts
+            .par_iter()
+            .map(|snap_mount| {
+                let opt_creation = snap_mount
+                    .file_name()
+                    .and_then(|snap_name| creation_times.get(snap_name))
+                    .copied();
+
+                let modify_time = snap_mount
+                    .symlink_metadata()
+                    .and_then(|md| md.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+
+                (opt_creation, modify_time, snap_mount)
+            })
+            .collect();
+
+        // creation times are only to the second, so ties are broken by modify time, and then by path
+        with_times.sort_by(|a, b| b.cmp(a));
+
+        let snap_mounts: Arc<Vec<PathBuf>> = Arc::new(
+            with_times
+                .into_iter()
+                .map(|(_opt_creation, _modify_time, snap_mount)| snap_mount.clone())
+                .collect(),
+        );
+
+        SNAP_MOUNTS_NEWEST_FIRST
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(self.dataset_of_interest.to_path_buf(), snap_mounts.clone());
+
+        snap_mounts
+    }
+
+    // the path of this dir within each snapshot in which it exists, in the order the snapshots were taken, newest first
+    fn snap_dirs_newest_first(&self) -> Vec<PathData> {
+        self.snap_mounts_newest_first()
+            .iter()
+            .map(|snap_mount| snap_mount.join(self.relative_path))
+            .filter_map(|snap_dir| {
+                let md = snap_dir.symlink_metadata().ok()?;
+                Some(PathData::new(&snap_dir, Some(md)))
+            })
+            .collect()
+    }
+
+    // snapshots may since have been taken, or destroyed, or another dataset mounted in a dataset's place
+    pub fn clear_cache() {
+        SNAP_MOUNTS_NEWEST_FIRST
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    pub fn clear_cache_of(dataset: &Path) {
+        SNAP_MOUNTS_NEWEST_FIRST
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(dataset);
+    }
+
+    // key: snapshot name, as it appears in the snapshot directory, val: its creation time
+    fn snap_creation_times(&self) -> HashMap<OsString, SystemTime> {
+        let dataset_name = match self.fs_info.map_of_datasets.get(self.dataset_of_interest) {
+            Some(md) if md.fs_type == FilesystemType::Zfs && md.mount_type == MountType::Local => {
+                md.source.to_string_lossy()
+            }
+            _ => return HashMap::new(),
+        };
+
+        let zfs_command = match ExternalCommand::optional(
+            "zfs",
+            "snapshots are ordered by modify time, rather than by creation",
+        ) {
+            Some(zfs_command) => zfs_command,
+            None => return HashMap::new(),
+        };
+
+        AllSnaps::of_dataset(&zfs_command, &dataset_name)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|snap_info| {
+                let (_dataset, snap_name) = snap_info.name.split_once('@')?;
+
+                Some((OsString::from(snap_name), snap_info.creation))
+            })
+            .collect()
+    }
+
+    // the user already knows which snapshot they want, so there is no uniqueness to consider,
+    // and no reason to read any other snapshot
+    pub fn versions_of_snap(&self, snap_name: &str) -> Vec<PathData> {
+        let snap_mounts: Vec<PathBuf> = self
+            .snap_mounts
+            .iter()
+            .filter(|snap_mount| {
+                // ZFS: <mount>/.zfs/snapshot/<snap name>, btrfs-snapper: <mount>/.snapshots/<snap number>/snapshot
+                let opt_name = if snap_mount.ends_with(BTRFS_SNAPPER_SUFFIX) {
+                    snap_mount.parent().and_then(Path::file_name)
+                } else {
+                    snap_mount.file_name()
+                };
+
+                matches!(opt_name, Some(name) if name == snap_name)
+            })
+            .cloned()
+            .collect();
+
+        self.versions_unprocessed(&snap_mounts, &ListSnapsOfType::All)
+            .map(PathData::from)
+            .collect()
     }
 
     pub fn last_version(&self) -> Option<PathData> {
@@ -279,18 +965,21 @@ impl<'a> RelativePathAndSnapMounts<'a> {
         sorted_versions.pop()
     }
 
-    fn versions_unprocessed(
-        &'a self,
-        uniqueness: &'a ListSnapsOfType,
-    ) -> impl ParallelIterator<Item = CompareVersionsContainer> + 'a {
+    fn versions_unprocessed<'b>(
+        &'b self,
+        snap_mounts: &'b [PathBuf],
+        uniqueness: &'b ListSnapsOfType,
+    ) -> impl ParallelIterator<Item = CompareVersionsContainer> + 'b {
         // get the DirEntry for our snapshot path which will have all our possible
         // snapshots, like so: .zfs/snapshots/<some snap name>/
-        self
-            .snap_mounts
+        snap_mounts
             .par_iter()
-            .map(|path| path.join(self.relative_path))
-            .filter_map(|joined_path| {
-                match joined_path.symlink_metadata() {
+            .filter_map(|snap_mount| {
+                let joined_path = snap_mount.join(self.relative_path);
+
+                let _opt_permit = DatasetPermit::acquire(&joined_path);
+
+                match Retry::io(self.dataset_of_interest, || joined_path.symlink_metadata()) {
                     Ok(md) => {
                         Some(CompareVersionsContainer::new(PathData::new(joined_path.as_path(), Some(md)), uniqueness))
                     },
@@ -304,6 +993,13 @@ impl<'a> RelativePathAndSnapMounts<'a> {
                                 \nDetails: {err}");
                                 std::process::exit(1)
                             },
+                            // the snapshot may store the path with a different case than the live path
+                            ErrorKind::NotFound if CaseSensitivity::is_insensitive(self.dataset_of_interest) => {
+                                let resolved = CaseSensitivity::resolve(snap_mount, self.relative_path)?;
+                                let md = resolved.symlink_metadata().ok()?;
+
+                                Some(CompareVersionsContainer::new(PathData::new(resolved.as_path(), Some(md)), uniqueness))
+                            },
                             // if file metadata is not found, or is otherwise not available, 
                             // continue, it simply means we do not have a snapshot of this file
                             _ => None,
@@ -318,13 +1014,77 @@ impl<'a> RelativePathAndSnapMounts<'a> {
     fn sort_dedup_versions(
         iter: impl ParallelIterator<Item = CompareVersionsContainer>,
         snaps_of_type: &ListSnapsOfType,
+        opt_tolerance: Option<Duration>,
     ) -> Vec<PathData> {
         match snaps_of_type {
             ListSnapsOfType::All => iter.map(PathData::from).collect(),
-            ListSnapsOfType::UniqueContents | ListSnapsOfType::UniqueMetadata => {
-                let sorted_and_deduped: BTreeSet<CompareVersionsContainer> = iter.collect();
+            ListSnapsOfType::UniqueContents => {
+                let mut sorted_and_deduped: BTreeSet<CompareVersionsContainer> = BTreeSet::new();
+                Self::insert_unique(&mut sorted_and_deduped, iter.collect());
                 sorted_and_deduped.into_iter().map(PathData::from).collect()
             }
+            ListSnapsOfType::UniqueMetadata => {
+                let mut sorted_and_deduped: BTreeSet<CompareVersionsContainer> = BTreeSet::new();
+                Self::insert_unique(&mut sorted_and_deduped, iter.collect());
+                let versions = sorted_and_deduped.into_iter().map(PathData::from);
+
+                match opt_tolerance {
+                    Some(tolerance) => Self::dedup_within_tolerance(versions, tolerance),
+                    None => versions.collect(),
+                }
+            }
         }
     }
+
+    // a collect into a BTreeSet makes no promise as to which of several equal versions is retained,
+    // so versions are inserted in the order found, and a version equal to one already retained is omitted
+    #[allow(clippy::mutable_key_type)]
+    fn insert_unique(
+        unique: &mut BTreeSet<CompareVersionsContainer>,
+        found: Vec<CompareVersionsContainer>,
+    ) {
+        found.into_iter().for_each(|version| {
+            if let Some(retained) = unique.get(&version) {
+                DuplicateVersions::record(retained.pathdata(), version.into());
+                return;
+            }
+
+            unique.insert(version);
+        });
+    }
+
+    // snapshots (or alias targets) upon a filesystem with coarse modify times, like FAT, can't be compared to the
+    // nanosecond, so the tolerance is at least that filesystem's granularity
+    pub fn mtime_tolerance(&self) -> Option<Duration> {
+        FsCapabilities::effective_tolerance(
+            GLOBAL_CONFIG.opt_mtime_tolerance,
+            &[self.dataset_of_interest],
+        )
+    }
+
+    // a tolerance makes for a comparison which is not transitive, so we can't simply build it into Ord,
+    // instead versions already sorted by modify time are compared to the last version retained
+    fn dedup_within_tolerance(
+        versions: impl Iterator<Item = PathData>,
+        tolerance: Duration,
+    ) -> Vec<PathData> {
+        let mut retained: Vec<PathData> = Vec::new();
+
+        versions.for_each(|version| {
+            if let Some(last) = retained.last() {
+                if FsCapabilities::is_within_tolerance(
+                    &last.md_infallible(),
+                    &version.md_infallible(),
+                    tolerance,
+                ) {
+                    DuplicateVersions::record(last, version);
+                    return;
+                }
+            }
+
+            retained.push(version)
+        });
+
+        retained
+    }
 }
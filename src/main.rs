@@ -17,6 +17,7 @@
 
 mod data {
     pub mod filesystem_info;
+    pub mod json;
     pub mod paths;
     pub mod selection;
 }
@@ -29,26 +30,62 @@ mod display_versions {
     pub mod wrapper;
 }
 mod exec {
+    pub mod assert_snapshotted;
     pub mod deleted;
+    pub mod drift;
+    pub mod follow_stdin;
+    pub mod grep;
+    pub mod info;
     pub mod interactive;
+    pub mod most_changed;
     pub mod preview;
     pub mod prune;
+    pub mod recover_deleted;
     pub mod recursive;
     pub mod roll_forward;
+    pub mod rpc;
     pub mod snap_mounts;
 }
 mod config {
     pub mod generate;
     pub mod install_hot_keys;
+    pub mod manual;
 }
 mod library {
+    pub mod alt_root;
+    pub mod case_sensitivity;
+    pub mod checksum;
+    pub mod clipboard;
+    pub mod clone_restore;
+    pub mod dataset_permit;
+    pub mod date_locale;
+    pub mod deleted_index;
     pub mod diff_copy;
+    pub mod escalate;
+    pub mod external_commands;
+    pub mod fs_capabilities;
+    pub mod hooks;
     pub mod iter_extensions;
+    pub mod messages;
+    pub mod mount_watch;
+    pub mod num_versions_cache;
+    pub mod output_sink;
+    pub mod pool_health;
+    pub mod profile;
+    pub mod query_cache;
+    pub mod redaction;
     pub mod results;
+    pub mod retry;
+    pub mod session_report;
     pub mod snap_guard;
     pub mod utility;
+    pub mod xdg;
+    pub mod zfs_allow;
+    pub mod zpool_history;
 }
 mod lookup {
+    pub mod all_snaps;
+    pub mod count;
     pub mod deleted;
     pub mod file_mounts;
     pub mod snap_names;
@@ -62,19 +99,39 @@ mod parse {
 }
 
 use crate::display_map::format::PrintAsMap;
+use exec::grep::GrepVersions;
+use exec::most_changed::MostChanged;
 use exec::prune::PruneSnaps;
+use exec::recover_deleted::RecoverDeleted;
 use exec::roll_forward::RollForward;
 use exec::snap_mounts::SnapshotMounts;
-use library::utility::print_output_buf;
+use library::utility::{print_output_buf, print_output_bytes};
 use once_cell::sync::Lazy;
+use std::io::Write;
 
 use crate::config::generate::{Config, ExecMode};
+use crate::lookup::all_snaps::AllSnaps;
+use crate::lookup::count::CountVersions;
 use crate::lookup::file_mounts::MountsForFiles;
 
+use crate::data::json::JsonResponse;
 use crate::display_versions::wrapper::VersionsDisplayWrapper;
+use crate::exec::assert_snapshotted::AssertSnapshotted;
+use crate::exec::drift::Drift;
+use crate::exec::follow_stdin::FollowStdin;
+use crate::exec::info::PathInfo;
 use crate::exec::interactive::InteractiveBrowse;
 use crate::exec::recursive::NonInteractiveRecursiveWrapper;
-use crate::library::results::HttmResult;
+use crate::exec::rpc::Rpc;
+use crate::library::clone_restore::CloneRestore;
+use crate::library::deleted_index::DeletedIndex;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::mount_watch::MountWatch;
+use crate::library::num_versions_cache::NumVersionsCache;
+use crate::library::output_sink::OutputSink;
+use crate::library::profile::{Phase, Profile};
+use crate::library::results::{HttmErrorKind, HttmResult, JsonError, SkippedPaths};
+use crate::library::session_report::SessionReport;
 use crate::lookup::snap_names::SnapNameMap;
 use crate::lookup::versions::VersionsMap;
 
@@ -87,10 +144,34 @@ pub const NILFS2_SNAPSHOT_ID_KEY: &str = "cp=";
 
 fn main() {
     match exec() {
-        Ok(_) => std::process::exit(0),
+        Ok(_) => {
+            Profile::report();
+            std::process::exit(0)
+        }
         Err(error) => {
-            eprintln!("Error: {error}");
-            std::process::exit(1)
+            let kind = HttmErrorKind::of(error.as_ref());
+
+            // a failed run never replaces an OUTPUT file
+            OutputSink::discard();
+
+            // a JSON consumer expects a JSON response, even upon failure
+            if GLOBAL_CONFIG.opt_json {
+                let message = error.to_string();
+                let body = JsonError {
+                    kind,
+                    message: &message,
+                };
+                let output_buf = JsonResponse::error(&body).to_json(&GLOBAL_CONFIG.print_mode);
+
+                // straight to stdout, as the error may be that of the OUTPUT file itself
+                let mut out_locked = std::io::stdout().lock();
+                let _ = out_locked.write_all(output_buf.as_bytes());
+                let _ = out_locked.flush();
+            } else {
+                eprintln!("Error: {error}");
+            }
+
+            std::process::exit(kind.exit_code())
         }
     }
 }
@@ -101,28 +182,54 @@ static GLOBAL_CONFIG: Lazy<Config> = Lazy::new(|| {
     Config::new()
         .map_err(|error| {
             eprintln!("Error: {error}");
-            std::process::exit(1)
+            std::process::exit(HttmErrorKind::of(error.as_ref()).exit_code())
         })
         .unwrap()
 });
 
 fn exec() -> HttmResult<()> {
+    if GLOBAL_CONFIG.opt_report {
+        SessionReport::register();
+    }
+
+    // modes which cannot work without, say, "zfs" fail here, before anything has been done
+    ExternalCommand::preflight()?;
+
+    if let Some(output_file) = &GLOBAL_CONFIG.opt_output {
+        OutputSink::open(output_file)?;
+    }
+
     // fn exec() handles the basic display cases, and sends other cases to be processed elsewhere
-    match &GLOBAL_CONFIG.exec_mode {
+    let res = match &GLOBAL_CONFIG.exec_mode {
         // ExecMode::Interactive *may* return back to this function to be printed
         ExecMode::Interactive(interactive_mode) => {
+            MountWatch::spawn();
+
             let pathdata_set = InteractiveBrowse::exec(interactive_mode)?;
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &pathdata_set)?;
-            let output_buf = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_string();
+            Profile::time(Phase::Display, || {
+                let output_buf =
+                    VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_bytes()?;
 
-            print_output_buf(output_buf)
+                print_output_bytes(&output_buf)
+            })
         }
         // ExecMode::Display will be just printed, we already know the paths
+        ExecMode::Display | ExecMode::NumVersions(_) if GLOBAL_CONFIG.opt_follow_stdin => {
+            FollowStdin::exec()
+        }
         ExecMode::Display | ExecMode::NumVersions(_) => {
-            let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
-            let output_buf = VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_string();
+            let versions_map = if GLOBAL_CONFIG.opt_cache {
+                NumVersionsCache::versions_map(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths, None)?
+            } else {
+                VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?
+            };
+            Profile::time(Phase::Display, || {
+                let output_buf =
+                    VersionsDisplayWrapper::from(&GLOBAL_CONFIG, versions_map).to_bytes()?;
 
-            print_output_buf(output_buf)
+                print_output_bytes(&output_buf)
+            })
         }
         // ExecMode::NonInteractiveRecursive, ExecMode::SnapFileMount, and ExecMode::MountsForFiles will print their
         // output elsewhere
@@ -131,10 +238,12 @@ fn exec() -> HttmResult<()> {
         ExecMode::SnapsForFiles(opt_filters) => {
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
             let snap_name_map = SnapNameMap::new(versions_map, opt_filters)?;
-            let printable_map = PrintAsMap::from(&snap_name_map);
-            let output_buf = printable_map.to_string();
+            Profile::time(Phase::Display, || {
+                let printable_map = PrintAsMap::from(&snap_name_map);
+                let output_buf = printable_map.to_string();
 
-            print_output_buf(output_buf)
+                print_output_buf(output_buf)
+            })
         }
         ExecMode::Prune(opt_filters) => {
             let versions_map = VersionsMap::new(&GLOBAL_CONFIG, &GLOBAL_CONFIG.paths)?;
@@ -148,5 +257,23 @@ fn exec() -> HttmResult<()> {
             print_output_buf(output_buf)
         }
         ExecMode::RollForward(roll_config) => RollForward::new(roll_config.clone())?.exec(),
-    }
+        ExecMode::Grep(regex) => GrepVersions::exec(regex),
+        ExecMode::Count => CountVersions::exec(),
+        ExecMode::ListSnapsAll(opt_pattern) => AllSnaps::exec(opt_pattern),
+        ExecMode::MostChanged(config) => MostChanged::exec(config),
+        ExecMode::Index(progress_bar) => DeletedIndex::exec(progress_bar),
+        ExecMode::Info => PathInfo::exec(),
+        ExecMode::AssertSnapshotted(opt_within) => AssertSnapshotted::exec(opt_within),
+        ExecMode::Drift => Drift::exec(),
+        ExecMode::Rpc => Rpc::exec(),
+        ExecMode::CleanupClones => CloneRestore::cleanup(),
+        ExecMode::RecoverDeleted(config) => RecoverDeleted::exec(config),
+    };
+
+    res?;
+
+    // in STRICT mode, any path skipped along the way is a failure
+    SkippedPaths::report()?;
+
+    OutputSink::commit()
 }
@@ -18,7 +18,9 @@
 use std::{ffi::OsString, ops::Deref, path::Path, path::PathBuf};
 
 use hashbrown::HashMap;
+use proc_mounts::MountIter;
 
+use crate::library::alt_root::AltRoot;
 use crate::library::results::{HttmError, HttmResult};
 use crate::library::utility::fs_type_from_hidden_dir;
 
@@ -33,6 +35,36 @@ pub enum FilesystemType {
 pub struct RemotePathAndFsType {
     pub remote_dir: PathBuf,
     pub fs_type: FilesystemType,
+    pub opt_remote_snap: Option<RemoteSnapCommand>,
+}
+
+// a user supplied command, like "ssh nas zfs snapshot tank/home@{snap_name}", which takes a snapshot
+// of the dataset behind an alias on the machine which actually hosts the dataset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteSnapCommand {
+    template: String,
+}
+
+impl RemoteSnapCommand {
+    pub const PLACEHOLDER: &'static str = "{snap_name}";
+
+    pub fn new(template: &str) -> HttmResult<Self> {
+        if !template.contains(Self::PLACEHOLDER) {
+            let msg = format!(
+                "A REMOTE_SNAP_COMMAND must contain the {} placeholder: {template}",
+                Self::PLACEHOLDER
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
+        Ok(Self {
+            template: template.to_owned(),
+        })
+    }
+
+    pub fn command(&self, snap_name: &str) -> String {
+        self.template.replace(Self::PLACEHOLDER, snap_name)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,12 +86,51 @@ impl Deref for MapOfAliases {
     }
 }
 
+// network and fuse filesystems upon which a metadata call may take a long round trip
+const HIGH_LATENCY_FS_TYPES: [&str; 7] =
+    ["fuse.sshfs", "sshfs", "nfs", "nfs4", "cifs", "smb3", "9p"];
+
 impl MapOfAliases {
+    // is the remote dir of any alias located on a network filesystem, like sshfs?
+    pub fn is_high_latency(&self) -> bool {
+        let mount_iter = match MountIter::new() {
+            Ok(mount_iter) => mount_iter,
+            Err(_) => return false,
+        };
+
+        let high_latency_mounts: Vec<PathBuf> = mount_iter
+            .flatten()
+            .filter(|mount_info| HIGH_LATENCY_FS_TYPES.contains(&mount_info.fstype.as_str()))
+            .map(|mount_info| mount_info.dest)
+            .collect();
+
+        self.inner.values().any(|remote_path_and_fs_type| {
+            high_latency_mounts
+                .iter()
+                .any(|mount| remote_path_and_fs_type.remote_dir.starts_with(mount))
+        })
+    }
+
+    // the local dir and remote snapshot command for the alias whose remote dir is this mount, if any
+    pub fn remote_snap_command(&self, remote_dir: &Path) -> Option<(&Path, &RemoteSnapCommand)> {
+        self.inner.iter().find_map(|(local_dir, alias_info)| {
+            if alias_info.remote_dir != remote_dir {
+                return None;
+            }
+
+            alias_info
+                .opt_remote_snap
+                .as_ref()
+                .map(|remote_snap| (local_dir.as_path(), remote_snap))
+        })
+    }
+
     pub fn new(
         raw_local_dir: &Option<OsString>,
         raw_snap_dir: &Option<OsString>,
         pwd: &Path,
         opt_input_aliases: &Option<Vec<String>>,
+        opt_remote_snap_commands: &Option<Vec<String>>,
     ) -> HttmResult<Self> {
         // user defined dir exists?: check that path contains the hidden snapshot directory
         let snap_point = raw_snap_dir.as_ref().map(|value| {
@@ -99,8 +170,33 @@ impl MapOfAliases {
             aliases_iter.push(value)
         }
 
+        // remote snapshot commands are keyed by the local dir of the alias they snapshot
+        let mut remote_snap_commands: HashMap<PathBuf, RemoteSnapCommand> =
+            match opt_remote_snap_commands {
+                Some(raw_commands) => raw_commands
+                    .iter()
+                    .map(|raw| {
+                        let (local_dir, template) = raw.split_once(':').ok_or_else(|| {
+                            HttmError::new(
+                                "Must use specified delimiter (':') between the local dir and the command for REMOTE_SNAP_COMMAND.",
+                            )
+                        })?;
+
+                        Ok((
+                            AltRoot::into_root(PathBuf::from(local_dir)),
+                            RemoteSnapCommand::new(template)?,
+                        ))
+                    })
+                    .collect::<HttmResult<HashMap<PathBuf, RemoteSnapCommand>>>()?,
+                None => HashMap::new(),
+            };
+
         let map_of_aliases: HashMap<PathBuf, RemotePathAndFsType> = aliases_iter
             .into_iter()
+            // alias paths are relative to the alt root, if specified, just like input paths
+            .map(|(local_dir, snap_dir)| {
+                (AltRoot::into_root(local_dir), AltRoot::into_root(snap_dir))
+            })
             .filter_map(|(local_dir, snap_dir)| {
                 if !local_dir.exists() || !snap_dir.exists() {
                     [local_dir, snap_dir]
@@ -120,17 +216,29 @@ impl MapOfAliases {
             .filter_map(|(local_dir, remote_dir)| {
                 fs_type_from_hidden_dir(&remote_dir)
                     .map(|fs_type| {
+                        let opt_remote_snap = remote_snap_commands.remove(&local_dir);
+
                         (
                             local_dir,
                             RemotePathAndFsType {
                                 remote_dir,
                                 fs_type,
+                                opt_remote_snap,
                             },
                         )
                     })
             })
             .collect();
 
+        // a remote command for a dir which is not an alias would otherwise be silently ignored
+        if let Some(local_dir) = remote_snap_commands.keys().next() {
+            let msg = format!(
+                "REMOTE_SNAP_COMMAND was specified for a directory which is not a valid alias: {:?}",
+                local_dir
+            );
+            return Err(HttmError::new(&msg).into());
+        }
+
         Ok(map_of_aliases.into())
     }
 }
@@ -15,13 +15,16 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-use std::{ops::Deref, path::Path, path::PathBuf};
+use std::process::Command as ExecProcess;
+use std::{ffi::OsStr, ffi::OsString, ops::Deref, path::Path, path::PathBuf};
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use rayon::prelude::*;
 
+use crate::library::external_commands::ExternalCommand;
 use crate::library::results::{HttmError, HttmResult};
-use crate::parse::mounts::MapOfDatasets;
+use crate::parse::aliases::FilesystemType;
+use crate::parse::mounts::{MapOfDatasets, MountType};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MapOfAlts {
@@ -50,11 +53,17 @@ impl Deref for MapOfAlts {
 
 impl MapOfAlts {
     // instead of looking up, precompute possible alt replicated mounts before exec
-    pub fn new(map_of_datasets: &MapOfDatasets) -> Self {
+    pub fn new(map_of_datasets: &MapOfDatasets, opt_infer_backups: bool) -> Self {
+        let opt_lineage = if opt_infer_backups {
+            Some(SnapLineage::new(map_of_datasets))
+        } else {
+            None
+        };
+
         let res: HashMap<PathBuf, AltMetadata> = map_of_datasets
             .par_iter()
             .flat_map(|(mount, _dataset_info)| {
-                Self::alt_replicated_from_mount(mount, map_of_datasets)
+                Self::alt_replicated_from_mount(mount, map_of_datasets, opt_lineage.as_ref())
                     .map(|datasets| (mount.clone(), datasets))
             })
             .collect();
@@ -65,6 +74,7 @@ impl MapOfAlts {
     fn alt_replicated_from_mount(
         proximate_dataset_mount: &Path,
         map_of_datasets: &MapOfDatasets,
+        opt_lineage: Option<&SnapLineage>,
     ) -> HttmResult<AltMetadata> {
         let proximate_dataset_fs_name = match &map_of_datasets.get(proximate_dataset_mount) {
             Some(dataset_info) => dataset_info.source.as_os_str(),
@@ -75,13 +85,15 @@ impl MapOfAlts {
 
         // find a filesystem that ends with our most local filesystem name
         // but which has a prefix, like a different pool name: rpool might be
-        // replicated to tank/rpool
+        // replicated to tank/rpool, or, when inferring backups, any filesystem
+        // which was received from ours, whatever its name or mount
         let mut alt_replicated_mounts: Vec<PathBuf> = map_of_datasets
             .iter()
             .map(|(mount, dataset_info)| (mount, Path::new(&dataset_info.source)))
             .filter(|(_mount, source)| {
                 source.as_os_str() != proximate_dataset_fs_name
-                    && source.ends_with(proximate_dataset_fs_name)
+                    && (source.ends_with(proximate_dataset_fs_name)
+                        || matches!(opt_lineage, Some(lineage) if lineage.is_replica(proximate_dataset_fs_name, source.as_os_str())))
             })
             .map(|(mount, _source)| mount)
             .cloned()
@@ -99,3 +111,93 @@ impl MapOfAlts {
         }
     }
 }
+
+// zfs send preserves the guid of each snapshot sent, so a dataset received from another,
+// wherever it is mounted and whatever it is named, shares at least one snapshot guid with it
+struct SnapLineage {
+    // key: dataset name, val: guids of that dataset's snapshots
+    inner: HashMap<OsString, HashSet<String>>,
+}
+
+impl SnapLineage {
+    fn new(map_of_datasets: &MapOfDatasets) -> Self {
+        let dataset_names: HashSet<&OsStr> = map_of_datasets
+            .values()
+            .filter(|dataset_info| {
+                dataset_info.fs_type == FilesystemType::Zfs
+                    && dataset_info.mount_type == MountType::Local
+            })
+            .map(|dataset_info| dataset_info.source.as_os_str())
+            .collect();
+
+        if dataset_names.is_empty() {
+            return Self {
+                inner: HashMap::new(),
+            };
+        }
+
+        let zfs_command = match ExternalCommand::optional(
+            "zfs",
+            "INFER_BACKUP_ALIASES cannot compare the snapshots of datasets",
+        ) {
+            Some(zfs_command) => zfs_command,
+            None => {
+                return Self {
+                    inner: HashMap::new(),
+                }
+            }
+        };
+
+        // -d 1: only the snapshots of each dataset named, not those of its children
+        let mut process_args: Vec<&OsStr> = [
+            "list",
+            "-H",
+            "-p",
+            "-t",
+            "snapshot",
+            "-d",
+            "1",
+            "-o",
+            "name,guid",
+        ]
+        .iter()
+        .map(OsStr::new)
+        .collect();
+        process_args.extend(dataset_names.iter());
+
+        let stdout = match ExecProcess::new(zfs_command).args(&process_args).output() {
+            Ok(process_output) => process_output.stdout,
+            Err(_) => Vec::new(),
+        };
+
+        let mut inner: HashMap<OsString, HashSet<String>> = HashMap::new();
+
+        std::str::from_utf8(&stdout)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter_map(|(snap_name, guid)| {
+                snap_name
+                    .split_once('@')
+                    .map(|(dataset_name, _snap)| (dataset_name, guid))
+            })
+            .for_each(|(dataset_name, guid)| {
+                inner
+                    .entry(OsString::from(dataset_name))
+                    .or_default()
+                    .insert(guid.trim().to_owned());
+            });
+
+        Self { inner }
+    }
+
+    fn is_replica(&self, dataset_name: &OsStr, other_dataset_name: &OsStr) -> bool {
+        match (
+            self.inner.get(dataset_name),
+            self.inner.get(other_dataset_name),
+        ) {
+            (Some(guids), Some(other_guids)) => !guids.is_disjoint(other_guids),
+            _ => false,
+        }
+    }
+}
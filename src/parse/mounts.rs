@@ -16,20 +16,24 @@
 // that was distributed with this source code.
 
 use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::ops::Deref;
-use std::{path::PathBuf, process::Command as ExecProcess};
+use std::os::unix::ffi::OsStringExt;
+use std::{path::Path, path::PathBuf, process::Command as ExecProcess};
 
 use hashbrown::{HashMap, HashSet};
+use once_cell::sync::OnceCell;
 use proc_mounts::MountIter;
 use rayon::iter::Either;
 use rayon::prelude::*;
-use which::which;
 
-use crate::library::results::{HttmError, HttmResult};
+use crate::library::alt_root::AltRoot;
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult};
 use crate::library::utility::{find_common_path, fs_type_from_hidden_dir};
 use crate::parse::aliases::FilesystemType;
 use crate::parse::snaps::MapOfSnaps;
-use crate::{NILFS2_SNAPSHOT_ID_KEY, ZFS_HIDDEN_DIRECTORY};
+use crate::{NILFS2_SNAPSHOT_ID_KEY, ZFS_HIDDEN_DIRECTORY, ZFS_SNAPSHOT_DIRECTORY};
 
 pub const ZFS_FSTYPE: &str = "zfs";
 pub const NILFS2_FSTYPE: &str = "nilfs2";
@@ -37,6 +41,12 @@ pub const BTRFS_FSTYPE: &str = "btrfs";
 pub const SMB_FSTYPE: &str = "smbfs";
 pub const NFS_FSTYPE: &str = "nfs";
 pub const AFP_FSTYPE: &str = "afpfs";
+pub const NULLFS_FSTYPE: &str = "nullfs";
+pub const PROC_SELF_MOUNTINFO: &str = "/proc/self/mountinfo";
+pub const DEFAULT_EXCLUDE_PROPERTY: &str = "com.sun:auto-snapshot=false";
+
+// as with the alt root, bind mounts must be known before any input paths are converted to PathData
+static MAP_OF_BINDS: OnceCell<MapOfBinds> = OnceCell::new();
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MountType {
@@ -51,6 +61,40 @@ pub struct DatasetMetadata {
     pub mount_type: MountType,
 }
 
+// inside a container, the mount table may not say which dataset is mounted where, or may say so wrongly,
+// so the user may tell us: the dataset named is visible, with its snapshot directory, at the mount specified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetHint {
+    pub dataset: PathBuf,
+    pub mount: PathBuf,
+}
+
+impl DatasetHint {
+    pub fn new(raw_hint: &str) -> HttmResult<Self> {
+        let (dataset, mount) = raw_hint
+            .split_once(':')
+            .filter(|(dataset, mount)| !dataset.is_empty() && mount.starts_with('/'))
+            .ok_or_else(|| {
+                HttmError::with_kind(HttmErrorKind::ParseError, "DATASET_HINT must be specified as a dataset name and an absolute mount path, delimited by a colon, ':', like so: \"pool/data:/data\".")
+            })?;
+
+        // hint paths are relative to the alt root, if specified, just like input paths
+        let mount = AltRoot::into_root(PathBuf::from(mount));
+
+        if !mount.join(ZFS_SNAPSHOT_DIRECTORY).exists() {
+            eprintln!(
+                "WARNING: The snapshot directory for a dataset hint does not exist, or is not visible from within this namespace: {:?}",
+                mount.join(ZFS_SNAPSHOT_DIRECTORY)
+            );
+        }
+
+        Ok(Self {
+            dataset: PathBuf::from(dataset),
+            mount,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FilterDirs {
     inner: HashSet<PathBuf>,
@@ -95,6 +139,291 @@ impl MaxLen for MapOfDatasets {
     }
 }
 
+impl MapOfDatasets {
+    // for /usr/bin, we prefer the most proximate: /usr/bin to /usr and /
+    // ancestors() iterates in this deepest first order, so the first match is always
+    // the longest matching mount, even where datasets are nested several levels deep
+    pub fn proximate_mount<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.ancestors()
+            .skip_while(|ancestor| ancestor.components().count() > self.max_len)
+            .find(|ancestor| self.inner.contains_key(*ancestor))
+    }
+
+    // is this dir the mount of a dataset nested beneath the specified dataset mount?
+    pub fn is_nested_mount(&self, dir: &Path, dataset_mount: &Path) -> bool {
+        dir != dataset_mount && dir.starts_with(dataset_mount) && self.inner.contains_key(dir)
+    }
+}
+
+// key: bind mount, val: the path beneath the source dataset's mount which is bound there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapOfBinds {
+    inner: HashMap<PathBuf, PathBuf>,
+    max_len: usize,
+}
+
+impl Deref for MapOfBinds {
+    type Target = HashMap<PathBuf, PathBuf>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl MaxLen for MapOfBinds {
+    fn max_len(&self) -> usize {
+        self.max_len
+    }
+}
+
+struct MountInfoEntry {
+    device: String,
+    root: PathBuf,
+    mount_point: PathBuf,
+    fs_type: String,
+    opt_subvol_id: Option<String>,
+}
+
+impl MapOfBinds {
+    pub fn set() -> HttmResult<()> {
+        let map_of_binds = if cfg!(target_os = "linux") {
+            Self::new()?
+        } else if cfg!(target_os = "freebsd") {
+            Self::from_nullfs()?
+        } else {
+            return Err(HttmError::new(
+                "FOLLOW_BINDS requires /proc/self/mountinfo, or nullfs mounts, and is only available on Linux and FreeBSD.",
+            )
+            .into());
+        };
+
+        MAP_OF_BINDS
+            .set(map_of_binds)
+            .map_err(|_err| HttmError::new("FOLLOW_BINDS may only be set once.").into())
+    }
+
+    pub fn get() -> Option<&'static MapOfBinds> {
+        MAP_OF_BINDS.get()
+    }
+
+    // mountinfo, unlike the mount table, records the root of each mount within its filesystem, where a bind mount
+    // of "/srv/data/share" to "/mnt/share" has the root "/data/share", if the dataset is mounted at "/srv"
+    fn new() -> HttmResult<Self> {
+        let mountinfo = std::fs::read_to_string(PROC_SELF_MOUNTINFO).map_err(|err| {
+            HttmError::with_context("httm could not read /proc/self/mountinfo: ", &err)
+        })?;
+
+        let entries: Vec<MountInfoEntry> = mountinfo.lines().filter_map(Self::parse_line).collect();
+
+        let mut inner: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+        // entries are listed in the order mounted, so a later mount stacked upon a bind mount hides it
+        entries
+            .iter()
+            .for_each(|entry| match Self::source_of(entry, &entries) {
+                Some(source) => {
+                    inner.insert(entry.mount_point.clone(), source);
+                }
+                None => {
+                    inner.remove(&entry.mount_point);
+                }
+            });
+
+        Ok(Self::from_inner(inner))
+    }
+
+    // a nullfs mount is FreeBSD's bind mount, and, unlike Linux, the mount table records its source dir directly,
+    // like "/usr/jails/www/data" mounted from "/srv/data"
+    fn from_nullfs() -> HttmResult<Self> {
+        let mut inner: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+        // as with mountinfo, entries are listed in the order mounted
+        StatfsEntry::read()?.into_iter().for_each(|entry| {
+            if entry.fs_type == NULLFS_FSTYPE {
+                inner.insert(entry.mount, entry.source);
+            } else {
+                inner.remove(&entry.mount);
+            }
+        });
+
+        Ok(Self::from_inner(inner))
+    }
+
+    fn from_inner(inner: HashMap<PathBuf, PathBuf>) -> Self {
+        let max_len = inner
+            .keys()
+            .map(|mount| mount.components().count())
+            .max()
+            .unwrap_or(usize::MAX);
+
+        Self { inner, max_len }
+    }
+
+    // the source of a bind mount is the mount of the same device whose root is the bind's nearest ancestor,
+    // usually the dataset's own mount, with a root of "/"
+    //
+    // btrfs subvolumes share a device, and each subvolume mounted has the subvolume's path as its root, so
+    // a subvolume mount, like "/home" of "/@home", is not a bind of the top level, and the source must be a
+    // mount of the same subvolume
+    fn source_of(entry: &MountInfoEntry, entries: &[MountInfoEntry]) -> Option<PathBuf> {
+        if !matches!(
+            entry.fs_type.as_str(),
+            ZFS_FSTYPE | BTRFS_FSTYPE | NILFS2_FSTYPE
+        ) {
+            return None;
+        }
+
+        let original = entries
+            .iter()
+            .filter(|other| {
+                other.device == entry.device
+                    && other.opt_subvol_id == entry.opt_subvol_id
+                    && entry.root.starts_with(&other.root)
+            })
+            .min_by_key(|other| other.root.components().count())?;
+
+        if original.root == entry.root {
+            return None;
+        }
+
+        let relative_path = entry.root.strip_prefix(&original.root).ok()?;
+
+        Some(original.mount_point.join(relative_path))
+    }
+
+    // fields: id, parent id, major:minor, root, mount point, options, optional fields..., "-", fs type, source, super options
+    fn parse_line(line: &str) -> Option<MountInfoEntry> {
+        let mut fields = line.split(' ');
+
+        let device = fields.nth(2)?.to_owned();
+        let root = PathBuf::from(Self::unescape(fields.next()?));
+        let mount_point = PathBuf::from(Self::unescape(fields.next()?));
+        let fs_type = fields
+            .find(|field| *field == "-")
+            .and(fields.next())?
+            .to_owned();
+        // btrfs records the subvolume mounted among the super options, like "rw,subvolid=257,subvol=/@home"
+        let opt_subvol_id = fields.nth(1).and_then(|super_options| {
+            super_options
+                .split(',')
+                .find_map(|option| option.strip_prefix("subvolid="))
+                .map(str::to_owned)
+        });
+
+        Some(MountInfoEntry {
+            device,
+            root,
+            mount_point,
+            fs_type,
+            opt_subvol_id,
+        })
+    }
+
+    // the kernel escapes space, tab, newline and backslash as octal, like "\040"
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut unescaped: Vec<u8> = Vec::with_capacity(bytes.len());
+        let mut idx = 0usize;
+
+        while idx < bytes.len() {
+            if bytes[idx] == b'\\' {
+                if let Some(value) = bytes
+                    .get(idx + 1..idx + 4)
+                    .and_then(|octal| std::str::from_utf8(octal).ok())
+                    .and_then(|octal| u8::from_str_radix(octal, 8).ok())
+                {
+                    unescaped.push(value);
+                    idx += 4;
+                    continue;
+                }
+            }
+
+            unescaped.push(bytes[idx]);
+            idx += 1;
+        }
+
+        String::from_utf8_lossy(&unescaped).into_owned()
+    }
+
+    // "/mnt/share/file" becomes "/srv/data/share/file", if "/mnt/share" is a bind mount of "/srv/data/share"
+    pub fn source_path(&self, path: &Path) -> Option<PathBuf> {
+        path.ancestors()
+            .skip_while(|ancestor| ancestor.components().count() > self.max_len)
+            .find_map(|ancestor| {
+                let source = self.inner.get(ancestor)?;
+                let relative_path = path.strip_prefix(ancestor).ok()?;
+
+                Some(source.join(relative_path))
+            })
+    }
+}
+
+// a single entry of the mount table, as FreeBSD's getmntinfo(3) reports it
+struct StatfsEntry {
+    fs_type: String,
+    source: PathBuf,
+    mount: PathBuf,
+}
+
+impl StatfsEntry {
+    fn read() -> HttmResult<Vec<Self>> {
+        let entries = Self::raw_entries()?
+            .into_iter()
+            .map(|(fs_type, source, mount)| Self {
+                fs_type: String::from_utf8_lossy(&fs_type).into_owned(),
+                source: PathBuf::from(OsString::from_vec(source)),
+                mount: PathBuf::from(OsString::from_vec(mount)),
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    // the fs type, source and mount point of each entry, as bytes
+    #[cfg(target_os = "freebsd")]
+    fn raw_entries() -> HttmResult<Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>> {
+        use std::ffi::CStr;
+
+        let mut buffer: *mut libc::statfs = std::ptr::null_mut();
+
+        // SAFETY: on success, getmntinfo points the buffer at an array of count entries, which it owns,
+        // and which remains valid until it is called again
+        let count = unsafe { libc::getmntinfo(&mut buffer, libc::MNT_NOWAIT) };
+
+        if count <= 0 || buffer.is_null() {
+            return Err(HttmError::with_context(
+                "httm could not read the mount table via getmntinfo",
+                &std::io::Error::last_os_error(),
+            )
+            .into());
+        }
+
+        let stats = unsafe { std::slice::from_raw_parts(buffer, count as usize) };
+
+        // SAFETY: each field is a NULL terminated string within a fixed length array
+        let field =
+            |raw: &[libc::c_char]| unsafe { CStr::from_ptr(raw.as_ptr()) }.to_bytes().to_vec();
+
+        let entries = stats
+            .iter()
+            .map(|stat| {
+                (
+                    field(&stat.f_fstypename),
+                    field(&stat.f_mntfromname),
+                    field(&stat.f_mntonname),
+                )
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    #[cfg(not(target_os = "freebsd"))]
+    fn raw_entries() -> HttmResult<Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>> {
+        Err(HttmError::new("getmntinfo is only used to read the mount table on FreeBSD.").into())
+    }
+}
+
 pub struct BaseFilesystemInfo {
     pub map_of_datasets: MapOfDatasets,
     pub map_of_snaps: MapOfSnaps,
@@ -104,13 +433,85 @@ pub struct BaseFilesystemInfo {
 impl BaseFilesystemInfo {
     // divide by the type of system we are on
     // Linux allows us the read proc mounts
-    pub fn new() -> HttmResult<Self> {
-        let (raw_datasets, filter_dirs_set) = if cfg!(target_os = "linux") {
+    pub fn new(
+        opt_exclude_property: Option<&str>,
+        dataset_hints: &[DatasetHint],
+        only_datasets: &[PathBuf],
+    ) -> HttmResult<Self> {
+        let (mut raw_datasets, mut filter_dirs_set) = if cfg!(target_os = "linux") {
             Self::from_proc_mounts()?
+        } else if cfg!(target_os = "freebsd") {
+            Self::from_getmntinfo()?
         } else {
             Self::from_mount_cmd()?
         };
 
+        // hints override whatever the mount table had to say about a dataset, or about the mount
+        dataset_hints.iter().for_each(|hint| {
+            raw_datasets.retain(|mount, dataset_md| {
+                dataset_md.fs_type != FilesystemType::Zfs
+                    || dataset_md.source != hint.dataset
+                    || *mount == hint.mount
+            });
+
+            filter_dirs_set.remove(&hint.mount);
+
+            raw_datasets.insert(
+                hint.mount.clone(),
+                DatasetMetadata {
+                    source: hint.dataset.clone(),
+                    fs_type: FilesystemType::Zfs,
+                    mount_type: MountType::Local,
+                },
+            );
+        });
+
+        // datasets an administrator has marked as excluded are treated as any other non-snapshot-able mount
+        if let Some(exclude_property) = opt_exclude_property {
+            let excluded = Self::excluded_datasets(exclude_property, &raw_datasets)?;
+
+            raw_datasets.retain(|mount, dataset_md| {
+                if dataset_md.fs_type == FilesystemType::Zfs
+                    && excluded.contains(&dataset_md.source)
+                {
+                    filter_dirs_set.insert(mount.clone());
+                    return false;
+                }
+
+                true
+            });
+        }
+
+        // when restricted to certain datasets, by name or by mount, every other dataset is
+        // treated as any other non-snapshot-able mount, and never searched for snapshots
+        if !only_datasets.is_empty() {
+            raw_datasets.retain(|mount, dataset_md| {
+                if only_datasets
+                    .iter()
+                    .any(|only| only == mount || *only == dataset_md.source)
+                {
+                    return true;
+                }
+
+                filter_dirs_set.insert(mount.clone());
+                false
+            });
+
+            if raw_datasets.is_empty() {
+                return Err(HttmError::new(
+                    "httm could not find any dataset matching those specified by ONLY_DATASET.",
+                )
+                .into());
+            }
+        }
+
+        // when an alt root is specified, mounts outside of the alt root are of no interest,
+        // but datasets mounted above the alt root may still contain it, so keep those
+        if AltRoot::get().is_some() {
+            raw_datasets.retain(|mount, _| AltRoot::is_relevant(mount));
+            filter_dirs_set.retain(|dir| AltRoot::is_relevant(dir));
+        }
+
         let map_of_snaps = MapOfSnaps::new(&raw_datasets)?;
 
         let map_of_datasets = {
@@ -146,36 +547,85 @@ impl BaseFilesystemInfo {
         })
     }
 
+    // the property is specified as "name=value", and a dataset is excluded if its value, set locally
+    // or inherited, matches.  a single exec of "zfs get" covers every dataset on the system
+    fn excluded_datasets(
+        exclude_property: &str,
+        raw_datasets: &HashMap<PathBuf, DatasetMetadata>,
+    ) -> HttmResult<HashSet<PathBuf>> {
+        let (property_name, excluded_value) = exclude_property.split_once('=').ok_or_else(|| {
+            HttmError::with_kind(HttmErrorKind::ParseError, "EXCLUDE_PROPERTY must be specified as a property name and a value, like so: \"com.sun:auto-snapshot=false\".")
+        })?;
+
+        // only ZFS datasets carry the property, so "zfs" is only required when there are some
+        if !raw_datasets
+            .values()
+            .any(|dataset_md| dataset_md.fs_type == FilesystemType::Zfs)
+        {
+            return Ok(HashSet::new());
+        }
+
+        let zfs_command = ExternalCommand::require("zfs")?;
+
+        let process_args = vec![
+            "get",
+            "-H",
+            "-t",
+            "filesystem",
+            "-o",
+            "name,value",
+            property_name,
+        ];
+
+        let process_output = ExecProcess::new(zfs_command).args(&process_args).output()?;
+        let stderr_string = std::str::from_utf8(&process_output.stderr)?.trim();
+
+        if !stderr_string.is_empty() {
+            let msg = "httm was unable to read the EXCLUDE_PROPERTY. The 'zfs' command issued the following error: ".to_owned() + stderr_string;
+            return Err(HttmError::new(&msg).into());
+        }
+
+        let excluded = std::str::from_utf8(&process_output.stdout)?
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(_name, value)| *value == excluded_value)
+            .map(|(name, _value)| PathBuf::from(name))
+            .collect();
+
+        Ok(excluded)
+    }
+
     // parsing from proc mounts is both faster and necessary for certain btrfs features
     // for instance, allows us to read subvolumes mounts, like "/@" or "/@home"
     fn from_proc_mounts() -> HttmResult<(HashMap<PathBuf, DatasetMetadata>, HashSet<PathBuf>)> {
-        let (map_of_datasets, filter_dirs): (HashMap<PathBuf, DatasetMetadata>, HashSet<PathBuf>) =
-            MountIter::new()?
-                .par_bridge()
-                .flatten()
-                // but exclude snapshot mounts.  we want only the raw filesystems
-                .filter(|mount_info| {
-                    if mount_info.fstype.as_str() == ZFS_FSTYPE
-                        && mount_info
-                            .dest
-                            .to_string_lossy()
-                            .contains(ZFS_HIDDEN_DIRECTORY)
-                    {
-                        return false;
-                    }
+        let entries: Vec<(usize, Either<(PathBuf, DatasetMetadata), PathBuf>)> = MountIter::new()?
+            .flatten()
+            .enumerate()
+            .par_bridge()
+            // but exclude snapshot mounts.  we want only the raw filesystems
+            .filter(|(_idx, mount_info)| {
+                if mount_info.fstype.as_str() == ZFS_FSTYPE
+                    && mount_info
+                        .dest
+                        .to_string_lossy()
+                        .contains(ZFS_HIDDEN_DIRECTORY)
+                {
+                    return false;
+                }
 
-                    if mount_info.fstype.as_str() == NILFS2_FSTYPE
-                        && mount_info
-                            .options
-                            .iter()
-                            .any(|opt| opt.contains(NILFS2_SNAPSHOT_ID_KEY))
-                    {
-                        return false;
-                    }
+                if mount_info.fstype.as_str() == NILFS2_FSTYPE
+                    && mount_info
+                        .options
+                        .iter()
+                        .any(|opt| opt.contains(NILFS2_SNAPSHOT_ID_KEY))
+                {
+                    return false;
+                }
 
-                    true
-                })
-                .partition_map(|mount_info| match mount_info.fstype.as_str() {
+                true
+            })
+            .map(|(idx, mount_info)| {
+                let entry = match mount_info.fstype.as_str() {
                     ZFS_FSTYPE => Either::Left((
                         mount_info.dest,
                         DatasetMetadata {
@@ -236,10 +686,122 @@ impl BaseFilesystemInfo {
                         },
                     )),
                     _ => Either::Right(mount_info.dest),
-                });
+                };
+
+                (idx, entry)
+            })
+            .collect();
+
+        let (map_of_datasets, filter_dirs) = Self::visible_mounts(entries);
+
+        if map_of_datasets.is_empty() {
+            Err(HttmError::with_kind(
+                HttmErrorKind::UnsupportedFilesystem,
+                "httm could not find any valid datasets on the system.",
+            )
+            .into())
+        } else {
+            Ok((map_of_datasets, filter_dirs))
+        }
+    }
+
+    // the mount table lists mounts in the order mounted, and where one mount is stacked upon
+    // another at the same mount point, only the last is visible.  par_bridge() does not preserve
+    // this order, so we restore it here, else we might choose a dataset which is hidden beneath
+    fn visible_mounts(
+        mut entries: Vec<(usize, Either<(PathBuf, DatasetMetadata), PathBuf>)>,
+    ) -> (HashMap<PathBuf, DatasetMetadata>, HashSet<PathBuf>) {
+        entries.sort_unstable_by_key(|(idx, _entry)| *idx);
+
+        let mut map_of_datasets: HashMap<PathBuf, DatasetMetadata> = HashMap::new();
+        let mut filter_dirs: HashSet<PathBuf> = HashSet::new();
+
+        entries.into_iter().for_each(|(_idx, entry)| match entry {
+            Either::Left((mount, dataset_md)) => {
+                filter_dirs.remove(&mount);
+                map_of_datasets.insert(mount, dataset_md);
+            }
+            Either::Right(mount) => {
+                map_of_datasets.remove(&mount);
+                filter_dirs.insert(mount);
+            }
+        });
+
+        (map_of_datasets, filter_dirs)
+    }
+
+    // FreeBSD's mount table is as structured as Linux's proc mounts, so there is no need to parse the output
+    // of the mount command, which can't tell a space within a mount point from a delimiter
+    fn from_getmntinfo() -> HttmResult<(HashMap<PathBuf, DatasetMetadata>, HashSet<PathBuf>)> {
+        let statfs_entries = StatfsEntry::read()?;
+
+        // key: mount, val: dataset, for resolving the dataset of a nullfs mount from its source dir
+        let zfs_datasets: HashMap<PathBuf, PathBuf> = statfs_entries
+            .iter()
+            .filter(|entry| entry.fs_type == ZFS_FSTYPE)
+            .map(|entry| (entry.mount.clone(), entry.source.clone()))
+            .collect();
+
+        let entries: Vec<(usize, Either<(PathBuf, DatasetMetadata), PathBuf>)> = statfs_entries
+            .into_iter()
+            .enumerate()
+            // but exclude snapshot mounts, which are automounted beneath the hidden snapshot directory
+            .filter(|(_idx, entry)| {
+                entry.fs_type != ZFS_FSTYPE
+                    || !(entry.source.to_string_lossy().contains('@')
+                        || entry.mount.to_string_lossy().contains(ZFS_HIDDEN_DIRECTORY))
+            })
+            .map(|(idx, entry)| {
+                let res = match entry.fs_type.as_str() {
+                    // a jail may be shown datasets mounted outside of its root, which it can't reach
+                    ZFS_FSTYPE if entry.mount.exists() => Either::Left((
+                        entry.mount,
+                        DatasetMetadata {
+                            source: entry.source,
+                            fs_type: FilesystemType::Zfs,
+                            mount_type: MountType::Local,
+                        },
+                    )),
+                    SMB_FSTYPE | NFS_FSTYPE => match fs_type_from_hidden_dir(&entry.mount) {
+                        Some(fs_type) => Either::Left((
+                            entry.mount,
+                            DatasetMetadata {
+                                source: entry.source,
+                                fs_type,
+                                mount_type: MountType::Network,
+                            },
+                        )),
+                        None => Either::Right(entry.mount),
+                    },
+                    // a nullfs mount of a dataset's own mount, as is common for jails, is that dataset,
+                    // with the same hidden snapshot directory, but nullfs mounts of dirs beneath a mount
+                    // have no snapshot directory, see FOLLOW_BINDS
+                    NULLFS_FSTYPE => match zfs_datasets.get(&entry.source) {
+                        Some(dataset) => Either::Left((
+                            entry.mount,
+                            DatasetMetadata {
+                                source: dataset.clone(),
+                                fs_type: FilesystemType::Zfs,
+                                mount_type: MountType::Local,
+                            },
+                        )),
+                        None => Either::Right(entry.mount),
+                    },
+                    _ => Either::Right(entry.mount),
+                };
+
+                (idx, res)
+            })
+            .collect();
+
+        let (map_of_datasets, filter_dirs) = Self::visible_mounts(entries);
 
         if map_of_datasets.is_empty() {
-            Err(HttmError::new("httm could not find any valid datasets on the system.").into())
+            Err(HttmError::with_kind(
+                HttmErrorKind::UnsupportedFilesystem,
+                "httm could not find any valid datasets on the system.",
+            )
+            .into())
         } else {
             Ok((map_of_datasets, filter_dirs))
         }
@@ -250,11 +812,7 @@ impl BaseFilesystemInfo {
     fn from_mount_cmd() -> HttmResult<(HashMap<PathBuf, DatasetMetadata>, HashSet<PathBuf>)> {
         // do we have the necessary commands for search if user has not defined a snap point?
         // if so run the mount search, if not print some errors
-        let mount_command = which("mount").map_err(|_err| {
-            HttmError::new(
-                "'mount' command not be found. Make sure the command 'mount' is in your path.",
-            )
-        })?;
+        let mount_command = ExternalCommand::require("mount")?;
 
         let command_output = &ExecProcess::new(mount_command).output()?;
 
@@ -311,7 +869,11 @@ impl BaseFilesystemInfo {
             });
 
         if map_of_datasets.is_empty() {
-            Err(HttmError::new("httm could not find any valid datasets on the system.").into())
+            Err(HttmError::with_kind(
+                HttmErrorKind::UnsupportedFilesystem,
+                "httm could not find any valid datasets on the system.",
+            )
+            .into())
         } else {
             Ok((map_of_datasets, filter_dirs))
         }
@@ -17,16 +17,43 @@
 
 use std::{fs::read_dir, ops::Deref, path::Path, path::PathBuf, process::Command as ExecProcess};
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
+use once_cell::sync::Lazy;
 use proc_mounts::MountIter;
 use rayon::prelude::*;
-use which::which;
 
-use crate::library::results::{HttmError, HttmResult};
+use crate::library::external_commands::ExternalCommand;
+use crate::library::results::{HttmError, HttmErrorKind, HttmResult};
+use crate::library::retry::Retry;
 use crate::parse::aliases::FilesystemType;
 use crate::parse::mounts::{DatasetMetadata, MountType};
 use crate::{BTRFS_SNAPPER_HIDDEN_DIRECTORY, BTRFS_SNAPPER_SUFFIX, ZFS_SNAPSHOT_DIRECTORY};
 
+const SNAPPER_CONFIGS_DIRECTORY: &str = "/etc/snapper/configs";
+const SNAPPER_SUBVOLUME_KEY: &str = "SUBVOLUME=";
+
+// subvolumes which snapper is configured to snapshot, whose snapshots are therefore laid out at
+// "<SUBVOLUME>/.snapshots/<NUM>/snapshot", even when ".snapshots" is itself a separately mounted
+// subvolume, as is the default on openSUSE
+static SNAPPER_SUBVOLUMES: Lazy<HashSet<PathBuf>> = Lazy::new(|| {
+    let configs = match read_dir(SNAPPER_CONFIGS_DIRECTORY) {
+        Ok(configs) => configs,
+        Err(_) => return HashSet::new(),
+    };
+
+    configs
+        .flatten()
+        .filter_map(|config| std::fs::read_to_string(config.path()).ok())
+        .filter_map(|contents| {
+            contents.lines().find_map(|line| {
+                line.trim()
+                    .strip_prefix(SNAPPER_SUBVOLUME_KEY)
+                    .map(|value| PathBuf::from(value.trim_matches('"')))
+            })
+        })
+        .collect()
+});
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MapOfSnaps {
     inner: HashMap<PathBuf, Vec<PathBuf>>,
@@ -52,36 +79,63 @@ impl MapOfSnaps {
         let map_of_snaps: HashMap<PathBuf, Vec<PathBuf>> = map_of_datasets
             .par_iter()
             .flat_map(|(mount, dataset_info)| {
-                let snap_mounts: HttmResult<Vec<PathBuf>> = match dataset_info.fs_type {
-                    FilesystemType::Zfs | FilesystemType::Nilfs2 => {
-                        Self::from_defined_mounts(mount, dataset_info)
-                    }
-                    FilesystemType::Btrfs => match dataset_info.mount_type {
-                        MountType::Local => Self::from_btrfs_cmd(mount),
-                        MountType::Network => Self::from_defined_mounts(mount, dataset_info),
-                    },
-                };
-
-                snap_mounts.map(|snap_mounts| (mount.clone(), snap_mounts))
+                Self::snap_mounts_of(mount, dataset_info)
+                    .map(|snap_mounts| (mount.clone(), snap_mounts))
             })
             .collect();
 
         if map_of_snaps.is_empty() {
-            Err(HttmError::new("httm could not find any valid datasets on the system.").into())
+            Err(HttmError::with_kind(
+                HttmErrorKind::UnsupportedFilesystem,
+                "httm could not find any valid datasets on the system.",
+            )
+            .into())
         } else {
             Ok(map_of_snaps.into())
         }
     }
 
+    // re-read the snap mounts of a single dataset, as when a snapshot is taken mid-session
+    pub fn with_snaps_of(&self, mount: &Path, dataset_info: &DatasetMetadata) -> HttmResult<Self> {
+        let mut map_of_snaps = self.inner.clone();
+
+        map_of_snaps.insert(
+            mount.to_path_buf(),
+            Self::snap_mounts_of(mount, dataset_info)?,
+        );
+
+        Ok(map_of_snaps.into())
+    }
+
+    fn snap_mounts_of(mount: &Path, dataset_info: &DatasetMetadata) -> HttmResult<Vec<PathBuf>> {
+        match dataset_info.fs_type {
+            FilesystemType::Zfs | FilesystemType::Nilfs2 => {
+                Self::from_defined_mounts(mount, dataset_info)
+            }
+            FilesystemType::Btrfs => match dataset_info.mount_type {
+                // reading the snapper layout requires neither root nor the btrfs command,
+                // but fall back to the btrfs command if that layout is unreadable
+                MountType::Local if SNAPPER_SUBVOLUMES.contains(mount) => {
+                    Self::from_defined_mounts(mount, dataset_info)
+                        .or_else(|_err| Self::from_btrfs_cmd(mount))
+                }
+                MountType::Local => Self::from_btrfs_cmd(mount),
+                MountType::Network => Self::from_defined_mounts(mount, dataset_info),
+            },
+        }
+    }
+
     // build paths to all snap mounts
     fn from_btrfs_cmd(mount: &Path) -> HttmResult<Vec<PathBuf>> {
-        let btrfs_command = which("btrfs").map_err(|_err| {
-            HttmError::new(
-                "'btrfs' command not found. Make sure the command 'btrfs' is in your path.",
-            )
-        })?;
+        // the notice is all the user needs to hear, as a dataset without snapshots is no error
+        let exec_command = match ExternalCommand::optional(
+            "btrfs",
+            "snapshots of btrfs datasets not laid out by snapper cannot be found",
+        ) {
+            Some(btrfs_command) => btrfs_command,
+            None => return Ok(Vec::new()),
+        };
 
-        let exec_command = btrfs_command;
         let arg_path = mount.to_string_lossy();
         let args = vec!["subvolume", "show", &arg_path];
 
@@ -125,18 +179,20 @@ impl MapOfSnaps {
         dataset_metadata: &DatasetMetadata,
     ) -> HttmResult<Vec<PathBuf>> {
         let snaps = match dataset_metadata.fs_type {
-            FilesystemType::Btrfs => {
-                read_dir(mount_point_path.join(BTRFS_SNAPPER_HIDDEN_DIRECTORY))?
-                    .flatten()
-                    .par_bridge()
-                    .map(|entry| entry.path().join(BTRFS_SNAPPER_SUFFIX))
-                    .collect()
-            }
-            FilesystemType::Zfs => read_dir(mount_point_path.join(ZFS_SNAPSHOT_DIRECTORY))?
-                .flatten()
-                .par_bridge()
-                .map(|entry| entry.path())
-                .collect(),
+            FilesystemType::Btrfs => Retry::io(mount_point_path, || {
+                read_dir(mount_point_path.join(BTRFS_SNAPPER_HIDDEN_DIRECTORY))
+            })?
+            .flatten()
+            .par_bridge()
+            .map(|entry| entry.path().join(BTRFS_SNAPPER_SUFFIX))
+            .collect(),
+            FilesystemType::Zfs => Retry::io(mount_point_path, || {
+                read_dir(mount_point_path.join(ZFS_SNAPSHOT_DIRECTORY))
+            })?
+            .flatten()
+            .par_bridge()
+            .map(|entry| entry.path())
+            .collect(),
             FilesystemType::Nilfs2 => {
                 let source_path = Path::new(&dataset_metadata.source);
 
@@ -0,0 +1,336 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+// ephemeral ZFS pools and btrfs filesystems, backed by sparse files, for the integration tests
+//
+// each fixture is created fresh per test, under a unique name, and is destroyed on drop,
+// so tests may run concurrently and leave nothing behind, even when an assertion fails
+
+use std::fs::{create_dir_all, remove_dir_all, File};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static FIXTURE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// large enough for either filesystem's minimum, and, as the file is sparse, costs next to nothing
+const BACKING_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+pub fn httm() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_httm"));
+    // keep the user's environment, like HTTM_DEFAULTS, from changing what's under test
+    command.env_remove("HTTM_DEFAULTS");
+    command.stdin(Stdio::null());
+    command
+}
+
+pub fn run(command: &mut Command) -> Output {
+    let output = command
+        .output()
+        .unwrap_or_else(|err| panic!("could not execute {command:?}: {err}"));
+
+    assert!(
+        output.status.success(),
+        "{command:?} failed with {}:\nstdout: {}\nstderr: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output
+}
+
+pub fn stdout_lines(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_owned())
+        .collect()
+}
+
+// these tests create pools and mount filesystems, which requires root and the userland tools,
+// absent either, a test is skipped, with a note why, rather than failed
+pub fn should_skip(required_commands: &[&str]) -> bool {
+    if !is_root() {
+        eprintln!("SKIPPED: integration tests must be run as root.");
+        return true;
+    }
+
+    match required_commands
+        .iter()
+        .find(|command| which(command).is_none())
+    {
+        Some(missing) => {
+            eprintln!("SKIPPED: '{missing}' command not found in PATH.");
+            true
+        }
+        None => false,
+    }
+}
+
+fn is_root() -> bool {
+    // avoids a dev-dependency upon nix or libc just for the euid
+    Command::new("id")
+        .arg("-u")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "0")
+        .unwrap_or(false)
+}
+
+fn which(command: &str) -> Option<PathBuf> {
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(command))
+            .find(|path| path.is_file())
+    })
+}
+
+struct Scratch {
+    dir: PathBuf,
+    backing_file: PathBuf,
+    mount: PathBuf,
+}
+
+impl Scratch {
+    fn new(prefix: &str) -> (String, Self) {
+        let name = format!(
+            "{prefix}_{}_{}",
+            std::process::id(),
+            FIXTURE_COUNT.fetch_add(1, Ordering::SeqCst)
+        );
+
+        let dir = std::env::temp_dir().join(&name);
+        let mount = dir.join("mnt");
+        create_dir_all(&mount).expect("could not create fixture dir");
+
+        let backing_file = dir.join("backing.img");
+        File::create(&backing_file)
+            .and_then(|file| file.set_len(BACKING_FILE_SIZE))
+            .expect("could not create backing file");
+
+        (
+            name,
+            Self {
+                dir,
+                backing_file,
+                mount,
+            },
+        )
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.dir);
+    }
+}
+
+pub struct ZfsPool {
+    pub name: String,
+    scratch: Scratch,
+}
+
+impl ZfsPool {
+    pub fn new() -> Self {
+        let (name, scratch) = Scratch::new("httm_test");
+
+        run(Command::new("zpool")
+            .arg("create")
+            .arg("-O")
+            .arg(format!("mountpoint={}", scratch.mount.display()))
+            .arg(&name)
+            .arg(&scratch.backing_file));
+
+        Self { name, scratch }
+    }
+
+    pub fn mount(&self) -> &Path {
+        &self.scratch.mount
+    }
+
+    pub fn snapshot(&self, snap_name: &str) {
+        run(Command::new("zfs")
+            .arg("snapshot")
+            .arg(format!("{}@{snap_name}", self.name)));
+    }
+
+    pub fn snapshot_names(&self) -> Vec<String> {
+        let output = run(Command::new("zfs")
+            .args(["list", "-H", "-o", "name", "-t", "snapshot", "-d", "1"])
+            .arg(&self.name));
+
+        stdout_lines(&output)
+    }
+
+    // a child dataset, and any parents required, which inherit their mount points from the pool
+    pub fn create_dataset(&self, relative_name: &str) -> PathBuf {
+        run(Command::new("zfs")
+            .args(["create", "-p"])
+            .arg(format!("{}/{relative_name}", self.name)));
+
+        self.mount().join(relative_name)
+    }
+
+    // a child dataset with a mount point of its own, rather than one inherited from the pool
+    pub fn create_dataset_mounted_at(&self, relative_name: &str, mount: &Path) {
+        run(Command::new("zfs")
+            .arg("create")
+            .arg("-o")
+            .arg(format!("mountpoint={}", mount.display()))
+            .arg(format!("{}/{relative_name}", self.name)));
+    }
+
+    pub fn snapshot_dataset(&self, relative_name: &str, snap_name: &str) {
+        run(Command::new("zfs")
+            .arg("snapshot")
+            .arg(format!("{}/{relative_name}@{snap_name}", self.name)));
+    }
+
+    pub fn dataset_snapshot_names(&self, relative_name: &str) -> Vec<String> {
+        let output = run(Command::new("zfs")
+            .args(["list", "-H", "-o", "name", "-t", "snapshot", "-d", "1"])
+            .arg(format!("{}/{relative_name}", self.name)));
+
+        stdout_lines(&output)
+    }
+}
+
+impl Drop for ZfsPool {
+    fn drop(&mut self) {
+        let _ = Command::new("zpool")
+            .args(["destroy", "-f"])
+            .arg(&self.name)
+            .status();
+    }
+}
+
+pub struct BtrfsFilesystem {
+    scratch: Scratch,
+    snap_count: usize,
+}
+
+impl BtrfsFilesystem {
+    pub fn new() -> Self {
+        let (_name, scratch) = Scratch::new("httm_test_btrfs");
+
+        run(Command::new("mkfs.btrfs")
+            .args(["-q", "-f"])
+            .arg(&scratch.backing_file));
+
+        run(Command::new("mount")
+            .args(["-o", "loop"])
+            .arg(&scratch.backing_file)
+            .arg(&scratch.mount));
+
+        Self {
+            scratch,
+            snap_count: 0,
+        }
+    }
+
+    pub fn mount(&self) -> &Path {
+        &self.scratch.mount
+    }
+
+    // laid out as snapper would, ".snapshots/<num>/snapshot"
+    pub fn snapshot(&mut self) -> PathBuf {
+        self.snap_count += 1;
+
+        let snap_dir = self
+            .scratch
+            .mount
+            .join(".snapshots")
+            .join(self.snap_count.to_string());
+        create_dir_all(&snap_dir).expect("could not create snapshot dir");
+
+        let snap_path = snap_dir.join("snapshot");
+
+        run(Command::new("btrfs")
+            .args(["subvolume", "snapshot", "-r"])
+            .arg(&self.scratch.mount)
+            .arg(&snap_path));
+
+        snap_path
+    }
+}
+
+impl Drop for BtrfsFilesystem {
+    fn drop(&mut self) {
+        let _ = Command::new("umount").arg(&self.scratch.mount).status();
+    }
+}
+
+// FAT stores modify times in 2 second increments, and neither ownership nor xattrs,
+// as is common for the USB drives to which users back up
+pub struct VfatFilesystem {
+    scratch: Scratch,
+}
+
+impl VfatFilesystem {
+    pub fn new() -> Self {
+        let (_name, scratch) = Scratch::new("httm_test_vfat");
+
+        run(Command::new("mkfs.vfat").arg(&scratch.backing_file));
+
+        run(Command::new("mount")
+            .args(["-t", "vfat", "-o", "loop"])
+            .arg(&scratch.backing_file)
+            .arg(&scratch.mount));
+
+        Self { scratch }
+    }
+
+    pub fn mount(&self) -> &Path {
+        &self.scratch.mount
+    }
+}
+
+impl Drop for VfatFilesystem {
+    fn drop(&mut self) {
+        let _ = Command::new("umount").arg(&self.scratch.mount).status();
+    }
+}
+
+// a nullfs mount is FreeBSD's bind mount, as is common for jails
+#[cfg(target_os = "freebsd")]
+pub struct NullfsMount {
+    scratch: Scratch,
+}
+
+#[cfg(target_os = "freebsd")]
+impl NullfsMount {
+    pub fn new(source: &Path) -> Self {
+        let (_name, scratch) = Scratch::new("httm_test_nullfs");
+
+        run(Command::new("mount")
+            .args(["-t", "nullfs"])
+            .arg(source)
+            .arg(&scratch.mount));
+
+        Self { scratch }
+    }
+
+    pub fn mount(&self) -> &Path {
+        &self.scratch.mount
+    }
+}
+
+#[cfg(target_os = "freebsd")]
+impl Drop for NullfsMount {
+    fn drop(&mut self) {
+        let _ = Command::new("umount").arg(&self.scratch.mount).status();
+    }
+}
@@ -0,0 +1,472 @@
+//       ___           ___           ___           ___
+//      /\__\         /\  \         /\  \         /\__\
+//     /:/  /         \:\  \        \:\  \       /::|  |
+//    /:/__/           \:\  \        \:\  \     /:|:|  |
+//   /::\  \ ___       /::\  \       /::\  \   /:/|:|__|__
+//  /:/\:\  /\__\     /:/\:\__\     /:/\:\__\ /:/ |::::\__\
+//  \/__\:\/:/  /    /:/  \/__/    /:/  \/__/ \/__/~~/:/  /
+//       \::/  /    /:/  /        /:/  /            /:/  /
+//       /:/  /     \/__/         \/__/            /:/  /
+//      /:/  /                                    /:/  /
+//      \/__/                                     \/__/
+//
+// Copyright (c) 2023, Robert Swinford <robert.swinford<...at...>gmail.com>
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+// end-to-end tests against real, ephemeral, ZFS pools and btrfs filesystems
+//
+// these require root, the ZFS and btrfs userland tools, and loop device support, and so are only
+// built with the "integration-tests" feature: sudo -E cargo test --features integration-tests
+
+mod common;
+
+use std::fs::{read_dir, read_to_string, write, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+use common::{httm, run, should_skip, stdout_lines, BtrfsFilesystem, VfatFilesystem, ZfsPool};
+
+// two snapshotted versions, each unique, and a live version, unique again
+fn zfs_fixture() -> Option<(ZfsPool, PathBuf)> {
+    if should_skip(&["zpool", "zfs"]) {
+        return None;
+    }
+
+    let pool = ZfsPool::new();
+    let file = pool.mount().join("fixture.txt");
+
+    write(&file, "version one\n").unwrap();
+    pool.snapshot("snap1");
+    write(&file, "version two, which is longer\n").unwrap();
+    pool.snapshot("snap2");
+    write(&file, "live version, longer still, and unique\n").unwrap();
+
+    Some((pool, file))
+}
+
+#[test]
+fn zfs_versions_lookup() {
+    let (pool, file) = match zfs_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let lines = stdout_lines(&run(httm().arg("-n").arg(&file)));
+
+    ["snap1", "snap2"].iter().for_each(|snap_name| {
+        let version = pool
+            .mount()
+            .join(".zfs/snapshot")
+            .join(snap_name)
+            .join("fixture.txt");
+
+        assert!(
+            lines.iter().any(|line| line == &version.to_string_lossy()),
+            "{version:?} not found in: {lines:?}"
+        );
+    });
+
+    let lines = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+    assert!(!lines.iter().any(|line| line == &file.to_string_lossy()));
+    assert_eq!(lines.len(), 2);
+}
+
+#[test]
+fn zfs_restore() {
+    let (_pool, file) = match zfs_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let request = format!(
+        "{{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"restore\", \"params\": \
+        {{\"path\": {:?}, \"snap\": \"snap1\", \"mode\": \"overwrite\", \"force\": true}}}}\n",
+        file.to_string_lossy()
+    );
+
+    let mut child = httm()
+        .arg("--rpc")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("could not spawn httm");
+
+    child
+        .stdin
+        .take()
+        .expect("no stdin")
+        .write_all(request.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    let response = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(response.contains("\"result\""), "unexpected: {response}");
+    assert_eq!(read_to_string(&file).unwrap(), "version one\n");
+}
+
+#[test]
+fn zfs_snap() {
+    let (pool, file) = match zfs_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let before = pool.snapshot_names().len();
+
+    run(httm().arg("--snap=integration").arg(&file));
+
+    let snap_names = pool.snapshot_names();
+
+    assert_eq!(snap_names.len(), before + 1);
+    assert!(snap_names
+        .iter()
+        .any(|snap_name| snap_name.ends_with("_integration")));
+}
+
+// the prune itself asks for the user's consent in an interactive view, which no test can give,
+// so this exercises the selection of snapshots, which is exactly what a prune would destroy
+#[test]
+fn zfs_prune_selection() {
+    let (pool, file) = match zfs_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let output =
+        String::from_utf8_lossy(&run(httm().arg("--list-snaps=0,snap1").arg(&file)).stdout)
+            .to_string();
+
+    assert!(output.contains(&format!("{}@snap1", pool.name)));
+    assert!(!output.contains(&format!("{}@snap2", pool.name)));
+}
+
+#[test]
+fn zfs_roll_forward() {
+    let (pool, file) = match zfs_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let added = pool.mount().join("added_since.txt");
+    write(&added, "not on snap1\n").unwrap();
+
+    let before = pool.snapshot_names().len();
+
+    run(httm().arg(format!("--roll-forward={}@snap1", pool.name)));
+
+    assert_eq!(read_to_string(&file).unwrap(), "version one\n");
+    assert!(!added.exists());
+    // one precautionary snapshot before, and one after, the roll forward
+    assert_eq!(pool.snapshot_names().len(), before + 2);
+}
+
+// versions of a file upon the deepest of several nested datasets are found upon that dataset's
+// snapshots, and not upon a parent's, and the same is true of which dataset a snapshot is taken of
+#[test]
+fn zfs_nested_datasets() {
+    if should_skip(&["zpool", "zfs"]) {
+        return;
+    }
+
+    let pool = ZfsPool::new();
+    let projects = pool.create_dataset("home/user/projects");
+    let file = projects.join("fixture.txt");
+
+    write(&file, "version one\n").unwrap();
+    pool.snapshot_dataset("home/user/projects", "child_snap");
+    // the parent's snapshot holds only the child's empty mount point
+    pool.snapshot_dataset("home/user", "parent_snap");
+    write(&file, "live version, which is longer\n").unwrap();
+
+    let lines = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+    let version = projects.join(".zfs/snapshot/child_snap/fixture.txt");
+
+    assert_eq!(lines, vec![version.to_string_lossy().to_string()]);
+
+    let parent_snaps_before = pool.dataset_snapshot_names("home/user").len();
+
+    run(httm().arg("--snap=nested").arg(&file));
+
+    assert!(pool
+        .dataset_snapshot_names("home/user/projects")
+        .iter()
+        .any(|snap_name| snap_name.ends_with("_nested")));
+    assert_eq!(
+        pool.dataset_snapshot_names("home/user").len(),
+        parent_snaps_before
+    );
+}
+
+// the mount table escapes a space within a mount point, which must not be mistaken for a delimiter
+#[test]
+fn zfs_mount_with_space() {
+    if should_skip(&["zpool", "zfs"]) {
+        return;
+    }
+
+    let pool = ZfsPool::new();
+    let spaced = pool.mount().join("with space");
+    pool.create_dataset_mounted_at("spaced", &spaced);
+
+    let file = spaced.join("fixture.txt");
+
+    write(&file, "version one\n").unwrap();
+    pool.snapshot_dataset("spaced", "snap1");
+    write(&file, "live version, which is longer\n").unwrap();
+
+    let lines = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+    let version = spaced.join(".zfs/snapshot/snap1/fixture.txt");
+
+    assert_eq!(lines, vec![version.to_string_lossy().to_string()]);
+}
+
+// a nullfs mount of a dataset's own mount, as is common for jails, is that dataset, with its snapshots
+#[cfg(target_os = "freebsd")]
+#[test]
+fn zfs_nullfs_mount() {
+    let (pool, _file) = match zfs_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    if should_skip(&["mount", "umount"]) {
+        return;
+    }
+
+    let nullfs = common::NullfsMount::new(pool.mount());
+    let file = nullfs.mount().join("fixture.txt");
+
+    let lines = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+
+    assert_eq!(lines.len(), 2, "unexpected: {lines:?}");
+
+    ["snap1", "snap2"].iter().for_each(|snap_name| {
+        let version = nullfs
+            .mount()
+            .join(".zfs/snapshot")
+            .join(snap_name)
+            .join("fixture.txt");
+
+        assert!(
+            lines.iter().any(|line| line == &version.to_string_lossy()),
+            "{version:?} not found in: {lines:?}"
+        );
+    });
+}
+
+// identical versions, in snapshots whose names sort opposite to the order in which they were created,
+// are displayed as the newest snapshot's copy, and the others are listed only with SHOW_DUPLICATES
+#[test]
+fn zfs_duplicate_versions() {
+    if should_skip(&["zpool", "zfs"]) {
+        return;
+    }
+
+    let pool = ZfsPool::new();
+    let file = pool.mount().join("fixture.txt");
+
+    write(&file, "the same version\n").unwrap();
+
+    // the creation property is only precise to the second
+    ["z_oldest", "m_middle", "a_newest"]
+        .iter()
+        .for_each(|snap_name| {
+            pool.snapshot(snap_name);
+            sleep(Duration::from_millis(1100));
+        });
+
+    write(&file, "live version, which is longer\n").unwrap();
+
+    let version_of = |snap_name: &str| {
+        pool.mount()
+            .join(".zfs/snapshot")
+            .join(snap_name)
+            .join("fixture.txt")
+            .to_string_lossy()
+            .to_string()
+    };
+
+    let lines = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+    assert_eq!(lines, vec![version_of("a_newest")]);
+
+    let output = String::from_utf8_lossy(&run(httm().arg("--show-duplicates").arg(&file)).stdout)
+        .to_string();
+    let is_duplicate = |snap_name: &str| {
+        output
+            .lines()
+            .any(|line| line.contains(&version_of(snap_name)) && line.ends_with("# duplicate"))
+    };
+
+    assert!(is_duplicate("z_oldest"), "unexpected: {output}");
+    assert!(is_duplicate("m_middle"), "unexpected: {output}");
+    assert!(!is_duplicate("a_newest"), "unexpected: {output}");
+}
+
+// snapshots taken within the same second tie upon their creation, and whichever copy breaks the tie,
+// it must be the same copy upon every run
+#[test]
+fn zfs_duplicate_versions_tie() {
+    if should_skip(&["zpool", "zfs"]) {
+        return;
+    }
+
+    let pool = ZfsPool::new();
+    let file = pool.mount().join("fixture.txt");
+
+    write(&file, "the same version\n").unwrap();
+    pool.snapshot("tie_b");
+    pool.snapshot("tie_a");
+    write(&file, "live version, which is longer\n").unwrap();
+
+    let first = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+    assert_eq!(first.len(), 1, "unexpected: {first:?}");
+
+    (0..4).for_each(|_| {
+        let again = stdout_lines(&run(httm().args(["-n", "--no-live"]).arg(&file)));
+        assert_eq!(again, first);
+    });
+}
+
+// a backup upon a FAT drive, aliased to the pool, whose snapshot holds the same version, with a modify
+// time upon an odd second, which FAT can only store rounded to an even second
+fn vfat_alias_fixture() -> Option<(ZfsPool, VfatFilesystem, PathBuf)> {
+    if should_skip(&["zpool", "zfs", "mkfs.vfat", "mount", "umount"]) {
+        return None;
+    }
+
+    let pool = ZfsPool::new();
+    let vfat = VfatFilesystem::new();
+    let odd_second = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_001);
+
+    [pool.mount(), vfat.mount()].iter().for_each(|dir| {
+        let file = dir.join("fixture.txt");
+
+        write(&file, "the same version\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&file)
+            .and_then(|file| file.set_modified(odd_second))
+            .unwrap();
+    });
+
+    pool.snapshot("snap1");
+
+    let file = vfat.mount().join("fixture.txt");
+
+    Some((pool, vfat, file))
+}
+
+fn vfat_alias(pool: &ZfsPool, vfat: &VfatFilesystem) -> String {
+    format!(
+        "--map-aliases={}:{}",
+        vfat.mount().display(),
+        pool.mount().display()
+    )
+}
+
+#[test]
+fn vfat_alias_omit_ditto() {
+    let (pool, vfat, file) = match vfat_alias_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let version = pool.mount().join(".zfs/snapshot/snap1/fixture.txt");
+
+    let lines = stdout_lines(&run(httm()
+        .arg("-n")
+        .arg(vfat_alias(&pool, &vfat))
+        .arg(&file)));
+
+    assert!(
+        lines.iter().any(|line| line == &version.to_string_lossy()),
+        "{version:?} not found in: {lines:?}"
+    );
+
+    // the modify times differ by less than FAT's granularity, so the snapshot version is a ditto
+    let lines = stdout_lines(&run(httm()
+        .args(["-n", "--omit-ditto"])
+        .arg(vfat_alias(&pool, &vfat))
+        .arg(&file)));
+
+    assert_eq!(lines, vec![file.to_string_lossy().to_string()]);
+}
+
+// FAT can store neither ownership nor xattrs, which a preserving restore skips, rather than fails upon
+#[test]
+fn vfat_alias_restore() {
+    let (pool, vfat, file) = match vfat_alias_fixture() {
+        Some(fixture) => fixture,
+        None => return,
+    };
+
+    let request = format!(
+        "{{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"restore\", \"params\": \
+        {{\"path\": {:?}, \"snap\": \"snap1\", \"mode\": \"copy-and-preserve\"}}}}\n",
+        file.to_string_lossy()
+    );
+
+    let mut child = httm()
+        .arg("--rpc")
+        .arg(vfat_alias(&pool, &vfat))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("could not spawn httm");
+
+    child
+        .stdin
+        .take()
+        .expect("no stdin")
+        .write_all(request.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    let response = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(response.contains("\"result\""), "unexpected: {response}");
+
+    let restored: Vec<PathBuf> = read_dir(vfat.mount())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path != &file)
+        .collect();
+
+    assert_eq!(restored.len(), 1, "unexpected: {restored:?}");
+    assert_eq!(read_to_string(&restored[0]).unwrap(), "the same version\n");
+}
+
+#[test]
+fn btrfs_versions_lookup() {
+    if should_skip(&["mkfs.btrfs", "btrfs", "mount", "umount"]) {
+        return;
+    }
+
+    let mut filesystem = BtrfsFilesystem::new();
+    let file = filesystem.mount().join("fixture.txt");
+
+    write(&file, "version one\n").unwrap();
+    let first = filesystem.snapshot();
+    write(&file, "version two, which is longer\n").unwrap();
+    let second = filesystem.snapshot();
+    write(&file, "live version, longer still, and unique\n").unwrap();
+
+    let lines = stdout_lines(&run(httm().arg("-n").arg(&file)));
+
+    [first, second].iter().for_each(|snap| {
+        let version = snap.join("fixture.txt");
+
+        assert!(
+            lines.iter().any(|line| line == &version.to_string_lossy()),
+            "{version:?} not found in: {lines:?}"
+        );
+    });
+}
@@ -0,0 +1,58 @@
+# httm message catalog, English baseline
+#
+# This file uses a small subset of the Fluent syntax (https://projectfluent.org/):
+# "key = value" entries, with indented continuation lines for multiline values,
+# and variables written as placeables, like "{ $name }".
+#
+# To translate, copy this file to <language>.ftl, for instance "de.ftl" or "pt_BR.ftl",
+# translate the values, and install it to /usr/share/httm/translations, or to the
+# directory specified by the HTTM_TRANSLATIONS_DIR environment variable.  Any key
+# missing from a translation falls back to this English baseline.
+
+## Help texts
+
+help-browse = interactive browse and search a specified directory to display unique file versions.  More than one directory may be specified, and each will be searched and merged into a single view.
+help-select = interactive browse and search a specified directory to display unique file versions.  Continue to another dialog to select a snapshot version to dump to stdout.  This argument optionally takes a value.  The default behavior/value, "path", dumps the path of the snapshot version selected, whereas "contents" dumps the contents of that snapshot version.
+help-pick = display the unique versions of a single specified file, and let the user pick exactly one.  The path of the chosen snapshot version is printed to stdout without any decoration, and httm exits 0.  If the user cancels the selection, or no snapshot version exists, httm exits non-zero.  Useful for integrating httm into file managers and scripts.
+
+## Interactive prompts
+
+browse-aborted = httm interactive file browse session was aborted.  Quitting.
+select-aborted = httm select/restore/prune session was aborted.  Quitting.
+restore-aborted = httm restore session was aborted.  Quitting.
+restore-consent-header = httm will copy a file from a snapshot:
+restore-consent-question = Before httm restores this file, it would like your consent. Continue? (YES/NO)
+restore-completed-header = httm copied a file from a snapshot:
+restore-completed = Restore completed successfully.
+restore-declined = User declined restore.  No files were restored.
+restore-choice-header = How should httm restore this file?
+restore-choice-default = The default is the mode requested at the command line: { $mode }
+prune-consent-files = User has requested snapshots related to the following file/s be pruned:
+prune-consent-snaps = httm will destroy the following snapshot/s:
+prune-consent-question = Before httm destroys these snapshot/s, it would like your consent. Continue? (YES/NO)
+prune-completed-files = httm pruned snapshots related to the following file/s:
+prune-completed-snaps = By destroying the following snapshot/s:
+prune-completed = Prune completed successfully.
+prune-declined = User declined prune.  No files were pruned.
+rollback-succeeded = Rollback succeeded.
+recover-consent-header = httm will restore the following deleted files from their most recent snapshot versions:
+recover-consent-question = Before httm restores these files, it would like your consent. Continue? (YES/NO)
+recover-completed-header = httm restored the following deleted files:
+recover-completed = Recovery completed.
+recover-declined = User declined recovery.  No files were restored.
+
+## Error messages
+
+error-no-snapshots = Cannot select or restore from the following paths as they have no snapshots:
+error-no-valid-selection = None of the selected strings could be converted to paths.
+error-no-deleted = httm could not find any deleted files beneath the paths specified.
+error-no-last-snap = No last snapshot for the requested input file exists.
+error-source-missing = Source location does not exist on disk. Quitting.
+error-restore-exists = httm will not restore to that file, as a file with the same path name already exists. Quitting.
+error-browse-panicked = Interactive browse thread panicked.
+error-select-failed = httm select/restore/prune session failed.
+error-restore-failed = httm restore session failed.
+error-recover-failed = httm could not recover { $failed } of the { $total } deleted files selected.
+error-restore-rollback =
+    httm restore failed for the following reason: { $reason }.
+    Attempting roll back to precautionary pre-execution snapshot.